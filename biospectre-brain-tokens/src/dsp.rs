@@ -0,0 +1,270 @@
+use std::f32::consts::PI;
+
+/// In-place radix-2 FFT over separate real/imaginary buffers.
+/// `re.len()` must be a power of two.
+pub fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    debug_assert!(n.is_power_of_two() && im.len() == n);
+
+    // Bit-reversal permutation.
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let ang = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_im, w_re) = (ang * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Rough FLOP count of one radix-2 FFT of length `n`.
+pub fn fft_flops(n: usize) -> u64 {
+    if n < 2 {
+        return 0;
+    }
+    5 * n as u64 * n.trailing_zeros() as u64
+}
+
+pub fn hann(n: usize) -> Vec<f32> {
+    if n < 2 {
+        return vec![1.0; n];
+    }
+    (0..n)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / (n - 1) as f32).cos())
+        .collect()
+}
+
+/// One-sided power spectral density (units²/Hz).
+#[derive(Debug, Clone)]
+pub struct Psd {
+    pub freq_resolution_hz: f32,
+    pub power: Vec<f32>,
+    /// Approximate FLOPs spent computing it.
+    pub flops: u64,
+}
+
+impl Psd {
+    pub fn freq(&self, bin: usize) -> f32 {
+        bin as f32 * self.freq_resolution_hz
+    }
+
+    /// Integrated power over `[lo_hz, hi_hz)`.
+    pub fn band_power(&self, lo_hz: f32, hi_hz: f32) -> f32 {
+        self.power
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                let f = self.freq(*i);
+                f >= lo_hz && f < hi_hz
+            })
+            .map(|(_, p)| p * self.freq_resolution_hz)
+            .sum()
+    }
+}
+
+/// Welch's method: Hann-windowed, mean-detrended segments of `segment_len`
+/// samples with 50% overlap, zero-padded to a power of two and averaged.
+pub fn welch_psd(signal: &[f32], sample_rate_hz: f32, segment_len: usize) -> Psd {
+    let seg = segment_len.clamp(1, signal.len().max(1));
+    let nfft = seg.next_power_of_two();
+    let step = (seg / 2).max(1);
+    let window = hann(seg);
+    let win_power: f32 = window.iter().map(|w| w * w).sum();
+    let bins = nfft / 2 + 1;
+
+    let mut power = vec![0.0_f32; bins];
+    let mut segments = 0;
+    let mut flops = 0;
+    let mut re = vec![0.0_f32; nfft];
+    let mut im = vec![0.0_f32; nfft];
+    let mut start = 0;
+    while start + seg <= signal.len() {
+        let chunk = &signal[start..start + seg];
+        let mean = chunk.iter().sum::<f32>() / seg as f32;
+        re.iter_mut().for_each(|v| *v = 0.0);
+        im.iter_mut().for_each(|v| *v = 0.0);
+        for (i, (x, w)) in chunk.iter().zip(&window).enumerate() {
+            re[i] = (x - mean) * w;
+        }
+        fft(&mut re, &mut im);
+        for (k, p) in power.iter_mut().enumerate() {
+            *p += re[k] * re[k] + im[k] * im[k];
+        }
+        flops += fft_flops(nfft) + 4 * seg as u64 + 3 * bins as u64;
+        segments += 1;
+        start += step;
+    }
+
+    let scale = if segments > 0 && win_power > 0.0 {
+        1.0 / (sample_rate_hz * win_power * segments as f32)
+    } else {
+        0.0
+    };
+    for (k, p) in power.iter_mut().enumerate() {
+        *p *= scale;
+        // Fold negative frequencies into the one-sided spectrum.
+        if k != 0 && k != nfft / 2 {
+            *p *= 2.0;
+        }
+    }
+    Psd { freq_resolution_hz: sample_rate_hz / nfft as f32, power, flops }
+}
+
+/// Second-order IIR section (RBJ cookbook coefficients, a0 normalized).
+#[derive(Debug, Clone, Copy)]
+pub struct Biquad {
+    b: [f32; 3],
+    a: [f32; 2],
+}
+
+impl Biquad {
+    /// Constant 0 dB peak-gain band-pass between `lo_hz` and `hi_hz`.
+    pub fn bandpass(sample_rate_hz: f32, lo_hz: f32, hi_hz: f32) -> Self {
+        let center = (lo_hz * hi_hz).sqrt();
+        let q = center / (hi_hz - lo_hz);
+        let w0 = 2.0 * PI * center / sample_rate_hz;
+        let alpha = w0.sin() / (2.0 * q);
+        let a0 = 1.0 + alpha;
+        Self {
+            b: [alpha / a0, 0.0, -alpha / a0],
+            a: [-2.0 * w0.cos() / a0, (1.0 - alpha) / a0],
+        }
+    }
+
+    pub fn apply(&self, input: &[f32]) -> Vec<f32> {
+        let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+        input
+            .iter()
+            .map(|&x| {
+                let y = self.b[0] * x + self.b[1] * x1 + self.b[2] * x2 - self.a[0] * y1 - self.a[1] * y2;
+                x2 = x1;
+                x1 = x;
+                y2 = y1;
+                y1 = y;
+                y
+            })
+            .collect()
+    }
+
+    /// Forward-backward filtering: zero phase, squared magnitude response.
+    pub fn filtfilt(&self, input: &[f32]) -> Vec<f32> {
+        let mut out = self.apply(input);
+        out.reverse();
+        let mut out = self.apply(&out);
+        out.reverse();
+        out
+    }
+}
+
+/// Centered moving RMS over `window` samples.
+pub fn moving_rms(signal: &[f32], window: usize) -> Vec<f32> {
+    let n = signal.len();
+    let half = window.max(1) / 2;
+    let mut prefix = Vec::with_capacity(n + 1);
+    prefix.push(0.0_f64);
+    for x in signal {
+        prefix.push(prefix.last().unwrap() + (*x as f64) * (*x as f64));
+    }
+    (0..n)
+        .map(|i| {
+            let lo = i.saturating_sub(half);
+            let hi = (i + half + 1).min(n);
+            ((prefix[hi] - prefix[lo]) / (hi - lo) as f64).sqrt() as f32
+        })
+        .collect()
+}
+
+pub fn median(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut v = values.to_vec();
+    v.sort_by(|a, b| a.total_cmp(b));
+    let mid = v.len() / 2;
+    if v.len().is_multiple_of(2) {
+        (v[mid - 1] + v[mid]) / 2.0
+    } else {
+        v[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq_hz: f32, amplitude: f32, sample_rate_hz: f32, secs: f32) -> Vec<f32> {
+        let n = (sample_rate_hz * secs) as usize;
+        (0..n)
+            .map(|i| amplitude * (2.0 * PI * freq_hz * i as f32 / sample_rate_hz).sin())
+            .collect()
+    }
+
+    #[test]
+    fn fft_of_impulse_is_flat() {
+        let mut re = vec![0.0; 8];
+        let mut im = vec![0.0; 8];
+        re[0] = 1.0;
+        fft(&mut re, &mut im);
+        assert!(re.iter().all(|v| (v - 1.0).abs() < 1e-6));
+        assert!(im.iter().all(|v| v.abs() < 1e-6));
+    }
+
+    #[test]
+    fn welch_band_power_of_a_sine() {
+        // A sine of amplitude A carries A²/2 of power, all near its frequency.
+        let fs = 256.0;
+        let signal = sine(10.0, 20.0, fs, 30.0);
+        let psd = welch_psd(&signal, fs, 1024);
+        assert_eq!(psd.freq_resolution_hz, 0.25);
+        assert!(psd.flops > 0);
+
+        let total = psd.band_power(0.0, fs / 2.0);
+        assert!((total - 200.0).abs() / 200.0 < 0.05, "total {}", total);
+        let alpha = psd.band_power(8.0, 12.0);
+        assert!(alpha / total > 0.99, "alpha share {}", alpha / total);
+        assert!(psd.band_power(0.5, 4.0) / total < 1e-3);
+    }
+
+    #[test]
+    fn bandpass_keeps_passband_and_rejects_stopband() {
+        let fs = 200.0;
+        let filter = Biquad::bandpass(fs, 11.0, 16.0);
+        let rms = |x: &[f32]| (x.iter().map(|v| v * v).sum::<f32>() / x.len() as f32).sqrt();
+        let pass = filter.filtfilt(&sine(13.0, 1.0, fs, 10.0));
+        let stop = filter.filtfilt(&sine(2.0, 1.0, fs, 10.0));
+        assert!(rms(&pass[200..1800]) > 0.6);
+        assert!(rms(&stop[200..1800]) < 0.05);
+    }
+
+    #[test]
+    fn moving_rms_and_median() {
+        assert_eq!(moving_rms(&[3.0, 3.0, 3.0, 3.0], 3), vec![3.0; 4]);
+        assert_eq!(median(&[5.0, 1.0, 3.0]), 3.0);
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+        assert_eq!(median(&[]), 0.0);
+        assert_eq!(hann(3), vec![0.0, 1.0, 0.0]);
+    }
+}
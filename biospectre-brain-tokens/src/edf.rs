@@ -0,0 +1,292 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum EdfError {
+    Io(std::io::Error),
+    Truncated { needed: usize, got: usize },
+    BadField { field: &'static str, value: String },
+    UnknownSignal(String),
+}
+
+impl fmt::Display for EdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EdfError::Io(e) => write!(f, "EDF read failed: {}", e),
+            EdfError::Truncated { needed, got } => {
+                write!(f, "EDF truncated: needed {} bytes, got {}", needed, got)
+            }
+            EdfError::BadField { field, value } => write!(f, "EDF header field {} invalid: {:?}", field, value),
+            EdfError::UnknownSignal(label) => write!(f, "EDF has no signal labelled {:?}", label),
+        }
+    }
+}
+
+impl std::error::Error for EdfError {}
+
+impl From<std::io::Error> for EdfError {
+    fn from(e: std::io::Error) -> Self {
+        EdfError::Io(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EdfSignal {
+    pub label: String,
+    pub physical_dimension: String,
+    pub physical_min: f64,
+    pub physical_max: f64,
+    pub digital_min: i32,
+    pub digital_max: i32,
+    pub samples_per_record: usize,
+}
+
+impl EdfSignal {
+    fn gain(&self) -> f64 {
+        let span = (self.digital_max - self.digital_min) as f64;
+        if span == 0.0 {
+            1.0
+        } else {
+            (self.physical_max - self.physical_min) / span
+        }
+    }
+
+    /// Factor converting this signal's physical unit to microvolts.
+    fn to_microvolts(&self) -> f64 {
+        match self.physical_dimension.trim() {
+            "V" => 1e6,
+            "mV" => 1e3,
+            "nV" => 1e-3,
+            _ => 1.0,
+        }
+    }
+}
+
+/// An EDF/EDF+ recording held in memory. Only the header fields needed for
+/// scaling samples are kept; EDF+ annotation channels are readable as raw
+/// signals but not interpreted.
+#[derive(Debug, Clone)]
+pub struct EdfFile {
+    pub start_date: String,
+    pub start_time: String,
+    pub record_duration_sec: f64,
+    pub signals: Vec<EdfSignal>,
+    records: usize,
+    header_bytes: usize,
+    data: Vec<u8>,
+}
+
+fn ascii_field(bytes: &[u8], at: &mut usize, len: usize) -> Result<String, EdfError> {
+    let end = *at + len;
+    if end > bytes.len() {
+        return Err(EdfError::Truncated { needed: end, got: bytes.len() });
+    }
+    let s = String::from_utf8_lossy(&bytes[*at..end]).trim().to_string();
+    *at = end;
+    Ok(s)
+}
+
+fn parse_field<T: std::str::FromStr>(value: String, field: &'static str) -> Result<T, EdfError> {
+    value.parse().map_err(|_| EdfError::BadField { field, value })
+}
+
+impl EdfFile {
+    pub fn read(path: &Path) -> Result<Self, EdfError> {
+        Self::parse(fs::read(path)?)
+    }
+
+    pub fn parse(data: Vec<u8>) -> Result<Self, EdfError> {
+        let mut at = 0;
+        let _version = ascii_field(&data, &mut at, 8)?;
+        let _patient = ascii_field(&data, &mut at, 80)?;
+        let _recording = ascii_field(&data, &mut at, 80)?;
+        let start_date = ascii_field(&data, &mut at, 8)?;
+        let start_time = ascii_field(&data, &mut at, 8)?;
+        let header_bytes: usize = parse_field(ascii_field(&data, &mut at, 8)?, "header bytes")?;
+        let _reserved = ascii_field(&data, &mut at, 44)?;
+        let declared_records: i64 = parse_field(ascii_field(&data, &mut at, 8)?, "number of records")?;
+        let record_duration_sec: f64 = parse_field(ascii_field(&data, &mut at, 8)?, "record duration")?;
+        let ns: usize = parse_field(ascii_field(&data, &mut at, 4)?, "number of signals")?;
+
+        let mut columns = |len: usize| -> Result<Vec<String>, EdfError> {
+            (0..ns).map(|_| ascii_field(&data, &mut at, len)).collect()
+        };
+        let labels = columns(16)?;
+        let _transducers = columns(80)?;
+        let dims = columns(8)?;
+        let pmins = columns(8)?;
+        let pmaxs = columns(8)?;
+        let dmins = columns(8)?;
+        let dmaxs = columns(8)?;
+        let _prefilters = columns(80)?;
+        let counts = columns(8)?;
+        let _reserved = columns(32)?;
+
+        let mut signals = Vec::with_capacity(ns);
+        for i in 0..ns {
+            signals.push(EdfSignal {
+                label: labels[i].clone(),
+                physical_dimension: dims[i].clone(),
+                physical_min: parse_field(pmins[i].clone(), "physical minimum")?,
+                physical_max: parse_field(pmaxs[i].clone(), "physical maximum")?,
+                digital_min: parse_field(dmins[i].clone(), "digital minimum")?,
+                digital_max: parse_field(dmaxs[i].clone(), "digital maximum")?,
+                samples_per_record: parse_field(counts[i].clone(), "samples per record")?,
+            });
+        }
+        if ns.checked_add(1).and_then(|n| n.checked_mul(256)) != Some(header_bytes) {
+            return Err(EdfError::BadField { field: "header bytes", value: header_bytes.to_string() });
+        }
+
+        // Header counts are untrusted: every size is computed with checked
+        // arithmetic so an absurd header is an error, not a panic.
+        let record_bytes = signals
+            .iter()
+            .try_fold(0usize, |acc, s| s.samples_per_record.checked_mul(2).and_then(|b| acc.checked_add(b)))
+            .ok_or_else(|| EdfError::BadField { field: "samples per record", value: counts.join(",") })?;
+        let available = (data.len() - header_bytes).checked_div(record_bytes).unwrap_or(0);
+        // -1 means "unknown" (recording still in progress): use what is there.
+        let records = if declared_records < 0 { available } else { declared_records as usize };
+        let needed = records
+            .checked_mul(record_bytes)
+            .and_then(|b| b.checked_add(header_bytes))
+            .ok_or_else(|| EdfError::BadField { field: "number of records", value: declared_records.to_string() })?;
+        if needed > data.len() {
+            return Err(EdfError::Truncated { needed, got: data.len() });
+        }
+
+        Ok(Self { start_date, start_time, record_duration_sec, signals, records, header_bytes, data })
+    }
+
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    pub fn signal_index(&self, label: &str) -> Result<usize, EdfError> {
+        self.signals
+            .iter()
+            .position(|s| s.label.eq_ignore_ascii_case(label))
+            .ok_or_else(|| EdfError::UnknownSignal(label.to_string()))
+    }
+
+    pub fn sample_rate_hz(&self, index: usize) -> f32 {
+        (self.signals[index].samples_per_record as f64 / self.record_duration_sec) as f32
+    }
+
+    /// Every sample of one signal, scaled to microvolts.
+    pub fn samples_uv(&self, index: usize) -> Vec<f32> {
+        let record_bytes: usize = self.signals.iter().map(|s| s.samples_per_record * 2).sum();
+        let offset: usize = self.signals[..index].iter().map(|s| s.samples_per_record * 2).sum();
+        let sig = &self.signals[index];
+        let (gain, uv) = (sig.gain(), sig.to_microvolts());
+
+        let mut out = Vec::with_capacity(self.records * sig.samples_per_record);
+        for r in 0..self.records {
+            let start = self.header_bytes + r * record_bytes + offset;
+            for pair in self.data[start..start + sig.samples_per_record * 2].chunks_exact(2) {
+                let digital = i16::from_le_bytes([pair[0], pair[1]]) as f64;
+                let physical = (digital - sig.digital_min as f64) * gain + sig.physical_min;
+                out.push((physical * uv) as f32);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One EDF signal header: label, dimension, physical and digital range,
+    /// samples per record.
+    type SignalSpec<'a> = (&'a str, &'a str, f64, f64, i32, i32, usize);
+
+    fn field(out: &mut Vec<u8>, value: &str, len: usize) {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize(len, b' ');
+        out.extend_from_slice(&bytes[..len]);
+    }
+
+    /// A minimal EDF image; `records` is written verbatim into the header.
+    fn edf_bytes(records: &str, duration_sec: &str, signals: &[SignalSpec<'_>], samples: &[i16]) -> Vec<u8> {
+        let mut out = Vec::new();
+        field(&mut out, "0", 8);
+        field(&mut out, "X X X X", 80);
+        field(&mut out, "Startdate X X X X", 80);
+        field(&mut out, "01.01.26", 8);
+        field(&mut out, "22.00.00", 8);
+        field(&mut out, &(256 * (signals.len() + 1)).to_string(), 8);
+        field(&mut out, "", 44);
+        field(&mut out, records, 8);
+        field(&mut out, duration_sec, 8);
+        field(&mut out, &signals.len().to_string(), 4);
+        let mut column = |len: usize, f: &dyn Fn(&SignalSpec<'_>) -> String| {
+            for s in signals {
+                field(&mut out, &f(s), len);
+            }
+        };
+        column(16, &|s| s.0.to_string());
+        column(80, &|_| String::new());
+        column(8, &|s| s.1.to_string());
+        column(8, &|s| s.2.to_string());
+        column(8, &|s| s.3.to_string());
+        column(8, &|s| s.4.to_string());
+        column(8, &|s| s.5.to_string());
+        column(80, &|_| String::new());
+        column(8, &|s| s.6.to_string());
+        column(32, &|_| String::new());
+        for x in samples {
+            out.extend_from_slice(&x.to_le_bytes());
+        }
+        out
+    }
+
+    const EEG: SignalSpec<'static> = ("EEG C4-M1", "uV", -100.0, 100.0, -1000, 1000, 4);
+    const EOG: SignalSpec<'static> = ("EOG", "mV", -1.0, 1.0, -1000, 1000, 2);
+
+    #[test]
+    fn parses_and_scales_interleaved_records() {
+        // Two records of [4 EEG samples, 2 EOG samples].
+        let samples = [0, 500, -1000, 1000, 10, -10, 250, 0, 0, 0, 1000, 1000];
+        let edf = EdfFile::parse(edf_bytes("2", "1", &[EEG, EOG], &samples)).unwrap();
+        assert_eq!(edf.records(), 2);
+        assert_eq!(edf.start_time, "22.00.00");
+        assert_eq!(edf.signal_index("eeg c4-m1").unwrap(), 0);
+        assert!(matches!(edf.signal_index("EMG"), Err(EdfError::UnknownSignal(_))));
+        assert_eq!(edf.sample_rate_hz(0), 4.0);
+
+        let eeg = edf.samples_uv(0);
+        assert_eq!(eeg, vec![0.0, 50.0, -100.0, 100.0, 25.0, 0.0, 0.0, 0.0]);
+        // mV scaled to µV.
+        let eog = edf.samples_uv(1);
+        assert_eq!(eog, vec![10.0, -10.0, 1000.0, 1000.0]);
+    }
+
+    #[test]
+    fn unknown_record_count_uses_available_data() {
+        let edf = EdfFile::parse(edf_bytes("-1", "1", &[EEG], &[1, 2, 3, 4, 5, 6, 7, 8, 9])).unwrap();
+        assert_eq!(edf.records(), 2);
+    }
+
+    #[test]
+    fn truncated_files_are_rejected() {
+        let full = edf_bytes("2", "1", &[EEG], &[0; 8]);
+        let err = EdfFile::parse(full[..full.len() - 1].to_vec()).unwrap_err();
+        assert!(matches!(err, EdfError::Truncated { got, .. } if got == full.len() - 1));
+        assert!(matches!(EdfFile::parse(full[..300].to_vec()), Err(EdfError::Truncated { .. })));
+        assert!(matches!(EdfFile::parse(Vec::new()), Err(EdfError::Truncated { .. })));
+    }
+
+    #[test]
+    fn overflowing_headers_are_errors() {
+        let huge: SignalSpec<'static> = ("EEG", "uV", -1.0, 1.0, -1, 1, 99_999_999);
+        let signals = vec![huge; 1000];
+        let err = EdfFile::parse(edf_bytes("99999999", "1", &signals, &[])).unwrap_err();
+        assert!(matches!(err, EdfError::BadField { field: "number of records", .. }), "{}", err);
+
+        let mut bad = edf_bytes("1", "1", &[EEG], &[0; 4]);
+        bad[184..192].copy_from_slice(b"999     ");
+        assert!(matches!(EdfFile::parse(bad), Err(EdfError::BadField { field: "header bytes", .. })));
+    }
+}
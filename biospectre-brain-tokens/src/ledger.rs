@@ -0,0 +1,537 @@
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use crate::tokens::BiophysicalTokenBundle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Blood,
+    Brain,
+    Protein,
+    Dracula,
+    Eco,
+}
+
+/// Something that changed the bundle. Balances are never stored, only
+/// derived by folding these over the genesis bundle.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenEvent {
+    /// Resets the brain soft-cap allowance.
+    NightStarted,
+    /// Brain tokens minted from one epoch's factors (after clamping).
+    EpochMint { sn3: f32, s_unknown: f32, eco_flops: u64, amount: f32 },
+    /// Mint that would have exceeded `nightly_soft_cap`; `dropped` is the
+    /// part not credited.
+    SoftCapClamp { dropped: f32 },
+    /// Top-up of any token (e.g. blood or protein recovery).
+    Mint { token: TokenKind, amount: f32 },
+    Spend { token: TokenKind, amount: f32, workload_id: String },
+    /// A Dracula-wave burst began; its quanta are a separate `Spend`.
+    CooldownStarted { cooldown_sec: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LedgerEntry {
+    pub seq: u64,
+    pub at: SystemTime,
+    pub event: TokenEvent,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LedgerError {
+    InsufficientBalance { token: TokenKind, balance: f32, requested: f32 },
+    CooldownActive { ready_at: SystemTime },
+    NegativeAmount(f32),
+    SoftCapExceeded { minted: f32, cap: f32 },
+    DraculaSpendOutsideBurst,
+    OutOfOrder { seq: u64 },
+    /// An `EpochMint` whose amount is not what the mint policy gives for
+    /// its factors and the soft-cap room left at that point.
+    MintMismatch { seq: u64, recorded: f32, expected: f32 },
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerError::InsufficientBalance { token, balance, requested } => {
+                write!(f, "{:?} balance {} cannot cover {}", token, balance, requested)
+            }
+            LedgerError::CooldownActive { ready_at } => write!(f, "Dracula-wave cooldown active until {:?}", ready_at),
+            LedgerError::NegativeAmount(a) => write!(f, "amount must be non-negative, got {}", a),
+            LedgerError::SoftCapExceeded { minted, cap } => {
+                write!(f, "{} brain tokens minted tonight exceeds soft cap {}", minted, cap)
+            }
+            LedgerError::DraculaSpendOutsideBurst => write!(f, "Dracula quanta spent without starting a burst"),
+            LedgerError::OutOfOrder { seq } => write!(f, "ledger entry {} is out of sequence or time order", seq),
+            LedgerError::MintMismatch { seq, recorded, expected } => {
+                write!(f, "ledger entry {} mints {} brain tokens, policy gives {}", seq, recorded, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {}
+
+/// How epoch factors turn into brain tokens.
+#[derive(Debug, Clone)]
+pub struct MintPolicy {
+    pub brain_per_sn3: f32,
+    /// Brain tokens deducted per GFLOP spent computing the epoch.
+    pub brain_penalty_per_gflop: f32,
+}
+
+impl Default for MintPolicy {
+    fn default() -> Self {
+        Self { brain_per_sn3: 1.0, brain_penalty_per_gflop: 0.1 }
+    }
+}
+
+impl MintPolicy {
+    /// Unclamped mint: SN3 scaled by certainty (1 - s_unknown), less the
+    /// compute penalty, never negative.
+    pub fn epoch_amount(&self, sn3: f32, s_unknown: f32, eco_flops: u64) -> f32 {
+        let certainty = (1.0 - s_unknown).clamp(0.0, 1.0);
+        let penalty = eco_flops as f32 / 1e9 * self.brain_penalty_per_gflop;
+        (sn3.max(0.0) * self.brain_per_sn3 * certainty - penalty).max(0.0)
+    }
+}
+
+/// The bundle as derived from events, plus the fold-only bookkeeping.
+#[derive(Debug, Clone)]
+pub struct LedgerState {
+    pub tokens: BiophysicalTokenBundle,
+    pub minted_tonight: f32,
+    /// Set by `CooldownStarted`; the next event must be its Dracula spend.
+    burst_open: bool,
+}
+
+impl LedgerState {
+    pub fn new(genesis: BiophysicalTokenBundle) -> Self {
+        Self { tokens: genesis, minted_tonight: 0.0, burst_open: false }
+    }
+
+    pub fn balance(&self, token: TokenKind) -> f32 {
+        let t = &self.tokens;
+        match token {
+            TokenKind::Blood => t.blood.balance_ml_equiv,
+            TokenKind::Brain => t.brain.balance,
+            TokenKind::Protein => t.protein.balance_g_equiv,
+            TokenKind::Dracula => t.dracula.balance_quanta,
+            TokenKind::Eco => t.eco.balance_nj_equiv,
+        }
+    }
+
+    fn balance_mut(&mut self, token: TokenKind) -> &mut f32 {
+        let t = &mut self.tokens;
+        match token {
+            TokenKind::Blood => &mut t.blood.balance_ml_equiv,
+            TokenKind::Brain => &mut t.brain.balance,
+            TokenKind::Protein => &mut t.protein.balance_g_equiv,
+            TokenKind::Dracula => &mut t.dracula.balance_quanta,
+            TokenKind::Eco => &mut t.eco.balance_nj_equiv,
+        }
+    }
+
+    pub fn cooldown_ready_at(&self) -> Option<SystemTime> {
+        self.tokens
+            .dracula
+            .last_burst_utc
+            .map(|t| t + Duration::from_secs(self.tokens.dracula.cooldown_sec))
+    }
+
+    /// Fold one entry in, rejecting any event that would break an
+    /// invariant, so a tampered or hand-built log cannot replay.
+    pub fn apply(&mut self, entry: &LedgerEntry) -> Result<(), LedgerError> {
+        let burst_open = std::mem::take(&mut self.burst_open);
+        match &entry.event {
+            TokenEvent::NightStarted => self.minted_tonight = 0.0,
+            TokenEvent::EpochMint { sn3, s_unknown, eco_flops, amount } => {
+                non_negative(*amount)?;
+                let minted = self.minted_tonight + amount;
+                if minted > self.tokens.brain.nightly_soft_cap + CAP_TOLERANCE {
+                    return Err(LedgerError::SoftCapExceeded { minted, cap: self.tokens.brain.nightly_soft_cap });
+                }
+                self.minted_tonight = minted;
+                self.tokens.brain.balance += amount;
+                self.tokens.brain.epoch_mint_factors = (*sn3, *s_unknown, *eco_flops);
+            }
+            TokenEvent::SoftCapClamp { dropped } => non_negative(*dropped)?,
+            TokenEvent::Mint { token, amount } => {
+                non_negative(*amount)?;
+                *self.balance_mut(*token) += amount;
+            }
+            TokenEvent::Spend { token, amount, .. } => {
+                non_negative(*amount)?;
+                if *token == TokenKind::Dracula && !burst_open {
+                    return Err(LedgerError::DraculaSpendOutsideBurst);
+                }
+                let balance = self.balance(*token);
+                if balance < *amount {
+                    return Err(LedgerError::InsufficientBalance { token: *token, balance, requested: *amount });
+                }
+                *self.balance_mut(*token) -= amount;
+            }
+            TokenEvent::CooldownStarted { cooldown_sec } => {
+                if let Some(ready_at) = self.cooldown_ready_at() {
+                    if entry.at < ready_at {
+                        return Err(LedgerError::CooldownActive { ready_at });
+                    }
+                }
+                self.tokens.dracula.last_burst_utc = Some(entry.at);
+                self.tokens.dracula.cooldown_sec = *cooldown_sec;
+                self.burst_open = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Slack for float rounding when re-checking the soft cap and mint
+/// amounts on replay.
+const CAP_TOLERANCE: f32 = 1e-4;
+
+fn non_negative(amount: f32) -> Result<(), LedgerError> {
+    if amount >= 0.0 && amount.is_finite() {
+        Ok(())
+    } else {
+        Err(LedgerError::NegativeAmount(amount))
+    }
+}
+
+/// Append-only, event-sourced history of a `BiophysicalTokenBundle`.
+/// Commands validate against the current fold and append events; nothing
+/// mutates balances directly.
+#[derive(Debug, Clone)]
+pub struct TokenLedger {
+    genesis: BiophysicalTokenBundle,
+    entries: Vec<LedgerEntry>,
+    state: LedgerState,
+    pub policy: MintPolicy,
+}
+
+impl TokenLedger {
+    pub fn new(genesis: BiophysicalTokenBundle) -> Self {
+        Self {
+            state: LedgerState::new(genesis.clone()),
+            genesis,
+            entries: Vec::new(),
+            policy: MintPolicy::default(),
+        }
+    }
+
+    /// Rebuild a ledger from its genesis and history under the default
+    /// [`MintPolicy`], validating every entry in order.
+    pub fn replay(genesis: BiophysicalTokenBundle, entries: Vec<LedgerEntry>) -> Result<Self, LedgerError> {
+        Self::replay_with_policy(genesis, MintPolicy::default(), entries)
+    }
+
+    /// Like [`replay`](Self::replay), but every `EpochMint` amount is
+    /// re-derived from `policy` rather than trusted from the log.
+    pub fn replay_with_policy(
+        genesis: BiophysicalTokenBundle,
+        policy: MintPolicy,
+        entries: Vec<LedgerEntry>,
+    ) -> Result<Self, LedgerError> {
+        let mut ledger = Self::new(genesis);
+        ledger.policy = policy;
+        for entry in entries {
+            ledger.check_order(&entry)?;
+            if let TokenEvent::EpochMint { sn3, s_unknown, eco_flops, amount } = entry.event {
+                let (_, expected) = ledger.epoch_mint(sn3, s_unknown, eco_flops);
+                if (amount - expected).abs() > CAP_TOLERANCE {
+                    return Err(LedgerError::MintMismatch { seq: entry.seq, recorded: amount, expected });
+                }
+            }
+            ledger.state.apply(&entry)?;
+            ledger.entries.push(entry);
+        }
+        Ok(ledger)
+    }
+
+    pub fn genesis(&self) -> &BiophysicalTokenBundle {
+        &self.genesis
+    }
+
+    pub fn entries(&self) -> &[LedgerEntry] {
+        &self.entries
+    }
+
+    pub fn state(&self) -> &LedgerState {
+        &self.state
+    }
+
+    pub fn balances(&self) -> &BiophysicalTokenBundle {
+        &self.state.tokens
+    }
+
+    /// What the policy wants to mint for these factors, and what is left
+    /// of it after the nightly soft cap.
+    fn epoch_mint(&self, sn3: f32, s_unknown: f32, eco_flops: u64) -> (f32, f32) {
+        let wanted = self.policy.epoch_amount(sn3, s_unknown, eco_flops);
+        let room = (self.state.tokens.brain.nightly_soft_cap - self.state.minted_tonight).max(0.0);
+        (wanted, wanted.min(room))
+    }
+
+    fn check_order(&self, entry: &LedgerEntry) -> Result<(), LedgerError> {
+        let expected = self.entries.len() as u64 + 1;
+        let in_time = self.entries.last().is_none_or(|last| entry.at >= last.at);
+        if entry.seq != expected || !in_time {
+            return Err(LedgerError::OutOfOrder { seq: entry.seq });
+        }
+        Ok(())
+    }
+
+    /// Apply all events atomically: either every one lands or none do.
+    fn append(&mut self, at: SystemTime, events: Vec<TokenEvent>) -> Result<(), LedgerError> {
+        let mut next = self.state.clone();
+        let mut staged = Vec::with_capacity(events.len());
+        for (i, event) in events.into_iter().enumerate() {
+            let entry = LedgerEntry { seq: self.entries.len() as u64 + 1 + i as u64, at, event };
+            if i == 0 {
+                self.check_order(&entry)?;
+            }
+            next.apply(&entry)?;
+            staged.push(entry);
+        }
+        self.state = next;
+        self.entries.extend(staged);
+        Ok(())
+    }
+
+    pub fn start_night(&mut self, at: SystemTime) -> Result<(), LedgerError> {
+        self.append(at, vec![TokenEvent::NightStarted])
+    }
+
+    /// Mint brain tokens for one epoch, clamped to what is left of the
+    /// nightly soft cap. Returns the amount credited.
+    pub fn mint_epoch(&mut self, at: SystemTime, sn3: f32, s_unknown: f32, eco_flops: u64) -> Result<f32, LedgerError> {
+        let (wanted, amount) = self.epoch_mint(sn3, s_unknown, eco_flops);
+        let mut events = vec![TokenEvent::EpochMint { sn3, s_unknown, eco_flops, amount }];
+        if wanted > amount {
+            events.push(TokenEvent::SoftCapClamp { dropped: wanted - amount });
+        }
+        self.append(at, events)?;
+        Ok(amount)
+    }
+
+    pub fn mint(&mut self, at: SystemTime, token: TokenKind, amount: f32) -> Result<(), LedgerError> {
+        self.append(at, vec![TokenEvent::Mint { token, amount }])
+    }
+
+    /// Spend a token. Dracula quanta are only ever spent as a
+    /// [`burst`](Self::burst), so the cooldown always applies.
+    pub fn spend(&mut self, at: SystemTime, token: TokenKind, amount: f32, workload_id: &str) -> Result<(), LedgerError> {
+        if token == TokenKind::Dracula {
+            return self.burst(at, amount, workload_id);
+        }
+        self.append(at, vec![TokenEvent::Spend { token, amount, workload_id: workload_id.to_string() }])
+    }
+
+    /// Start a Dracula-wave burst: the cooldown must have elapsed, then the
+    /// quanta are spent and a new cooldown starts.
+    pub fn burst(&mut self, at: SystemTime, quanta: f32, workload_id: &str) -> Result<(), LedgerError> {
+        let cooldown_sec = self.state.tokens.dracula.cooldown_sec;
+        self.append(
+            at,
+            vec![
+                TokenEvent::CooldownStarted { cooldown_sec },
+                TokenEvent::Spend { token: TokenKind::Dracula, amount: quanta, workload_id: workload_id.to_string() },
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokens::{BloodTokenState, BrainTokenState, DraculaWaveState, EcoTokenState, ProteinTokenState};
+
+    const KINDS: [TokenKind; 5] =
+        [TokenKind::Blood, TokenKind::Brain, TokenKind::Protein, TokenKind::Dracula, TokenKind::Eco];
+
+    /// Small deterministic generator so property runs are reproducible.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        fn unit(&mut self) -> f32 {
+            (self.next() >> 40) as f32 / (1u64 << 24) as f32
+        }
+    }
+
+    fn genesis() -> BiophysicalTokenBundle {
+        BiophysicalTokenBundle {
+            blood: BloodTokenState { balance_ml_equiv: 20.0, max_drain_per_night: 5.0 },
+            brain: BrainTokenState { balance: 5.0, epoch_mint_factors: (0.0, 0.0, 0), nightly_soft_cap: 12.0 },
+            protein: ProteinTokenState { balance_g_equiv: 3.0, synaptic_plasticity_weight: 1.0, catabolic_penalty: 0.1 },
+            dracula: DraculaWaveState {
+                balance_quanta: 10.0,
+                dutycycle_max_pct: 20.0,
+                cooldown_sec: 90,
+                last_burst_utc: None,
+            },
+            eco: EcoTokenState { balance_nj_equiv: 1_000.0, target_reduction_vs_baseline_pct: 10.0 },
+        }
+    }
+
+    fn random_history(seed: u64, ops: usize) -> TokenLedger {
+        let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+        let mut ledger = TokenLedger::new(genesis());
+        let mut now = SystemTime::UNIX_EPOCH;
+        for _ in 0..ops {
+            now += Duration::from_secs(rng.below(60));
+            let kind = KINDS[rng.below(5) as usize];
+            // Commands may legitimately fail; failures must leave no trace.
+            let before = ledger.entries().len();
+            let result = match rng.below(6) {
+                0 => ledger.start_night(now),
+                1 | 2 => ledger.mint_epoch(now, rng.unit() * 4.0, rng.unit(), rng.below(3_000_000_000)).map(|_| ()),
+                3 => ledger.mint(now, kind, rng.unit() * 3.0),
+                4 => ledger.spend(now, kind, rng.unit() * 8.0, "w"),
+                _ => ledger.burst(now, rng.unit() * 4.0, "w"),
+            };
+            if result.is_err() {
+                assert_eq!(ledger.entries().len(), before);
+            }
+        }
+        ledger
+    }
+
+    #[test]
+    fn balances_never_go_negative() {
+        for seed in 0..200 {
+            let ledger = random_history(seed, 300);
+            for kind in KINDS {
+                assert!(ledger.state().balance(kind) >= 0.0, "seed {} {:?}", seed, kind);
+            }
+        }
+    }
+
+    #[test]
+    fn soft_cap_is_honored_every_night() {
+        for seed in 0..200 {
+            let ledger = random_history(seed, 300);
+            let cap = ledger.genesis().brain.nightly_soft_cap;
+            let mut tonight = 0.0;
+            for e in ledger.entries() {
+                match &e.event {
+                    TokenEvent::NightStarted => tonight = 0.0,
+                    TokenEvent::EpochMint { amount, .. } => tonight += amount,
+                    _ => {}
+                }
+                assert!(tonight <= cap + 1e-3, "seed {} minted {}", seed, tonight);
+            }
+        }
+    }
+
+    #[test]
+    fn bursts_respect_cooldown() {
+        for seed in 0..200 {
+            let ledger = random_history(seed, 300);
+            let cooldown = Duration::from_secs(ledger.genesis().dracula.cooldown_sec);
+            let bursts: Vec<SystemTime> = ledger
+                .entries()
+                .iter()
+                .filter(|e| matches!(e.event, TokenEvent::CooldownStarted { .. }))
+                .map(|e| e.at)
+                .collect();
+            for pair in bursts.windows(2) {
+                assert!(pair[1].duration_since(pair[0]).unwrap() >= cooldown, "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn replay_reproduces_balances() {
+        for seed in 0..50 {
+            let ledger = random_history(seed, 300);
+            let replayed = TokenLedger::replay(ledger.genesis().clone(), ledger.entries().to_vec()).unwrap();
+            for kind in KINDS {
+                assert_eq!(replayed.state().balance(kind), ledger.state().balance(kind));
+            }
+            assert_eq!(replayed.balances().dracula.last_burst_utc, ledger.balances().dracula.last_burst_utc);
+        }
+    }
+
+    #[test]
+    fn replay_rejects_tampered_history() {
+        let t0 = SystemTime::UNIX_EPOCH;
+        let mut ledger = TokenLedger::new(genesis());
+        ledger.burst(t0, 1.0, "a").unwrap();
+        assert!(matches!(
+            ledger.burst(t0 + Duration::from_secs(30), 1.0, "b"),
+            Err(LedgerError::CooldownActive { .. })
+        ));
+        ledger.mint_epoch(t0 + Duration::from_secs(30), 20.0, 0.0, 0).unwrap();
+        assert_eq!(ledger.state().minted_tonight, 12.0);
+
+        let mut forged = ledger.entries().to_vec();
+        forged.push(LedgerEntry {
+            seq: forged.len() as u64 + 1,
+            at: t0 + Duration::from_secs(40),
+            event: TokenEvent::Spend { token: TokenKind::Protein, amount: 99.0, workload_id: "x".into() },
+        });
+        assert!(matches!(
+            TokenLedger::replay(genesis(), forged.clone()),
+            Err(LedgerError::InsufficientBalance { token: TokenKind::Protein, .. })
+        ));
+
+        // Dracula quanta spent without a burst would dodge the cooldown.
+        forged.pop();
+        forged.push(LedgerEntry {
+            seq: forged.len() as u64 + 1,
+            at: t0 + Duration::from_secs(40),
+            event: TokenEvent::Spend { token: TokenKind::Dracula, amount: 1.0, workload_id: "x".into() },
+        });
+        assert_eq!(TokenLedger::replay(genesis(), forged).unwrap_err(), LedgerError::DraculaSpendOutsideBurst);
+    }
+
+    #[test]
+    fn replay_rederives_epoch_mints() {
+        let t0 = SystemTime::UNIX_EPOCH;
+        let mut ledger = TokenLedger::new(genesis());
+        ledger.mint_epoch(t0, 3.0, 0.5, 0).unwrap();
+        ledger.mint_epoch(t0, 20.0, 0.0, 0).unwrap();
+        let entries = ledger.entries().to_vec();
+        assert!(TokenLedger::replay(genesis(), entries.clone()).is_ok());
+
+        // An inflated mint that still fits under the soft cap.
+        let mut forged = entries.clone();
+        forged[0].event = TokenEvent::EpochMint { sn3: 3.0, s_unknown: 0.5, eco_flops: 0, amount: 3.0 };
+        assert_eq!(
+            TokenLedger::replay(genesis(), forged).unwrap_err(),
+            LedgerError::MintMismatch { seq: 1, recorded: 3.0, expected: 1.5 }
+        );
+
+        // The clamped mint must match the room left, not just stay under the cap.
+        let mut forged = entries.clone();
+        forged[1].event = TokenEvent::EpochMint { sn3: 20.0, s_unknown: 0.0, eco_flops: 0, amount: 1.0 };
+        assert!(matches!(
+            TokenLedger::replay(genesis(), forged),
+            Err(LedgerError::MintMismatch { seq: 2, .. })
+        ));
+
+        // The same history under a different policy does not replay.
+        let stingy = MintPolicy { brain_per_sn3: 0.5, ..MintPolicy::default() };
+        assert!(matches!(
+            TokenLedger::replay_with_policy(genesis(), stingy.clone(), entries),
+            Err(LedgerError::MintMismatch { seq: 1, .. })
+        ));
+
+        let mut ledger = TokenLedger::new(genesis());
+        ledger.policy = stingy.clone();
+        ledger.mint_epoch(t0, 3.0, 0.0, 0).unwrap();
+        let replayed = TokenLedger::replay_with_policy(genesis(), stingy, ledger.entries().to_vec()).unwrap();
+        assert_eq!(replayed.state().balance(TokenKind::Brain), 6.5);
+    }
+}
@@ -1,3 +1,12 @@
+// NeuralRope7D component layout.
+pub const ROPE_THETA_ALPHA: usize = 0;
+pub const ROPE_SN2: usize = 1;
+pub const ROPE_DELTA_DOMINANCE: usize = 2;
+pub const ROPE_S_UNKNOWN: usize = 3;
+pub const ROPE_SN1: usize = 4;
+pub const ROPE_SPINDLE_DENSITY: usize = 5;
+pub const ROPE_SN3: usize = 6;
+
 #[derive(Debug, Clone)]
 pub struct NeuralRope7D {
     pub components: [f32; 7],
@@ -11,7 +20,7 @@ pub struct NeuralRope5D {
 const PROJECTION_5X7: [[f32; 7]; 5] = [
     // Row 0: emphasize SN3 and delta dominance
     [0.0, 0.0, 0.6, 0.2, 0.0, 0.0, 0.6],
-    // Row 1: emphasize S? and spindle density
+    // Row 1: emphasize S_unknown and spindle density
     [0.0, 0.0, 0.1, 0.7, 0.0, 0.6, 0.0],
     // Row 2: theta/alpha and SN1
     [0.6, 0.0, 0.0, 0.0, 0.6, 0.0, 0.0],
@@ -0,0 +1,519 @@
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+use crate::scheduler::{BrainTokenScheduler, EpochMeta, Rejection, WorkloadRequest};
+use crate::sleep_features::EPOCH_SEC;
+use crate::tokens::BiophysicalTokenBundle;
+
+/// A workload queued for the night. Costs in `request` (and the blood and
+/// protein drains here) are totals for the whole run, not per epoch.
+#[derive(Debug, Clone)]
+pub struct PlannedWorkload {
+    pub request: WorkloadRequest,
+    /// Higher runs first.
+    pub priority: u32,
+    pub earliest_epoch: usize,
+    /// Must finish by this epoch (exclusive); `None` means end of night.
+    pub deadline_epoch: Option<usize>,
+    pub duration_epochs: usize,
+    pub blood_ml: f32,
+    pub protein_g: f32,
+}
+
+impl PlannedWorkload {
+    pub fn new(request: WorkloadRequest, priority: u32) -> Self {
+        Self {
+            request,
+            priority,
+            earliest_epoch: 0,
+            deadline_epoch: None,
+            duration_epochs: 1,
+            blood_ml: 0.0,
+            protein_g: 0.0,
+        }
+    }
+
+    pub fn window(mut self, earliest_epoch: usize, deadline_epoch: Option<usize>) -> Self {
+        self.earliest_epoch = earliest_epoch;
+        self.deadline_epoch = deadline_epoch;
+        self
+    }
+
+    pub fn duration(mut self, epochs: usize) -> Self {
+        self.duration_epochs = epochs.max(1);
+        self
+    }
+
+    pub fn drains(mut self, blood_ml: f32, protein_g: f32) -> Self {
+        self.blood_ml = blood_ml;
+        self.protein_g = protein_g;
+        self
+    }
+
+    fn is_burst(&self) -> bool {
+        self.request.required_dracula_quanta > 0.0
+    }
+}
+
+/// Night-wide budgets on top of the per-epoch scheduler gates.
+#[derive(Debug, Clone)]
+pub struct PlannerLimits {
+    pub eco_budget_nj: f32,
+    pub max_blood_drain_ml: f32,
+    pub max_protein_drain_g: f32,
+}
+
+impl PlannerLimits {
+    /// Eco budget is the bundle's allowed reduction headroom; blood is
+    /// capped by the nightly drain limit and the balance; protein by its
+    /// balance.
+    pub fn from_bundle(tokens: &BiophysicalTokenBundle) -> Self {
+        Self {
+            eco_budget_nj: tokens.eco.balance_nj_equiv * tokens.eco.target_reduction_vs_baseline_pct / 100.0,
+            max_blood_drain_ml: tokens.blood.max_drain_per_night.min(tokens.blood.balance_ml_equiv),
+            max_protein_drain_g: tokens.protein.balance_g_equiv,
+        }
+    }
+}
+
+/// Why a planning step refused a workload or a candidate window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PlanRejection {
+    Epoch(Rejection),
+    EpochOccupied,
+    DraculaCooldown,
+    DutyCycleCap,
+    BloodDrainLimit,
+    ProteinDrainLimit,
+    EcoBudget,
+    /// The earliest/deadline window is shorter than the workload.
+    NoWindow,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledWorkload {
+    pub id: String,
+    pub start_epoch: usize,
+    /// Exclusive.
+    pub end_epoch: usize,
+    pub start_offset_sec: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnscheduledWorkload {
+    pub id: String,
+    /// The most frequent rejection across candidate windows.
+    pub reason: PlanRejection,
+    /// Every rejection seen, with how many windows it ruled out.
+    pub rejections: Vec<(PlanRejection, usize)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NightPlan {
+    /// In start order.
+    pub scheduled: Vec<ScheduledWorkload>,
+    /// In planning (priority) order.
+    pub unscheduled: Vec<UnscheduledWorkload>,
+    pub brain_tokens_spent: f32,
+    pub dracula_quanta_spent: f32,
+    pub blood_drain_ml: f32,
+    pub protein_drain_g: f32,
+    pub eco_energy_nj: f32,
+    /// Share of night epochs occupied by Dracula-wave bursts, percent.
+    pub dracula_duty_pct: f32,
+}
+
+/// Greedy night planner: workloads go in priority order (then deadline,
+/// then id) into the earliest window where every epoch passes
+/// [`BrainTokenScheduler::check`] and the night-wide limits still hold.
+/// Token balances are drawn down as workloads are placed, so later
+/// workloads see what is left.
+pub struct NightPlanner {
+    pub limits: PlannerLimits,
+}
+
+impl NightPlanner {
+    pub fn new(limits: PlannerLimits) -> Self {
+        Self { limits }
+    }
+
+    pub fn plan(
+        &self,
+        tokens: &BiophysicalTokenBundle,
+        epochs: &[EpochMeta],
+        workloads: &[PlannedWorkload],
+        night_start: SystemTime,
+    ) -> NightPlan {
+        let mut order: Vec<&PlannedWorkload> = workloads.iter().collect();
+        order.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(a.deadline_epoch.unwrap_or(usize::MAX).cmp(&b.deadline_epoch.unwrap_or(usize::MAX)))
+                .then(a.request.id.cmp(&b.request.id))
+        });
+
+        let mut state = PlanState {
+            tokens: tokens.clone(),
+            occupied: vec![false; epochs.len()],
+            bursts: Vec::new(),
+            plan: NightPlan::default(),
+        };
+        let duty_cap_epochs = (tokens.dracula.dutycycle_max_pct / 100.0 * epochs.len() as f32).floor() as usize;
+        let cooldown = Duration::from_secs(tokens.dracula.cooldown_sec);
+        let prior_burst_ready = tokens.dracula.last_burst_utc.map(|t| t + cooldown);
+
+        for w in order {
+            if let Some(reason) = self.budget_rejection(&state.plan, w) {
+                state.reject(w, reason, vec![(reason, 1)]);
+                continue;
+            }
+            if w.is_burst() && state.burst_epochs() + w.duration_epochs > duty_cap_epochs {
+                state.reject(w, PlanRejection::DutyCycleCap, vec![(PlanRejection::DutyCycleCap, 1)]);
+                continue;
+            }
+
+            let last_start = w.deadline_epoch.unwrap_or(epochs.len()).min(epochs.len());
+            let mut seen: HashMap<PlanRejection, usize> = HashMap::new();
+            let mut placed = None;
+            let mut start = w.earliest_epoch;
+            while start + w.duration_epochs <= last_start {
+                match state.window_rejection(w, epochs, start, cooldown, prior_burst_ready, night_start) {
+                    None => {
+                        placed = Some(start);
+                        break;
+                    }
+                    Some(r) => *seen.entry(r).or_default() += 1,
+                }
+                start += 1;
+            }
+
+            match placed {
+                Some(start) => state.place(w, start),
+                None => {
+                    let mut rejections: Vec<(PlanRejection, usize)> = seen.into_iter().collect();
+                    rejections.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+                    let reason = rejections.first().map_or(PlanRejection::NoWindow, |r| r.0);
+                    state.reject(w, reason, rejections);
+                }
+            }
+        }
+
+        let mut plan = state.plan;
+        plan.scheduled.sort_by_key(|s| s.start_epoch);
+        if !epochs.is_empty() {
+            let burst_epochs: usize = state.bursts.iter().map(|(s, e)| e - s).sum();
+            plan.dracula_duty_pct = burst_epochs as f32 / epochs.len() as f32 * 100.0;
+        }
+        plan
+    }
+
+    fn budget_rejection(&self, plan: &NightPlan, w: &PlannedWorkload) -> Option<PlanRejection> {
+        if plan.blood_drain_ml + w.blood_ml > self.limits.max_blood_drain_ml {
+            Some(PlanRejection::BloodDrainLimit)
+        } else if plan.protein_drain_g + w.protein_g > self.limits.max_protein_drain_g {
+            Some(PlanRejection::ProteinDrainLimit)
+        } else if plan.eco_energy_nj + w.request.required_eco_nj > self.limits.eco_budget_nj {
+            Some(PlanRejection::EcoBudget)
+        } else {
+            None
+        }
+    }
+}
+
+struct PlanState {
+    tokens: BiophysicalTokenBundle,
+    occupied: Vec<bool>,
+    /// Burst epoch ranges `[start, end)`.
+    bursts: Vec<(usize, usize)>,
+    plan: NightPlan,
+}
+
+impl PlanState {
+    fn burst_epochs(&self) -> usize {
+        self.bursts.iter().map(|(s, e)| e - s).sum()
+    }
+
+    fn window_rejection(
+        &self,
+        w: &PlannedWorkload,
+        epochs: &[EpochMeta],
+        start: usize,
+        cooldown: Duration,
+        prior_burst_ready: Option<SystemTime>,
+        night_start: SystemTime,
+    ) -> Option<PlanRejection> {
+        let end = start + w.duration_epochs;
+        if self.occupied[start..end].iter().any(|o| *o) {
+            return Some(PlanRejection::EpochOccupied);
+        }
+        if w.is_burst() {
+            let start_at = night_start + Duration::from_secs_f32(start as f32 * EPOCH_SEC);
+            if prior_burst_ready.is_some_and(|ready| start_at < ready) {
+                return Some(PlanRejection::DraculaCooldown);
+            }
+            // Cooldown must separate this burst from every placed burst, on
+            // either side.
+            let gap = (cooldown.as_secs_f32() / EPOCH_SEC).ceil() as usize;
+            if self.bursts.iter().any(|&(s, e)| start < e + gap && s < end + gap) {
+                return Some(PlanRejection::DraculaCooldown);
+            }
+        }
+        epochs[start..end]
+            .iter()
+            .find_map(|epoch| BrainTokenScheduler::check(&self.tokens, epoch, &w.request).err())
+            .map(PlanRejection::Epoch)
+    }
+
+    fn place(&mut self, w: &PlannedWorkload, start: usize) {
+        let end = start + w.duration_epochs;
+        self.occupied[start..end].iter_mut().for_each(|o| *o = true);
+        if w.is_burst() {
+            self.bursts.push((start, end));
+        }
+        self.tokens.brain.balance -= w.request.required_brain_tokens;
+        self.tokens.dracula.balance_quanta -= w.request.required_dracula_quanta;
+
+        let plan = &mut self.plan;
+        plan.brain_tokens_spent += w.request.required_brain_tokens;
+        plan.dracula_quanta_spent += w.request.required_dracula_quanta;
+        plan.blood_drain_ml += w.blood_ml;
+        plan.protein_drain_g += w.protein_g;
+        plan.eco_energy_nj += w.request.required_eco_nj;
+        plan.scheduled.push(ScheduledWorkload {
+            id: w.request.id.clone(),
+            start_epoch: start,
+            end_epoch: end,
+            start_offset_sec: start as f32 * EPOCH_SEC,
+        });
+    }
+
+    fn reject(&mut self, w: &PlannedWorkload, reason: PlanRejection, rejections: Vec<(PlanRejection, usize)>) {
+        self.plan.unscheduled.push(UnscheduledWorkload { id: w.request.id.clone(), reason, rejections });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::StageKind;
+    use crate::tokens::{BloodTokenState, BrainTokenState, DraculaWaveState, EcoTokenState, ProteinTokenState};
+
+    fn bundle() -> BiophysicalTokenBundle {
+        BiophysicalTokenBundle {
+            blood: BloodTokenState { balance_ml_equiv: 20.0, max_drain_per_night: 5.0 },
+            brain: BrainTokenState { balance: 10.0, epoch_mint_factors: (0.0, 0.0, 0), nightly_soft_cap: 12.0 },
+            protein: ProteinTokenState { balance_g_equiv: 3.0, synaptic_plasticity_weight: 1.0, catabolic_penalty: 0.1 },
+            dracula: DraculaWaveState {
+                balance_quanta: 10.0,
+                dutycycle_max_pct: 20.0,
+                cooldown_sec: 60,
+                last_burst_utc: None,
+            },
+            eco: EcoTokenState { balance_nj_equiv: 1_000.0, target_reduction_vs_baseline_pct: 50.0 },
+        }
+    }
+
+    fn epoch(stage: StageKind, sn3: f32) -> EpochMeta {
+        EpochMeta { stage, sn3, s_unknown: 0.0, eco_flops: 0, eco_energy_nj: 1.0 }
+    }
+
+    fn deep_night(n: usize) -> Vec<EpochMeta> {
+        vec![epoch(StageKind::N3, 3.0); n]
+    }
+
+    fn job(id: &str, brain: f32, dracula: f32, eco: f32, n3_only: bool) -> WorkloadRequest {
+        WorkloadRequest {
+            id: id.to_string(),
+            required_brain_tokens: brain,
+            required_dracula_quanta: dracula,
+            required_eco_nj: eco,
+            allow_heavy_only_in_n3: n3_only,
+        }
+    }
+
+    fn plan(tokens: &BiophysicalTokenBundle, epochs: &[EpochMeta], workloads: &[PlannedWorkload]) -> NightPlan {
+        NightPlanner::new(PlannerLimits::from_bundle(tokens)).plan(tokens, epochs, workloads, SystemTime::UNIX_EPOCH)
+    }
+
+    fn start_of(plan: &NightPlan, id: &str) -> usize {
+        plan.scheduled.iter().find(|s| s.id == id).map(|s| s.start_epoch).expect("scheduled")
+    }
+
+    fn reason_of(plan: &NightPlan, id: &str) -> PlanRejection {
+        plan.unscheduled.iter().find(|u| u.id == id).map(|u| u.reason).expect("unscheduled")
+    }
+
+    #[test]
+    fn heavy_work_waits_for_deep_n3() {
+        let tokens = bundle();
+        let epochs = vec![
+            epoch(StageKind::N2, 3.0),
+            epoch(StageKind::N3, 1.0),
+            epoch(StageKind::N3, 3.0),
+            epoch(StageKind::N3, 3.0),
+        ];
+        let workloads = [
+            PlannedWorkload::new(job("heavy", 1.0, 0.0, 10.0, true), 2).duration(2),
+            PlannedWorkload::new(job("light", 1.0, 0.0, 10.0, false), 1),
+        ];
+        let plan = plan(&tokens, &epochs, &workloads);
+        assert_eq!(start_of(&plan, "heavy"), 2);
+        assert_eq!(start_of(&plan, "light"), 0);
+
+        let late = [PlannedWorkload::new(job("heavy", 1.0, 0.0, 10.0, true), 1).window(0, Some(2))];
+        let plan = self::plan(&tokens, &epochs, &late);
+        assert_eq!(reason_of(&plan, "heavy"), PlanRejection::Epoch(Rejection::NotDeepN3));
+    }
+
+    #[test]
+    fn bursts_are_separated_by_the_cooldown() {
+        let tokens = bundle();
+        let epochs = deep_night(20);
+        let workloads = [
+            PlannedWorkload::new(job("a", 1.0, 1.0, 10.0, false), 2),
+            PlannedWorkload::new(job("b", 1.0, 1.0, 10.0, false), 1),
+        ];
+        let plan = plan(&tokens, &epochs, &workloads);
+        // 60 s cooldown is two 30 s epochs after the first burst ends.
+        assert_eq!(start_of(&plan, "a"), 0);
+        assert_eq!(start_of(&plan, "b"), 3);
+    }
+
+    #[test]
+    fn prior_burst_cooldown_carries_into_the_night() {
+        let mut tokens = bundle();
+        tokens.dracula.last_burst_utc = Some(SystemTime::UNIX_EPOCH);
+        let epochs = deep_night(20);
+        let workloads = [
+            PlannedWorkload::new(job("burst", 1.0, 1.0, 10.0, false), 1),
+            PlannedWorkload::new(job("plain", 1.0, 0.0, 10.0, false), 0),
+        ];
+        let plan = plan(&tokens, &epochs, &workloads);
+        assert_eq!(start_of(&plan, "burst"), 2);
+        assert_eq!(start_of(&plan, "plain"), 0);
+
+        let boxed = [PlannedWorkload::new(job("burst", 1.0, 1.0, 10.0, false), 1).window(0, Some(2))];
+        let plan = self::plan(&tokens, &epochs, &boxed);
+        assert_eq!(reason_of(&plan, "burst"), PlanRejection::DraculaCooldown);
+    }
+
+    #[test]
+    fn duty_cycle_caps_burst_epochs() {
+        let mut tokens = bundle();
+        tokens.dracula.cooldown_sec = 0;
+        // 20% of 10 epochs allows two burst epochs.
+        let epochs = deep_night(10);
+        let workloads = [
+            PlannedWorkload::new(job("a", 1.0, 1.0, 10.0, false), 3),
+            PlannedWorkload::new(job("b", 1.0, 1.0, 10.0, false), 2),
+            PlannedWorkload::new(job("c", 1.0, 1.0, 10.0, false), 1),
+        ];
+        let plan = plan(&tokens, &epochs, &workloads);
+        assert_eq!(plan.scheduled.len(), 2);
+        assert_eq!(reason_of(&plan, "c"), PlanRejection::DutyCycleCap);
+        assert_eq!(plan.dracula_duty_pct, 20.0);
+
+        let long = [PlannedWorkload::new(job("long", 1.0, 1.0, 10.0, false), 1).duration(3)];
+        let plan = self::plan(&tokens, &epochs, &long);
+        assert_eq!(reason_of(&plan, "long"), PlanRejection::DutyCycleCap);
+    }
+
+    #[test]
+    fn night_budgets_accumulate_across_workloads() {
+        let tokens = bundle();
+        let epochs = deep_night(10);
+        let limits = PlannerLimits::from_bundle(&tokens);
+        assert_eq!(limits.max_blood_drain_ml, 5.0);
+        assert_eq!(limits.max_protein_drain_g, 3.0);
+        assert_eq!(limits.eco_budget_nj, 500.0);
+
+        let workloads = [
+            PlannedWorkload::new(job("blood-1", 1.0, 0.0, 10.0, false), 9).drains(3.0, 0.0),
+            PlannedWorkload::new(job("blood-2", 1.0, 0.0, 10.0, false), 8).drains(3.0, 0.0),
+            PlannedWorkload::new(job("protein-1", 1.0, 0.0, 10.0, false), 7).drains(0.0, 2.0),
+            PlannedWorkload::new(job("protein-2", 1.0, 0.0, 10.0, false), 6).drains(0.0, 2.0),
+            PlannedWorkload::new(job("eco-1", 1.0, 0.0, 300.0, false), 5),
+            PlannedWorkload::new(job("eco-2", 1.0, 0.0, 300.0, false), 4),
+        ];
+        let plan = plan(&tokens, &epochs, &workloads);
+        let ids: Vec<&str> = plan.scheduled.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["blood-1", "protein-1", "eco-1"]);
+        assert_eq!(reason_of(&plan, "blood-2"), PlanRejection::BloodDrainLimit);
+        assert_eq!(reason_of(&plan, "protein-2"), PlanRejection::ProteinDrainLimit);
+        assert_eq!(reason_of(&plan, "eco-2"), PlanRejection::EcoBudget);
+        assert_eq!(plan.blood_drain_ml, 3.0);
+        assert_eq!(plan.protein_drain_g, 2.0);
+        assert_eq!(plan.eco_energy_nj, 320.0);
+    }
+
+    #[test]
+    fn occupied_and_missing_windows_are_reported() {
+        let tokens = bundle();
+        let epochs = deep_night(4);
+        let workloads = [
+            PlannedWorkload::new(job("first", 1.0, 0.0, 10.0, false), 2).window(1, Some(3)).duration(2),
+            PlannedWorkload::new(job("second", 1.0, 0.0, 10.0, false), 1).window(1, Some(3)),
+            PlannedWorkload::new(job("cramped", 1.0, 0.0, 10.0, false), 0).window(3, Some(4)).duration(2),
+        ];
+        let plan = plan(&tokens, &epochs, &workloads);
+        assert_eq!(start_of(&plan, "first"), 1);
+        assert_eq!(reason_of(&plan, "second"), PlanRejection::EpochOccupied);
+        let cramped = plan.unscheduled.iter().find(|u| u.id == "cramped").unwrap();
+        assert_eq!(cramped.reason, PlanRejection::NoWindow);
+        assert!(cramped.rejections.is_empty());
+    }
+
+    #[test]
+    fn every_epoch_rejection_surfaces_as_a_reason() {
+        let tokens = bundle();
+        let mut epochs = deep_night(4);
+        epochs[0].stage = StageKind::Rem;
+        let mut spiky = deep_night(4);
+        spiky.iter_mut().for_each(|e| e.eco_energy_nj = 100.0);
+
+        let cases = [
+            (epochs.clone(), job("n3", 1.0, 0.0, 10.0, true), 0..1, Rejection::NotDeepN3),
+            (spiky, job("spike", 1.0, 0.0, 10.0, false), 0..4, Rejection::EcoSpike),
+            (epochs.clone(), job("brain", 11.0, 0.0, 10.0, false), 0..4, Rejection::InsufficientBrainTokens),
+            (epochs.clone(), job("dracula", 1.0, 11.0, 10.0, false), 0..4, Rejection::InsufficientDraculaQuanta),
+            (epochs, job("eco", 1.0, 0.0, 600.0, false), 0..4, Rejection::EcoOverTarget),
+        ];
+        // Lift the night eco budget so the per-epoch target gate is the one that bites.
+        let limits = PlannerLimits { eco_budget_nj: 10_000.0, ..PlannerLimits::from_bundle(&tokens) };
+        let mut relaxed = tokens.clone();
+        relaxed.dracula.dutycycle_max_pct = 100.0;
+
+        for (epochs, request, window, expected) in cases {
+            let id = request.id.clone();
+            let w = PlannedWorkload::new(request.clone(), 1).window(window.start, Some(window.end));
+            let plan = NightPlanner::new(limits.clone()).plan(&relaxed, &epochs, &[w], SystemTime::UNIX_EPOCH);
+            assert_eq!(reason_of(&plan, &id), PlanRejection::Epoch(expected), "{id}");
+            // The planner reports exactly what the per-epoch scheduler says.
+            for epoch in &epochs[window] {
+                assert_eq!(BrainTokenScheduler::check(&relaxed, epoch, &request), Err(expected), "{id}");
+            }
+        }
+    }
+
+    #[test]
+    fn placed_workloads_draw_down_tokens_for_later_ones() {
+        let tokens = bundle();
+        let epochs = deep_night(10);
+        let workloads = [
+            PlannedWorkload::new(job("big", 7.0, 0.0, 10.0, false), 2),
+            PlannedWorkload::new(job("next", 7.0, 0.0, 10.0, false), 1),
+        ];
+        let plan = plan(&tokens, &epochs, &workloads);
+        assert_eq!(plan.brain_tokens_spent, 7.0);
+        assert_eq!(reason_of(&plan, "next"), PlanRejection::Epoch(Rejection::InsufficientBrainTokens));
+
+        let mut left = tokens.clone();
+        left.brain.balance -= plan.brain_tokens_spent;
+        let request = &workloads[1].request;
+        assert_eq!(
+            BrainTokenScheduler::check(&left, &epochs[1], request),
+            Err(Rejection::InsufficientBrainTokens)
+        );
+        assert_eq!(BrainTokenScheduler::check(&tokens, &epochs[1], request), Ok(()));
+    }
+}
@@ -28,6 +28,16 @@ pub struct WorkloadRequest {
     pub allow_heavy_only_in_n3: bool,
 }
 
+/// Why a request cannot run in an epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rejection {
+    NotDeepN3,
+    EcoSpike,
+    InsufficientBrainTokens,
+    InsufficientDraculaQuanta,
+    EcoOverTarget,
+}
+
 pub struct BrainTokenScheduler;
 
 impl BrainTokenScheduler {
@@ -36,22 +46,38 @@ impl BrainTokenScheduler {
         epoch: &EpochMeta,
         request: &WorkloadRequest,
     ) -> bool {
+        Self::check(tokens, epoch, request).is_ok()
+    }
+
+    /// `decide`, with the first failing gate as the error.
+    pub fn check(
+        tokens: &BiophysicalTokenBundle,
+        epoch: &EpochMeta,
+        request: &WorkloadRequest,
+    ) -> Result<(), Rejection> {
         // Gate heavy workloads to N3 with high SN3, optional.
-        if request.allow_heavy_only_in_n3 {
-            if epoch.stage != StageKind::N3 || epoch.sn3 < 2.0 {
-                return false;
-            }
+        if request.allow_heavy_only_in_n3 && (epoch.stage != StageKind::N3 || epoch.sn3 < 2.0) {
+            return Err(Rejection::NotDeepN3);
         }
 
         // Never schedule if eco-energy spike is extreme vs epoch baseline.
         if epoch.eco_energy_nj > request.required_eco_nj * 5.0 {
-            return false;
+            return Err(Rejection::EcoSpike);
         }
 
-        tokens.can_schedule_workload(
+        if tokens.brain.balance < request.required_brain_tokens {
+            return Err(Rejection::InsufficientBrainTokens);
+        }
+        if tokens.dracula.balance_quanta < request.required_dracula_quanta {
+            return Err(Rejection::InsufficientDraculaQuanta);
+        }
+        if !tokens.can_schedule_workload(
             request.required_brain_tokens,
             request.required_dracula_quanta,
             request.required_eco_nj,
-        )
+        ) {
+            return Err(Rejection::EcoOverTarget);
+        }
+        Ok(())
     }
 }
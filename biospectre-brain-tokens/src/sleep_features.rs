@@ -0,0 +1,341 @@
+use crate::dsp::{self, Biquad};
+use crate::edf::{EdfError, EdfFile};
+use crate::neural_rope::{
+    NeuralRope7D, ROPE_DELTA_DOMINANCE, ROPE_S_UNKNOWN, ROPE_SN1, ROPE_SN2, ROPE_SN3, ROPE_SPINDLE_DENSITY,
+    ROPE_THETA_ALPHA,
+};
+use crate::scheduler::{EpochMeta, StageKind};
+
+pub const EPOCH_SEC: f32 = 30.0;
+
+// Frequency bands, Hz: [lo, hi).
+pub const DELTA_BAND: (f32, f32) = (0.5, 4.0);
+pub const THETA_BAND: (f32, f32) = (4.0, 8.0);
+pub const ALPHA_BAND: (f32, f32) = (8.0, 12.0);
+pub const SIGMA_BAND: (f32, f32) = (12.0, 15.0);
+pub const BETA_BAND: (f32, f32) = (15.0, 30.0);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BandPowers {
+    pub delta: f32,
+    pub theta: f32,
+    pub alpha: f32,
+    pub sigma: f32,
+    pub beta: f32,
+}
+
+impl BandPowers {
+    pub fn total(&self) -> f32 {
+        self.delta + self.theta + self.alpha + self.sigma + self.beta
+    }
+
+    fn relative(&self, band: f32) -> f32 {
+        let total = self.total();
+        if total > 0.0 {
+            band / total
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Tunables for extraction and the heuristic stager. Defaults suit a single
+/// central EEG derivation (e.g. C4-M1) in microvolts.
+#[derive(Debug, Clone)]
+pub struct ExtractionConfig {
+    /// Welch segment length.
+    pub welch_segment_sec: f32,
+    pub spindle_band: (f32, f32),
+    pub spindle_rms_window_sec: f32,
+    /// Envelope threshold as a multiple of the epoch's median envelope.
+    pub spindle_threshold_ratio: f32,
+    /// Absolute envelope floor, µV, so flat epochs don't yield spindles.
+    pub spindle_min_rms_uv: f32,
+    pub spindle_duration_sec: (f32, f32),
+    /// Samples beyond this amplitude count as artifact.
+    pub artifact_abs_uv: f32,
+    /// Above this artifact fraction the epoch is `Unknown`.
+    pub max_artifact_fraction: f32,
+    /// Delta share of total power for N3.
+    pub n3_delta_dominance: f32,
+    /// Spindles per minute for N2.
+    pub n2_spindles_per_min: f32,
+    /// Relative alpha (or beta) above which the epoch is Wake.
+    pub wake_alpha_rel: f32,
+    pub wake_beta_rel: f32,
+    /// Relative beta separating REM from N1 once theta dominates.
+    pub rem_beta_rel: f32,
+    /// Energy charged per FLOP of extraction, for `EpochMeta::eco_energy_nj`.
+    pub nj_per_flop: f32,
+}
+
+impl Default for ExtractionConfig {
+    fn default() -> Self {
+        Self {
+            welch_segment_sec: 4.0,
+            spindle_band: (11.0, 16.0),
+            spindle_rms_window_sec: 0.2,
+            spindle_threshold_ratio: 2.5,
+            spindle_min_rms_uv: 5.0,
+            spindle_duration_sec: (0.5, 2.0),
+            artifact_abs_uv: 250.0,
+            max_artifact_fraction: 0.3,
+            n3_delta_dominance: 0.6,
+            n2_spindles_per_min: 1.0,
+            wake_alpha_rel: 0.3,
+            wake_beta_rel: 0.35,
+            rem_beta_rel: 0.15,
+            nj_per_flop: 0.05,
+        }
+    }
+}
+
+/// Everything extracted from one 30 s epoch.
+#[derive(Debug, Clone)]
+pub struct EpochFeatures {
+    pub index: usize,
+    pub bands: BandPowers,
+    /// Delta share of 0.5–30 Hz power, 0–1.
+    pub delta_dominance: f32,
+    pub spindle_count: usize,
+    pub spindles_per_min: f32,
+    pub artifact_fraction: f32,
+    pub stage: StageKind,
+    /// Stage-evidence scores; ~1 is borderline, ≥2 is strong.
+    /// SN1: theta/alpha. SN2: spindles per minute over the N2 threshold.
+    /// SN3: delta over all other bands.
+    pub sn1: f32,
+    pub sn2: f32,
+    pub sn3: f32,
+    /// Uncertainty: artifact share, or 1.0 for an unstageable epoch.
+    pub s_unknown: f32,
+    pub flops: u64,
+}
+
+impl EpochFeatures {
+    pub fn theta_alpha_ratio(&self) -> f32 {
+        ratio(self.bands.theta, self.bands.alpha)
+    }
+
+    pub fn to_epoch_meta(&self, cfg: &ExtractionConfig) -> EpochMeta {
+        EpochMeta {
+            stage: self.stage,
+            sn3: self.sn3,
+            s_unknown: self.s_unknown,
+            eco_flops: self.flops,
+            eco_energy_nj: self.flops as f32 * cfg.nj_per_flop,
+        }
+    }
+
+    pub fn to_rope(&self) -> NeuralRope7D {
+        let mut components = [0.0_f32; 7];
+        components[ROPE_THETA_ALPHA] = self.theta_alpha_ratio();
+        components[ROPE_SN2] = self.sn2;
+        components[ROPE_DELTA_DOMINANCE] = self.delta_dominance;
+        components[ROPE_S_UNKNOWN] = self.s_unknown;
+        components[ROPE_SN1] = self.sn1;
+        components[ROPE_SPINDLE_DENSITY] = self.spindles_per_min;
+        components[ROPE_SN3] = self.sn3;
+        NeuralRope7D { components }
+    }
+}
+
+/// Ceiling for band ratios, so a near-silent band can't swamp the rope.
+const MAX_RATIO: f32 = 100.0;
+
+fn ratio(num: f32, den: f32) -> f32 {
+    if den > f32::EPSILON {
+        (num / den).min(MAX_RATIO)
+    } else if num > 0.0 {
+        MAX_RATIO
+    } else {
+        0.0
+    }
+}
+
+pub fn band_powers(epoch: &[f32], sample_rate_hz: f32, cfg: &ExtractionConfig) -> (BandPowers, u64) {
+    let seg = (cfg.welch_segment_sec * sample_rate_hz).round() as usize;
+    let psd = dsp::welch_psd(epoch, sample_rate_hz, seg);
+    let bands = BandPowers {
+        delta: psd.band_power(DELTA_BAND.0, DELTA_BAND.1),
+        theta: psd.band_power(THETA_BAND.0, THETA_BAND.1),
+        alpha: psd.band_power(ALPHA_BAND.0, ALPHA_BAND.1),
+        sigma: psd.band_power(SIGMA_BAND.0, SIGMA_BAND.1),
+        beta: psd.band_power(BETA_BAND.0, BETA_BAND.1),
+    };
+    (bands, psd.flops)
+}
+
+/// Count sleep spindles: band-pass, moving-RMS envelope, then runs above
+/// threshold whose duration is within `spindle_duration_sec`.
+pub fn detect_spindles(epoch: &[f32], sample_rate_hz: f32, cfg: &ExtractionConfig) -> (usize, u64) {
+    let (lo, hi) = cfg.spindle_band;
+    if hi >= sample_rate_hz / 2.0 {
+        return (0, 0);
+    }
+    let filtered = Biquad::bandpass(sample_rate_hz, lo, hi).filtfilt(epoch);
+    let window = ((cfg.spindle_rms_window_sec * sample_rate_hz).round() as usize).max(1);
+    let envelope = dsp::moving_rms(&filtered, window);
+    let threshold = (dsp::median(&envelope) * cfg.spindle_threshold_ratio).max(cfg.spindle_min_rms_uv);
+
+    let min_len = (cfg.spindle_duration_sec.0 * sample_rate_hz) as usize;
+    let max_len = (cfg.spindle_duration_sec.1 * sample_rate_hz) as usize;
+    let mut count = 0;
+    let mut run = 0;
+    for above in envelope.iter().map(|e| *e > threshold).chain(std::iter::once(false)) {
+        if above {
+            run += 1;
+        } else {
+            if run >= min_len && run <= max_len {
+                count += 1;
+            }
+            run = 0;
+        }
+    }
+    // Two biquad passes (~10 FLOPs/sample) plus envelope and threshold.
+    let flops = 25 * epoch.len() as u64;
+    (count, flops)
+}
+
+/// Heuristic single-channel stager, loosely after AASM spectral
+/// signatures. Without EOG/EMG, REM and N1 are told apart by beta only.
+pub fn heuristic_stage(
+    bands: &BandPowers,
+    spindles_per_min: f32,
+    artifact_fraction: f32,
+    cfg: &ExtractionConfig,
+) -> StageKind {
+    // A non-finite sample poisons the whole spectrum (NaN total).
+    if artifact_fraction > cfg.max_artifact_fraction || !(bands.total() > 0.0 && bands.total().is_finite()) {
+        return StageKind::Unknown;
+    }
+    let delta = bands.relative(bands.delta);
+    let alpha = bands.relative(bands.alpha);
+    let beta = bands.relative(bands.beta);
+    let theta = bands.relative(bands.theta);
+
+    if delta >= cfg.n3_delta_dominance {
+        StageKind::N3
+    } else if alpha >= cfg.wake_alpha_rel || beta >= cfg.wake_beta_rel {
+        StageKind::Wake
+    } else if spindles_per_min >= cfg.n2_spindles_per_min {
+        StageKind::N2
+    } else if theta > alpha && beta >= cfg.rem_beta_rel {
+        StageKind::Rem
+    } else {
+        StageKind::N1
+    }
+}
+
+/// Extract features from one epoch of microvolt samples.
+pub fn extract_epoch(index: usize, epoch: &[f32], sample_rate_hz: f32, cfg: &ExtractionConfig) -> EpochFeatures {
+    let (bands, psd_flops) = band_powers(epoch, sample_rate_hz, cfg);
+    let (spindle_count, spindle_flops) = detect_spindles(epoch, sample_rate_hz, cfg);
+    let minutes = epoch.len() as f32 / sample_rate_hz / 60.0;
+    let spindles_per_min = if minutes > 0.0 { spindle_count as f32 / minutes } else { 0.0 };
+    let artifact_fraction = if epoch.is_empty() {
+        1.0
+    } else {
+        epoch.iter().filter(|x| !x.is_finite() || x.abs() > cfg.artifact_abs_uv).count() as f32 / epoch.len() as f32
+    };
+
+    let stage = heuristic_stage(&bands, spindles_per_min, artifact_fraction, cfg);
+    let delta_dominance = bands.relative(bands.delta);
+    EpochFeatures {
+        index,
+        bands,
+        delta_dominance,
+        spindle_count,
+        spindles_per_min,
+        artifact_fraction,
+        stage,
+        sn1: ratio(bands.theta, bands.alpha),
+        sn2: spindles_per_min / cfg.n2_spindles_per_min.max(f32::EPSILON),
+        sn3: ratio(bands.delta, bands.total() - bands.delta),
+        s_unknown: if stage == StageKind::Unknown { 1.0 } else { artifact_fraction },
+        flops: psd_flops + spindle_flops,
+    }
+}
+
+/// Split a night into consecutive 30 s epochs (a trailing partial epoch is
+/// dropped) and extract each.
+pub fn extract_night(samples_uv: &[f32], sample_rate_hz: f32, cfg: &ExtractionConfig) -> Vec<EpochFeatures> {
+    let len = (EPOCH_SEC * sample_rate_hz).round() as usize;
+    if len == 0 {
+        return Vec::new();
+    }
+    samples_uv
+        .chunks_exact(len)
+        .enumerate()
+        .map(|(i, epoch)| extract_epoch(i, epoch, sample_rate_hz, cfg))
+        .collect()
+}
+
+/// Extract a night from one EEG channel of an EDF recording.
+pub fn extract_edf(edf: &EdfFile, channel: &str, cfg: &ExtractionConfig) -> Result<Vec<EpochFeatures>, EdfError> {
+    let idx = edf.signal_index(channel)?;
+    Ok(extract_night(&edf.samples_uv(idx), edf.sample_rate_hz(idx), cfg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const FS: f32 = 128.0;
+
+    fn epoch_of(components: &[(f32, f32)]) -> Vec<f32> {
+        let n = (EPOCH_SEC * FS) as usize;
+        (0..n)
+            .map(|i| {
+                let t = i as f32 / FS;
+                components.iter().map(|(f, a)| a * (2.0 * PI * f * t).sin()).sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn delta_dominated_epoch_is_n3() {
+        let cfg = ExtractionConfig::default();
+        let f = extract_epoch(0, &epoch_of(&[(1.5, 60.0), (10.0, 5.0)]), FS, &cfg);
+        assert_eq!(f.stage, StageKind::N3);
+        assert!(f.delta_dominance > 0.9);
+        assert!(f.sn3 >= 2.0);
+        assert_eq!(f.s_unknown, 0.0);
+        let meta = f.to_epoch_meta(&cfg);
+        assert_eq!(meta.stage, StageKind::N3);
+        assert_eq!(meta.eco_flops, f.flops);
+    }
+
+    #[test]
+    fn alpha_dominated_epoch_is_wake() {
+        let f = extract_epoch(0, &epoch_of(&[(10.0, 30.0), (2.0, 5.0)]), FS, &ExtractionConfig::default());
+        assert_eq!(f.stage, StageKind::Wake);
+    }
+
+    #[test]
+    fn artifacts_and_non_finite_samples_are_unknown() {
+        let cfg = ExtractionConfig::default();
+        let mut epoch = epoch_of(&[(1.5, 60.0)]);
+        epoch[100] = f32::NAN;
+        let f = extract_epoch(0, &epoch, FS, &cfg);
+        assert_eq!(f.stage, StageKind::Unknown);
+        assert_eq!(f.s_unknown, 1.0);
+
+        let clipped = vec![400.0; (EPOCH_SEC * FS) as usize];
+        assert_eq!(extract_epoch(0, &clipped, FS, &cfg).stage, StageKind::Unknown);
+        let flat = vec![0.0; (EPOCH_SEC * FS) as usize];
+        assert_eq!(extract_epoch(0, &flat, FS, &cfg).stage, StageKind::Unknown);
+    }
+
+    #[test]
+    fn night_is_split_into_whole_epochs() {
+        let mut night = epoch_of(&[(1.5, 60.0)]);
+        night.extend(epoch_of(&[(10.0, 30.0)]));
+        night.extend(vec![0.0; 100]);
+        let epochs = extract_night(&night, FS, &ExtractionConfig::default());
+        let stages: Vec<StageKind> = epochs.iter().map(|e| e.stage).collect();
+        assert_eq!(stages, vec![StageKind::N3, StageKind::Wake]);
+        assert_eq!(epochs[1].index, 1);
+    }
+}
@@ -1,8 +1,15 @@
 use std::collections::HashMap;
-use crate::did_verification::{DIDSignature, DIDVerificationResult, verify_did_signature, did_to_public_key};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::did_verification::{
+    default_resolver, split_did_url, verify_with_resolver, DIDSignature, DIDVerificationResult, DidResolver,
+};
+use crate::eco_policy_state::{HostPolicyState, JournaledWallet, QuotaDenial, QuotaLedger};
+use crate::eco_trend::{EcoTrend, TrendDirection, TrendPolicy};
+use crate::upgrade_decision_log::{UpgradeDecisionInput, UpgradeDecisionLog, UpgradeDecisionRecord};
 
 /// Categories of tokenized assets under protection.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AssetKind {
     BrainToken,
     EcoToken,
@@ -11,7 +18,7 @@ pub enum AssetKind {
 }
 
 /// Integration types for cybernetic enhancements (software-only).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum IntegrationType {
     CognitiveLoadReduction,
     FocusStabilization,
@@ -31,7 +38,7 @@ pub struct TokenizedAsset {
 }
 
 /// Nightly eco-profile summary, aligned with N-stage and cluster shards.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NightlyEcoProfile {
     pub avg_flops_per_epoch: f32,
     pub avg_energynj_per_epoch: f32,
@@ -42,7 +49,7 @@ pub struct NightlyEcoProfile {
 }
 
 /// Governance policy for upgrades and assistance.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpgradePolicy {
     /// Minimum eco tokens required to unlock evolution upgrades.
     pub min_eco_tokens_for_upgrade: u64,
@@ -55,7 +62,7 @@ pub struct UpgradePolicy {
 }
 
 /// Aggregate wallet and state for one host.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct AssetWallet {
     pub assets: HashMap<AssetKind, u64>,
 }
@@ -91,6 +98,8 @@ pub struct UpgradeDecision {
 pub struct AssetEcoGovernor {
     policy: UpgradePolicy,
     stakeholder_did: String,
+    resolver: Arc<dyn DidResolver>,
+    trend_policy: TrendPolicy,
 }
 
 impl AssetEcoGovernor {
@@ -98,17 +107,226 @@ impl AssetEcoGovernor {
         Self {
             policy,
             stakeholder_did: stakeholder_did.to_string(),
+            resolver: default_resolver(),
+            trend_policy: TrendPolicy::default(),
         }
     }
 
-    /// Verify DID signature before processing upgrade
+    pub fn with_trend_policy(mut self, trend_policy: TrendPolicy) -> Self {
+        self.trend_policy = trend_policy;
+        self
+    }
+
+    /// Use a specific resolver (e.g. one with a did:web cache directory or
+    /// registered Bostrom keys) instead of the default did:key-only setup.
+    pub fn with_resolver(mut self, resolver: Arc<dyn DidResolver>) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    /// Verify DID signature before processing upgrade. The signature must
+    /// come from the stakeholder DID and cover exactly `message`.
     pub fn verify_did_signature(
         &self,
         signature: &DIDSignature,
         message: &str,
     ) -> Result<DIDVerificationResult, anyhow::Error> {
-        let public_key = did_to_public_key(&self.stakeholder_did)?;
-        verify_did_signature(signature, &public_key)
+        if split_did_url(&signature.did).0 != split_did_url(&self.stakeholder_did).0
+            || signature.message != message
+        {
+            return Ok(DIDVerificationResult::Invalid);
+        }
+        verify_with_resolver(signature, self.resolver.as_ref())
+    }
+
+    fn is_heavy(integration: IntegrationType) -> bool {
+        matches!(
+            integration,
+            IntegrationType::DreamExcavationDepth | IntegrationType::FocusStabilization
+        )
+    }
+
+    /// Multi-night gate: a steeply declining energy trend blocks every
+    /// upgrade, unstable clarity blocks heavy ones.
+    fn trend_denial(&self, trend: &EcoTrend, integration: IntegrationType) -> Option<String> {
+        if trend.direction == TrendDirection::Declining
+            && trend.energy_slope_pct_per_night < self.trend_policy.min_energy_slope_pct_per_night
+        {
+            return Some(format!(
+                "Energy reduction declining {:.2} pts/night over {} nights (limit {:.2}).",
+                trend.energy_slope_pct_per_night,
+                trend.nights,
+                self.trend_policy.min_energy_slope_pct_per_night
+            ));
+        }
+        if Self::is_heavy(integration)
+            && trend.clarity_variance > self.trend_policy.max_clarity_variance_for_heavy
+        {
+            return Some(format!(
+                "Clarity variance {:.3} over {} nights exceeds {:.3} for heavy integration.",
+                trend.clarity_variance, trend.nights, self.trend_policy.max_clarity_variance_for_heavy
+            ));
+        }
+        None
+    }
+
+    /// `decide_upgrade` against a host's persisted state: the latest night
+    /// from its history (denied once older than `max_latest_age_secs`), its
+    /// multi-night trend, the integration's cooldown and quota, and journaled
+    /// debits. A grant is saved with the host state; if that save fails the
+    /// debits are reversed and the upgrade denied. The outcome is then
+    /// appended to `log`. If that append fails, a grant is rolled back and
+    /// the error returned, so no upgrade takes effect without a record.
+    #[allow(clippy::too_many_arguments)]
+    pub fn decide_upgrade_for_host(
+        &self,
+        state: &mut HostPolicyState,
+        log: &mut UpgradeDecisionLog,
+        integration: IntegrationType,
+        eco_cost: u64,
+        evolution_cost: u64,
+        signature: Option<DIDSignature>,
+        now_unix: u64,
+    ) -> anyhow::Result<(UpgradeDecision, UpgradeDecisionRecord)> {
+        let quotas_before = state.quotas.clone();
+        let decision = self.settle_for_host(state, integration, eco_cost, evolution_cost, signature, now_unix);
+        let latest = state.history.latest().map(|r| r.profile.clone());
+        let appended = log.append(UpgradeDecisionInput {
+            host_id: &state.host_id,
+            at_unix: now_unix,
+            integration,
+            eco_cost,
+            evolution_cost,
+            nightly_profile: latest.as_ref(),
+            decision: &decision,
+        });
+        match appended {
+            Ok(record) => Ok((decision, record)),
+            Err(e) if decision.allowed => {
+                match Self::undo_grant(state, quotas_before, integration, eco_cost, evolution_cost, now_unix)
+                    .and_then(|()| state.save())
+                {
+                    Ok(()) => Err(e.context("failed to log upgrade decision; grant rolled back")),
+                    Err(undo) => {
+                        Err(e.context(format!("failed to log upgrade decision; grant rollback failed: {}", undo)))
+                    }
+                }
+            }
+            Err(e) => Err(e.context("failed to log upgrade decision")),
+        }
+    }
+
+    /// Restore the quotas from before a grant and reverse both its debits.
+    fn undo_grant(
+        state: &mut HostPolicyState,
+        quotas_before: QuotaLedger,
+        integration: IntegrationType,
+        eco_cost: u64,
+        evolution_cost: u64,
+        now_unix: u64,
+    ) -> anyhow::Result<()> {
+        state.quotas = quotas_before;
+        let memo = format!("reverse upgrade {:?}", integration);
+        state.wallet.credit(AssetKind::EvolutionPoint, evolution_cost, now_unix, &memo)?;
+        state.wallet.credit(AssetKind::EcoToken, eco_cost, now_unix, &memo)
+    }
+
+    /// The decision half of `decide_upgrade_for_host`, without logging.
+    fn settle_for_host(
+        &self,
+        state: &mut HostPolicyState,
+        integration: IntegrationType,
+        eco_cost: u64,
+        evolution_cost: u64,
+        signature: Option<DIDSignature>,
+        now_unix: u64,
+    ) -> UpgradeDecision {
+        let deny = |reason: String, signature: Option<DIDSignature>| UpgradeDecision {
+            allowed: false,
+            reason,
+            did_signature: signature,
+        };
+
+        let latest = match (state.history.latest(), state.history.latest_age_secs(now_unix)) {
+            (Some(_), Some(age)) if age > self.trend_policy.max_latest_age_secs => {
+                return deny(
+                    format!(
+                        "Latest night for host {} is {}s old (limit {}s).",
+                        state.host_id, age, self.trend_policy.max_latest_age_secs
+                    ),
+                    signature,
+                );
+            }
+            (Some(r), _) => r.profile.clone(),
+            (None, _) => return deny(format!("No nightly history for host {}.", state.host_id), signature),
+        };
+        match state.quotas.check(integration, now_unix) {
+            Ok(()) => {}
+            Err(QuotaDenial::CooldownActive { remaining_secs }) => {
+                return deny(
+                    format!("{:?} cooling down for another {}s.", integration, remaining_secs),
+                    signature,
+                );
+            }
+            Err(QuotaDenial::QuotaExhausted { used, max, resets_in_secs }) => {
+                return deny(
+                    format!(
+                        "{:?} quota used {}/{}; next slot in {}s.",
+                        integration, used, max, resets_in_secs
+                    ),
+                    signature,
+                );
+            }
+        }
+        if let Some(trend) = state.history.trend(&self.trend_policy) {
+            if let Some(reason) = self.trend_denial(&trend, integration) {
+                return deny(reason, signature);
+            }
+        }
+
+        // Run the single-night checks against a scratch copy, then apply the
+        // debits through the journal so they are durable and auditable.
+        let mut scratch = state.wallet.wallet().clone();
+        let decision = self.decide_upgrade(&mut scratch, &latest, integration, eco_cost, evolution_cost, signature);
+        if !decision.allowed {
+            return decision;
+        }
+        let memo = format!("upgrade {:?}", integration);
+        let reverse = |wallet: &mut JournaledWallet, kind: AssetKind, amount: u64| {
+            wallet.credit(kind, amount, now_unix, &format!("reverse {}", memo))
+        };
+        let wallet = &mut state.wallet;
+        let debited = match wallet.debit(AssetKind::EcoToken, eco_cost, now_unix, &memo) {
+            Ok(true) => match wallet.debit(AssetKind::EvolutionPoint, evolution_cost, now_unix, &memo) {
+                Ok(true) => Ok(()),
+                second => {
+                    // Reverse the eco debit so the journal never shows half an upgrade.
+                    let reversal = reverse(wallet, AssetKind::EcoToken, eco_cost);
+                    match (second, reversal) {
+                        (Err(e), _) | (_, Err(e)) => Err(format!("Wallet journal error: {}", e)),
+                        _ => Err("Concurrent debit conflict; retry upgrade decision.".to_string()),
+                    }
+                }
+            },
+            Ok(false) => Err("Concurrent debit conflict; retry upgrade decision.".to_string()),
+            Err(e) => Err(format!("Wallet journal error: {}", e)),
+        };
+        if let Err(reason) = debited {
+            return deny(reason, decision.did_signature);
+        }
+
+        // The grant is part of the decision: persist it before returning.
+        let quotas_before = state.quotas.clone();
+        state.quotas.record(integration, now_unix);
+        if let Err(e) = state.save() {
+            let reversal = Self::undo_grant(state, quotas_before, integration, eco_cost, evolution_cost, now_unix);
+            let reason = match reversal {
+                Ok(()) => format!("Host state save error: {}", e),
+                Err(r) => format!("Host state save error: {}; debit reversal failed: {}", e, r),
+            };
+            return deny(reason, decision.did_signature);
+        }
+        decision
     }
 
     /// Compute a simple eco-alignment score from nightly profile.
@@ -136,7 +354,7 @@ impl AssetEcoGovernor {
         signature: Option<DIDSignature>,
     ) -> UpgradeDecision {
         // Verify DID signature is present and valid
-        if let Some(sig) = signature {
+        if let Some(sig) = signature.as_ref() {
             let message = format!(
                 "upgrade:{}:eco_cost:{}:evolution_cost:{}",
                 integration as i32,
@@ -144,13 +362,13 @@ impl AssetEcoGovernor {
                 evolution_cost
             );
             
-            match self.verify_did_signature(&sig, &message) {
+            match self.verify_did_signature(sig, &message) {
                 Ok(DIDVerificationResult::Valid) => {}
                 Ok(DIDVerificationResult::Invalid) => {
                     return UpgradeDecision {
                         allowed: false,
                         reason: "Invalid DID signature for upgrade request".to_string(),
-                        did_signature: Some(sig),
+                        did_signature: Some(sig.clone()),
                     };
                 }
                 Ok(DIDVerificationResult::MissingSignature) => {
@@ -164,14 +382,14 @@ impl AssetEcoGovernor {
                     return UpgradeDecision {
                         allowed: false,
                         reason: "Invalid DID signature format".to_string(),
-                        did_signature: Some(sig),
+                        did_signature: Some(sig.clone()),
                     };
                 }
                 Err(e) => {
                     return UpgradeDecision {
                         allowed: false,
                         reason: format!("DID verification error: {}", e),
-                        did_signature: Some(sig),
+                        did_signature: Some(sig.clone()),
                     };
                 }
             }
@@ -213,10 +431,7 @@ impl AssetEcoGovernor {
         let clarity = nightly.cognitive_clarity_score_01;
 
         // High-intensity integration-types require stricter clarity/stress limits.
-        let heavy = matches!(
-            integration,
-            IntegrationType::DreamExcavationDepth | IntegrationType::FocusStabilization
-        );
+        let heavy = Self::is_heavy(integration);
 
         if heavy {
             if clarity < self.policy.min_clarity_for_heavy {
@@ -1,8 +1,13 @@
 use anyhow::{Context, Result};
-use ed25519_dalek::{PublicKey, Signature, Signer, Verifier};
+use ed25519_dalek::{PublicKey, Signature, Verifier};
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
-use std::str::FromStr;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
 
 /// DID signature verification result
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -16,12 +21,17 @@ pub enum DIDVerificationResult {
 /// DID signature verification structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DIDSignature {
+    /// Signer DID, optionally with a `#fragment` naming the verification method.
     pub did: String,
     pub signature: String,
     pub message: String,
 }
 
-/// Verify a DID signature against a public key
+/// Verify a DID signature against a known ed25519 key.
+///
+/// Legacy scheme: ed25519 over the Keccak-256 digest of the message. New
+/// callers should use [`verify_with_resolver`], which verifies the raw
+/// message with whatever key type the signer's DID document declares.
 pub fn verify_did_signature(
     signature: &DIDSignature,
     public_key: &PublicKey,
@@ -32,7 +42,7 @@ pub fn verify_did_signature(
     if signature_bytes.len() != 64 {
         return Ok(DIDVerificationResult::InvalidFormat);
     }
-    let signature = Signature::from_slice(&signature_bytes)
+    let sig = Signature::try_from(signature_bytes.as_slice())
         .context("Invalid signature format")?;
 
     // Hash the message
@@ -41,33 +51,852 @@ pub fn verify_did_signature(
     let message_hash = hasher.finalize();
 
     // Verify the signature
-    if public_key.verify(&message_hash, &signature).is_ok() {
+    if public_key.verify(&message_hash, &sig).is_ok() {
         Ok(DIDVerificationResult::Valid)
     } else {
         Ok(DIDVerificationResult::Invalid)
     }
 }
 
-/// Extract public key from DID identifier
+/// Extract the ed25519 public key of a `did:key` identifier.
+///
+/// Other DID methods need a [`DidResolver`]; see [`default_resolver`].
 pub fn did_to_public_key(did: &str) -> Result<PublicKey> {
-    // In practice, this would map DID to a public key via a DID resolver
-    // For this implementation, we'll use a simplified approach
-    // (In production, would connect to a DID resolver service)
-    
-    // Extract the actual public key part from the DID
-    // This is a simplified example - in reality would use a DID resolver
-    let public_key_str = match did {
-        "did:ion:EiD8J2b3K8k9Q8x9L7m2n4p1q5r6s7t8u9v0w1x2y3z4A5B6C7D8E9F0" => {
-            "038a3d0a0e6b5e0d5b3c2a1f0e9d8c7b6a5f4e3d2c1b0a9f8e7d6c5b4a3f2e1d0c9b8a7"
-        }
-        "bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7" => {
-            "03f1a9d5b3c2e1f0d9c8b7a6f5e4d3c2b1a0f9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2"
-        }
-        _ => return Err(anyhow::anyhow!("Unknown DID format")),
+    let doc = DidKeyResolver.resolve(did)?;
+    let method = doc
+        .verification_methods
+        .iter()
+        .find(|m| m.key_type == KeyType::Ed25519)
+        .ok_or_else(|| anyhow::anyhow!("{} has no ed25519 verification method", did))?;
+    PublicKey::from_bytes(&method.public_key)
+        .context("Invalid public key format")
+}
+
+// ---------------------------------------------------------------------------
+// Key material and DID documents
+// ---------------------------------------------------------------------------
+
+/// Multicodec prefixes (unsigned varint) for the key types we verify.
+const MULTICODEC_ED25519_PUB: [u8; 2] = [0xed, 0x01];
+const MULTICODEC_SECP256K1_PUB: [u8; 2] = [0xe7, 0x01];
+
+/// Signature scheme of a verification method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeyType {
+    /// Pure ed25519 over the message bytes.
+    Ed25519,
+    /// ECDSA over SHA-256 of the message, compact `r || s` or DER signature.
+    Secp256k1,
+}
+
+impl KeyType {
+    /// Key type implied by a W3C verification method `type`; `None` for
+    /// `Multikey`, whose type comes from the multicodec header instead.
+    fn from_method_type(kind: &str) -> Result<Option<KeyType>, DidResolutionError> {
+        match kind {
+            "Ed25519VerificationKey2018" | "Ed25519VerificationKey2020" => Ok(Some(KeyType::Ed25519)),
+            "EcdsaSecp256k1VerificationKey2019" => Ok(Some(KeyType::Secp256k1)),
+            "Multikey" => Ok(None),
+            other => Err(DidResolutionError::InvalidDocument(format!(
+                "unsupported verification method type {}",
+                other
+            ))),
+        }
+    }
+
+    fn key_len_ok(self, len: usize) -> bool {
+        match self {
+            KeyType::Ed25519 => len == 32,
+            KeyType::Secp256k1 => len == 33 || len == 65,
+        }
+    }
+}
+
+/// Split a multicodec-prefixed public key into its type and raw bytes.
+fn split_multicodec(bytes: &[u8]) -> Option<(KeyType, &[u8])> {
+    if let Some(rest) = bytes.strip_prefix(&MULTICODEC_ED25519_PUB[..]) {
+        Some((KeyType::Ed25519, rest))
+    } else {
+        bytes
+            .strip_prefix(&MULTICODEC_SECP256K1_PUB[..])
+            .map(|rest| (KeyType::Secp256k1, rest))
+    }
+}
+
+/// Decode a multibase string. Only base58btc (`z`) is accepted, as that is
+/// what `did:key` and `publicKeyMultibase` use in practice.
+fn decode_multibase(value: &str) -> Result<Vec<u8>, DidResolutionError> {
+    let encoded = value.strip_prefix('z').ok_or_else(|| {
+        DidResolutionError::InvalidDocument(format!("unsupported multibase encoding in {}", value))
+    })?;
+    bs58::decode(encoded)
+        .into_vec()
+        .map_err(|e| DidResolutionError::InvalidDocument(format!("bad base58btc {}: {}", value, e)))
+}
+
+/// A resolved verification method with its decoded key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationMethod {
+    /// Absolute DID URL, e.g. `did:web:example.com#key-1`.
+    pub id: String,
+    pub controller: String,
+    pub key_type: KeyType,
+    pub public_key: Vec<u8>,
+}
+
+impl VerificationMethod {
+    fn new(
+        id: String,
+        controller: String,
+        key_type: KeyType,
+        public_key: Vec<u8>,
+    ) -> Result<Self, DidResolutionError> {
+        if !key_type.key_len_ok(public_key.len()) {
+            return Err(DidResolutionError::InvalidDocument(format!(
+                "{}: {} byte key is not a valid {:?} public key",
+                id,
+                public_key.len(),
+                key_type
+            )));
+        }
+        Ok(Self { id, controller, key_type, public_key })
+    }
+
+    /// Check `signature` over `message` with this method's declared scheme.
+    /// `None` when the signature cannot be meant for this key type.
+    fn verify(&self, message: &[u8], signature: &[u8]) -> Option<bool> {
+        match self.key_type {
+            KeyType::Ed25519 => {
+                let sig = Signature::try_from(signature).ok()?;
+                let key = PublicKey::from_bytes(&self.public_key).ok()?;
+                Some(key.verify(message, &sig).is_ok())
+            }
+            KeyType::Secp256k1 => {
+                use k256::ecdsa::signature::Verifier as _;
+                let sig = k256::ecdsa::Signature::from_slice(signature)
+                    .or_else(|_| k256::ecdsa::Signature::from_der(signature))
+                    .ok()?;
+                let key = k256::ecdsa::VerifyingKey::from_sec1_bytes(&self.public_key).ok()?;
+                Some(key.verify(message, &sig).is_ok())
+            }
+        }
+    }
+}
+
+/// The subset of a W3C DID document used for signature checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DidDocument {
+    pub id: String,
+    pub verification_methods: Vec<VerificationMethod>,
+    /// Method ids allowed to sign assertions; empty means any method.
+    pub assertion_methods: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawDidDocument {
+    id: String,
+    #[serde(default)]
+    verification_method: Vec<RawVerificationMethod>,
+    #[serde(default)]
+    assertion_method: Vec<RawMethodRef>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawMethodRef {
+    Id(String),
+    Embedded(RawVerificationMethod),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawVerificationMethod {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    controller: Option<String>,
+    public_key_multibase: Option<String>,
+    public_key_base58: Option<String>,
+    public_key_hex: Option<String>,
+}
+
+/// Make a relative method id (`#key-1`) absolute against the document id.
+fn absolute_id(doc_id: &str, id: &str) -> String {
+    if id.starts_with('#') {
+        format!("{}{}", doc_id, id)
+    } else {
+        id.to_string()
+    }
+}
+
+impl RawVerificationMethod {
+    fn decode(self, doc_id: &str) -> Result<VerificationMethod, DidResolutionError> {
+        let id = absolute_id(doc_id, &self.id);
+        let declared = KeyType::from_method_type(&self.kind)?;
+        let (key_type, key) = if let Some(mb) = &self.public_key_multibase {
+            let bytes = decode_multibase(mb)?;
+            match (declared, split_multicodec(&bytes)) {
+                (Some(d), Some((t, raw))) if d == t => (d, raw.to_vec()),
+                (Some(d), Some((t, _))) => {
+                    return Err(DidResolutionError::InvalidDocument(format!(
+                        "{}: declared {:?} but key is {:?}",
+                        id, d, t
+                    )))
+                }
+                // Ed25519VerificationKey2018-style raw multibase keys.
+                (Some(d), None) => (d, bytes),
+                (None, Some((t, raw))) => (t, raw.to_vec()),
+                (None, None) => {
+                    return Err(DidResolutionError::InvalidDocument(format!(
+                        "{}: Multikey without a known multicodec header",
+                        id
+                    )))
+                }
+            }
+        } else {
+            let d = declared.ok_or_else(|| {
+                DidResolutionError::InvalidDocument(format!("{}: Multikey requires publicKeyMultibase", id))
+            })?;
+            let bytes = if let Some(b58) = &self.public_key_base58 {
+                bs58::decode(b58)
+                    .into_vec()
+                    .map_err(|e| DidResolutionError::InvalidDocument(format!("{}: {}", id, e)))?
+            } else if let Some(h) = &self.public_key_hex {
+                hex::decode(h).map_err(|e| DidResolutionError::InvalidDocument(format!("{}: {}", id, e)))?
+            } else {
+                return Err(DidResolutionError::InvalidDocument(format!("{}: no supported key encoding", id)));
+            };
+            (d, bytes)
+        };
+        let controller = self.controller.unwrap_or_else(|| doc_id.to_string());
+        VerificationMethod::new(id, controller, key_type, key)
+    }
+}
+
+impl DidDocument {
+    /// Parse a JSON(-LD) DID document.
+    pub fn from_json(json: &str) -> Result<Self, DidResolutionError> {
+        let raw: RawDidDocument = serde_json::from_str(json)
+            .map_err(|e| DidResolutionError::InvalidDocument(e.to_string()))?;
+        let mut verification_methods = Vec::new();
+        for m in raw.verification_method {
+            verification_methods.push(m.decode(&raw.id)?);
+        }
+        let mut assertion_methods = Vec::new();
+        for r in raw.assertion_method {
+            match r {
+                RawMethodRef::Id(id) => assertion_methods.push(absolute_id(&raw.id, &id)),
+                RawMethodRef::Embedded(m) => {
+                    let m = m.decode(&raw.id)?;
+                    assertion_methods.push(m.id.clone());
+                    verification_methods.push(m);
+                }
+            }
+        }
+        Ok(Self { id: raw.id, verification_methods, assertion_methods })
+    }
+
+    pub fn method(&self, id: &str) -> Option<&VerificationMethod> {
+        let id = absolute_id(&self.id, id);
+        self.verification_methods.iter().find(|m| m.id == id)
+    }
+
+    /// Methods allowed to sign: `assertionMethod` when declared, else all.
+    pub fn signing_methods(&self) -> Vec<&VerificationMethod> {
+        if self.assertion_methods.is_empty() {
+            self.verification_methods.iter().collect()
+        } else {
+            self.verification_methods
+                .iter()
+                .filter(|m| self.assertion_methods.contains(&m.id))
+                .collect()
+        }
+    }
+}
+
+/// Split a DID URL into the DID and an optional `#fragment`.
+pub fn split_did_url(did_url: &str) -> (&str, Option<&str>) {
+    match did_url.split_once('#') {
+        Some((did, frag)) => (did, Some(frag)),
+        None => (did_url, None),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Resolvers
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DidResolutionError {
+    /// No configured resolver handles this DID method.
+    UnsupportedMethod(String),
+    /// The method is supported but the DID is unknown (cache miss, unregistered).
+    NotFound(String),
+    InvalidDid(String),
+    InvalidDocument(String),
+    /// Resolver state is unusable (a lock was poisoned by a panic).
+    Unavailable(String),
+}
+
+impl fmt::Display for DidResolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DidResolutionError::UnsupportedMethod(did) => write!(f, "no resolver for {}", did),
+            DidResolutionError::NotFound(did) => write!(f, "{} not found", did),
+            DidResolutionError::InvalidDid(msg) => write!(f, "invalid DID: {}", msg),
+            DidResolutionError::InvalidDocument(msg) => write!(f, "invalid DID document: {}", msg),
+            DidResolutionError::Unavailable(what) => write!(f, "{} unavailable: lock poisoned", what),
+        }
+    }
+}
+
+impl std::error::Error for DidResolutionError {}
+
+/// Maps a poisoned resolver lock to a resolution error instead of panicking.
+fn poisoned<T>(what: &'static str) -> impl FnOnce(PoisonError<T>) -> DidResolutionError {
+    move |_| DidResolutionError::Unavailable(what.to_string())
+}
+
+/// Resolves a DID (without fragment) to its document.
+pub trait DidResolver: Send + Sync {
+    /// Whether this resolver handles the DID's method.
+    fn handles(&self, did: &str) -> bool;
+
+    fn resolve(&self, did: &str) -> Result<DidDocument, DidResolutionError>;
+}
+
+impl<R: DidResolver + ?Sized> DidResolver for Arc<R> {
+    fn handles(&self, did: &str) -> bool {
+        (**self).handles(did)
+    }
+
+    fn resolve(&self, did: &str) -> Result<DidDocument, DidResolutionError> {
+        (**self).resolve(did)
+    }
+}
+
+/// Offline `did:key` resolution: the key is the identifier.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DidKeyResolver;
+
+impl DidResolver for DidKeyResolver {
+    fn handles(&self, did: &str) -> bool {
+        did.starts_with("did:key:")
+    }
+
+    fn resolve(&self, did: &str) -> Result<DidDocument, DidResolutionError> {
+        let (did, _) = split_did_url(did);
+        let multibase = did
+            .strip_prefix("did:key:")
+            .ok_or_else(|| DidResolutionError::UnsupportedMethod(did.to_string()))?;
+        let bytes = decode_multibase(multibase).map_err(|_| DidResolutionError::InvalidDid(did.to_string()))?;
+        let (key_type, raw) = split_multicodec(&bytes).ok_or_else(|| {
+            DidResolutionError::InvalidDid(format!("{}: unsupported multicodec key", did))
+        })?;
+        let id = format!("{}#{}", did, multibase);
+        let method = VerificationMethod::new(id.clone(), did.to_string(), key_type, raw.to_vec())?;
+        Ok(DidDocument {
+            id: did.to_string(),
+            verification_methods: vec![method],
+            assertion_methods: vec![id],
+        })
+    }
+}
+
+/// `did:web` resolved from pre-fetched documents, never the network.
+///
+/// Documents are looked up in memory first, then under `cache_dir` using the
+/// did:web URL layout: `did:web:example.com` is
+/// `<cache_dir>/example.com/.well-known/did.json`, `did:web:example.com:u:alice`
+/// is `<cache_dir>/example.com/u/alice/did.json`.
+#[derive(Debug, Default)]
+pub struct DidWebResolver {
+    cache_dir: Option<PathBuf>,
+    documents: RwLock<HashMap<String, DidDocument>>,
+}
+
+impl DidWebResolver {
+    pub fn new(cache_dir: Option<PathBuf>) -> Self {
+        Self { cache_dir, documents: RwLock::new(HashMap::new()) }
+    }
+
+    pub fn insert(&self, doc: DidDocument) -> Result<(), DidResolutionError> {
+        self.documents.write().map_err(poisoned("did:web documents"))?.insert(doc.id.clone(), doc);
+        Ok(())
+    }
+
+    /// Cache-relative path of a did:web document.
+    pub fn document_path(did: &str) -> Result<PathBuf, DidResolutionError> {
+        let rest = did
+            .strip_prefix("did:web:")
+            .ok_or_else(|| DidResolutionError::UnsupportedMethod(did.to_string()))?;
+        let mut segments = rest.split(':');
+        let host = segments.next().unwrap_or_default().replace("%3A", ":");
+        if host.is_empty() || host.contains('/') || host.contains("..") {
+            return Err(DidResolutionError::InvalidDid(did.to_string()));
+        }
+        let mut path = PathBuf::from(host);
+        let mut any = false;
+        for seg in segments {
+            if seg.is_empty() || seg == ".." || seg.contains('/') {
+                return Err(DidResolutionError::InvalidDid(did.to_string()));
+            }
+            path.push(seg);
+            any = true;
+        }
+        if !any {
+            path.push(".well-known");
+        }
+        path.push("did.json");
+        Ok(path)
+    }
+}
+
+impl DidResolver for DidWebResolver {
+    fn handles(&self, did: &str) -> bool {
+        did.starts_with("did:web:")
+    }
+
+    fn resolve(&self, did: &str) -> Result<DidDocument, DidResolutionError> {
+        let (did, _) = split_did_url(did);
+        if let Some(doc) = self.documents.read().map_err(poisoned("did:web documents"))?.get(did) {
+            return Ok(doc.clone());
+        }
+        let rel = Self::document_path(did)?;
+        let dir = self
+            .cache_dir
+            .as_ref()
+            .ok_or_else(|| DidResolutionError::NotFound(did.to_string()))?;
+        let json = fs::read_to_string(dir.join(rel))
+            .map_err(|_| DidResolutionError::NotFound(did.to_string()))?;
+        let doc = DidDocument::from_json(&json)?;
+        if doc.id != did {
+            return Err(DidResolutionError::InvalidDocument(format!(
+                "cached document id {} does not match {}",
+                doc.id, did
+            )));
+        }
+        Ok(doc)
+    }
+}
+
+/// A stakeholder key as supplied by configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BostromKeyEntry {
+    pub address: String,
+    /// Multicodec-prefixed base58btc key, e.g. `z6Mk...` for ed25519.
+    pub public_key_multibase: String,
+}
+
+/// Public keys registered for Bostrom (`bostrom1...`) account addresses,
+/// which act as DIDs for on-chain stakeholders.
+#[derive(Debug, Default)]
+pub struct BostromKeyRegistry {
+    keys: RwLock<HashMap<String, (KeyType, Vec<u8>)>>,
+}
+
+impl BostromKeyRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn is_bostrom_address(address: &str) -> bool {
+        address.strip_prefix("bostrom1").is_some_and(|data| {
+            data.len() >= 6
+                && data
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || (c.is_ascii_lowercase() && !matches!(c, 'b' | 'i' | 'o')))
+                && !data.contains('1')
+        })
+    }
+
+    pub fn register(
+        &self,
+        address: &str,
+        key_type: KeyType,
+        public_key: Vec<u8>,
+    ) -> Result<(), DidResolutionError> {
+        if !Self::is_bostrom_address(address) {
+            return Err(DidResolutionError::InvalidDid(address.to_string()));
+        }
+        if !key_type.key_len_ok(public_key.len()) {
+            return Err(DidResolutionError::InvalidDocument(format!(
+                "{}: {} byte key is not a valid {:?} public key",
+                address,
+                public_key.len(),
+                key_type
+            )));
+        }
+        self.keys
+            .write()
+            .map_err(poisoned("Bostrom key registry"))?
+            .insert(address.to_string(), (key_type, public_key));
+        Ok(())
+    }
+
+    /// Register a multicodec-prefixed multibase key (the `did:key` encoding).
+    pub fn register_multibase(&self, address: &str, public_key_multibase: &str) -> Result<(), DidResolutionError> {
+        let bytes = decode_multibase(public_key_multibase)?;
+        let (key_type, raw) = split_multicodec(&bytes).ok_or_else(|| {
+            DidResolutionError::InvalidDocument(format!("{}: unsupported multicodec key", address))
+        })?;
+        self.register(address, key_type, raw.to_vec())
+    }
+
+    /// Registry seeded from configured stakeholder keys.
+    pub fn from_entries(entries: &[BostromKeyEntry]) -> Result<Self, DidResolutionError> {
+        let registry = Self::new();
+        for entry in entries {
+            registry.register_multibase(&entry.address, &entry.public_key_multibase)?;
+        }
+        Ok(registry)
+    }
+
+    pub fn revoke(&self, address: &str) -> Result<bool, DidResolutionError> {
+        Ok(self.keys.write().map_err(poisoned("Bostrom key registry"))?.remove(address).is_some())
+    }
+}
+
+impl DidResolver for BostromKeyRegistry {
+    fn handles(&self, did: &str) -> bool {
+        did.starts_with("bostrom1")
+    }
+
+    fn resolve(&self, did: &str) -> Result<DidDocument, DidResolutionError> {
+        let (address, _) = split_did_url(did);
+        let (key_type, key) = self
+            .keys
+            .read()
+            .map_err(poisoned("Bostrom key registry"))?
+            .get(address)
+            .cloned()
+            .ok_or_else(|| DidResolutionError::NotFound(address.to_string()))?;
+        let id = format!("{}#key-1", address);
+        Ok(DidDocument {
+            id: address.to_string(),
+            verification_methods: vec![VerificationMethod::new(id.clone(), address.to_string(), key_type, key)?],
+            assertion_methods: vec![id],
+        })
+    }
+}
+
+/// Dispatches to the first resolver that handles the DID's method.
+#[derive(Default)]
+pub struct ResolverChain {
+    resolvers: Vec<Box<dyn DidResolver>>,
+}
+
+impl ResolverChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, resolver: impl DidResolver + 'static) -> Self {
+        self.resolvers.push(Box::new(resolver));
+        self
+    }
+}
+
+impl DidResolver for ResolverChain {
+    fn handles(&self, did: &str) -> bool {
+        self.resolvers.iter().any(|r| r.handles(did))
+    }
+
+    fn resolve(&self, did: &str) -> Result<DidDocument, DidResolutionError> {
+        self.resolvers
+            .iter()
+            .find(|r| r.handles(did))
+            .ok_or_else(|| DidResolutionError::UnsupportedMethod(did.to_string()))?
+            .resolve(did)
+    }
+}
+
+/// Caches successful resolutions for `ttl`. Failures are not cached, so a
+/// document added to a did:web cache or registry is picked up immediately.
+pub struct CachingResolver<R> {
+    inner: R,
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, DidDocument)>>,
+}
+
+impl<R: DidResolver> CachingResolver<R> {
+    pub fn new(inner: R, ttl: Duration) -> Self {
+        Self { inner, ttl, entries: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn invalidate(&self, did: &str) -> Result<(), DidResolutionError> {
+        self.entries.lock().map_err(poisoned("resolution cache"))?.remove(split_did_url(did).0);
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), DidResolutionError> {
+        self.entries.lock().map_err(poisoned("resolution cache"))?.clear();
+        Ok(())
+    }
+
+    pub fn cached_len(&self) -> Result<usize, DidResolutionError> {
+        Ok(self.entries.lock().map_err(poisoned("resolution cache"))?.len())
+    }
+}
+
+impl<R: DidResolver> DidResolver for CachingResolver<R> {
+    fn handles(&self, did: &str) -> bool {
+        self.inner.handles(did)
+    }
+
+    fn resolve(&self, did: &str) -> Result<DidDocument, DidResolutionError> {
+        let (did, _) = split_did_url(did);
+        let now = Instant::now();
+        if let Some((at, doc)) = self.entries.lock().map_err(poisoned("resolution cache"))?.get(did) {
+            if now.duration_since(*at) < self.ttl {
+                return Ok(doc.clone());
+            }
+        }
+        let doc = self.inner.resolve(did)?;
+        self.entries
+            .lock()
+            .map_err(poisoned("resolution cache"))?
+            .insert(did.to_string(), (now, doc.clone()));
+        Ok(doc)
+    }
+}
+
+/// Default resolution cache lifetime.
+pub const DEFAULT_RESOLUTION_TTL: Duration = Duration::from_secs(300);
+
+/// did:key only: the did:web cache and Bostrom registry are empty, so
+/// Bostrom stakeholders cannot verify. Services should build their resolver
+/// with [`configured_resolver`] from their stakeholder key configuration.
+pub fn default_resolver() -> Arc<dyn DidResolver> {
+    configured_resolver(BostromKeyRegistry::new(), None)
+}
+
+/// did:key, did:web from `did_web_cache_dir` and the given Bostrom keys,
+/// behind a resolution cache.
+pub fn configured_resolver(
+    bostrom_keys: BostromKeyRegistry,
+    did_web_cache_dir: Option<PathBuf>,
+) -> Arc<dyn DidResolver> {
+    Arc::new(CachingResolver::new(
+        ResolverChain::new()
+            .with(DidKeyResolver)
+            .with(DidWebResolver::new(did_web_cache_dir))
+            .with(bostrom_keys),
+        DEFAULT_RESOLUTION_TTL,
+    ))
+}
+
+/// Verify a DID signature by resolving the signer's DID document.
+///
+/// `signature.did` may carry a `#fragment` to pin the verification method,
+/// which must be one of the document's assertion methods; otherwise every
+/// assertion method is tried. The hex signature is checked
+/// over the raw message bytes using the scheme of each method's declared
+/// key type.
+pub fn verify_with_resolver(
+    signature: &DIDSignature,
+    resolver: &dyn DidResolver,
+) -> Result<DIDVerificationResult> {
+    if signature.signature.is_empty() {
+        return Ok(DIDVerificationResult::MissingSignature);
+    }
+    let Ok(sig_bytes) = hex::decode(&signature.signature) else {
+        return Ok(DIDVerificationResult::InvalidFormat);
     };
 
-    // Parse the public key
-    let public_key_bytes = hex::decode(public_key_str)?;
-    PublicKey::from_bytes(&public_key_bytes)
-        .context("Invalid public key format")
+    let (did, fragment) = split_did_url(&signature.did);
+    let doc = resolver
+        .resolve(did)
+        .with_context(|| format!("Failed to resolve {}", did))?;
+    let candidates = match fragment {
+        Some(frag) => {
+            let method = doc
+                .method(&format!("#{}", frag))
+                .ok_or_else(|| anyhow::anyhow!("{} has no verification method #{}", did, frag))?;
+            if !doc.signing_methods().contains(&method) {
+                return Ok(DIDVerificationResult::Invalid);
+            }
+            vec![method]
+        }
+        None => doc.signing_methods(),
+    };
+
+    let mut any_compatible = false;
+    for method in candidates {
+        match method.verify(signature.message.as_bytes(), &sig_bytes) {
+            Some(true) => return Ok(DIDVerificationResult::Valid),
+            Some(false) => any_compatible = true,
+            None => {}
+        }
+    }
+    Ok(if any_compatible {
+        DIDVerificationResult::Invalid
+    } else {
+        DIDVerificationResult::InvalidFormat
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Keypair, SecretKey, Signer};
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        let public = PublicKey::from(&secret);
+        Keypair { secret, public }
+    }
+
+    fn multibase(key: &Keypair) -> String {
+        let mut bytes = MULTICODEC_ED25519_PUB.to_vec();
+        bytes.extend_from_slice(key.public.as_bytes());
+        format!("z{}", bs58::encode(bytes).into_string())
+    }
+
+    fn signed(did: &str, key: &Keypair, message: &str) -> DIDSignature {
+        DIDSignature {
+            did: did.to_string(),
+            signature: hex::encode(key.sign(message.as_bytes()).to_bytes()),
+            message: message.to_string(),
+        }
+    }
+
+    /// did:web document with two ed25519 keys, only `#key-1` for assertions.
+    fn web_document(did: &str, assertion: &Keypair, other: &Keypair) -> String {
+        serde_json::json!({
+            "id": did,
+            "verificationMethod": [
+                { "id": "#key-1", "type": "Ed25519VerificationKey2020", "publicKeyMultibase": multibase(assertion) },
+                { "id": "#key-2", "type": "Ed25519VerificationKey2020", "publicKeyMultibase": multibase(other) },
+            ],
+            "assertionMethod": ["#key-1"],
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_did_key_signature_verifies() {
+        let key = keypair(1);
+        let did = format!("did:key:{}", multibase(&key));
+        let resolver = default_resolver();
+
+        let sig = signed(&did, &key, "upgrade:focus");
+        assert_eq!(verify_with_resolver(&sig, resolver.as_ref()).unwrap(), DIDVerificationResult::Valid);
+        assert_eq!(did_to_public_key(&did).unwrap(), key.public);
+
+        let other = signed(&did, &keypair(2), "upgrade:focus");
+        assert_eq!(verify_with_resolver(&other, resolver.as_ref()).unwrap(), DIDVerificationResult::Invalid);
+    }
+
+    #[test]
+    fn test_tampered_message_is_invalid() {
+        let key = keypair(3);
+        let did = format!("did:key:{}", multibase(&key));
+        let mut sig = signed(&did, &key, "eco_cost=200");
+        sig.message = "eco_cost=20".into();
+        assert_eq!(
+            verify_with_resolver(&sig, default_resolver().as_ref()).unwrap(),
+            DIDVerificationResult::Invalid
+        );
+    }
+
+    #[test]
+    fn test_did_web_document_paths() {
+        let path = |did| DidWebResolver::document_path(did).unwrap();
+        assert_eq!(path("did:web:example.com"), PathBuf::from("example.com/.well-known/did.json"));
+        assert_eq!(path("did:web:example.com:u:alice"), PathBuf::from("example.com/u/alice/did.json"));
+        assert_eq!(path("did:web:example.com%3A8443"), PathBuf::from("example.com:8443/.well-known/did.json"));
+        for bad in ["did:web:", "did:web:example.com:..", "did:web:example.com::x", "did:web:..evil"] {
+            assert!(DidWebResolver::document_path(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_did_web_resolves_from_cache_dir() {
+        let dir = std::env::temp_dir().join(format!("did_web_cache_{}", std::process::id()));
+        let did = "did:web:example.com:u:alice";
+        let doc_path = dir.join(DidWebResolver::document_path(did).unwrap());
+        fs::create_dir_all(doc_path.parent().unwrap()).unwrap();
+        let key = keypair(4);
+        fs::write(&doc_path, web_document(did, &key, &keypair(5))).unwrap();
+
+        let resolver = configured_resolver(BostromKeyRegistry::new(), Some(dir.clone()));
+        let sig = signed(did, &key, "hello");
+        assert_eq!(verify_with_resolver(&sig, resolver.as_ref()).unwrap(), DIDVerificationResult::Valid);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_only_assertion_methods_may_sign() {
+        let did = "did:web:example.com";
+        let (assertion, other) = (keypair(6), keypair(7));
+        let web = DidWebResolver::new(None);
+        web.insert(DidDocument::from_json(&web_document(did, &assertion, &other)).unwrap()).unwrap();
+
+        let pinned = |frag: &str, key: &Keypair| signed(&format!("{}#{}", did, frag), key, "grant");
+        assert_eq!(verify_with_resolver(&pinned("key-1", &assertion), &web).unwrap(), DIDVerificationResult::Valid);
+        // key-2 is in the document but not an assertion method.
+        assert_eq!(verify_with_resolver(&pinned("key-2", &other), &web).unwrap(), DIDVerificationResult::Invalid);
+        assert_eq!(verify_with_resolver(&signed(did, &other, "grant"), &web).unwrap(), DIDVerificationResult::Invalid);
+        assert!(verify_with_resolver(&pinned("key-9", &assertion), &web).is_err());
+    }
+
+    #[test]
+    fn test_poisoned_locks_are_resolution_errors() {
+        let address = "bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7";
+        let registry = Arc::new(BostromKeyRegistry::new());
+        let held = registry.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = held.keys.write().unwrap();
+            panic!("poison the registry");
+        })
+        .join();
+        assert!(matches!(registry.resolve(address), Err(DidResolutionError::Unavailable(_))));
+        assert!(registry.revoke(address).is_err());
+
+        let did = format!("did:key:{}", multibase(&keypair(10)));
+        let cache = Arc::new(CachingResolver::new(DidKeyResolver, DEFAULT_RESOLUTION_TTL));
+        let held = cache.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = held.entries.lock().unwrap();
+            panic!("poison the cache");
+        })
+        .join();
+        assert!(matches!(cache.resolve(&did), Err(DidResolutionError::Unavailable(_))));
+        assert!(cache.cached_len().is_err());
+    }
+
+    #[test]
+    fn test_unknown_dids_fail_to_resolve() {
+        let key = keypair(8);
+        let resolver = default_resolver();
+        for did in [
+            "did:web:unknown.example",
+            "bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7",
+            "did:ion:EiD8J2b3K8k9Q8x9L7m2n4p1q5r6s7t8u9v0w1x2y3z4A5B6C7D8E9F0",
+        ] {
+            assert!(verify_with_resolver(&signed(did, &key, "m"), resolver.as_ref()).is_err(), "{}", did);
+        }
+    }
+
+    #[test]
+    fn test_configured_bostrom_stakeholder_verifies() {
+        let key = keypair(9);
+        let address = "bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7";
+        let registry = BostromKeyRegistry::from_entries(&[BostromKeyEntry {
+            address: address.into(),
+            public_key_multibase: multibase(&key),
+        }])
+        .unwrap();
+        let resolver = configured_resolver(registry, None);
+
+        let sig = signed(address, &key, "upgrade");
+        assert_eq!(verify_with_resolver(&sig, resolver.as_ref()).unwrap(), DIDVerificationResult::Valid);
+        assert!(BostromKeyRegistry::from_entries(&[BostromKeyEntry {
+            address: "cosmos1abcdef".into(),
+            public_key_multibase: multibase(&key),
+        }])
+        .is_err());
+    }
 }
@@ -0,0 +1,702 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::asset_eco_governor::{AssetKind, AssetWallet, IntegrationType, NightlyEcoProfile};
+use crate::eco_trend::NightlyHistory;
+use crate::upgrade_decision_log::GENESIS_CHECKSUM;
+
+// ---------------------------------------------------------------------------
+// Cooldowns and quotas per integration type
+// ---------------------------------------------------------------------------
+
+/// Rate limits for one integration type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrationQuota {
+    /// Minimum time between two grants.
+    pub cooldown_secs: u64,
+    /// Grants allowed within any `window_secs` span.
+    pub max_grants: u32,
+    pub window_secs: u64,
+}
+
+impl IntegrationQuota {
+    /// Defaults: heavy integrations once a day and twice a week, the rest
+    /// hourly and once a day on average.
+    pub fn default_for(integration: IntegrationType) -> Self {
+        const DAY: u64 = 24 * 3600;
+        match integration {
+            IntegrationType::DreamExcavationDepth | IntegrationType::FocusStabilization => Self {
+                cooldown_secs: DAY,
+                max_grants: 2,
+                window_secs: 7 * DAY,
+            },
+            _ => Self {
+                cooldown_secs: 3600,
+                max_grants: 7,
+                window_secs: 7 * DAY,
+            },
+        }
+    }
+}
+
+/// Why a quota check failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuotaDenial {
+    CooldownActive { remaining_secs: u64 },
+    QuotaExhausted { used: u32, max: u32, resets_in_secs: u64 },
+}
+
+/// Grant timestamps per integration type, checked against its quota.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuotaLedger {
+    /// Overrides for `IntegrationQuota::default_for`.
+    pub quotas: HashMap<IntegrationType, IntegrationQuota>,
+    grants: HashMap<IntegrationType, Vec<u64>>,
+}
+
+impl QuotaLedger {
+    pub fn quota(&self, integration: IntegrationType) -> IntegrationQuota {
+        self.quotas
+            .get(&integration)
+            .copied()
+            .unwrap_or_else(|| IntegrationQuota::default_for(integration))
+    }
+
+    pub fn check(&self, integration: IntegrationType, now_unix: u64) -> Result<(), QuotaDenial> {
+        let quota = self.quota(integration);
+        let grants = match self.grants.get(&integration) {
+            Some(g) if !g.is_empty() => g,
+            _ => return Ok(()),
+        };
+        let last = *grants.last().unwrap();
+        let since_last = now_unix.saturating_sub(last);
+        if since_last < quota.cooldown_secs {
+            return Err(QuotaDenial::CooldownActive {
+                remaining_secs: quota.cooldown_secs - since_last,
+            });
+        }
+        let in_window: Vec<u64> = grants
+            .iter()
+            .copied()
+            .filter(|&t| t + quota.window_secs > now_unix)
+            .collect();
+        if in_window.len() as u32 >= quota.max_grants {
+            // The oldest grant in the window frees a slot when it ages out.
+            return Err(QuotaDenial::QuotaExhausted {
+                used: in_window.len() as u32,
+                max: quota.max_grants,
+                resets_in_secs: in_window[0] + quota.window_secs - now_unix,
+            });
+        }
+        Ok(())
+    }
+
+    /// Record a grant and drop timestamps no window can still see.
+    pub fn record(&mut self, integration: IntegrationType, now_unix: u64) {
+        let window = self.quota(integration).window_secs;
+        let grants = self.grants.entry(integration).or_default();
+        grants.push(now_unix);
+        grants.retain(|&t| t + window > now_unix);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Journaled wallet
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WalletOp {
+    Credit,
+    Debit,
+}
+
+/// One balance change. `balance_after` lets a replay catch an entry that
+/// disagrees with the ones before it; the checksum chain catches edits that
+/// keep the arithmetic consistent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletTxn {
+    pub seq: u64,
+    pub at_unix: u64,
+    pub asset: AssetKind,
+    pub op: WalletOp,
+    pub amount: u64,
+    pub balance_after: u64,
+    pub memo: String,
+    pub prev_checksum: String,
+    /// SHA3-256 over `prev_checksum` and this entry with `checksum` empty,
+    /// chaining each entry to the one before it.
+    pub checksum: String,
+}
+
+impl WalletTxn {
+    fn compute_checksum(&self) -> Result<String> {
+        let mut unsealed = self.clone();
+        unsealed.checksum = String::new();
+        let mut hasher = Sha3_256::new();
+        hasher.update(self.prev_checksum.as_bytes());
+        hasher.update(serde_json::to_vec(&unsealed)?);
+        Ok(hex::encode(hasher.finalize()))
+    }
+}
+
+/// `AssetWallet` whose every credit and debit is appended to a journal
+/// (JSON lines, fsynced per entry when backed by a file). Balances are
+/// never stored separately: opening the journal replays it and re-checks
+/// the checksum chain, so an edited, reordered or gapped journal is
+/// rejected. Dropping entries from the end leaves a valid chain; compare
+/// [`head_checksum`](Self::head_checksum) with a copy kept elsewhere to
+/// catch that (`HostPolicyState` keeps one in `state.json`).
+#[derive(Debug, Default)]
+pub struct JournaledWallet {
+    wallet: AssetWallet,
+    journal: Vec<WalletTxn>,
+    path: Option<PathBuf>,
+}
+
+impl JournaledWallet {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Open (or create) a journal file and restore balances from it.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut wallet = Self {
+            path: Some(path.clone()),
+            ..Self::default()
+        };
+        if !path.exists() {
+            return Ok(wallet);
+        }
+        let file = File::open(&path).with_context(|| format!("open {}", path.display()))?;
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let txn: WalletTxn = serde_json::from_str(&line)
+                .with_context(|| format!("{}:{}: bad journal entry", path.display(), i + 1))?;
+            wallet.replay(&txn)?;
+            wallet.journal.push(txn);
+        }
+        Ok(wallet)
+    }
+
+    fn replay(&mut self, txn: &WalletTxn) -> Result<()> {
+        let expected_seq = self.journal.len() as u64 + 1;
+        if txn.seq != expected_seq {
+            bail!("journal seq {} out of order (expected {})", txn.seq, expected_seq);
+        }
+        match txn.op {
+            WalletOp::Credit => self.wallet.credit(txn.asset, txn.amount),
+            WalletOp::Debit => {
+                if !self.wallet.try_debit(txn.asset, txn.amount) {
+                    bail!("journal seq {} debits more {:?} than the balance", txn.seq, txn.asset);
+                }
+            }
+        }
+        let balance = self.wallet.balance(txn.asset);
+        if balance != txn.balance_after {
+            bail!(
+                "journal seq {} balance mismatch: replayed {}, recorded {}",
+                txn.seq,
+                balance,
+                txn.balance_after
+            );
+        }
+        if txn.prev_checksum != self.head_checksum() {
+            bail!("journal seq {} does not chain to the previous entry", txn.seq);
+        }
+        if txn.checksum != txn.compute_checksum()? {
+            bail!("journal seq {} checksum mismatch", txn.seq);
+        }
+        Ok(())
+    }
+
+    pub fn wallet(&self) -> &AssetWallet {
+        &self.wallet
+    }
+
+    pub fn balance(&self, kind: AssetKind) -> u64 {
+        self.wallet.balance(kind)
+    }
+
+    pub fn journal(&self) -> &[WalletTxn] {
+        &self.journal
+    }
+
+    /// Checksum of the newest entry, or `GENESIS_CHECKSUM` when empty.
+    pub fn head_checksum(&self) -> &str {
+        self.journal.last().map_or(GENESIS_CHECKSUM, |t| t.checksum.as_str())
+    }
+
+    /// Whether `checksum` is the genesis value or that of some entry.
+    pub fn has_checksum(&self, checksum: &str) -> bool {
+        checksum == GENESIS_CHECKSUM || self.journal.iter().any(|t| t.checksum == checksum)
+    }
+
+    pub fn credit(&mut self, kind: AssetKind, amount: u64, at_unix: u64, memo: &str) -> Result<()> {
+        self.apply(WalletOp::Credit, kind, amount, at_unix, memo)
+    }
+
+    /// Debit if the balance covers it; `Ok(false)` leaves the journal untouched.
+    pub fn debit(&mut self, kind: AssetKind, amount: u64, at_unix: u64, memo: &str) -> Result<bool> {
+        if self.wallet.balance(kind) < amount {
+            return Ok(false);
+        }
+        self.apply(WalletOp::Debit, kind, amount, at_unix, memo)?;
+        Ok(true)
+    }
+
+    fn apply(&mut self, op: WalletOp, asset: AssetKind, amount: u64, at_unix: u64, memo: &str) -> Result<()> {
+        let current = self.wallet.balance(asset);
+        let balance_after = match op {
+            WalletOp::Credit => current.checked_add(amount).context("balance overflow")?,
+            WalletOp::Debit => current - amount,
+        };
+        let mut txn = WalletTxn {
+            seq: self.journal.len() as u64 + 1,
+            at_unix,
+            asset,
+            op,
+            amount,
+            balance_after,
+            memo: memo.to_string(),
+            prev_checksum: self.head_checksum().to_string(),
+            checksum: String::new(),
+        };
+        txn.checksum = txn.compute_checksum()?;
+        // Write ahead: the in-memory balance only changes once the entry is durable.
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("open {}", path.display()))?;
+            writeln!(file, "{}", serde_json::to_string(&txn)?)?;
+            file.sync_data()?;
+        }
+        self.wallet.assets.insert(asset, balance_after);
+        self.journal.push(txn);
+        Ok(())
+    }
+
+    /// Balances as they were right after journal entry `seq` (0 = empty).
+    pub fn balances_at(&self, seq: u64) -> AssetWallet {
+        let mut wallet = AssetWallet::default();
+        for txn in self.journal.iter().take_while(|t| t.seq <= seq) {
+            wallet.assets.insert(txn.asset, txn.balance_after);
+        }
+        wallet
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Per-host state
+// ---------------------------------------------------------------------------
+
+/// Default number of nights kept per host.
+pub const DEFAULT_HISTORY_NIGHTS: usize = 14;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedHostState {
+    host_id: String,
+    history: NightlyHistory,
+    quotas: QuotaLedger,
+    /// Wallet journal head at the last save; empty in states saved before
+    /// the journal was chained.
+    #[serde(default)]
+    wallet_head: String,
+}
+
+/// Everything the governor remembers about one host between nights.
+///
+/// On disk under `<dir>/<host_id>/`: `state.json` (history and quota
+/// grants, replaced atomically) and `wallet.jsonl` (append-only journal).
+/// `state.json` also records the journal head, so loading fails if the
+/// journal lost entries that existed at the last save.
+#[derive(Debug)]
+pub struct HostPolicyState {
+    pub host_id: String,
+    pub history: NightlyHistory,
+    pub quotas: QuotaLedger,
+    pub wallet: JournaledWallet,
+    dir: Option<PathBuf>,
+}
+
+impl HostPolicyState {
+    pub fn in_memory(host_id: &str) -> Self {
+        Self {
+            host_id: host_id.to_string(),
+            history: NightlyHistory::new(DEFAULT_HISTORY_NIGHTS),
+            quotas: QuotaLedger::default(),
+            wallet: JournaledWallet::in_memory(),
+            dir: None,
+        }
+    }
+
+    fn host_dir(root: &Path, host_id: &str) -> Result<PathBuf> {
+        if host_id.is_empty() || host_id.contains(['/', '\\']) || host_id == "." || host_id == ".." {
+            bail!("invalid host id {:?}", host_id);
+        }
+        Ok(root.join(host_id))
+    }
+
+    /// Load a host's state from `root`, starting empty if none exists.
+    pub fn load(root: impl AsRef<Path>, host_id: &str) -> Result<Self> {
+        let dir = Self::host_dir(root.as_ref(), host_id)?;
+        fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+        let state_path = dir.join("state.json");
+        let (history, quotas, wallet_head) = if state_path.exists() {
+            let persisted: PersistedHostState = serde_json::from_str(&fs::read_to_string(&state_path)?)
+                .with_context(|| format!("parse {}", state_path.display()))?;
+            if persisted.host_id != host_id {
+                bail!("{} belongs to host {}", state_path.display(), persisted.host_id);
+            }
+            (persisted.history, persisted.quotas, persisted.wallet_head)
+        } else {
+            (NightlyHistory::new(DEFAULT_HISTORY_NIGHTS), QuotaLedger::default(), String::new())
+        };
+        let wallet_path = dir.join("wallet.jsonl");
+        let wallet = JournaledWallet::open(&wallet_path)?;
+        // Entries appended after the last save are fine; losing saved ones is not.
+        if !wallet_head.is_empty() && !wallet.has_checksum(&wallet_head) {
+            bail!(
+                "{} is missing entries up to head {} recorded in {}",
+                wallet_path.display(),
+                wallet_head,
+                state_path.display()
+            );
+        }
+        Ok(Self {
+            host_id: host_id.to_string(),
+            history,
+            quotas,
+            wallet,
+            dir: Some(dir),
+        })
+    }
+
+    /// Persist history and quota grants. The wallet journal is already
+    /// durable per transaction. No-op for in-memory state.
+    pub fn save(&self) -> Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let persisted = PersistedHostState {
+            host_id: self.host_id.clone(),
+            history: self.history.clone(),
+            quotas: self.quotas.clone(),
+            wallet_head: self.wallet.head_checksum().to_string(),
+        };
+        let tmp = dir.join("state.json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(&persisted)?)?;
+        fs::rename(&tmp, dir.join("state.json"))?;
+        Ok(())
+    }
+
+    pub fn record_night(&mut self, night_start_unix: u64, profile: NightlyEcoProfile) {
+        self.history.push(night_start_unix, profile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset_eco_governor::{AssetEcoGovernor, UpgradePolicy};
+    use crate::did_verification::DIDSignature;
+    use crate::upgrade_decision_log::{UpgradeDecisionLog, UpgradeOutcome};
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+
+    const DAY: u64 = 24 * 3600;
+
+    /// Temp directory removed on drop.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("eco_policy_state_{}_{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn quota_ledger(cooldown_secs: u64, max_grants: u32, window_secs: u64) -> QuotaLedger {
+        let mut ledger = QuotaLedger::default();
+        ledger.quotas.insert(
+            IntegrationType::EcoOptimizer,
+            IntegrationQuota { cooldown_secs, max_grants, window_secs },
+        );
+        ledger
+    }
+
+    #[test]
+    fn test_cooldown_ends_exactly_at_cooldown_secs() {
+        let mut ledger = quota_ledger(100, 10, 1000);
+        let it = IntegrationType::EcoOptimizer;
+        assert_eq!(ledger.check(it, 0), Ok(()));
+        ledger.record(it, 0);
+        assert_eq!(ledger.check(it, 99), Err(QuotaDenial::CooldownActive { remaining_secs: 1 }));
+        assert_eq!(ledger.check(it, 100), Ok(()));
+    }
+
+    #[test]
+    fn test_quota_window_edges() {
+        let mut ledger = quota_ledger(0, 2, 1000);
+        let it = IntegrationType::EcoOptimizer;
+        ledger.record(it, 0);
+        ledger.record(it, 500);
+        assert_eq!(
+            ledger.check(it, 999),
+            Err(QuotaDenial::QuotaExhausted { used: 2, max: 2, resets_in_secs: 1 })
+        );
+        // The grant at 0 leaves the window at exactly 1000.
+        assert_eq!(ledger.check(it, 1000), Ok(()));
+        ledger.record(it, 1000);
+        assert_eq!(ledger.grants[&it], vec![500, 1000]);
+    }
+
+    #[test]
+    fn test_journal_replay_restores_balances() {
+        let scratch = Scratch::new("replay");
+        let path = scratch.0.join("wallet.jsonl");
+        {
+            let mut w = JournaledWallet::open(&path).unwrap();
+            w.credit(AssetKind::EcoToken, 300, 1, "mint").unwrap();
+            w.credit(AssetKind::EvolutionPoint, 20, 2, "mint").unwrap();
+            assert!(w.debit(AssetKind::EcoToken, 120, 3, "upgrade").unwrap());
+            assert!(!w.debit(AssetKind::EcoToken, 1000, 4, "too much").unwrap());
+        }
+        let w = JournaledWallet::open(&path).unwrap();
+        assert_eq!(w.journal().len(), 3);
+        assert_eq!(w.balance(AssetKind::EcoToken), 180);
+        assert_eq!(w.balance(AssetKind::EvolutionPoint), 20);
+        assert_eq!(w.balances_at(1).balance(AssetKind::EcoToken), 300);
+    }
+
+    fn rewrite_journal(path: &Path, edit: impl Fn(&mut Vec<WalletTxn>)) {
+        let mut txns: Vec<WalletTxn> = fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        edit(&mut txns);
+        let body: String = txns.iter().map(|t| serde_json::to_string(t).unwrap() + "\n").collect();
+        fs::write(path, body).unwrap();
+    }
+
+    #[test]
+    fn test_journal_tampering_is_detected() {
+        let scratch = Scratch::new("tamper");
+        let path = scratch.0.join("wallet.jsonl");
+        let mut w = JournaledWallet::open(&path).unwrap();
+        w.credit(AssetKind::EcoToken, 300, 1, "mint").unwrap();
+        w.debit(AssetKind::EcoToken, 100, 2, "upgrade").unwrap();
+        w.credit(AssetKind::EcoToken, 50, 3, "mint").unwrap();
+        let pristine = fs::read_to_string(&path).unwrap();
+
+        rewrite_journal(&path, |t| t[1].amount = 10);
+        let err = JournaledWallet::open(&path).unwrap_err().to_string();
+        assert!(err.contains("balance mismatch"), "{}", err);
+
+        // Consistent arithmetic is not enough once the chain is broken.
+        fs::write(&path, &pristine).unwrap();
+        rewrite_journal(&path, |t| {
+            t[1].amount = 10;
+            t[1].balance_after = 290;
+            t[2].balance_after = 340;
+        });
+        let err = JournaledWallet::open(&path).unwrap_err().to_string();
+        assert!(err.contains("checksum mismatch"), "{}", err);
+
+        fs::write(&path, &pristine).unwrap();
+        rewrite_journal(&path, |t| t.swap(1, 2));
+        let err = JournaledWallet::open(&path).unwrap_err().to_string();
+        assert!(err.contains("out of order"), "{}", err);
+
+        fs::write(&path, &pristine).unwrap();
+        rewrite_journal(&path, |t| {
+            t.remove(0);
+        });
+        assert!(JournaledWallet::open(&path).is_err());
+    }
+
+    #[test]
+    fn test_dropped_journal_tail_is_caught_by_the_saved_head() {
+        let scratch = Scratch::new("tail");
+        {
+            let mut state = HostPolicyState::load(&scratch.0, "host-1").unwrap();
+            state.wallet.credit(AssetKind::EcoToken, 300, 1, "mint").unwrap();
+            state.wallet.debit(AssetKind::EcoToken, 100, 2, "upgrade").unwrap();
+            state.save().unwrap();
+            // Appended after the last save: still loads.
+            state.wallet.credit(AssetKind::EcoToken, 50, 3, "mint").unwrap();
+        }
+        assert_eq!(HostPolicyState::load(&scratch.0, "host-1").unwrap().wallet.balance(AssetKind::EcoToken), 250);
+
+        let path = scratch.0.join("host-1").join("wallet.jsonl");
+        rewrite_journal(&path, |t| t.truncate(1));
+        // The shortened journal is a valid chain on its own...
+        assert_eq!(JournaledWallet::open(&path).unwrap().balance(AssetKind::EcoToken), 300);
+        // ...but no longer reaches the head recorded in state.json.
+        let err = HostPolicyState::load(&scratch.0, "host-1").unwrap_err().to_string();
+        assert!(err.contains("missing entries"), "{}", err);
+    }
+
+    #[test]
+    fn test_host_ids_cannot_escape_the_root() {
+        let scratch = Scratch::new("host_ids");
+        for bad in ["", ".", "..", "a/b", "a\\b"] {
+            assert!(HostPolicyState::load(&scratch.0, bad).is_err(), "{:?}", bad);
+        }
+        assert!(HostPolicyState::load(&scratch.0, "host-1").is_ok());
+    }
+
+    fn keypair() -> Keypair {
+        let secret = SecretKey::from_bytes(&[42; 32]).unwrap();
+        let public = PublicKey::from(&secret);
+        Keypair { secret, public }
+    }
+
+    fn did_key(key: &Keypair) -> String {
+        let mut bytes = vec![0xed, 0x01];
+        bytes.extend_from_slice(key.public.as_bytes());
+        format!("did:key:z{}", bs58::encode(bytes).into_string())
+    }
+
+    fn sign(key: &Keypair, integration: IntegrationType, eco_cost: u64, evolution_cost: u64) -> DIDSignature {
+        let message = format!(
+            "upgrade:{}:eco_cost:{}:evolution_cost:{}",
+            integration as i32, eco_cost, evolution_cost
+        );
+        DIDSignature {
+            did: did_key(key),
+            signature: hex::encode(key.sign(message.as_bytes()).to_bytes()),
+            message,
+        }
+    }
+
+    fn governor(key: &Keypair) -> AssetEcoGovernor {
+        AssetEcoGovernor::new(
+            UpgradePolicy {
+                min_eco_tokens_for_upgrade: 100,
+                min_energy_reduction_pct: 20.0,
+                min_clarity_for_heavy: 0.6,
+                max_stress_score: 0.9,
+            },
+            &did_key(key),
+        )
+    }
+
+    fn good_night() -> NightlyEcoProfile {
+        NightlyEcoProfile {
+            avg_flops_per_epoch: 1.0e9,
+            avg_energynj_per_epoch: 5.0e3,
+            energy_reduction_vs_baseline_pct: 30.0,
+            device_hours_saved: 2.0,
+            cognitive_clarity_score_01: 0.8,
+            emotional_salience_score_01: 0.3,
+        }
+    }
+
+    fn funded_host(root: &Path) -> HostPolicyState {
+        let mut state = HostPolicyState::load(root, "host-1").unwrap();
+        state.wallet.credit(AssetKind::EcoToken, 500, 0, "mint").unwrap();
+        state.wallet.credit(AssetKind::EvolutionPoint, 50, 0, "mint").unwrap();
+        state.record_night(10 * DAY, good_night());
+        state.save().unwrap();
+        state
+    }
+
+    #[test]
+    fn test_grant_is_persisted_with_the_decision() {
+        let scratch = Scratch::new("grant");
+        let key = keypair();
+        let it = IntegrationType::EcoOptimizer;
+        let now = 10 * DAY + 3600;
+        let mut state = funded_host(&scratch.0);
+        let mut log = UpgradeDecisionLog::open(scratch.0.join("decisions.jsonl")).unwrap();
+
+        let (decision, record) = governor(&key)
+            .decide_upgrade_for_host(&mut state, &mut log, it, 50, 5, Some(sign(&key, it, 50, 5)), now)
+            .unwrap();
+        assert!(decision.allowed, "{}", decision.reason);
+        assert_eq!(record.outcome, UpgradeOutcome::Allowed);
+        assert_eq!(record.host_id, "host-1");
+        drop(state);
+
+        // No explicit save: a restart still sees the grant and the debits.
+        let reloaded = HostPolicyState::load(&scratch.0, "host-1").unwrap();
+        assert!(matches!(reloaded.quotas.check(it, now + 1), Err(QuotaDenial::CooldownActive { .. })));
+        assert_eq!(reloaded.wallet.balance(AssetKind::EcoToken), 450);
+        assert_eq!(reloaded.wallet.balance(AssetKind::EvolutionPoint), 45);
+        let logged = UpgradeDecisionLog::open(scratch.0.join("decisions.jsonl")).unwrap();
+        assert_eq!(logged.records(), [record]);
+    }
+
+    #[test]
+    fn test_grant_is_rolled_back_when_logging_fails() {
+        let scratch = Scratch::new("unlogged");
+        let key = keypair();
+        let it = IntegrationType::EcoOptimizer;
+        let now = 10 * DAY + 3600;
+        let mut state = funded_host(&scratch.0);
+        // The parent directory does not exist, so the append cannot land.
+        let mut log = UpgradeDecisionLog::open(scratch.0.join("missing").join("decisions.jsonl")).unwrap();
+
+        let result =
+            governor(&key).decide_upgrade_for_host(&mut state, &mut log, it, 50, 5, Some(sign(&key, it, 50, 5)), now);
+        assert!(result.is_err());
+        assert!(log.records().is_empty());
+        assert_eq!(state.quotas.check(it, now + 1), Ok(()));
+        drop(state);
+
+        let reloaded = HostPolicyState::load(&scratch.0, "host-1").unwrap();
+        assert_eq!(reloaded.quotas.check(it, now + 1), Ok(()));
+        assert_eq!(reloaded.wallet.balance(AssetKind::EcoToken), 500);
+        assert_eq!(reloaded.wallet.balance(AssetKind::EvolutionPoint), 50);
+    }
+
+    #[test]
+    fn test_stale_latest_night_is_denied() {
+        let scratch = Scratch::new("stale");
+        let key = keypair();
+        let it = IntegrationType::EcoOptimizer;
+        let mut state = funded_host(&scratch.0);
+        let gov = governor(&key);
+        let mut log = UpgradeDecisionLog::in_memory();
+
+        let stale = 10 * DAY + gov_limit() + 1;
+        let (decision, record) = gov
+            .decide_upgrade_for_host(&mut state, &mut log, it, 50, 5, Some(sign(&key, it, 50, 5)), stale)
+            .unwrap();
+        assert!(!decision.allowed);
+        assert!(decision.reason.contains("old"), "{}", decision.reason);
+        assert_eq!(record.outcome, UpgradeOutcome::Denied);
+        assert_eq!(record.reason, decision.reason);
+        assert_eq!(state.wallet.balance(AssetKind::EcoToken), 500);
+
+        let fresh = 10 * DAY + gov_limit();
+        let (decision, _) = gov
+            .decide_upgrade_for_host(&mut state, &mut log, it, 50, 5, Some(sign(&key, it, 50, 5)), fresh)
+            .unwrap();
+        assert!(decision.allowed, "{}", decision.reason);
+        assert_eq!(log.records().len(), 2);
+    }
+
+    fn gov_limit() -> u64 {
+        crate::eco_trend::TrendPolicy::default().max_latest_age_secs
+    }
+}
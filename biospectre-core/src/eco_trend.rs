@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::asset_eco_governor::NightlyEcoProfile;
+
+/// One night's profile, keyed by the night's start (unix seconds).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NightlyRecord {
+    pub night_start_unix: u64,
+    pub profile: NightlyEcoProfile,
+}
+
+/// Direction of the energy-reduction trend over the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrendDirection {
+    Improving,
+    Stable,
+    Declining,
+}
+
+/// Multi-night summary used alongside the latest night's profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EcoTrend {
+    pub nights: usize,
+    /// Least-squares slope of energy reduction, percentage points per night.
+    pub energy_slope_pct_per_night: f32,
+    pub direction: TrendDirection,
+    pub mean_energy_reduction_pct: f32,
+    pub clarity_mean: f32,
+    /// Sample variance of cognitive clarity; high values mean unstable nights.
+    pub clarity_variance: f32,
+}
+
+/// Thresholds applied to an `EcoTrend`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendPolicy {
+    /// Nights needed before a trend is computed at all.
+    pub min_nights: usize,
+    /// |slope| below this is `Stable`.
+    pub stable_band_pct_per_night: f32,
+    /// Declining faster than this (a negative number) blocks upgrades.
+    pub min_energy_slope_pct_per_night: f32,
+    /// Clarity variance above this blocks heavy integrations.
+    pub max_clarity_variance_for_heavy: f32,
+    /// Latest night older than this (from its start) blocks upgrades.
+    #[serde(default = "default_max_latest_age_secs")]
+    pub max_latest_age_secs: u64,
+}
+
+fn default_max_latest_age_secs() -> u64 {
+    48 * 3600
+}
+
+impl Default for TrendPolicy {
+    fn default() -> Self {
+        Self {
+            min_nights: 3,
+            stable_band_pct_per_night: 0.5,
+            min_energy_slope_pct_per_night: -2.0,
+            max_clarity_variance_for_heavy: 0.02,
+            max_latest_age_secs: default_max_latest_age_secs(),
+        }
+    }
+}
+
+/// Rolling window of the most recent nights for one host.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NightlyHistory {
+    pub capacity: usize,
+    records: VecDeque<NightlyRecord>,
+}
+
+impl NightlyHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            records: VecDeque::new(),
+        }
+    }
+
+    /// Add a night, replacing an existing record for the same night and
+    /// keeping records ordered by night start.
+    pub fn push(&mut self, night_start_unix: u64, profile: NightlyEcoProfile) {
+        let record = NightlyRecord { night_start_unix, profile };
+        match self
+            .records
+            .binary_search_by_key(&night_start_unix, |r| r.night_start_unix)
+        {
+            Ok(i) => self.records[i] = record,
+            Err(i) => self.records.insert(i, record),
+        }
+        while self.records.len() > self.capacity {
+            self.records.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn latest(&self) -> Option<&NightlyRecord> {
+        self.records.back()
+    }
+
+    /// Seconds between the latest night's start and `now_unix`.
+    pub fn latest_age_secs(&self, now_unix: u64) -> Option<u64> {
+        self.latest().map(|r| now_unix.saturating_sub(r.night_start_unix))
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &NightlyRecord> {
+        self.records.iter()
+    }
+
+    /// Trend over the window, or `None` with fewer than `min_nights` nights.
+    pub fn trend(&self, policy: &TrendPolicy) -> Option<EcoTrend> {
+        let n = self.records.len();
+        if n < policy.min_nights.max(2) {
+            return None;
+        }
+        let nf = n as f32;
+        let energy: Vec<f32> = self
+            .records
+            .iter()
+            .map(|r| r.profile.energy_reduction_vs_baseline_pct)
+            .collect();
+        let clarity: Vec<f32> = self
+            .records
+            .iter()
+            .map(|r| r.profile.cognitive_clarity_score_01)
+            .collect();
+
+        // Slope against night index, so gaps between nights don't skew it.
+        let x_mean = (nf - 1.0) / 2.0;
+        let e_mean = energy.iter().sum::<f32>() / nf;
+        let (mut sxy, mut sxx) = (0.0f32, 0.0f32);
+        for (i, e) in energy.iter().enumerate() {
+            let dx = i as f32 - x_mean;
+            sxy += dx * (e - e_mean);
+            sxx += dx * dx;
+        }
+        let slope = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+
+        let c_mean = clarity.iter().sum::<f32>() / nf;
+        let c_var = clarity.iter().map(|c| (c - c_mean).powi(2)).sum::<f32>() / (nf - 1.0);
+
+        let direction = if slope > policy.stable_band_pct_per_night {
+            TrendDirection::Improving
+        } else if slope < -policy.stable_band_pct_per_night {
+            TrendDirection::Declining
+        } else {
+            TrendDirection::Stable
+        };
+
+        Some(EcoTrend {
+            nights: n,
+            energy_slope_pct_per_night: slope,
+            direction,
+            mean_energy_reduction_pct: e_mean,
+            clarity_mean: c_mean,
+            clarity_variance: c_var,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NIGHT: u64 = 24 * 3600;
+
+    fn profile(energy_pct: f32, clarity: f32) -> NightlyEcoProfile {
+        NightlyEcoProfile {
+            avg_flops_per_epoch: 1.0e9,
+            avg_energynj_per_epoch: 5.0e3,
+            energy_reduction_vs_baseline_pct: energy_pct,
+            device_hours_saved: 1.5,
+            cognitive_clarity_score_01: clarity,
+            emotional_salience_score_01: 0.3,
+        }
+    }
+
+    fn history(energy: &[f32], clarity: &[f32]) -> NightlyHistory {
+        let mut h = NightlyHistory::new(14);
+        for (i, (&e, &c)) in energy.iter().zip(clarity).enumerate() {
+            h.push(i as u64 * NIGHT, profile(e, c));
+        }
+        h
+    }
+
+    #[test]
+    fn test_trend_needs_min_nights() {
+        let policy = TrendPolicy::default();
+        assert!(history(&[30.0, 31.0], &[0.8, 0.8]).trend(&policy).is_none());
+        assert!(history(&[30.0, 31.0, 32.0], &[0.8, 0.8, 0.8]).trend(&policy).is_some());
+    }
+
+    #[test]
+    fn test_trend_slope_and_direction() {
+        let policy = TrendPolicy::default();
+        let up = history(&[20.0, 22.0, 24.0, 26.0], &[0.8; 4]).trend(&policy).unwrap();
+        assert!((up.energy_slope_pct_per_night - 2.0).abs() < 1e-5);
+        assert_eq!(up.direction, TrendDirection::Improving);
+        assert!((up.mean_energy_reduction_pct - 23.0).abs() < 1e-5);
+        assert!(up.clarity_variance.abs() < 1e-9);
+
+        let down = history(&[30.0, 27.0, 24.0], &[0.9, 0.5, 0.7]).trend(&policy).unwrap();
+        assert!((down.energy_slope_pct_per_night + 3.0).abs() < 1e-5);
+        assert_eq!(down.direction, TrendDirection::Declining);
+        assert!((down.clarity_variance - 0.04).abs() < 1e-5);
+
+        let flat = history(&[30.0, 30.2, 30.1], &[0.8; 3]).trend(&policy).unwrap();
+        assert_eq!(flat.direction, TrendDirection::Stable);
+    }
+
+    #[test]
+    fn test_history_orders_replaces_and_caps() {
+        let mut h = NightlyHistory::new(2);
+        h.push(2 * NIGHT, profile(20.0, 0.8));
+        h.push(NIGHT, profile(10.0, 0.8));
+        h.push(2 * NIGHT, profile(25.0, 0.8));
+        assert_eq!(h.len(), 2);
+        assert_eq!(h.latest().unwrap().profile.energy_reduction_vs_baseline_pct, 25.0);
+        h.push(3 * NIGHT, profile(30.0, 0.8));
+        let starts: Vec<u64> = h.records().map(|r| r.night_start_unix).collect();
+        assert_eq!(starts, vec![2 * NIGHT, 3 * NIGHT]);
+        assert_eq!(h.latest_age_secs(3 * NIGHT + 60), Some(60));
+        assert_eq!(h.latest_age_secs(0), Some(0));
+    }
+}
@@ -1,7 +1,7 @@
-use std::cmp::Ordering;
+use serde::{Deserialize, Serialize};
 
 /// Token balances for the host.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HostTokens {
     pub blood: u64,
     pub protein: u64,
@@ -11,7 +11,7 @@ pub struct HostTokens {
 }
 
 /// Biophysical status proxies (software-only, derived from measurements).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BiophysicalStatus {
     /// 0–1 proxy for hemodynamic stability (blood pressure, HR, anemia risk).
     pub blood_stability_01: f32,
@@ -28,7 +28,7 @@ pub struct BiophysicalStatus {
 }
 
 /// Scalar lifeforce summary and risk band.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LifeforceBand {
     Green,
     Yellow,
@@ -41,8 +41,35 @@ pub struct LifeforceState {
     pub band: LifeforceBand,
 }
 
+/// Exponents and band cut-offs used by `compute_lifeforce`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LifeforceWeights {
+    pub blood: f32,
+    pub protein: f32,
+    pub sugar: f32,
+    pub clarity: f32,
+    /// Lifeforce at or above this is Green.
+    pub green_min: f32,
+    /// Lifeforce at or above this (and below `green_min`) is Yellow.
+    pub yellow_min: f32,
+}
+
+impl Default for LifeforceWeights {
+    fn default() -> Self {
+        // Weights emphasize blood and brain clarity.
+        Self {
+            blood: 0.35,
+            protein: 0.20,
+            sugar: 0.20,
+            clarity: 0.25,
+            green_min: 0.75,
+            yellow_min: 0.50,
+        }
+    }
+}
+
 /// Policy thresholds for DW usage.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LifeforcePolicy {
     /// Minimum lifeforce required to allow DW at all.
     pub min_lifeforce_for_dw: f32,
@@ -52,6 +79,7 @@ pub struct LifeforcePolicy {
     pub max_blood_fraction_per_dw: f32,
     /// Minimum chi resilience for heavy DW actions.
     pub min_chi_for_heavy_dw: f32,
+    pub weights: LifeforceWeights,
 }
 
 impl Default for LifeforcePolicy {
@@ -61,15 +89,26 @@ impl Default for LifeforcePolicy {
             min_lifeforce_for_blood_spend: 0.65,
             max_blood_fraction_per_dw: 0.08, // 8% of current BLOOD per event
             min_chi_for_heavy_dw: 0.55,
+            weights: LifeforceWeights::default(),
         }
     }
 }
 
+/// Why a DW spend was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DwDenialReason {
+    NoDwTokens,
+    LifeforceBelowMinimum,
+    BloodFallbackDisallowed,
+}
+
 /// Result of a DW spend decision.
 #[derive(Debug, Clone)]
 pub struct DwSpendDecision {
     pub allowed: bool,
     pub reason: String,
+    /// Set exactly when `allowed` is false.
+    pub denial: Option<DwDenialReason>,
     pub blood_spent: u64,
     pub protein_spent: u64,
     pub sugar_spent: u64,
@@ -86,13 +125,14 @@ impl LifeforceGovernor {
         Self { policy }
     }
 
+    pub fn policy(&self) -> &LifeforcePolicy {
+        &self.policy
+    }
+
     /// Compute lifeforce scalar from biophysical status.
     pub fn compute_lifeforce(&self, status: &BiophysicalStatus) -> LifeforceState {
-        // Weights emphasize blood and brain clarity.
-        let wb = 0.35;
-        let wp = 0.20;
-        let ws = 0.20;
-        let wc = 0.25;
+        let w = &self.policy.weights;
+        let (wb, wp, ws, wc) = (w.blood, w.protein, w.sugar, w.clarity);
 
         // Clamp inputs to [0,1].
         let b = status.blood_stability_01.clamp(0.0, 1.0);
@@ -106,9 +146,9 @@ impl LifeforceGovernor {
 
         let lf_clamped = lifeforce_scalar.clamp(0.0, 1.0);
 
-        let band = if lf_clamped >= 0.75 {
+        let band = if lf_clamped >= w.green_min {
             LifeforceBand::Green
-        } else if lf_clamped >= 0.50 {
+        } else if lf_clamped >= w.yellow_min {
             LifeforceBand::Yellow
         } else {
             LifeforceBand::Red
@@ -134,6 +174,7 @@ impl LifeforceGovernor {
             return DwSpendDecision {
                 allowed: false,
                 reason: "No Dracula_Wave (DW) tokens available.".to_string(),
+                denial: Some(DwDenialReason::NoDwTokens),
                 blood_spent: 0,
                 protein_spent: 0,
                 sugar_spent: 0,
@@ -148,6 +189,7 @@ impl LifeforceGovernor {
                     "Lifeforce {:.2} below minimum {:.2} for any DW activity.",
                     lf_state.lifeforce_scalar_01, self.policy.min_lifeforce_for_dw
                 ),
+                denial: Some(DwDenialReason::LifeforceBelowMinimum),
                 blood_spent: 0,
                 protein_spent: 0,
                 sugar_spent: 0,
@@ -162,8 +204,8 @@ impl LifeforceGovernor {
         let base_protein_need = (5.0 * intensity).round() as u64;
         let base_sugar_need = (8.0 * intensity).round() as u64;
 
-        let mut protein_spent = base_protein_need.min(tokens.protein);
-        let mut sugar_spent = base_sugar_need.min(tokens.sugar);
+        let protein_spent = base_protein_need.min(tokens.protein);
+        let sugar_spent = base_sugar_need.min(tokens.sugar);
         let mut blood_spent = 0_u64;
 
         // If PROTEIN or SUGAR are insufficient, consider BLOOD as fallback.
@@ -189,6 +231,7 @@ impl LifeforceGovernor {
                 allowed: false,
                 reason: "Insufficient PROTEIN/SUGAR and BLOOD fallback disallowed by lifeforce bands."
                     .to_string(),
+                denial: Some(DwDenialReason::BloodFallbackDisallowed),
                 blood_spent: 0,
                 protein_spent: 0,
                 sugar_spent: 0,
@@ -223,6 +266,7 @@ impl LifeforceGovernor {
         DwSpendDecision {
             allowed: true,
             reason,
+            denial: None,
             blood_spent,
             protein_spent,
             sugar_spent,
@@ -0,0 +1,581 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use crate::lifeforce_governor::{
+    BiophysicalStatus, DwDenialReason, HostTokens, LifeforceBand, LifeforceGovernor, LifeforcePolicy,
+};
+
+// ---------------------------------------------------------------------------
+// Input series
+// ---------------------------------------------------------------------------
+
+/// One point of a recorded or synthetic status series.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimStep {
+    pub t_sec: u64,
+    pub status: BiophysicalStatus,
+    /// Intensity of a DW request made at this step, if any.
+    pub dw_intensity_01: Option<f32>,
+    /// Tokens credited before the step is evaluated (meals, rest, minting).
+    #[serde(default)]
+    pub refill: HostTokens,
+}
+
+const SERIES_COLUMNS: [&str; 8] = [
+    "t_sec",
+    "blood_stability_01",
+    "protein_reserve_01",
+    "sugar_stability_01",
+    "brain_clarity_01",
+    "chi_resilience_01",
+    "lifeforce_trend_01",
+    "dw_intensity_01",
+];
+
+/// Parse a status series from CSV. Required columns are `SERIES_COLUMNS`
+/// in any order (`dw_intensity_01` may be blank); optional `refill_blood`,
+/// `refill_protein`, `refill_sugar`, `refill_brain`, `refill_dw` columns
+/// credit tokens.
+pub fn parse_status_csv(text: &str) -> Result<Vec<SimStep>> {
+    let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty() && !l.starts_with('#'));
+    let (_, header) = lines.next().context("status CSV is empty")?;
+    let header: Vec<&str> = header.split(',').map(str::trim).collect();
+    let col = |name: &str| header.iter().position(|h| *h == name);
+    let mut idx = [0usize; 8];
+    for (i, name) in SERIES_COLUMNS.iter().enumerate() {
+        idx[i] = col(name).with_context(|| format!("status CSV missing column {}", name))?;
+    }
+    let refill_idx: Vec<Option<usize>> = ["refill_blood", "refill_protein", "refill_sugar", "refill_brain", "refill_dw"]
+        .iter()
+        .map(|n| col(n))
+        .collect();
+
+    let mut steps = Vec::new();
+    for (line_no, line) in lines {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        if cells.len() != header.len() {
+            bail!("line {}: expected {} cells, got {}", line_no + 1, header.len(), cells.len());
+        }
+        let f = |i: usize| -> Result<f32> {
+            cells[idx[i]]
+                .parse()
+                .with_context(|| format!("line {}: bad {} {:?}", line_no + 1, SERIES_COLUMNS[i], cells[idx[i]]))
+        };
+        let refill = |i: usize| -> Result<u64> {
+            match refill_idx[i] {
+                Some(c) if !cells[c].is_empty() => {
+                    cells[c].parse().with_context(|| format!("line {}: bad refill {:?}", line_no + 1, cells[c]))
+                }
+                _ => Ok(0),
+            }
+        };
+        let dw_cell = cells[idx[7]];
+        steps.push(SimStep {
+            t_sec: cells[idx[0]]
+                .parse()
+                .with_context(|| format!("line {}: bad t_sec {:?}", line_no + 1, cells[idx[0]]))?,
+            status: BiophysicalStatus {
+                blood_stability_01: f(1)?,
+                protein_reserve_01: f(2)?,
+                sugar_stability_01: f(3)?,
+                brain_clarity_01: f(4)?,
+                chi_resilience_01: f(5)?,
+                lifeforce_trend_01: f(6)?,
+            },
+            dw_intensity_01: if dw_cell.is_empty() { None } else { Some(f(7)?) },
+            refill: HostTokens {
+                blood: refill(0)?,
+                protein: refill(1)?,
+                sugar: refill(2)?,
+                brain: refill(3)?,
+                dw: refill(4)?,
+            },
+        });
+    }
+    check_time_order(&steps)?;
+    Ok(steps)
+}
+
+pub fn parse_status_json(text: &str) -> Result<Vec<SimStep>> {
+    let steps: Vec<SimStep> = serde_json::from_str(text).context("status series JSON")?;
+    check_time_order(&steps)?;
+    Ok(steps)
+}
+
+fn check_time_order(steps: &[SimStep]) -> Result<()> {
+    if steps.windows(2).any(|w| w[1].t_sec < w[0].t_sec) {
+        bail!("status series is not in time order");
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Single run
+// ---------------------------------------------------------------------------
+
+/// State after one step: a point on the depletion curve.
+#[derive(Debug, Clone, Serialize)]
+pub struct SimSample {
+    pub t_sec: u64,
+    pub lifeforce_01: f32,
+    pub band: LifeforceBand,
+    pub dw_requested: bool,
+    pub dw_allowed: bool,
+    pub denial: Option<DwDenialReason>,
+    pub tokens: HostTokens,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SimSummary {
+    pub steps: usize,
+    pub dw_requests: u64,
+    pub dw_allowed: u64,
+    pub denials: BTreeMap<DwDenialReason, u64>,
+    /// Seconds spent in each band; a step's band holds until the next step.
+    pub seconds_in_red: u64,
+    pub seconds_in_yellow: u64,
+    pub seconds_in_green: u64,
+    pub red_fraction: f32,
+    pub blood_spent: u64,
+    pub protein_spent: u64,
+    pub sugar_spent: u64,
+    pub final_tokens: HostTokens,
+    /// First time the DW balance hit zero.
+    pub dw_exhausted_at_sec: Option<u64>,
+}
+
+impl SimSummary {
+    pub fn denial_count(&self, reason: DwDenialReason) -> u64 {
+        self.denials.get(&reason).copied().unwrap_or(0)
+    }
+
+    pub fn allow_rate(&self) -> f32 {
+        if self.dw_requests == 0 {
+            0.0
+        } else {
+            self.dw_allowed as f32 / self.dw_requests as f32
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimRun {
+    pub policy: LifeforcePolicy,
+    pub summary: SimSummary,
+    pub samples: Vec<SimSample>,
+}
+
+/// Replay `steps` through a governor built from `policy`. Steps are
+/// expected in time order, as the parsers guarantee; a step that goes back
+/// in time adds no dwell to its band.
+pub fn simulate(policy: &LifeforcePolicy, initial: &HostTokens, steps: &[SimStep]) -> SimRun {
+    let governor = LifeforceGovernor::new(policy.clone());
+    let mut tokens = initial.clone();
+    let mut summary = SimSummary { steps: steps.len(), ..Default::default() };
+    let mut samples = Vec::with_capacity(steps.len());
+
+    for (i, step) in steps.iter().enumerate() {
+        tokens.blood = tokens.blood.saturating_add(step.refill.blood);
+        tokens.protein = tokens.protein.saturating_add(step.refill.protein);
+        tokens.sugar = tokens.sugar.saturating_add(step.refill.sugar);
+        tokens.brain = tokens.brain.saturating_add(step.refill.brain);
+        tokens.dw = tokens.dw.saturating_add(step.refill.dw);
+
+        let lf = governor.compute_lifeforce(&step.status);
+        let mut sample = SimSample {
+            t_sec: step.t_sec,
+            lifeforce_01: lf.lifeforce_scalar_01,
+            band: lf.band,
+            dw_requested: step.dw_intensity_01.is_some(),
+            dw_allowed: false,
+            denial: None,
+            tokens: HostTokens::default(),
+        };
+        if let Some(intensity) = step.dw_intensity_01 {
+            summary.dw_requests += 1;
+            let decision = governor.decide_dw_spend(&mut tokens, &step.status, intensity);
+            sample.dw_allowed = decision.allowed;
+            sample.denial = decision.denial;
+            match decision.denial {
+                None => {
+                    summary.dw_allowed += 1;
+                    summary.blood_spent += decision.blood_spent;
+                    summary.protein_spent += decision.protein_spent;
+                    summary.sugar_spent += decision.sugar_spent;
+                }
+                Some(reason) => *summary.denials.entry(reason).or_default() += 1,
+            }
+        }
+        if tokens.dw == 0 && summary.dw_exhausted_at_sec.is_none() {
+            summary.dw_exhausted_at_sec = Some(step.t_sec);
+        }
+
+        let dwell = steps.get(i + 1).and_then(|next| next.t_sec.checked_sub(step.t_sec)).unwrap_or(0);
+        match lf.band {
+            LifeforceBand::Red => summary.seconds_in_red += dwell,
+            LifeforceBand::Yellow => summary.seconds_in_yellow += dwell,
+            LifeforceBand::Green => summary.seconds_in_green += dwell,
+        }
+        sample.tokens = tokens.clone();
+        samples.push(sample);
+    }
+
+    let span = summary.seconds_in_red + summary.seconds_in_yellow + summary.seconds_in_green;
+    if span > 0 {
+        summary.red_fraction = summary.seconds_in_red as f32 / span as f32;
+    }
+    summary.final_tokens = tokens;
+    SimRun { policy: policy.clone(), summary, samples }
+}
+
+// ---------------------------------------------------------------------------
+// Parameter sweeps
+// ---------------------------------------------------------------------------
+
+/// A tunable `LifeforcePolicy` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PolicyParam {
+    MinLifeforceForDw,
+    MinLifeforceForBloodSpend,
+    MaxBloodFractionPerDw,
+    MinChiForHeavyDw,
+    WeightBlood,
+    WeightProtein,
+    WeightSugar,
+    WeightClarity,
+    GreenMin,
+    YellowMin,
+}
+
+impl PolicyParam {
+    pub fn name(self) -> &'static str {
+        match self {
+            PolicyParam::MinLifeforceForDw => "min_lifeforce_for_dw",
+            PolicyParam::MinLifeforceForBloodSpend => "min_lifeforce_for_blood_spend",
+            PolicyParam::MaxBloodFractionPerDw => "max_blood_fraction_per_dw",
+            PolicyParam::MinChiForHeavyDw => "min_chi_for_heavy_dw",
+            PolicyParam::WeightBlood => "weight_blood",
+            PolicyParam::WeightProtein => "weight_protein",
+            PolicyParam::WeightSugar => "weight_sugar",
+            PolicyParam::WeightClarity => "weight_clarity",
+            PolicyParam::GreenMin => "green_min",
+            PolicyParam::YellowMin => "yellow_min",
+        }
+    }
+
+    pub fn set(self, policy: &mut LifeforcePolicy, value: f32) {
+        let slot = match self {
+            PolicyParam::MinLifeforceForDw => &mut policy.min_lifeforce_for_dw,
+            PolicyParam::MinLifeforceForBloodSpend => &mut policy.min_lifeforce_for_blood_spend,
+            PolicyParam::MaxBloodFractionPerDw => &mut policy.max_blood_fraction_per_dw,
+            PolicyParam::MinChiForHeavyDw => &mut policy.min_chi_for_heavy_dw,
+            PolicyParam::WeightBlood => &mut policy.weights.blood,
+            PolicyParam::WeightProtein => &mut policy.weights.protein,
+            PolicyParam::WeightSugar => &mut policy.weights.sugar,
+            PolicyParam::WeightClarity => &mut policy.weights.clarity,
+            PolicyParam::GreenMin => &mut policy.weights.green_min,
+            PolicyParam::YellowMin => &mut policy.weights.yellow_min,
+        };
+        *slot = value;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepAxis {
+    pub param: PolicyParam,
+    pub values: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepPoint {
+    pub params: Vec<(PolicyParam, f32)>,
+    pub summary: SimSummary,
+}
+
+/// Cartesian sweep over `axes` around `base`. Runs keep only their
+/// summaries; re-run `simulate` for a curve of interest.
+#[derive(Debug, Clone)]
+pub struct PolicySweep {
+    pub base: LifeforcePolicy,
+    pub axes: Vec<SweepAxis>,
+}
+
+impl PolicySweep {
+    pub fn new(base: LifeforcePolicy) -> Self {
+        Self { base, axes: Vec::new() }
+    }
+
+    pub fn vary(mut self, param: PolicyParam, values: &[f32]) -> Self {
+        self.axes.push(SweepAxis { param, values: values.to_vec() });
+        self
+    }
+
+    /// Every policy in the sweep, first axis varying slowest.
+    pub fn policies(&self) -> Vec<(Vec<(PolicyParam, f32)>, LifeforcePolicy)> {
+        let mut out = vec![(Vec::new(), self.base.clone())];
+        for axis in &self.axes {
+            out = out
+                .into_iter()
+                .flat_map(|(params, policy)| {
+                    axis.values.iter().map(move |&v| {
+                        let mut p = policy.clone();
+                        axis.param.set(&mut p, v);
+                        let mut params = params.clone();
+                        params.push((axis.param, v));
+                        (params, p)
+                    })
+                })
+                .collect();
+        }
+        out
+    }
+
+    pub fn run(&self, initial: &HostTokens, steps: &[SimStep]) -> Vec<SweepPoint> {
+        self.policies()
+            .into_iter()
+            .map(|(params, policy)| SweepPoint { params, summary: simulate(&policy, initial, steps).summary })
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Output
+// ---------------------------------------------------------------------------
+
+const DENIAL_COLUMNS: [DwDenialReason; 3] = [
+    DwDenialReason::NoDwTokens,
+    DwDenialReason::LifeforceBelowMinimum,
+    DwDenialReason::BloodFallbackDisallowed,
+];
+
+fn denial_name(reason: DwDenialReason) -> &'static str {
+    match reason {
+        DwDenialReason::NoDwTokens => "denied_no_dw_tokens",
+        DwDenialReason::LifeforceBelowMinimum => "denied_lifeforce_below_minimum",
+        DwDenialReason::BloodFallbackDisallowed => "denied_blood_fallback_disallowed",
+    }
+}
+
+fn band_name(band: LifeforceBand) -> &'static str {
+    match band {
+        LifeforceBand::Green => "green",
+        LifeforceBand::Yellow => "yellow",
+        LifeforceBand::Red => "red",
+    }
+}
+
+impl SimRun {
+    /// Depletion curve, one row per step.
+    pub fn curve_csv(&self) -> String {
+        let mut out = String::from("t_sec,lifeforce_01,band,dw_requested,dw_allowed,denial,blood,protein,sugar,brain,dw\n");
+        for s in &self.samples {
+            let _ = writeln!(
+                out,
+                "{},{:.4},{},{},{},{},{},{},{},{},{}",
+                s.t_sec,
+                s.lifeforce_01,
+                band_name(s.band),
+                s.dw_requested,
+                s.dw_allowed,
+                s.denial.map_or("", denial_name),
+                s.tokens.blood,
+                s.tokens.protein,
+                s.tokens.sugar,
+                s.tokens.brain,
+                s.tokens.dw,
+            );
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// One row per sweep point: the varied parameters, then the summary.
+pub fn sweep_csv(points: &[SweepPoint]) -> String {
+    let mut out = String::new();
+    let Some(first) = points.first() else { return out };
+    for (param, _) in &first.params {
+        out.push_str(param.name());
+        out.push(',');
+    }
+    out.push_str("dw_requests,dw_allowed,allow_rate");
+    for reason in DENIAL_COLUMNS {
+        out.push(',');
+        out.push_str(denial_name(reason));
+    }
+    out.push_str(",seconds_in_red,red_fraction,blood_spent,protein_spent,sugar_spent,final_blood,final_dw,dw_exhausted_at_sec\n");
+
+    for p in points {
+        for (_, v) in &p.params {
+            let _ = write!(out, "{},", v);
+        }
+        let s = &p.summary;
+        let _ = write!(out, "{},{},{:.4}", s.dw_requests, s.dw_allowed, s.allow_rate());
+        for reason in DENIAL_COLUMNS {
+            let _ = write!(out, ",{}", s.denial_count(reason));
+        }
+        let _ = writeln!(
+            out,
+            ",{},{:.4},{},{},{},{},{},{}",
+            s.seconds_in_red,
+            s.red_fraction,
+            s.blood_spent,
+            s.protein_spent,
+            s.sugar_spent,
+            s.final_tokens.blood,
+            s.final_tokens.dw,
+            s.dw_exhausted_at_sec.map(|t| t.to_string()).unwrap_or_default(),
+        );
+    }
+    out
+}
+
+pub fn sweep_json(points: &[SweepPoint]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(points)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERIES: &str = "\
+# recorded night, columns deliberately out of order
+t_sec,dw_intensity_01,blood_stability_01,protein_reserve_01,sugar_stability_01,brain_clarity_01,chi_resilience_01,lifeforce_trend_01,refill_dw
+0,1.0,1.0,1.0,1.0,1.0,1.0,1.0,
+10,1.0,1.0,1.0,1.0,1.0,1.0,1.0,
+20,1.0,0.1,0.1,0.1,0.1,0.1,0.1,2
+50,1.0,0.6,0.6,0.6,0.6,1.0,1.0,
+60,,1.0,1.0,1.0,1.0,1.0,1.0,
+";
+
+    fn initial() -> HostTokens {
+        HostTokens { blood: 100, protein: 0, sugar: 0, brain: 0, dw: 1 }
+    }
+
+    #[test]
+    fn test_parse_csv_columns_and_refills() {
+        let steps = parse_status_csv(SERIES).unwrap();
+        assert_eq!(steps.len(), 5);
+        assert_eq!(steps[2].t_sec, 20);
+        assert_eq!(steps[2].status.blood_stability_01, 0.1);
+        assert_eq!(steps[2].refill, HostTokens { dw: 2, ..Default::default() });
+        assert_eq!(steps[3].status.chi_resilience_01, 1.0);
+        assert_eq!(steps[4].dw_intensity_01, None);
+    }
+
+    #[test]
+    fn test_parse_csv_rejects_bad_input() {
+        let missing = SERIES.replace("lifeforce_trend_01", "trend");
+        assert!(parse_status_csv(&missing).unwrap_err().to_string().contains("lifeforce_trend_01"));
+
+        let short = SERIES.replace("60,,1.0,", "60,1.0,");
+        assert!(parse_status_csv(&short).is_err());
+
+        let backwards = SERIES.replace("\n50,", "\n5,");
+        assert!(parse_status_csv(&backwards).unwrap_err().to_string().contains("time order"));
+    }
+
+    #[test]
+    fn test_parse_json_roundtrip_and_order() {
+        let steps = parse_status_csv(SERIES).unwrap();
+        let json = serde_json::to_string(&steps).unwrap();
+        assert_eq!(parse_status_json(&json).unwrap(), steps);
+
+        let mut backwards = steps;
+        backwards.swap(1, 3);
+        let json = serde_json::to_string(&backwards).unwrap();
+        assert!(parse_status_json(&json).unwrap_err().to_string().contains("time order"));
+    }
+
+    #[test]
+    fn test_simulate_counts_denials_by_reason() {
+        let steps = parse_status_csv(SERIES).unwrap();
+        let run = simulate(&LifeforcePolicy::default(), &initial(), &steps);
+        let s = &run.summary;
+
+        assert_eq!(s.dw_requests, 4);
+        assert_eq!(s.dw_allowed, 1);
+        assert_eq!(s.denial_count(DwDenialReason::NoDwTokens), 1);
+        assert_eq!(s.denial_count(DwDenialReason::LifeforceBelowMinimum), 1);
+        assert_eq!(s.denial_count(DwDenialReason::BloodFallbackDisallowed), 1);
+        assert_eq!(s.allow_rate(), 0.25);
+        // The one allowed spend covered its whole metabolic need from blood,
+        // capped at 8% of 100.
+        assert_eq!(s.blood_spent, 8);
+        assert_eq!(s.dw_exhausted_at_sec, Some(0));
+        assert_eq!(s.final_tokens, HostTokens { blood: 92, dw: 2, ..Default::default() });
+    }
+
+    #[test]
+    fn test_simulate_band_dwell() {
+        let steps = parse_status_csv(SERIES).unwrap();
+        let run = simulate(&LifeforcePolicy::default(), &initial(), &steps);
+        let s = &run.summary;
+
+        assert_eq!(s.seconds_in_red, 30);
+        assert_eq!(s.seconds_in_yellow, 10);
+        assert_eq!(s.seconds_in_green, 20);
+        assert_eq!(s.red_fraction, 0.5);
+        let bands: Vec<LifeforceBand> = run.samples.iter().map(|x| x.band).collect();
+        assert_eq!(
+            bands,
+            [LifeforceBand::Green, LifeforceBand::Green, LifeforceBand::Red, LifeforceBand::Yellow, LifeforceBand::Green]
+        );
+    }
+
+    #[test]
+    fn test_simulate_tolerates_unordered_steps_and_huge_refills() {
+        let mut steps = parse_status_csv(SERIES).unwrap();
+        steps.swap(1, 3);
+        steps[0].refill.blood = u64::MAX;
+        let run = simulate(&LifeforcePolicy::default(), &initial(), &steps);
+        // Saturated, then the whole 13-token metabolic need comes from blood.
+        assert_eq!(run.samples[0].tokens.blood, u64::MAX - 13);
+        let s = &run.summary;
+        // 0 -> 50 -> 20 (back in time, no dwell) -> 10 -> 60.
+        assert_eq!(s.seconds_in_red + s.seconds_in_yellow + s.seconds_in_green, 50 + 50);
+    }
+
+    #[test]
+    fn test_policy_sweep_grid() {
+        let steps = parse_status_csv(SERIES).unwrap();
+        let sweep = PolicySweep::new(LifeforcePolicy::default())
+            .vary(PolicyParam::MinLifeforceForDw, &[0.3, 0.7])
+            .vary(PolicyParam::MaxBloodFractionPerDw, &[0.0, 0.08]);
+        let points = sweep.run(&initial(), &steps);
+
+        let grid: Vec<Vec<(PolicyParam, f32)>> = points.iter().map(|p| p.params.clone()).collect();
+        assert_eq!(
+            grid,
+            [
+                vec![(PolicyParam::MinLifeforceForDw, 0.3), (PolicyParam::MaxBloodFractionPerDw, 0.0)],
+                vec![(PolicyParam::MinLifeforceForDw, 0.3), (PolicyParam::MaxBloodFractionPerDw, 0.08)],
+                vec![(PolicyParam::MinLifeforceForDw, 0.7), (PolicyParam::MaxBloodFractionPerDw, 0.0)],
+                vec![(PolicyParam::MinLifeforceForDw, 0.7), (PolicyParam::MaxBloodFractionPerDw, 0.08)],
+            ]
+        );
+
+        // No blood fallback: nothing is ever allowed and the DW token survives.
+        let s = &points[0].summary;
+        assert_eq!(s.dw_allowed, 0);
+        assert_eq!(s.denial_count(DwDenialReason::BloodFallbackDisallowed), 3);
+        assert_eq!(s.final_tokens.dw, 3);
+
+        let base = LifeforcePolicy { min_lifeforce_for_dw: 0.3, ..LifeforcePolicy::default() };
+        assert_eq!(points[1].summary, simulate(&base, &initial(), &steps).summary);
+
+        // A 0.7 floor turns the yellow step into a lifeforce denial.
+        assert_eq!(points[3].summary.denial_count(DwDenialReason::LifeforceBelowMinimum), 2);
+        assert_eq!(points[3].summary.denial_count(DwDenialReason::BloodFallbackDisallowed), 0);
+
+        let csv = sweep_csv(&points);
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("min_lifeforce_for_dw,max_blood_fraction_per_dw,dw_requests,"));
+        assert_eq!(lines.count(), 4);
+    }
+}
@@ -0,0 +1,529 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::asset_eco_governor::{IntegrationType, NightlyEcoProfile, UpgradeDecision};
+
+/// Schema tag carried by every record; bump when the record shape changes.
+pub const UPGRADE_DECISION_SCHEMA: &str = "biospectre.upgrade_decision.v1";
+
+/// `prev_checksum` of the first record.
+pub const GENESIS_CHECKSUM: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpgradeOutcome {
+    Allowed,
+    Denied,
+}
+
+/// Everything needed to explain one upgrade decision after the fact.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpgradeDecisionRecord {
+    pub schema: String,
+    pub seq: u64,
+    pub decision_id: String,
+    pub host_id: String,
+    pub recorded_at_unix: u64,
+    pub integration: IntegrationType,
+    pub eco_cost: u64,
+    pub evolution_cost: u64,
+    /// SHA3-256 of the nightly profile the decision was made against;
+    /// `None` when the host had no nightly history.
+    pub nightly_profile_hash: Option<String>,
+    pub signer_did: Option<String>,
+    pub outcome: UpgradeOutcome,
+    pub reason: String,
+    pub prev_checksum: String,
+    /// SHA3-256 over `prev_checksum` and this record with `checksum` empty,
+    /// chaining each record to the one before it.
+    pub checksum: String,
+}
+
+/// Input for one record; the log fills in sequence and checksums.
+#[derive(Debug, Clone)]
+pub struct UpgradeDecisionInput<'a> {
+    pub host_id: &'a str,
+    pub at_unix: u64,
+    pub integration: IntegrationType,
+    pub eco_cost: u64,
+    pub evolution_cost: u64,
+    pub nightly_profile: Option<&'a NightlyEcoProfile>,
+    pub decision: &'a UpgradeDecision,
+}
+
+/// Stable hash of a nightly profile (its JSON form, fields in declaration order).
+pub fn nightly_profile_hash(profile: &NightlyEcoProfile) -> Result<String> {
+    Ok(hex::encode(Sha3_256::digest(serde_json::to_vec(profile)?)))
+}
+
+impl UpgradeDecisionRecord {
+    fn compute_checksum(&self) -> Result<String> {
+        let mut unsealed = self.clone();
+        unsealed.checksum = String::new();
+        let mut hasher = Sha3_256::new();
+        hasher.update(self.prev_checksum.as_bytes());
+        hasher.update(serde_json::to_vec(&unsealed)?);
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    pub fn allowed(&self) -> bool {
+        self.outcome == UpgradeOutcome::Allowed
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// Filter for `UpgradeDecisionLog::query`; unset fields match everything.
+/// The time range is `[from_unix, until_unix)`.
+#[derive(Debug, Clone, Default)]
+pub struct DecisionQuery {
+    pub host_id: Option<String>,
+    pub integration: Option<IntegrationType>,
+    pub outcome: Option<UpgradeOutcome>,
+    pub from_unix: Option<u64>,
+    pub until_unix: Option<u64>,
+}
+
+impl DecisionQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn host(mut self, host_id: &str) -> Self {
+        self.host_id = Some(host_id.to_string());
+        self
+    }
+
+    pub fn integration(mut self, integration: IntegrationType) -> Self {
+        self.integration = Some(integration);
+        self
+    }
+
+    pub fn outcome(mut self, outcome: UpgradeOutcome) -> Self {
+        self.outcome = Some(outcome);
+        self
+    }
+
+    pub fn between(mut self, from_unix: u64, until_unix: u64) -> Self {
+        self.from_unix = Some(from_unix);
+        self.until_unix = Some(until_unix);
+        self
+    }
+
+    pub fn matches(&self, record: &UpgradeDecisionRecord) -> bool {
+        self.host_id.as_ref().is_none_or(|h| *h == record.host_id)
+            && self.integration.is_none_or(|i| i == record.integration)
+            && self.outcome.is_none_or(|o| o == record.outcome)
+            && self.from_unix.is_none_or(|t| record.recorded_at_unix >= t)
+            && self.until_unix.is_none_or(|t| record.recorded_at_unix < t)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Append-only log
+// ---------------------------------------------------------------------------
+
+/// Append-only JSONL log of upgrade decisions with a checksum chain.
+/// Opening a log re-validates schema, sequence and every checksum, so an
+/// edited or reordered file, or one with a record missing from the middle,
+/// is rejected. Dropping records from the end leaves a valid chain and is
+/// not detected here: compare [`head_checksum`](Self::head_checksum) with a
+/// copy kept elsewhere (e.g. the mirrored ALN shard) to catch that.
+#[derive(Debug, Default)]
+pub struct UpgradeDecisionLog {
+    path: Option<PathBuf>,
+    records: Vec<UpgradeDecisionRecord>,
+}
+
+impl UpgradeDecisionLog {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Open (or create) a log file and verify its contents.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut log = Self {
+            path: Some(path.clone()),
+            records: Vec::new(),
+        };
+        if !path.exists() {
+            return Ok(log);
+        }
+        let file = File::open(&path).with_context(|| format!("open {}", path.display()))?;
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: UpgradeDecisionRecord = serde_json::from_str(&line)
+                .with_context(|| format!("{}:{}: record does not match {}", path.display(), i + 1, UPGRADE_DECISION_SCHEMA))?;
+            log.verify_next(&record)
+                .with_context(|| format!("{}:{}", path.display(), i + 1))?;
+            log.records.push(record);
+        }
+        Ok(log)
+    }
+
+    /// Checksum of the newest record, or `GENESIS_CHECKSUM` when empty.
+    pub fn head_checksum(&self) -> &str {
+        self.records.last().map_or(GENESIS_CHECKSUM, |r| r.checksum.as_str())
+    }
+
+    fn verify_next(&self, record: &UpgradeDecisionRecord) -> Result<()> {
+        if record.schema != UPGRADE_DECISION_SCHEMA {
+            bail!("unknown schema {:?} (expected {})", record.schema, UPGRADE_DECISION_SCHEMA);
+        }
+        let expected_seq = self.records.len() as u64 + 1;
+        if record.seq != expected_seq {
+            bail!("decision seq {} out of order (expected {})", record.seq, expected_seq);
+        }
+        if record.prev_checksum != self.head_checksum() {
+            bail!("decision seq {} does not chain to the previous record", record.seq);
+        }
+        if record.checksum != record.compute_checksum()? {
+            bail!("decision seq {} checksum mismatch", record.seq);
+        }
+        Ok(())
+    }
+
+    pub fn records(&self) -> &[UpgradeDecisionRecord] {
+        &self.records
+    }
+
+    /// Build, seal and durably append a record for `input`. On error
+    /// nothing is recorded.
+    pub fn append(&mut self, input: UpgradeDecisionInput<'_>) -> Result<UpgradeDecisionRecord> {
+        if input.host_id.is_empty() {
+            bail!("upgrade decision needs a host id");
+        }
+        let seq = self.records.len() as u64 + 1;
+        let mut record = UpgradeDecisionRecord {
+            schema: UPGRADE_DECISION_SCHEMA.to_string(),
+            seq,
+            decision_id: format!("upg-{}-{}", input.at_unix, seq),
+            host_id: input.host_id.to_string(),
+            recorded_at_unix: input.at_unix,
+            integration: input.integration,
+            eco_cost: input.eco_cost,
+            evolution_cost: input.evolution_cost,
+            nightly_profile_hash: input.nightly_profile.map(nightly_profile_hash).transpose()?,
+            signer_did: input.decision.did_signature.as_ref().map(|s| s.did.clone()),
+            outcome: if input.decision.allowed {
+                UpgradeOutcome::Allowed
+            } else {
+                UpgradeOutcome::Denied
+            },
+            reason: input.decision.reason.clone(),
+            prev_checksum: self.head_checksum().to_string(),
+            checksum: String::new(),
+        };
+        record.checksum = record.compute_checksum()?;
+
+        // Write ahead: the record is only visible once it is on disk.
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("open {}", path.display()))?;
+            writeln!(file, "{}", serde_json::to_string(&record)?)?;
+            file.sync_all()?;
+        }
+        self.records.push(record.clone());
+        Ok(record)
+    }
+
+    pub fn query<'a>(&'a self, query: &'a DecisionQuery) -> impl Iterator<Item = &'a UpgradeDecisionRecord> + 'a {
+        self.records.iter().filter(move |r| query.matches(r))
+    }
+
+    /// Matching records as JSONL, checksums included, so an export can be
+    /// spot-checked against the log it came from.
+    pub fn export_jsonl(&self, query: &DecisionQuery) -> Result<String> {
+        let mut out = String::new();
+        for record in self.query(query) {
+            out.push_str(&serde_json::to_string(record)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    pub fn export_csv(&self, query: &DecisionQuery) -> String {
+        let mut out = String::from(
+            "seq,decision_id,host_id,recorded_at_unix,integration,eco_cost,evolution_cost,\
+             nightly_profile_hash,signer_did,outcome,reason,checksum\n",
+        );
+        for r in self.query(query) {
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{:?},{},{},{},{},{},{},{}",
+                r.seq,
+                r.decision_id,
+                csv_field(&r.host_id),
+                r.recorded_at_unix,
+                r.integration,
+                r.eco_cost,
+                r.evolution_cost,
+                r.nightly_profile_hash.as_deref().unwrap_or(""),
+                csv_field(r.signer_did.as_deref().unwrap_or("")),
+                if r.allowed() { "allowed" } else { "denied" },
+                csv_field(&r.reason),
+                r.checksum,
+            );
+        }
+        out
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::did_verification::DIDSignature;
+    use std::fs;
+
+    /// Temp directory removed on drop.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("upgrade_decision_log_{}_{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn log_path(&self) -> PathBuf {
+            self.0.join("decisions.jsonl")
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn night() -> NightlyEcoProfile {
+        NightlyEcoProfile {
+            avg_flops_per_epoch: 1.0e9,
+            avg_energynj_per_epoch: 5.0e3,
+            energy_reduction_vs_baseline_pct: 30.0,
+            device_hours_saved: 2.0,
+            cognitive_clarity_score_01: 0.8,
+            emotional_salience_score_01: 0.3,
+        }
+    }
+
+    fn decision(allowed: bool, reason: &str) -> UpgradeDecision {
+        UpgradeDecision {
+            allowed,
+            reason: reason.to_string(),
+            did_signature: Some(DIDSignature {
+                did: "did:key:z6Mkexample".to_string(),
+                signature: String::new(),
+                message: String::new(),
+            }),
+        }
+    }
+
+    fn append(log: &mut UpgradeDecisionLog, host_id: &str, at_unix: u64, integration: IntegrationType, allowed: bool) {
+        let profile = night();
+        log.append(UpgradeDecisionInput {
+            host_id,
+            at_unix,
+            integration,
+            eco_cost: 50,
+            evolution_cost: 5,
+            nightly_profile: Some(&profile),
+            decision: &decision(allowed, "reason"),
+        })
+        .unwrap();
+    }
+
+    /// Three decisions: host-a at 100 and 200, host-b at 300.
+    fn sample_log(path: &Path) -> UpgradeDecisionLog {
+        let mut log = UpgradeDecisionLog::open(path).unwrap();
+        append(&mut log, "host-a", 100, IntegrationType::EcoOptimizer, true);
+        append(&mut log, "host-a", 200, IntegrationType::FocusStabilization, false);
+        append(&mut log, "host-b", 300, IntegrationType::EcoOptimizer, true);
+        log
+    }
+
+    fn lines(path: &Path) -> Vec<String> {
+        fs::read_to_string(path).unwrap().lines().map(str::to_string).collect()
+    }
+
+    fn write_lines(path: &Path, lines: &[String]) {
+        fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn test_append_and_reopen_roundtrip() {
+        let scratch = Scratch::new("roundtrip");
+        let log = sample_log(&scratch.log_path());
+        let reopened = UpgradeDecisionLog::open(scratch.log_path()).unwrap();
+        assert_eq!(reopened.records(), log.records());
+        assert_eq!(reopened.head_checksum(), log.head_checksum());
+
+        let first = &reopened.records()[0];
+        assert_eq!(first.seq, 1);
+        assert_eq!(first.prev_checksum, GENESIS_CHECKSUM);
+        assert_eq!(first.nightly_profile_hash, Some(nightly_profile_hash(&night()).unwrap()));
+        assert_eq!(first.signer_did.as_deref(), Some("did:key:z6Mkexample"));
+        assert_eq!(reopened.records()[1].prev_checksum, first.checksum);
+        assert!(!reopened.records()[1].allowed());
+
+        // Appends after reopening continue the chain.
+        let mut reopened = reopened;
+        append(&mut reopened, "host-b", 400, IntegrationType::EcoOptimizer, false);
+        assert_eq!(UpgradeDecisionLog::open(scratch.log_path()).unwrap().records().len(), 4);
+    }
+
+    #[test]
+    fn test_record_without_history_has_no_profile_hash() {
+        let mut log = UpgradeDecisionLog::in_memory();
+        let record = log
+            .append(UpgradeDecisionInput {
+                host_id: "host-a",
+                at_unix: 1,
+                integration: IntegrationType::EcoOptimizer,
+                eco_cost: 0,
+                evolution_cost: 0,
+                nightly_profile: None,
+                decision: &decision(false, "No nightly history for host host-a."),
+            })
+            .unwrap();
+        assert_eq!(record.nightly_profile_hash, None);
+
+        let err = log.append(UpgradeDecisionInput {
+            host_id: "",
+            at_unix: 2,
+            integration: IntegrationType::EcoOptimizer,
+            eco_cost: 0,
+            evolution_cost: 0,
+            nightly_profile: None,
+            decision: &decision(false, "x"),
+        });
+        assert!(err.is_err());
+        assert_eq!(log.records().len(), 1);
+    }
+
+    #[test]
+    fn test_tampered_field_is_rejected() {
+        let scratch = Scratch::new("tamper");
+        sample_log(&scratch.log_path());
+        let mut lines = lines(&scratch.log_path());
+        lines[1] = lines[1].replace("\"outcome\":\"denied\"", "\"outcome\":\"allowed\"");
+        write_lines(&scratch.log_path(), &lines);
+
+        let err = UpgradeDecisionLog::open(scratch.log_path()).unwrap_err();
+        assert!(format!("{:#}", err).contains("checksum mismatch"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_reordered_or_missing_lines_are_rejected() {
+        let scratch = Scratch::new("reorder");
+        sample_log(&scratch.log_path());
+        let original = lines(&scratch.log_path());
+
+        let mut swapped = original.clone();
+        swapped.swap(1, 2);
+        write_lines(&scratch.log_path(), &swapped);
+        let err = UpgradeDecisionLog::open(scratch.log_path()).unwrap_err();
+        assert!(format!("{:#}", err).contains("out of order"), "{:#}", err);
+
+        let gap = [original[0].clone(), original[2].clone()];
+        write_lines(&scratch.log_path(), &gap);
+        assert!(UpgradeDecisionLog::open(scratch.log_path()).is_err());
+    }
+
+    #[test]
+    fn test_dropped_tail_shows_only_in_head_checksum() {
+        let scratch = Scratch::new("tail");
+        let head = sample_log(&scratch.log_path()).head_checksum().to_string();
+        let original = lines(&scratch.log_path());
+        write_lines(&scratch.log_path(), &original[..2]);
+
+        let truncated = UpgradeDecisionLog::open(scratch.log_path()).unwrap();
+        assert_eq!(truncated.records().len(), 2);
+        assert_ne!(truncated.head_checksum(), head);
+    }
+
+    #[test]
+    fn test_schema_mismatch_is_rejected() {
+        let scratch = Scratch::new("schema");
+        sample_log(&scratch.log_path());
+        let mut lines = lines(&scratch.log_path());
+        lines[0] = lines[0].replace(UPGRADE_DECISION_SCHEMA, "biospectre.upgrade_decision.v0");
+        write_lines(&scratch.log_path(), &lines);
+        let err = UpgradeDecisionLog::open(scratch.log_path()).unwrap_err();
+        assert!(format!("{:#}", err).contains("unknown schema"), "{:#}", err);
+
+        // A record with an extra field does not match v1 either.
+        sample_log(&scratch.0.join("extra.jsonl"));
+        let mut lines = self::lines(&scratch.0.join("extra.jsonl"));
+        lines[0] = lines[0].replacen('{', "{\"note\":\"x\",", 1);
+        write_lines(&scratch.0.join("extra.jsonl"), &lines);
+        let err = UpgradeDecisionLog::open(scratch.0.join("extra.jsonl")).unwrap_err();
+        assert!(format!("{:#}", err).contains("does not match"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let scratch = Scratch::new("query");
+        let log = sample_log(&scratch.log_path());
+        let seqs = |q: DecisionQuery| log.query(&q).map(|r| r.seq).collect::<Vec<_>>();
+
+        assert_eq!(seqs(DecisionQuery::new()), [1, 2, 3]);
+        assert_eq!(seqs(DecisionQuery::new().host("host-a")), [1, 2]);
+        assert_eq!(seqs(DecisionQuery::new().integration(IntegrationType::EcoOptimizer)), [1, 3]);
+        assert_eq!(seqs(DecisionQuery::new().outcome(UpgradeOutcome::Denied)), [2]);
+        assert_eq!(seqs(DecisionQuery::new().host("host-a").integration(IntegrationType::EcoOptimizer)), [1]);
+        // `from` is inclusive, `until` exclusive.
+        assert_eq!(seqs(DecisionQuery::new().between(100, 300)), [1, 2]);
+        assert_eq!(seqs(DecisionQuery::new().between(101, 301)), [2, 3]);
+        assert_eq!(seqs(DecisionQuery::new().between(200, 200)), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_exports() {
+        let mut log = UpgradeDecisionLog::in_memory();
+        let profile = night();
+        log.append(UpgradeDecisionInput {
+            host_id: "host,a",
+            at_unix: 5,
+            integration: IntegrationType::EcoOptimizer,
+            eco_cost: 50,
+            evolution_cost: 5,
+            nightly_profile: Some(&profile),
+            decision: &decision(false, "Energy \"reduction\" low,\nretry"),
+        })
+        .unwrap();
+
+        let csv = log.export_csv(&DecisionQuery::new());
+        let row = csv.split_once('\n').unwrap().1;
+        assert!(row.starts_with("1,upg-5-1,\"host,a\",5,EcoOptimizer,50,5,"), "{}", row);
+        assert!(row.contains(",denied,\"Energy \"\"reduction\"\" low,\nretry\","), "{}", row);
+
+        let jsonl = log.export_jsonl(&DecisionQuery::new()).unwrap();
+        let record: UpgradeDecisionRecord = serde_json::from_str(jsonl.trim_end()).unwrap();
+        assert_eq!(&record, &log.records()[0]);
+    }
+}
@@ -1,9 +1,16 @@
 use biospectre_core::asset_eco_governor::*;
-use biospectre_core::did_verification::DIDSignature;
+use biospectre_core::did_verification::{
+    configured_resolver, BostromKeyEntry, BostromKeyRegistry, DIDSignature, DidResolver,
+};
+use biospectre_core::eco_policy_state::HostPolicyState;
 use biospectre_core::qpudatashards::QPUDataShard;
+use biospectre_core::upgrade_decision_log::{
+    DecisionQuery, UpgradeDecisionInput, UpgradeDecisionLog, UpgradeDecisionRecord,
+};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
 
 /// Configuration for EcoGovernor integration with RealityOS
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,43 +18,123 @@ pub struct EcoGovernorConfig {
     pub stakeholder_did: String,
     pub policy: UpgradePolicy,
     pub aln_shard_path: PathBuf,
+    /// Append-only JSONL log of every upgrade decision.
+    pub decision_log_path: PathBuf,
+    /// Public keys of Bostrom stakeholder addresses.
+    #[serde(default)]
+    pub bostrom_keys: Vec<BostromKeyEntry>,
+    /// Directory of pre-fetched did:web documents.
+    #[serde(default)]
+    pub did_web_cache_dir: Option<PathBuf>,
 }
 
 /// EcoGovernor service for RealityOS
 pub struct EcoGovernorService {
     config: EcoGovernorConfig,
     governor: AssetEcoGovernor,
+    decision_log: Mutex<UpgradeDecisionLog>,
 }
 
 impl EcoGovernorService {
+    /// Fails if the stakeholder DID cannot be resolved with the configured
+    /// keys, since every upgrade signature would then be rejected.
     pub fn new(config: EcoGovernorConfig) -> Result<Self> {
-        let governor = AssetEcoGovernor::new(config.policy, &config.stakeholder_did);
+        let bostrom_keys = BostromKeyRegistry::from_entries(&config.bostrom_keys)
+            .context("Invalid stakeholder key in configuration")?;
+        let resolver = configured_resolver(bostrom_keys, config.did_web_cache_dir.clone());
+        resolver
+            .resolve(&config.stakeholder_did)
+            .with_context(|| format!("Cannot resolve stakeholder DID {}", config.stakeholder_did))?;
+        let governor = AssetEcoGovernor::new(config.policy.clone(), &config.stakeholder_did)
+            .with_resolver(resolver);
+        let decision_log = UpgradeDecisionLog::open(&config.decision_log_path)
+            .context("Failed to open upgrade decision log")?;
         Ok(Self {
             config,
             governor,
+            decision_log: Mutex::new(decision_log),
         })
     }
 
-    /// Process an upgrade request with DID verification
+    fn decision_log(&self) -> Result<MutexGuard<'_, UpgradeDecisionLog>> {
+        self.decision_log
+            .lock()
+            .map_err(|_| anyhow::anyhow!("upgrade decision log lock poisoned"))
+    }
+
+    /// Process an upgrade request with DID verification. The decision is
+    /// appended to the decision log first and `wallet` is only debited once
+    /// that append succeeds; if it fails the wallet is left untouched and
+    /// the error returned.
+    #[allow(clippy::too_many_arguments)]
     pub fn process_upgrade_request(
         &self,
+        host_id: &str,
         wallet: &mut AssetWallet,
         nightly_profile: &NightlyEcoProfile,
         integration: IntegrationType,
         eco_cost: u64,
         evolution_cost: u64,
         signature: DIDSignature,
-    ) -> UpgradeDecision {
-        self.governor.decide_upgrade(
-            wallet,
+    ) -> Result<(UpgradeDecision, UpgradeDecisionRecord)> {
+        let mut debited = wallet.clone();
+        let decision = self.governor.decide_upgrade(
+            &mut debited,
             nightly_profile,
             integration,
             eco_cost,
             evolution_cost,
             Some(signature),
+        );
+        let record = self.decision_log()?.append(UpgradeDecisionInput {
+            host_id,
+            at_unix: chrono::Utc::now().timestamp().max(0) as u64,
+            integration,
+            eco_cost,
+            evolution_cost,
+            nightly_profile: Some(nightly_profile),
+            decision: &decision,
+        })?;
+        *wallet = debited;
+        Ok((decision, record))
+    }
+
+    /// Process an upgrade request against a host's persisted policy state
+    /// (history, trend, quotas and journaled wallet), logging the decision
+    /// to the same decision log.
+    pub fn process_host_upgrade_request(
+        &self,
+        state: &mut HostPolicyState,
+        integration: IntegrationType,
+        eco_cost: u64,
+        evolution_cost: u64,
+        signature: DIDSignature,
+    ) -> Result<(UpgradeDecision, UpgradeDecisionRecord)> {
+        let mut log = self.decision_log()?;
+        self.governor.decide_upgrade_for_host(
+            state,
+            &mut log,
+            integration,
+            eco_cost,
+            evolution_cost,
+            Some(signature),
+            chrono::Utc::now().timestamp().max(0) as u64,
         )
     }
 
+    /// Logged decisions matching `query`, oldest first.
+    pub fn query_decisions(&self, query: &DecisionQuery) -> Result<Vec<UpgradeDecisionRecord>> {
+        Ok(self.decision_log()?.query(query).cloned().collect())
+    }
+
+    pub fn export_decisions_csv(&self, query: &DecisionQuery) -> Result<String> {
+        Ok(self.decision_log()?.export_csv(query))
+    }
+
+    pub fn export_decisions_jsonl(&self, query: &DecisionQuery) -> Result<String> {
+        self.decision_log()?.export_jsonl(query)
+    }
+
     /// Load and validate ALN shard for governance
     pub fn load_aln_shard(&self) -> Result<QPUDataShard> {
         let shard = QPUDataShard::load(&self.config.aln_shard_path)
@@ -61,23 +148,14 @@ impl EcoGovernorService {
         Ok(shard)
     }
 
-    /// Apply upgrade decision to ALN shard
+    /// Mirror a logged decision into the ALN shard's upgrade_decision_log.
     pub fn apply_upgrade_decision(
         &self,
         shard: &mut QPUDataShard,
-        decision: &UpgradeDecision,
+        record: &UpgradeDecisionRecord,
     ) -> Result<()> {
-        // Add decision to upgrade_decision_log
-        let decision_entry = serde_json::json!({
-            "decision_id": format!("upg-{}", chrono::Utc::now().timestamp()),
-            "integration_type": decision.reason,
-            "eco_cost": decision.reason,
-            "evolution_cost": decision.reason,
-            "allowed": decision.allowed,
-            "reason": decision.reason,
-            "did_signature": decision.did_signature.map(|sig| serde_json::json!(sig)),
-        });
-        
+        let decision_entry = serde_json::to_value(record)?;
+
         // Update shard with new decision
         shard.update("upgrade_decision_log", decision_entry)?;
         
@@ -0,0 +1,519 @@
+// =============================================================================
+// FILE: anchor_outbox.rs
+// PROJECT: Reality.os / SovereigntyCore
+// MODULE: Guards / Durable Googolswarm Anchoring Outbox
+// VERSION: 1.0.0
+// LICENSE: ALN-Sovereign-1.0 (Neurorights-Compliant)
+// DESCRIPTION:
+//   SABOTAGEEVENTs are written to QPU.Datashard first and queued here for
+//   anchoring. Anchoring is retried with exponential backoff until a txid
+//   arrives; entries are never dropped. The queue is persisted as JSON Lines
+//   so pending anchors survive restarts. The outbox lock is only held to
+//   claim and settle entries, never across a client call. MockAnchorService
+//   provides a local, network-free anchor endpoint for tests and offline labs.
+// =============================================================================
+
+#![deny(clippy::all)]
+#![deny(unsafe_code)]
+#![forbid(missing_docs)]
+
+use super::sabotage_detector::SabotageEvent;
+use crate::googolswarm::GoogolswarmAnchor;
+use crate::qpu_datashard::QpuDatashard;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Default wait for the inline anchoring attempt of a new event; a slower
+/// client finishes in the background and settles the entry itself.
+pub const INLINE_ANCHOR_TIMEOUT: Duration = Duration::from_secs(2);
+
+// =============================================================================
+// ANCHOR CLIENT / SINK ABSTRACTIONS
+// =============================================================================
+
+/// Anything that can anchor a SABOTAGEEVENT and return a transaction ID.
+pub trait AnchorClient {
+    /// Anchors the event; returns the txid or a transport error.
+    fn anchor(&self, event: &SabotageEvent) -> Result<String, String>;
+}
+
+impl AnchorClient for GoogolswarmAnchor {
+    fn anchor(&self, event: &SabotageEvent) -> Result<String, String> {
+        self.anchor_event(event).map_err(|e| e.to_string())
+    }
+}
+
+/// Where anchor txids are written back once they arrive.
+pub trait AnchorSink {
+    /// Records `txid` against the stored event.
+    fn apply_anchor(&self, event_id: u64, txid: &str) -> Result<(), String>;
+}
+
+impl AnchorSink for QpuDatashard {
+    fn apply_anchor(&self, event_id: u64, txid: &str) -> Result<(), String> {
+        self.update_record_anchor(event_id, txid)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Local anchor service: deterministic txids, switchable offline mode and
+/// injectable failures. No network access.
+#[derive(Debug, Default)]
+pub struct MockAnchorService {
+    offline: AtomicBool,
+    fail_next: AtomicU32,
+    calls: AtomicU32,
+}
+
+impl MockAnchorService {
+    /// Creates an online mock service.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Simulates loss (or recovery) of connectivity.
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
+    /// Fails the next `n` anchor calls.
+    pub fn fail_next(&self, n: u32) {
+        self.fail_next.store(n, Ordering::SeqCst);
+    }
+
+    /// Number of anchor calls received.
+    pub fn calls(&self) -> u32 {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    /// The txid this service issues for an event.
+    pub fn txid_for(event_id: u64) -> String {
+        format!("googolswarm_mock_{:016x}", event_id)
+    }
+}
+
+impl AnchorClient for MockAnchorService {
+    fn anchor(&self, event: &SabotageEvent) -> Result<String, String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.offline.load(Ordering::SeqCst) {
+            return Err("mock anchor service offline".into());
+        }
+        let pending = self.fail_next.load(Ordering::SeqCst);
+        if pending > 0 {
+            self.fail_next.store(pending - 1, Ordering::SeqCst);
+            return Err("mock anchor service injected failure".into());
+        }
+        Ok(Self::txid_for(event.event_id))
+    }
+}
+
+impl<T: AnchorClient + ?Sized> AnchorClient for Arc<T> {
+    fn anchor(&self, event: &SabotageEvent) -> Result<String, String> {
+        (**self).anchor(event)
+    }
+}
+
+// =============================================================================
+// OUTBOX
+// =============================================================================
+
+/// Exponential backoff between anchoring attempts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Delay after the first failure, in milliseconds.
+    pub base_delay_ms: i64,
+    /// Upper bound on the delay, in milliseconds.
+    pub max_delay_ms: i64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay_ms: 2_000,
+            max_delay_ms: 15 * 60 * 1_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the next attempt after `attempts` failures.
+    pub fn delay_after(&self, attempts: u32) -> ChronoDuration {
+        let factor = 1i64 << attempts.saturating_sub(1).min(30);
+        ChronoDuration::milliseconds(self.base_delay_ms.saturating_mul(factor).min(self.max_delay_ms))
+    }
+}
+
+/// One event awaiting anchoring or anchor write-back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    /// The event as written to QPU.Datashard.
+    pub event: SabotageEvent,
+    /// When the event was queued.
+    pub enqueued_at: DateTime<Utc>,
+    /// Failed anchoring attempts so far.
+    pub attempts: u32,
+    /// Earliest time of the next attempt.
+    pub next_attempt_at: DateTime<Utc>,
+    /// Last anchoring or write-back error.
+    pub last_error: Option<String>,
+    /// Txid received but not yet written back to the datashard.
+    pub txid: Option<String>,
+}
+
+/// Outcome of one drain pass.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DrainReport {
+    /// Events anchored and written back in this pass.
+    pub anchored: Vec<(u64, String)>,
+    /// Events whose attempt failed, with the error.
+    pub failed: Vec<(u64, String)>,
+    /// Entries still queued after the pass.
+    pub pending: usize,
+}
+
+/// Result of one attempt made outside the outbox lock.
+struct Attempt {
+    event_id: u64,
+    /// Txid received, even if the sink then failed.
+    txid: Option<String>,
+    /// The txid once written back, or the error.
+    result: Result<String, String>,
+}
+
+impl Attempt {
+    fn run(entry: &OutboxEntry, client: &dyn AnchorClient, sink: &dyn AnchorSink) -> Self {
+        let event_id = entry.event.event_id;
+        let txid = match entry.txid.clone() {
+            Some(txid) => Ok(txid),
+            None => client.anchor(&entry.event),
+        };
+        match txid {
+            Ok(txid) => Self {
+                event_id,
+                result: sink.apply_anchor(event_id, &txid).map(|_| txid.clone()),
+                txid: Some(txid),
+            },
+            Err(err) => Self {
+                event_id,
+                txid: None,
+                result: Err(err),
+            },
+        }
+    }
+}
+
+/// Durable FIFO of events awaiting Googolswarm anchoring.
+#[derive(Debug)]
+pub struct AnchorOutbox {
+    path: Option<PathBuf>,
+    entries: Vec<OutboxEntry>,
+    /// Entries claimed by an attempt in progress; not persisted, so a
+    /// restart retries them.
+    in_flight: HashSet<u64>,
+    /// Backoff between attempts.
+    pub policy: RetryPolicy,
+}
+
+impl AnchorOutbox {
+    /// Non-durable outbox (tests, or before a path is configured).
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: Vec::new(),
+            in_flight: HashSet::new(),
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Opens (or creates) a durable outbox at `path`, reloading pending entries.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let mut entries = Vec::new();
+        if path.exists() {
+            let raw = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            for (i, line) in raw.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+                let entry: OutboxEntry = serde_json::from_str(line)
+                    .map_err(|e| format!("{}:{}: {}", path.display(), i + 1, e))?;
+                entries.push(entry);
+            }
+        }
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            entries,
+            in_flight: HashSet::new(),
+            policy: RetryPolicy::default(),
+        })
+    }
+
+    /// Queued entries, oldest first.
+    pub fn entries(&self) -> &[OutboxEntry] {
+        &self.entries
+    }
+
+    /// Number of queued entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if nothing is queued.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Queues an event for anchoring, due immediately.
+    pub fn enqueue(&mut self, event: &SabotageEvent, now: DateTime<Utc>) -> Result<(), String> {
+        if self.entries.iter().any(|e| e.event.event_id == event.event_id) {
+            return Ok(());
+        }
+        self.entries.push(OutboxEntry {
+            event: event.clone(),
+            enqueued_at: now,
+            attempts: 0,
+            next_attempt_at: now,
+            last_error: None,
+            txid: None,
+        });
+        self.persist()
+    }
+
+    /// Attempts every due entry. Anchoring errors reschedule the entry with
+    /// backoff; a received txid is kept until the sink accepts it. Needs
+    /// exclusive access; use [`AnchorOutbox::drain_shared`] for an outbox
+    /// behind a mutex.
+    pub fn drain(
+        &mut self,
+        client: &dyn AnchorClient,
+        sink: &dyn AnchorSink,
+        now: DateTime<Utc>,
+    ) -> Result<DrainReport, String> {
+        let attempts = self
+            .claim(now, None)
+            .iter()
+            .map(|entry| Attempt::run(entry, client, sink))
+            .collect();
+        self.settle(attempts, now)
+    }
+
+    /// Like [`AnchorOutbox::drain`], but only holds the lock to claim due
+    /// entries and to record the results, never across a client call.
+    pub fn drain_shared(
+        outbox: &Mutex<AnchorOutbox>,
+        client: &dyn AnchorClient,
+        sink: &dyn AnchorSink,
+        now: DateTime<Utc>,
+    ) -> Result<DrainReport, String> {
+        let claimed = Self::lock(outbox)?.claim(now, None);
+        let attempts = claimed
+            .iter()
+            .map(|entry| Attempt::run(entry, client, sink))
+            .collect();
+        Self::lock(outbox)?.settle(attempts, now)
+    }
+
+    /// Attempts only `event_id`, waiting at most `timeout` for the client.
+    /// On timeout the attempt carries on in the background and settles the
+    /// entry when the client returns; the returned report then lists
+    /// nothing for the event.
+    pub fn attempt_shared<C, S>(
+        outbox: &Arc<Mutex<AnchorOutbox>>,
+        event_id: u64,
+        client: Arc<C>,
+        sink: Arc<S>,
+        now: DateTime<Utc>,
+        timeout: Duration,
+    ) -> Result<DrainReport, String>
+    where
+        C: AnchorClient + Send + Sync + ?Sized + 'static,
+        S: AnchorSink + Send + Sync + 'static,
+    {
+        let claimed = {
+            let mut guard = Self::lock(outbox)?;
+            let claimed = guard.claim(now, Some(event_id));
+            if claimed.is_empty() {
+                return Ok(DrainReport {
+                    pending: guard.len(),
+                    ..DrainReport::default()
+                });
+            }
+            claimed
+        };
+
+        let (done, report) = mpsc::channel();
+        let shared = outbox.clone();
+        std::thread::spawn(move || {
+            let attempts = claimed
+                .iter()
+                .map(|entry| Attempt::run(entry, &client, sink.as_ref()))
+                .collect();
+            let settled = Self::lock(&shared).and_then(|mut o| o.settle(attempts, Utc::now()));
+            let _ = done.send(settled);
+        });
+
+        match report.recv_timeout(timeout) {
+            Ok(settled) => settled,
+            Err(_) => Ok(DrainReport {
+                pending: Self::lock(outbox)?.len(),
+                ..DrainReport::default()
+            }),
+        }
+    }
+
+    fn lock(outbox: &Mutex<AnchorOutbox>) -> Result<std::sync::MutexGuard<'_, AnchorOutbox>, String> {
+        outbox.lock().map_err(|e| e.to_string())
+    }
+
+    /// Marks due entries (or just `only`) as in flight and returns copies to
+    /// attempt. In-flight entries are skipped by other passes.
+    fn claim(&mut self, now: DateTime<Utc>, only: Option<u64>) -> Vec<OutboxEntry> {
+        let mut claimed = Vec::new();
+        for entry in &self.entries {
+            let id = entry.event.event_id;
+            if entry.next_attempt_at > now
+                || only.is_some_and(|only| only != id)
+                || self.in_flight.contains(&id)
+            {
+                continue;
+            }
+            self.in_flight.insert(id);
+            claimed.push(entry.clone());
+        }
+        claimed
+    }
+
+    /// Records the results of claimed entries and releases them.
+    fn settle(&mut self, attempts: Vec<Attempt>, now: DateTime<Utc>) -> Result<DrainReport, String> {
+        let mut report = DrainReport::default();
+
+        for attempt in attempts {
+            self.in_flight.remove(&attempt.event_id);
+            let Some(i) = self.entries.iter().position(|e| e.event.event_id == attempt.event_id) else {
+                continue;
+            };
+            match attempt.result {
+                Ok(txid) => {
+                    self.entries.remove(i);
+                    report.anchored.push((attempt.event_id, txid));
+                }
+                Err(err) => {
+                    let entry = &mut self.entries[i];
+                    if attempt.txid.is_some() {
+                        entry.txid = attempt.txid;
+                    }
+                    entry.attempts += 1;
+                    entry.next_attempt_at = now + self.policy.delay_after(entry.attempts);
+                    entry.last_error = Some(err.clone());
+                    report.failed.push((attempt.event_id, err));
+                }
+            }
+        }
+
+        report.pending = self.entries.len();
+        self.persist()?;
+        Ok(report)
+    }
+
+    /// Rewrites the outbox file atomically (temp file + rename).
+    fn persist(&self) -> Result<(), String> {
+        let path = match &self.path {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+        let tmp = path.with_extension("jsonl.tmp");
+        let mut file = fs::File::create(&tmp).map_err(|e| format!("{}: {}", tmp.display(), e))?;
+        for entry in &self.entries {
+            let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+            writeln!(file, "{}", line).map_err(|e| e.to_string())?;
+        }
+        file.sync_all().map_err(|e| e.to_string())?;
+        fs::rename(&tmp, path).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+// =============================================================================
+// BACKGROUND WORKER
+// =============================================================================
+
+/// Handle to a background anchoring thread.
+pub struct AnchorWorker {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl AnchorWorker {
+    /// Drains `outbox` every `poll` until stopped. Errors stay in the outbox
+    /// entries (`last_error`) and are retried on the backoff schedule.
+    /// `client` may be a trait object (`Arc<dyn AnchorClient + Send + Sync>`).
+    pub fn spawn<C, S>(
+        outbox: Arc<Mutex<AnchorOutbox>>,
+        client: Arc<C>,
+        sink: Arc<S>,
+        poll: Duration,
+    ) -> Self
+    where
+        C: AnchorClient + Send + Sync + ?Sized + 'static,
+        S: AnchorSink + Send + Sync + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        let handle = std::thread::spawn(move || {
+            while !flag.load(Ordering::SeqCst) {
+                let _ = AnchorOutbox::drain_shared(&outbox, &client, sink.as_ref(), Utc::now());
+                std::thread::sleep(poll);
+            }
+        });
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Stops the worker and waits for the current pass to finish.
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(h) = self.handle.take() {
+            let _ = h.join();
+        }
+    }
+}
+
+impl Drop for AnchorWorker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+// =============================================================================
+// UNIT TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RetryPolicy {
+            base_delay_ms: 1_000,
+            max_delay_ms: 10_000,
+        };
+        let ms = |n| policy.delay_after(n).num_milliseconds();
+        assert_eq!(ms(1), 1_000);
+        assert_eq!(ms(2), 2_000);
+        assert_eq!(ms(4), 8_000);
+        assert_eq!(ms(5), 10_000);
+        assert_eq!(ms(40), 10_000);
+    }
+
+    #[test]
+    fn test_mock_txids_are_deterministic() {
+        assert_eq!(MockAnchorService::txid_for(7), MockAnchorService::txid_for(7));
+        assert_ne!(MockAnchorService::txid_for(7), MockAnchorService::txid_for(8));
+    }
+}
@@ -0,0 +1,464 @@
+// =============================================================================
+// FILE: blacklist_shard.rs
+// PROJECT: Reality.os / SovereigntyCore
+// MODULE: Guards / Bostrom Blacklist Shard Loader & Pattern Compiler
+// VERSION: 1.0.0
+// LICENSE: ALN-Sovereign-1.0 (Neurorights-Compliant)
+// DESCRIPTION:
+//   Parses the CSV-style ALN shard format (bostrom-blacklist-v1.aln) into
+//   BostromBlacklistShard and precompiles every blacklist pattern. Invalid
+//   rows and patterns are rejected at load time with their line numbers.
+// =============================================================================
+
+#![deny(clippy::all)]
+#![deny(unsafe_code)]
+#![forbid(missing_docs)]
+
+use super::sabotage_detector::{
+    BlacklistPattern, BostromBlacklistShard, NeurorightsBinding, SabotageRiskRule,
+    SabotageThreshold,
+};
+use aln_shard_loader::split_row;
+use regex::Regex;
+use std::path::Path;
+use thiserror::Error;
+
+// =============================================================================
+// ERROR TYPES
+// =============================================================================
+
+/// Load-time rejection of a shard row, tagged with its 1-based line number.
+#[derive(Debug, Error, PartialEq)]
+pub enum ShardParseError {
+    /// Malformed row: wrong field count, bad number, unknown kind or action.
+    #[error("line {line}: {message}")]
+    InvalidRow {
+        /// 1-based line number.
+        line: usize,
+        /// What is wrong with the row.
+        message: String,
+    },
+
+    /// A blacklist pattern that does not compile for its `op`.
+    #[error("line {line}: invalid {op} pattern `{pattern}`: {message}")]
+    InvalidPattern {
+        /// 1-based line number.
+        line: usize,
+        /// Declared op (eq, prefix, glob, regex).
+        op: String,
+        /// Pattern text as written in the shard.
+        pattern: String,
+        /// Compiler error.
+        message: String,
+    },
+
+    /// The shard file could not be read.
+    #[error("failed to read shard {path}: {message}")]
+    Io {
+        /// Shard path.
+        path: String,
+        /// I/O error text.
+        message: String,
+    },
+}
+
+// =============================================================================
+// PATTERN COMPILATION
+// =============================================================================
+
+/// A blacklist pattern compiled once at load time.
+#[derive(Debug, Clone)]
+pub enum CompiledPattern {
+    /// Exact match.
+    Eq(String),
+    /// Prefix match.
+    Prefix(String),
+    /// Glob translated to an anchored regex.
+    Glob(Regex),
+    /// Unanchored regex (use `^`/`$` in the shard to anchor).
+    Regex(Regex),
+}
+
+impl CompiledPattern {
+    /// Compiles `pattern.pattern` according to `pattern.op`.
+    pub fn compile(pattern: &BlacklistPattern) -> Result<Self, String> {
+        match pattern.op.as_str() {
+            "eq" => Ok(Self::Eq(pattern.pattern.clone())),
+            "prefix" => Ok(Self::Prefix(pattern.pattern.clone())),
+            "glob" => Regex::new(&glob_to_regex(&pattern.pattern)?)
+                .map(Self::Glob)
+                .map_err(|e| e.to_string()),
+            "regex" => Regex::new(&pattern.pattern)
+                .map(Self::Regex)
+                .map_err(|e| e.to_string()),
+            other => Err(format!("unknown op `{}` (expected eq, prefix, glob, regex)", other)),
+        }
+    }
+
+    /// Returns true if `identifier` matches.
+    pub fn is_match(&self, identifier: &str) -> bool {
+        match self {
+            Self::Eq(p) => identifier == p,
+            Self::Prefix(p) => identifier.starts_with(p.as_str()),
+            Self::Glob(re) | Self::Regex(re) => re.is_match(identifier),
+        }
+    }
+}
+
+/// Translates a glob into an anchored regex.
+///
+/// `**` matches any run of characters including `/`, `*` any run within one
+/// path segment, `?` one non-`/` character, and `[abc]`, `[a-z]`, `[!abc]`
+/// are character classes.
+pub fn glob_to_regex(glob: &str) -> Result<String, String> {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut out = String::from("^");
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                // `**/` also matches zero directories.
+                if chars.get(i + 2) == Some(&'/') {
+                    out.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    out.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => out.push_str("[^/]*"),
+            '?' => out.push_str("[^/]"),
+            '[' => {
+                let close = chars[i + 1..]
+                    .iter()
+                    .position(|&c| c == ']')
+                    .map(|p| p + i + 1)
+                    .ok_or_else(|| format!("unclosed character class at offset {}", i))?;
+                let mut body: String = chars[i + 1..close].iter().collect();
+                if body.is_empty() {
+                    return Err(format!("empty character class at offset {}", i));
+                }
+                out.push('[');
+                if let Some(rest) = body.strip_prefix('!') {
+                    out.push('^');
+                    body = rest.to_string();
+                }
+                for c in body.chars() {
+                    if c == '\\' || c == '[' || c == '^' {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out.push(']');
+                i = close;
+            }
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+    out.push('$');
+    Ok(out)
+}
+
+/// Shard patterns paired with their compiled matchers, in shard order.
+#[derive(Debug, Clone, Default)]
+pub struct CompiledBlacklist {
+    /// Pattern rows and their compiled form.
+    pub entries: Vec<(BlacklistPattern, CompiledPattern)>,
+}
+
+impl CompiledBlacklist {
+    /// Compiles every pattern of a parsed shard.
+    pub fn from_shard(shard: &BostromBlacklistShard) -> Result<Self, String> {
+        let entries = shard
+            .patterns
+            .iter()
+            .map(|p| CompiledPattern::compile(p).map(|c| (p.clone(), c)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { entries })
+    }
+
+    /// First pattern matching `identifier`, if any.
+    pub fn first_match(&self, identifier: &str) -> Option<&BlacklistPattern> {
+        self.entries
+            .iter()
+            .find(|(_, c)| c.is_match(identifier))
+            .map(|(p, _)| p)
+    }
+}
+
+// =============================================================================
+// CSV-STYLE ALN PARSER
+// =============================================================================
+
+/// Row kinds present in the shard that the detector does not consume.
+const PASSTHROUGH_ROWS: &[&str] = &["auditbind", "modebind", "evidence_schema", "telemetry_tier"];
+
+/// Valid `action` values for `sabotage_threshold` rows.
+const THRESHOLD_ACTIONS: &[&str] = &["LogOnly", "Deny", "UnsafeDefer", "HardFail", "Inhibit"];
+
+/// Valid `scalar` values for `sabotage_threshold` rows.
+const THRESHOLD_SCALARS: &[&str] = &[
+    "sabotage_risk",
+    "provenance_factor",
+    "blacklist_factor",
+    "biophysical_factor",
+    "integrity_factor",
+    "access_factor",
+    "knowledge_factor",
+];
+
+fn parse_unit(value: &str, what: &str) -> Result<f64, String> {
+    let v: f64 = value
+        .parse()
+        .map_err(|_| format!("{} `{}` is not a number", what, value))?;
+    if !(0.0..=1.0).contains(&v) {
+        return Err(format!("{} {} outside [0.0, 1.0]", what, v));
+    }
+    Ok(v)
+}
+
+impl BostromBlacklistShard {
+    /// Reads and parses a shard file.
+    pub fn from_aln_file(path: &Path) -> Result<Self, ShardParseError> {
+        let src = std::fs::read_to_string(path).map_err(|e| ShardParseError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::parse_aln(&src)
+    }
+
+    /// Parses the CSV-style shard text. `# version:`, `# anchor_txid:` and
+    /// `# brainidentity_bound:` metadata comments are honoured; every
+    /// blacklist pattern is compiled so bad globs/regexes fail here.
+    pub fn parse_aln(src: &str) -> Result<Self, ShardParseError> {
+        let mut shard = BostromBlacklistShard {
+            version: String::new(),
+            patterns: Vec::new(),
+            risk_rules: Vec::new(),
+            thresholds: Vec::new(),
+            neurorights_bindings: Vec::new(),
+            anchor_txid: String::new(),
+            brainidentity_bound: false,
+        };
+
+        for (idx, raw) in src.lines().enumerate() {
+            let line = idx + 1;
+            let text = raw.trim();
+            let row_err = |message: String| ShardParseError::InvalidRow { line, message };
+
+            if text.is_empty() {
+                continue;
+            }
+            if let Some(comment) = text.strip_prefix('#') {
+                if let Some((key, value)) = comment.trim().split_once(':') {
+                    let value = value.trim().to_string();
+                    match key.trim() {
+                        "version" => shard.version = value,
+                        "anchor_txid" => shard.anchor_txid = value,
+                        "brainidentity_bound" => shard.brainidentity_bound = value == "true",
+                        _ => {}
+                    }
+                }
+                continue;
+            }
+
+            let f = split_row(text).map_err(|kind| row_err(kind.to_string()))?;
+            let expect = |n: usize| {
+                if f.len() == n {
+                    Ok(())
+                } else {
+                    Err(row_err(format!("`{}` row has {} fields, expected {}", f[0], f.len(), n)))
+                }
+            };
+
+            match f[0].as_str() {
+                // Header line: aln,<namespace>,<shard>,<version>
+                "aln" => {
+                    if shard.version.is_empty() {
+                        shard.version = f.last().cloned().unwrap_or_default();
+                    }
+                }
+                "blacklist_pattern" => {
+                    expect(8)?;
+                    let pattern = BlacklistPattern {
+                        metric: f[0].clone(),
+                        domain: f[1].clone(),
+                        module: f[2].clone(),
+                        op: f[3].clone(),
+                        pattern: f[4].clone(),
+                        kind: f[5].clone(),
+                        reason: f[6].clone(),
+                        source: f[7].clone(),
+                    };
+                    if pattern.pattern.is_empty() {
+                        return Err(row_err("empty pattern".into()));
+                    }
+                    CompiledPattern::compile(&pattern).map_err(|message| {
+                        ShardParseError::InvalidPattern {
+                            line,
+                            op: pattern.op.clone(),
+                            pattern: pattern.pattern.clone(),
+                            message,
+                        }
+                    })?;
+                    shard.patterns.push(pattern);
+                }
+                "sabotage_rule" => {
+                    expect(8)?;
+                    shard.risk_rules.push(SabotageRiskRule {
+                        metric: f[0].clone(),
+                        domain: f[1].clone(),
+                        module: f[2].clone(),
+                        rule_id: f[3].clone(),
+                        component: f[4].clone(),
+                        weight: parse_unit(&f[5], "weight").map_err(row_err)?,
+                        description: f[6].clone(),
+                        source: f[7].clone(),
+                    });
+                }
+                "sabotage_threshold" => {
+                    expect(9)?;
+                    if !THRESHOLD_ACTIONS.contains(&f[6].as_str()) {
+                        return Err(row_err(format!("unknown action `{}`", f[6])));
+                    }
+                    if !THRESHOLD_SCALARS.contains(&f[4].as_str()) {
+                        return Err(row_err(format!("unknown scalar `{}`", f[4])));
+                    }
+                    shard.thresholds.push(SabotageThreshold {
+                        metric: f[0].clone(),
+                        domain: f[1].clone(),
+                        module: f[2].clone(),
+                        threshold_id: f[3].clone(),
+                        scalar: f[4].clone(),
+                        value: parse_unit(&f[5], "threshold").map_err(row_err)?,
+                        action: f[6].clone(),
+                        log_event: f[7].clone(),
+                        source: f[8].clone(),
+                    });
+                }
+                "neurorights_binding" => {
+                    expect(8)?;
+                    let severity_multiplier: f64 = f[6].parse().map_err(|_| {
+                        row_err(format!("severity_multiplier `{}` is not a number", f[6]))
+                    })?;
+                    shard.neurorights_bindings.push(NeurorightsBinding {
+                        metric: f[0].clone(),
+                        domain: f[1].clone(),
+                        module: f[2].clone(),
+                        jurisdiction_id: f[3].clone(),
+                        jurisdiction: f[4].clone(),
+                        right_type: f[5].clone(),
+                        severity_multiplier,
+                        source: f[7].clone(),
+                    });
+                }
+                kind if PASSTHROUGH_ROWS.contains(&kind) => {}
+                kind => return Err(row_err(format!("unknown row kind `{}`", kind))),
+            }
+        }
+
+        Ok(shard)
+    }
+}
+
+// =============================================================================
+// UNIT TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHARD: &str =
+        include_str!("../../../../Config/Sovereignty/policies/bostrom-blacklist-v1.aln");
+
+    fn pattern(op: &str, p: &str) -> BlacklistPattern {
+        BlacklistPattern {
+            metric: "blacklist_pattern".into(),
+            domain: "host".into(),
+            module: "test".into(),
+            op: op.into(),
+            pattern: p.into(),
+            kind: "test".into(),
+            reason: "test".into(),
+            source: "test".into(),
+        }
+    }
+
+    #[test]
+    fn test_parses_bostrom_blacklist_v1() {
+        let shard = BostromBlacklistShard::parse_aln(SHARD).expect("shard parses");
+        assert_eq!(shard.version, "1.0.0");
+        assert_eq!(shard.patterns.len(), 10);
+        assert_eq!(shard.risk_rules.len(), 7);
+        assert_eq!(shard.thresholds.len(), 5);
+        assert_eq!(shard.neurorights_bindings.len(), 5);
+        assert!(shard.brainidentity_bound);
+        assert_eq!(shard.thresholds[0].source, "governance-corridor-roe-2026-03");
+        assert_eq!(
+            shard.patterns[0].reason,
+            "enterprise/federal toy sabotage – orchestrator pattern"
+        );
+    }
+
+    #[test]
+    fn test_glob_semantics() {
+        let m = |g: &str, s: &str| CompiledPattern::compile(&pattern("glob", g)).unwrap().is_match(s);
+        assert!(m("*.federal.yaml", "ops.federal.yaml"));
+        assert!(!m("*.federal.yaml", "cfg/ops.federal.yaml"));
+        assert!(m("**/*.federal.yaml", "cfg/ops.federal.yaml"));
+        assert!(m("**/*.federal.yaml", "ops.federal.yaml"));
+        assert!(m("QCon?ocus", "QConLocus"));
+        assert!(m("fed[0-9]_*", "fed7_toy"));
+        assert!(!m("fed[!0-9]_*", "fed7_toy"));
+        assert!(!m("a.b", "axb"));
+    }
+
+    #[test]
+    fn test_regex_is_real_regex() {
+        let c = CompiledPattern::compile(&pattern("regex", r"^enterprise_(ci|cd)_\d+$")).unwrap();
+        assert!(c.is_match("enterprise_ci_42"));
+        assert!(!c.is_match("enterprise_ci_x"));
+    }
+
+    #[test]
+    fn test_rejects_invalid_rows_with_line_numbers() {
+        let bad_regex = "aln,sovereignty,bostrom-blacklist,v1\n# c\nblacklist_pattern,host,m,regex,(unclosed,k,r,s\n";
+        assert!(matches!(
+            BostromBlacklistShard::parse_aln(bad_regex),
+            Err(ShardParseError::InvalidPattern { line: 3, .. })
+        ));
+
+        let bad_glob = "blacklist_pattern,host,m,glob,[abc,k,r,s\n";
+        assert!(matches!(
+            BostromBlacklistShard::parse_aln(bad_glob),
+            Err(ShardParseError::InvalidPattern { line: 1, .. })
+        ));
+
+        let bad_op = "\nblacklist_pattern,host,m,fuzzy,x,k,r,s\n";
+        assert!(matches!(
+            BostromBlacklistShard::parse_aln(bad_op),
+            Err(ShardParseError::InvalidPattern { line: 2, .. })
+        ));
+
+        let bad_weight = "sabotage_rule,host,rohmodel,x,c,1.5,d,s\n";
+        assert!(matches!(
+            BostromBlacklistShard::parse_aln(bad_weight),
+            Err(ShardParseError::InvalidRow { line: 1, .. })
+        ));
+
+        let bad_scalar = "sabotage_threshold,host,rohmodel,t,mood,0.70,Deny,EV,source\n";
+        assert!(matches!(
+            BostromBlacklistShard::parse_aln(bad_scalar),
+            Err(ShardParseError::InvalidRow { line: 1, ref message }) if message == "unknown scalar `mood`"
+        ));
+
+        let stray_quote = "sabotage_threshold,host,rohmodel,t,sabotage_risk,0.70,Deny,EV,source\"\n";
+        assert_eq!(
+            BostromBlacklistShard::parse_aln(stray_quote).unwrap_err(),
+            ShardParseError::InvalidRow { line: 1, message: "stray `\"` in unquoted cell 9".into() }
+        );
+    }
+}
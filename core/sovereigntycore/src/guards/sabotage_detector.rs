@@ -20,12 +20,15 @@
 #![deny(unsafe_code)]
 #![forbid(missing_docs)]
 
-use crate::aln::parser::AlnShardLoader;
 use crate::brainidentity::BrainIdentityHash;
 use crate::crypto::{DidSignature, Ed25519KeyPair, Hash64};
 use crate::donutloop::DonutLoopLogger;
 use crate::evolution::{EvolutionProposal, EvolutionScope};
-use crate::googolswarm::GoogolswarmAnchor;
+use crate::guards::anchor_outbox::{
+    AnchorClient, AnchorOutbox, AnchorSink, AnchorWorker, DrainReport, OutboxEntry, RetryPolicy,
+    INLINE_ANCHOR_TIMEOUT,
+};
+use crate::guards::blacklist_shard::CompiledBlacklist;
 use crate::legal_profiles::NeurorightsViolation;
 use crate::qpu_datashard::{QpuDatashard, QpuEventRecord};
 use crate::rohmodel::RiskOfHarmScalar;
@@ -33,7 +36,9 @@ use crate::telemetry::{BiophysicalTelemetry, TelemetryTier};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 use thiserror::Error;
 
 // =============================================================================
@@ -75,6 +80,15 @@ pub enum SabotageDetectorError {
 
     #[error("Evolution proposal rejected: sabotage_risk {risk} exceeds threshold {threshold}")]
     EvolutionRejected { risk: f64, threshold: f64 },
+
+    #[error("Anchor outbox failure: {0}")]
+    AnchorOutboxError(String),
+
+    #[error("Failed to write DonutLoop audit entry: {0}")]
+    DonutLoopLogError(String),
+
+    #[error("Replay requires shard version {expected}, got {actual}")]
+    ShardVersionMismatch { expected: String, actual: String },
 }
 
 /// Result type alias for sabotage detector operations.
@@ -85,7 +99,7 @@ pub type SabotageDetectorResult<T> = Result<T, SabotageDetectorError>;
 // =============================================================================
 
 /// Represents a single blacklist pattern entry from bostrom-blacklist-v1.aln.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BlacklistPattern {
     /// Metric type identifier.
     pub metric: String,
@@ -260,8 +274,22 @@ pub enum SabotageAction {
     Inhibit,
 }
 
+impl SabotageAction {
+    /// Parses the `action` column of a `sabotage_threshold` row.
+    pub fn from_shard(action: &str) -> Option<Self> {
+        match action {
+            "LogOnly" => Some(Self::LogOnly),
+            "Deny" => Some(Self::Deny),
+            "UnsafeDefer" => Some(Self::UnsafeDefer),
+            "HardFail" => Some(Self::HardFail),
+            "Inhibit" => Some(Self::Inhibit),
+            _ => None,
+        }
+    }
+}
+
 /// Represents the computed sabotage_risk scalar with all components.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SabotageRiskScalar {
     /// Total computed risk (0.0 to 1.0).
     pub total: f64,
@@ -305,6 +333,20 @@ impl SabotageRiskScalar {
             .max(0.0);
         self.total
     }
+
+    /// Value of the scalar named by a `sabotage_threshold` row.
+    pub fn component(&self, scalar: &str) -> Option<f64> {
+        match scalar {
+            "sabotage_risk" => Some(self.total),
+            "provenance_factor" => Some(self.provenance),
+            "blacklist_factor" => Some(self.blacklist),
+            "biophysical_factor" => Some(self.biophysical),
+            "integrity_factor" => Some(self.integrity),
+            "access_factor" => Some(self.access),
+            "knowledge_factor" => Some(self.knowledge),
+            _ => None,
+        }
+    }
 }
 
 impl Default for SabotageRiskScalar {
@@ -313,26 +355,163 @@ impl Default for SabotageRiskScalar {
     }
 }
 
+// =============================================================================
+// DATA STRUCTURES — EVALUATION TRACE & REPLAY
+// =============================================================================
+
+/// One tier check and what it contributed to the sabotage_risk scalar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TierContribution {
+    /// Evaluation tier (1 = hard gates, 2 = amplifiers, 3 = quarantine).
+    pub tier: u8,
+    /// Risk component written (provenance, integrity, blacklist, ...).
+    pub component: String,
+    /// Check name within the tier.
+    pub check: String,
+    /// Whether the check fired.
+    pub fired: bool,
+    /// Shard rule whose weight was applied, if any.
+    pub rule_id: Option<String>,
+    /// Weight applied to the component (0.0 if not fired or rule missing).
+    pub weight: f64,
+    /// Blacklist pattern that matched, for the pattern check.
+    pub matched_pattern: Option<BlacklistPattern>,
+    /// Observed inputs (telemetry values, flags) the check looked at.
+    pub observed: Vec<(String, String)>,
+    /// Human-readable explanation.
+    pub explanation: String,
+}
+
+/// Full, side-effect-free explanation of one evaluation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvaluationTrace {
+    /// Version of the shard the evaluation ran against.
+    pub shard_version: String,
+    /// Per-tier contributions, in evaluation order.
+    pub contributions: Vec<TierContribution>,
+    /// Resulting risk scalar.
+    pub risk: SabotageRiskScalar,
+    /// Resulting action.
+    pub action: SabotageAction,
+    /// Which action rule decided.
+    pub action_reason: String,
+}
+
+impl EvaluationTrace {
+    /// Contributions that fired.
+    pub fn fired(&self) -> impl Iterator<Item = &TierContribution> {
+        self.contributions.iter().filter(|c| c.fired)
+    }
+}
+
+/// Stored inputs and outcome of a past decision, sufficient for replay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SabotageDecisionRecord {
+    /// When the decision was made.
+    pub decided_at: DateTime<Utc>,
+    /// Proposal as evaluated.
+    pub proposal: EvolutionProposal,
+    /// Telemetry snapshot as evaluated.
+    pub telemetry: BiophysicalTelemetry,
+    /// Trace produced at decision time (includes the shard version).
+    pub trace: EvaluationTrace,
+}
+
+/// Result of replaying a stored decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayOutcome {
+    /// Trace recomputed from the stored inputs.
+    pub replayed: EvaluationTrace,
+    /// True if the recomputed trace equals the stored one.
+    pub identical: bool,
+    /// Human-readable differences, empty when identical.
+    pub differences: Vec<String>,
+}
+
+/// Current vs. candidate shard evaluation of the same inputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhatIfReport {
+    /// Evaluation against the loaded shard.
+    pub current: EvaluationTrace,
+    /// Evaluation against the candidate shard.
+    pub candidate: EvaluationTrace,
+    /// Candidate total minus current total.
+    pub total_delta: f64,
+    /// True if the chosen action differs.
+    pub action_changed: bool,
+    /// Differences in fired checks or weights.
+    pub differences: Vec<String>,
+}
+
+/// Lists differences between two traces (contributions and action).
+pub fn diff_traces(a: &EvaluationTrace, b: &EvaluationTrace) -> Vec<String> {
+    let mut out = Vec::new();
+    if a.shard_version != b.shard_version {
+        out.push(format!("shard_version: {} -> {}", a.shard_version, b.shard_version));
+    }
+    for (x, y) in a.contributions.iter().zip(b.contributions.iter()) {
+        if x.fired != y.fired || x.weight != y.weight || x.rule_id != y.rule_id {
+            out.push(format!(
+                "{}: fired {} -> {}, weight {:.2} -> {:.2}",
+                x.check, x.fired, y.fired, x.weight, y.weight
+            ));
+        } else if x.matched_pattern.as_ref().map(|p| &p.pattern)
+            != y.matched_pattern.as_ref().map(|p| &p.pattern)
+        {
+            out.push(format!("{}: matched pattern changed", x.check));
+        }
+    }
+    if a.contributions.len() != b.contributions.len() {
+        out.push(format!(
+            "contribution count: {} -> {}",
+            a.contributions.len(),
+            b.contributions.len()
+        ));
+    }
+    if a.action != b.action {
+        out.push(format!("action: {:?} -> {:?}", a.action, b.action));
+    }
+    out
+}
+
 // =============================================================================
 // MAIN DETECTOR STRUCT
 // =============================================================================
 
+/// Order in which `sabotage_threshold` rows are checked, most severe
+/// first. Rows with other ids follow in shard order.
+const THRESHOLD_PRECEDENCE: &[&str] = &[
+    "firmware_breach",
+    "emergency_inhibit",
+    "federal_toy_block",
+    "psych_weaponization",
+    "ghost_intrusion",
+];
+
 /// Core sabotage detection engine with ALN policy integration.
 pub struct SabotageDetector {
     /// Path to the ALN blacklist shard.
     aln_shard_path: PathBuf,
     /// Parsed blacklist shard data.
     blacklist_shard: Option<BostromBlacklistShard>,
+    /// Blacklist patterns precompiled at shard load.
+    compiled_patterns: CompiledBlacklist,
     /// BrainIdentity hash for event binding.
     brainidentity_hash: BrainIdentityHash,
     /// Host DID keypair for signing events.
     host_did_keypair: Ed25519KeyPair,
-    /// QPU.Datashard logger for local ledger.
-    qpu_datashard: QpuDatashard,
-    /// DonutLoop logger for audit trail.
-    donutloop_logger: DonutLoopLogger,
+    /// QPU.Datashard logger for local ledger; shared with the anchor worker.
+    qpu_datashard: Arc<QpuDatashard>,
+    /// DonutLoop logger for audit trail; shared with the anchor worker.
+    donutloop_logger: Arc<DonutLoopLogger>,
     /// Googolswarm anchor client.
-    googolswarm_client: GoogolswarmAnchor,
+    googolswarm_client: Arc<dyn AnchorClient + Send + Sync>,
+    /// Events awaiting anchoring; retried with backoff.
+    anchor_outbox: Arc<Mutex<AnchorOutbox>>,
+    /// Background drain thread, if started.
+    anchor_worker: Option<AnchorWorker>,
+    /// Longest a new event waits for its inline anchoring attempt.
+    inline_anchor_timeout: Duration,
     /// Current event ID counter.
     event_id_counter: u64,
     /// Cached thresholds for fast evaluation.
@@ -350,7 +529,11 @@ impl SabotageDetector {
     /// * `host_did_keypair` — Host DID Ed25519 keypair
     /// * `qpu_datashard` — QPU.Datashard instance for local ledger
     /// * `donutloop_logger` — DonutLoop logger for audit trail
-    /// * `googolswarm_client` — Googolswarm anchor client
+    /// * `googolswarm_client` — Googolswarm anchor client (or `MockAnchorService`)
+    ///
+    /// The anchor outbox starts in memory; use `with_anchor_outbox` to make
+    /// pending anchors survive restarts and `start_anchor_worker` to retry
+    /// them in the background.
     ///
     /// # Returns
     /// * `SabotageDetectorResult<Self>` — Initialized detector or error
@@ -360,16 +543,20 @@ impl SabotageDetector {
         host_did_keypair: Ed25519KeyPair,
         qpu_datashard: QpuDatashard,
         donutloop_logger: DonutLoopLogger,
-        googolswarm_client: GoogolswarmAnchor,
+        googolswarm_client: impl AnchorClient + Send + Sync + 'static,
     ) -> SabotageDetectorResult<Self> {
         let mut detector = Self {
             aln_shard_path,
             blacklist_shard: None,
+            compiled_patterns: CompiledBlacklist::default(),
             brainidentity_hash,
             host_did_keypair,
-            qpu_datashard,
-            donutloop_logger,
-            googolswarm_client,
+            qpu_datashard: Arc::new(qpu_datashard),
+            donutloop_logger: Arc::new(donutloop_logger),
+            googolswarm_client: Arc::new(googolswarm_client),
+            anchor_outbox: Arc::new(Mutex::new(AnchorOutbox::in_memory())),
+            anchor_worker: None,
+            inline_anchor_timeout: INLINE_ANCHOR_TIMEOUT,
             event_id_counter: 0,
             thresholds: HashMap::new(),
             risk_rules: HashMap::new(),
@@ -381,13 +568,60 @@ impl SabotageDetector {
         Ok(detector)
     }
 
-    /// Loads and parses the ALN blacklist shard.
+    /// Uses a durable outbox file, reloading any anchors still pending.
+    pub fn with_anchor_outbox(self, path: &Path) -> SabotageDetectorResult<Self> {
+        let reopened =
+            AnchorOutbox::open(path).map_err(SabotageDetectorError::AnchorOutboxError)?;
+        {
+            let mut outbox = self.lock_outbox()?;
+            let policy = outbox.policy.clone();
+            *outbox = reopened;
+            outbox.policy = policy;
+        }
+        Ok(self)
+    }
+
+    /// Sets the backoff between anchoring attempts.
+    pub fn with_anchor_retry_policy(self, policy: RetryPolicy) -> SabotageDetectorResult<Self> {
+        self.lock_outbox()?.policy = policy;
+        Ok(self)
+    }
+
+    /// Sets how long `generate_sabotage_event` waits for the anchor client
+    /// before leaving the event to the outbox.
+    pub fn with_inline_anchor_timeout(mut self, timeout: Duration) -> Self {
+        self.inline_anchor_timeout = timeout;
+        self
+    }
+
+    /// Starts a background thread that drains the outbox every `poll`,
+    /// replacing any worker already running.
+    pub fn start_anchor_worker(&mut self, poll: Duration) {
+        self.stop_anchor_worker();
+        self.anchor_worker = Some(AnchorWorker::spawn(
+            self.anchor_outbox.clone(),
+            self.googolswarm_client.clone(),
+            Arc::new(self.anchor_write_back()),
+            poll,
+        ));
+    }
+
+    /// Stops the background worker, waiting for its current pass to finish.
+    pub fn stop_anchor_worker(&mut self) {
+        if let Some(worker) = self.anchor_worker.take() {
+            worker.stop();
+        }
+    }
+
+    /// Loads and parses the ALN blacklist shard, compiling every pattern.
+    /// Invalid rows or patterns are rejected with their line numbers.
     fn load_aln_shard(&mut self) -> SabotageDetectorResult<()> {
-        let loader = AlnShardLoader::new();
-        let shard_data = loader
-            .load::<BostromBlacklistShard>(&self.aln_shard_path)
+        let shard_data = BostromBlacklistShard::from_aln_file(&self.aln_shard_path)
             .map_err(|e| SabotageDetectorError::AlnLoadError(e.to_string()))?;
 
+        self.compiled_patterns = CompiledBlacklist::from_shard(&shard_data)
+            .map_err(SabotageDetectorError::AlnLoadError)?;
+
         // Cache thresholds for fast lookup
         for threshold in shard_data.thresholds.iter() {
             self.thresholds
@@ -427,235 +661,445 @@ impl SabotageDetector {
         proposal: &EvolutionProposal,
         telemetry: &BiophysicalTelemetry,
     ) -> SabotageDetectorResult<(SabotageRiskScalar, SabotageAction)> {
+        let trace = self.evaluate_proposal_traced(proposal, telemetry)?;
+        Ok((trace.risk, trace.action))
+    }
+
+    /// Evaluates a proposal and returns the per-tier trace. Blacklist matches
+    /// are logged to the audit trail, as with `evaluate_proposal`.
+    pub fn evaluate_proposal_traced(
+        &self,
+        proposal: &EvolutionProposal,
+        telemetry: &BiophysicalTelemetry,
+    ) -> SabotageDetectorResult<EvaluationTrace> {
+        let shard = self
+            .blacklist_shard
+            .as_ref()
+            .ok_or_else(|| SabotageDetectorError::AlnLoadError("Shard not loaded".into()))?;
+
+        let trace = self.evaluate_with_shard(shard, &self.compiled_patterns, proposal, telemetry);
+
+        if let Some(pattern) = trace.fired().find_map(|c| c.matched_pattern.as_ref()) {
+            self.log_pattern_match(pattern, &proposal.identifier)?;
+        }
+
+        Ok(trace)
+    }
+
+    /// Replays a stored decision. The caller supplies the shard the record was
+    /// decided under; its version must match the recorded one. No audit or
+    /// ledger side effects.
+    pub fn replay_decision(
+        &self,
+        record: &SabotageDecisionRecord,
+        shard: &BostromBlacklistShard,
+    ) -> SabotageDetectorResult<ReplayOutcome> {
+        if shard.version != record.trace.shard_version {
+            return Err(SabotageDetectorError::ShardVersionMismatch {
+                expected: record.trace.shard_version.clone(),
+                actual: shard.version.clone(),
+            });
+        }
+        let compiled =
+            CompiledBlacklist::from_shard(shard).map_err(SabotageDetectorError::AlnLoadError)?;
+        let replayed =
+            self.evaluate_with_shard(shard, &compiled, &record.proposal, &record.telemetry);
+        let differences = diff_traces(&record.trace, &replayed);
+
+        Ok(ReplayOutcome {
+            identical: differences.is_empty() && replayed == record.trace,
+            replayed,
+            differences,
+        })
+    }
+
+    /// Evaluates a proposal against the loaded shard and a candidate shard
+    /// version, without logging or anchoring anything.
+    pub fn what_if(
+        &self,
+        proposal: &EvolutionProposal,
+        telemetry: &BiophysicalTelemetry,
+        candidate: &BostromBlacklistShard,
+    ) -> SabotageDetectorResult<WhatIfReport> {
+        let shard = self
+            .blacklist_shard
+            .as_ref()
+            .ok_or_else(|| SabotageDetectorError::AlnLoadError("Shard not loaded".into()))?;
+        let candidate_compiled = CompiledBlacklist::from_shard(candidate)
+            .map_err(SabotageDetectorError::AlnLoadError)?;
+
+        let current = self.evaluate_with_shard(shard, &self.compiled_patterns, proposal, telemetry);
+        let candidate =
+            self.evaluate_with_shard(candidate, &candidate_compiled, proposal, telemetry);
+
+        Ok(WhatIfReport {
+            total_delta: candidate.risk.total - current.risk.total,
+            action_changed: candidate.action != current.action,
+            differences: diff_traces(&current, &candidate),
+            current,
+            candidate,
+        })
+    }
+
+    /// Builds a replayable record for a decision just made.
+    pub fn record_decision(
+        &self,
+        proposal: &EvolutionProposal,
+        telemetry: &BiophysicalTelemetry,
+        trace: &EvaluationTrace,
+    ) -> SabotageDecisionRecord {
+        SabotageDecisionRecord {
+            decided_at: Utc::now(),
+            proposal: proposal.clone(),
+            telemetry: telemetry.clone(),
+            trace: trace.clone(),
+        }
+    }
+
+    /// Pure evaluation of all tiers against a given shard. Deterministic in
+    /// (proposal, telemetry, shard, host DID).
+    fn evaluate_with_shard(
+        &self,
+        shard: &BostromBlacklistShard,
+        compiled: &CompiledBlacklist,
+        proposal: &EvolutionProposal,
+        telemetry: &BiophysicalTelemetry,
+    ) -> EvaluationTrace {
         let mut risk_scalar = SabotageRiskScalar::new();
+        let mut contributions = Vec::new();
 
         // Tier 1: System-level provenance (hard gate)
-        self.evaluate_provenance(proposal, &mut risk_scalar)?;
+        self.evaluate_provenance(shard, proposal, &mut risk_scalar, &mut contributions);
 
         // Tier 1: Firmware integrity check
-        self.evaluate_firmware_integrity(proposal, &mut risk_scalar)?;
+        self.evaluate_firmware_integrity(shard, proposal, &mut risk_scalar, &mut contributions);
 
         // Tier 2: Blacklist pattern matching
-        self.evaluate_blacklist_patterns(proposal, &mut risk_scalar)?;
+        Self::evaluate_blacklist_patterns(
+            shard,
+            compiled,
+            proposal,
+            &mut risk_scalar,
+            &mut contributions,
+        );
 
         // Tier 2: Biophysical stress correlation
-        self.evaluate_biophysical_stress(telemetry, &mut risk_scalar)?;
+        Self::evaluate_biophysical_stress(shard, telemetry, &mut risk_scalar, &mut contributions);
 
         // Tier 3: Access pattern analysis (ghost-access)
-        self.evaluate_access_patterns(proposal, &mut risk_scalar)?;
+        Self::evaluate_access_patterns(shard, proposal, &mut risk_scalar, &mut contributions);
 
         // Tier 3: SKO contamination risk
-        self.evaluate_knowledge_contamination(proposal, &mut risk_scalar)?;
+        Self::evaluate_knowledge_contamination(
+            shard,
+            proposal,
+            &mut risk_scalar,
+            &mut contributions,
+        );
 
         // Compute total risk
         risk_scalar.compute_total();
 
         // Determine action based on thresholds
-        let action = self.determine_action(&risk_scalar)?;
+        let (action, action_reason) = Self::determine_action(shard, &risk_scalar);
+
+        EvaluationTrace {
+            shard_version: shard.version.clone(),
+            contributions,
+            risk: risk_scalar,
+            action,
+            action_reason,
+        }
+    }
 
-        Ok((risk_scalar, action))
+    /// Records one check; applies `rule_id`'s weight to the component via
+    /// `apply` if the check fired.
+    #[allow(clippy::too_many_arguments)]
+    fn contribute(
+        shard: &BostromBlacklistShard,
+        contributions: &mut Vec<TierContribution>,
+        tier: u8,
+        component: &str,
+        check: &str,
+        fired: bool,
+        rule_id: &str,
+        observed: Vec<(String, String)>,
+        explanation: String,
+        apply: impl FnOnce(f64),
+    ) {
+        let rule = shard.risk_rules.iter().find(|r| r.rule_id == rule_id);
+        let weight = match (fired, rule) {
+            (true, Some(rule)) => {
+                apply(rule.weight);
+                rule.weight
+            }
+            _ => 0.0,
+        };
+        contributions.push(TierContribution {
+            tier,
+            component: component.into(),
+            check: check.into(),
+            fired,
+            rule_id: rule.map(|r| r.rule_id.clone()),
+            weight,
+            matched_pattern: None,
+            observed,
+            explanation,
+        });
     }
 
     /// Evaluates Tier 1 provenance factors.
     fn evaluate_provenance(
         &self,
+        shard: &BostromBlacklistShard,
         proposal: &EvolutionProposal,
         risk_scalar: &mut SabotageRiskScalar,
-    ) -> SabotageDetectorResult<()> {
-        let shard = self
-            .blacklist_shard
-            .as_ref()
-            .ok_or_else(|| SabotageDetectorError::AlnLoadError("Shard not loaded".into()))?;
-
+        contributions: &mut Vec<TierContribution>,
+    ) {
         // Check if proposal signer is in trusted DID list
-        if !self.is_trusted_did(&proposal.signer_did) {
-            if let Some(rule) = shard.risk_rules.iter().find(|r| r.rule_id == "ent_untrusted") {
-                risk_scalar.provenance = rule.weight;
-            }
-        }
+        let untrusted = !self.is_trusted_did(&proposal.signer_did);
+        Self::contribute(
+            shard,
+            contributions,
+            1,
+            "provenance",
+            "untrusted_signer",
+            untrusted,
+            "ent_untrusted",
+            vec![],
+            format!("signer {} trusted: {}", proposal.signer_did, !untrusted),
+            |w| risk_scalar.provenance = w,
+        );
 
         // Check for enterprise CI prefix in commit metadata
-        if proposal
+        let enterprise_ci = proposal
             .commit_metadata
             .as_ref()
             .map(|m| m.starts_with("enterprise_ci_"))
-            .unwrap_or(false)
-        {
-            if let Some(rule) = shard.risk_rules.iter().find(|r| r.rule_id == "ent_untrusted") {
-                risk_scalar.provenance = risk_scalar.provenance.max(rule.weight);
-            }
-        }
-
-        Ok(())
+            .unwrap_or(false);
+        Self::contribute(
+            shard,
+            contributions,
+            1,
+            "provenance",
+            "enterprise_ci_commit",
+            enterprise_ci,
+            "ent_untrusted",
+            vec![],
+            format!("commit metadata {:?}", proposal.commit_metadata),
+            |w| risk_scalar.provenance = risk_scalar.provenance.max(w),
+        );
     }
 
     /// Evaluates firmware integrity factors.
     fn evaluate_firmware_integrity(
         &self,
+        shard: &BostromBlacklistShard,
         proposal: &EvolutionProposal,
         risk_scalar: &mut SabotageRiskScalar,
-    ) -> SabotageDetectorResult<()> {
-        let shard = self
-            .blacklist_shard
-            .as_ref()
-            .ok_or_else(|| SabotageDetectorError::AlnLoadError("Shard not loaded".into()))?;
-
+        contributions: &mut Vec<TierContribution>,
+    ) {
         // Check firmware hash against DID-signed ledger value
-        if let Some(firmware_update) = &proposal.firmware_update {
-            if !firmware_update.is_did_signed(&self.host_did_keypair.public_key()) {
-                if let Some(rule) = shard.risk_rules.iter().find(|r| r.rule_id == "firmware_mismatch")
-                {
-                    risk_scalar.integrity = rule.weight;
-                }
-            }
-        }
-
-        Ok(())
+        let unsigned = proposal
+            .firmware_update
+            .as_ref()
+            .map(|fw| !fw.is_did_signed(&self.host_did_keypair.public_key()))
+            .unwrap_or(false);
+        Self::contribute(
+            shard,
+            contributions,
+            1,
+            "integrity",
+            "firmware_not_did_signed",
+            unsigned,
+            "firmware_mismatch",
+            vec![],
+            format!(
+                "firmware update present: {}, host-signed: {}",
+                proposal.firmware_update.is_some(),
+                !unsigned
+            ),
+            |w| risk_scalar.integrity = w,
+        );
     }
 
     /// Evaluates Tier 2 blacklist pattern matching.
     fn evaluate_blacklist_patterns(
-        &self,
+        shard: &BostromBlacklistShard,
+        compiled: &CompiledBlacklist,
         proposal: &EvolutionProposal,
         risk_scalar: &mut SabotageRiskScalar,
-    ) -> SabotageDetectorResult<()> {
-        let shard = self
-            .blacklist_shard
-            .as_ref()
-            .ok_or_else(|| SabotageDetectorError::AlnLoadError("Shard not loaded".into()))?;
-
-        let mut blacklist_matched = false;
-
-        for pattern in &shard.patterns {
-            if self.matches_pattern(&proposal.identifier, pattern) {
-                blacklist_matched = true;
-                self.log_pattern_match(pattern, &proposal.identifier)?;
-                break;
-            }
-        }
-
-        if blacklist_matched {
-            if let Some(rule) = shard.risk_rules.iter().find(|r| r.rule_id == "blacklist_match") {
-                risk_scalar.blacklist = rule.weight;
-            }
+        contributions: &mut Vec<TierContribution>,
+    ) {
+        let matched = compiled.first_match(&proposal.identifier).cloned();
+        let explanation = match &matched {
+            Some(p) => format!("{} {} {} ({})", proposal.identifier, p.op, p.pattern, p.reason),
+            None => format!("{} matched no blacklist pattern", proposal.identifier),
+        };
+        Self::contribute(
+            shard,
+            contributions,
+            2,
+            "blacklist",
+            "blacklist_pattern",
+            matched.is_some(),
+            "blacklist_match",
+            vec![],
+            explanation,
+            |w| risk_scalar.blacklist = w,
+        );
+        if let Some(last) = contributions.last_mut() {
+            last.matched_pattern = matched;
         }
 
         // Check scope factor for core safety crates
-        if matches!(
+        let high_scope = matches!(
             proposal.scope,
             EvolutionScope::Orchestrator | EvolutionScope::CoreSafety
-        ) {
-            if let Some(rule) = shard.risk_rules.iter().find(|r| r.rule_id == "scope_high") {
-                risk_scalar.blacklist = risk_scalar.blacklist.max(rule.weight);
-            }
-        }
-
-        Ok(())
+        );
+        Self::contribute(
+            shard,
+            contributions,
+            2,
+            "blacklist",
+            "scope_high",
+            high_scope,
+            "scope_high",
+            vec![],
+            format!("scope {:?}", proposal.scope),
+            |w| risk_scalar.blacklist = risk_scalar.blacklist.max(w),
+        );
     }
 
     /// Evaluates Tier 2 biophysical stress correlation.
     fn evaluate_biophysical_stress(
-        &self,
+        shard: &BostromBlacklistShard,
         telemetry: &BiophysicalTelemetry,
         risk_scalar: &mut SabotageRiskScalar,
-    ) -> SabotageDetectorResult<()> {
-        let shard = self
-            .blacklist_shard
-            .as_ref()
-            .ok_or_else(|| SabotageDetectorError::AlnLoadError("Shard not loaded".into()))?;
-
+        contributions: &mut Vec<TierContribution>,
+    ) {
         // Check for EEG/HRV stress spikes
-        if telemetry.eeg_stress_ratio > 0.7 || telemetry.hrv_anomaly_index > 0.6 {
-            if let Some(rule) = shard.risk_rules.iter().find(|r| r.rule_id == "psych_spike") {
-                risk_scalar.biophysical = rule.weight;
-            }
-        }
+        let spike = telemetry.eeg_stress_ratio > 0.7 || telemetry.hrv_anomaly_index > 0.6;
+        Self::contribute(
+            shard,
+            contributions,
+            2,
+            "biophysical",
+            "eeg_hrv_stress_spike",
+            spike,
+            "psych_spike",
+            vec![
+                ("eeg_stress_ratio".into(), telemetry.eeg_stress_ratio.to_string()),
+                ("hrv_anomaly_index".into(), telemetry.hrv_anomaly_index.to_string()),
+            ],
+            "eeg_stress_ratio > 0.7 or hrv_anomaly_index > 0.6".into(),
+            |w| risk_scalar.biophysical = w,
+        );
 
         // Check for nanoswarm weaponization indicators
-        if telemetry.nanoswarm_density > 0.8 && telemetry.sleep_corridor_violated {
-            if let Some(rule) = shard.risk_rules.iter().find(|r| r.rule_id == "psych_spike") {
-                risk_scalar.biophysical = risk_scalar.biophysical.max(rule.weight);
-            }
-        }
-
-        Ok(())
+        let weaponized = telemetry.nanoswarm_density > 0.8 && telemetry.sleep_corridor_violated;
+        Self::contribute(
+            shard,
+            contributions,
+            2,
+            "biophysical",
+            "nanoswarm_sleep_corridor",
+            weaponized,
+            "psych_spike",
+            vec![
+                ("nanoswarm_density".into(), telemetry.nanoswarm_density.to_string()),
+                (
+                    "sleep_corridor_violated".into(),
+                    telemetry.sleep_corridor_violated.to_string(),
+                ),
+            ],
+            "nanoswarm_density > 0.8 during sleep-corridor violation".into(),
+            |w| risk_scalar.biophysical = risk_scalar.biophysical.max(w),
+        );
     }
 
     /// Evaluates Tier 3 access pattern analysis.
     fn evaluate_access_patterns(
-        &self,
+        shard: &BostromBlacklistShard,
         proposal: &EvolutionProposal,
         risk_scalar: &mut SabotageRiskScalar,
-    ) -> SabotageDetectorResult<()> {
-        let shard = self
-            .blacklist_shard
-            .as_ref()
-            .ok_or_else(|| SabotageDetectorError::AlnLoadError("Shard not loaded".into()))?;
-
+        contributions: &mut Vec<TierContribution>,
+    ) {
         // Check for ghost-access patterns (unauthorized paths to INNER devices)
-        if proposal.accesses_inner_devices && !proposal.has_guarded_path {
-            if let Some(rule) = shard.risk_rules.iter().find(|r| r.rule_id == "ghost_path") {
-                risk_scalar.access = rule.weight;
-            }
-        }
-
-        Ok(())
+        let ghost = proposal.accesses_inner_devices && !proposal.has_guarded_path;
+        Self::contribute(
+            shard,
+            contributions,
+            3,
+            "access",
+            "ghost_access_path",
+            ghost,
+            "ghost_path",
+            vec![],
+            format!(
+                "accesses INNER devices: {}, guarded path: {}",
+                proposal.accesses_inner_devices, proposal.has_guarded_path
+            ),
+            |w| risk_scalar.access = w,
+        );
     }
 
     /// Evaluates Tier 3 SKO contamination risk.
     fn evaluate_knowledge_contamination(
-        &self,
+        shard: &BostromBlacklistShard,
         proposal: &EvolutionProposal,
         risk_scalar: &mut SabotageRiskScalar,
-    ) -> SabotageDetectorResult<()> {
-        let shard = self
-            .blacklist_shard
-            .as_ref()
-            .ok_or_else(|| SabotageDetectorError::AlnLoadError("Shard not loaded".into()))?;
-
+        contributions: &mut Vec<TierContribution>,
+    ) {
         // Check if proposal references contaminated SKOs
-        if proposal.references_quarantined_sko {
-            if let Some(rule) = shard.risk_rules.iter().find(|r| r.rule_id == "skO_contamination")
-            {
-                risk_scalar.knowledge = rule.weight;
-            }
-        }
-
-        Ok(())
+        Self::contribute(
+            shard,
+            contributions,
+            3,
+            "knowledge",
+            "quarantined_sko_reference",
+            proposal.references_quarantined_sko,
+            "skO_contamination",
+            vec![],
+            format!(
+                "references quarantined SKO: {}",
+                proposal.references_quarantined_sko
+            ),
+            |w| risk_scalar.knowledge = w,
+        );
     }
 
-    /// Determines the defensive action based on risk scalar and thresholds.
+    /// Determines the defensive action from the shard's `sabotage_threshold`
+    /// rows, with the rule that decided it. Rows are checked in
+    /// `THRESHOLD_PRECEDENCE` order, then any others in shard order; the
+    /// first whose scalar reaches its value decides.
     fn determine_action(
-        &self,
+        shard: &BostromBlacklistShard,
         risk_scalar: &SabotageRiskScalar,
-    ) -> SabotageDetectorResult<SabotageAction> {
-        let shard = self
-            .blacklist_shard
-            .as_ref()
-            .ok_or_else(|| SabotageDetectorError::AlnLoadError("Shard not loaded".into()))?;
-
-        // Check critical thresholds first
-        if risk_scalar.integrity >= 1.0 {
-            return Ok(SabotageAction::HardFail);
-        }
-
-        if risk_scalar.total >= 0.85 {
-            return Ok(SabotageAction::UnsafeDefer);
-        }
-
-        if risk_scalar.total >= 0.70 {
-            return Ok(SabotageAction::Deny);
-        }
-
-        if risk_scalar.biophysical >= 0.60 {
-            return Ok(SabotageAction::Inhibit);
-        }
-
-        if risk_scalar.access >= 0.75 {
-            return Ok(SabotageAction::Deny);
+    ) -> (SabotageAction, String) {
+        let rank = |t: &SabotageThreshold| {
+            THRESHOLD_PRECEDENCE
+                .iter()
+                .position(|id| *id == t.threshold_id)
+                .unwrap_or(THRESHOLD_PRECEDENCE.len())
+        };
+        let mut thresholds: Vec<&SabotageThreshold> = shard.thresholds.iter().collect();
+        thresholds.sort_by_key(|t| rank(t));
+
+        for threshold in thresholds {
+            let (Some(observed), Some(action)) = (
+                risk_scalar.component(&threshold.scalar),
+                SabotageAction::from_shard(&threshold.action),
+            ) else {
+                continue;
+            };
+            if observed >= threshold.value {
+                let reason = format!("{} >= {:.2}", threshold.scalar, threshold.value);
+                return (action, reason);
+            }
         }
 
-        Ok(SabotageAction::LogOnly)
+        (SabotageAction::LogOnly, "below all thresholds".into())
     }
 
     /// Generates and logs a SABOTAGEEVENT.
@@ -737,12 +1181,25 @@ impl SabotageDetector {
         // Write to QPU.Datashard
         self.write_to_datashard(&event)?;
 
-        // Anchor to Googolswarm
-        let anchor_txid = self.anchor_to_googolswarm(&event)?;
-        event.googolswarm_anchor_txid = Some(anchor_txid);
-
-        // Update event with anchor
-        self.update_event_with_anchor(&event)?;
+        // Queue for Googolswarm anchoring and make one bounded attempt for
+        // this event only; an unreachable or slow client leaves it pending.
+        self.lock_outbox()?
+            .enqueue(&event, Utc::now())
+            .map_err(SabotageDetectorError::AnchorOutboxError)?;
+        let report = AnchorOutbox::attempt_shared(
+            &self.anchor_outbox,
+            event.event_id,
+            self.googolswarm_client.clone(),
+            Arc::new(self.anchor_write_back()),
+            Utc::now(),
+            self.inline_anchor_timeout,
+        )
+        .map_err(SabotageDetectorError::AnchorOutboxError)?;
+        event.googolswarm_anchor_txid = report
+            .anchored
+            .iter()
+            .find(|(id, _)| *id == event.event_id)
+            .map(|(_, txid)| txid.clone());
 
         // Log to DonutLoop
         self.log_to_donutloop(&event)?;
@@ -757,39 +1214,6 @@ impl SabotageDetector {
             || did == "did:organiccpu:runtime:0xB05TR0M...50VERE1GN"
     }
 
-    /// Checks if an identifier matches a blacklist pattern.
-    fn matches_pattern(&self, identifier: &str, pattern: &BlacklistPattern) -> bool {
-        match pattern.op.as_str() {
-            "eq" => identifier == pattern.pattern,
-            "prefix" => identifier.starts_with(&pattern.pattern),
-            "glob" => self.glob_match(identifier, &pattern.pattern),
-            "regex" => self.regex_match(identifier, &pattern.pattern),
-            _ => false,
-        }
-    }
-
-    /// Simple glob pattern matching.
-    fn glob_match(&self, text: &str, pattern: &str) -> bool {
-        if pattern == "*" {
-            return true;
-        }
-        if pattern.starts_with("*.") {
-            let ext = &pattern[1..];
-            return text.ends_with(ext);
-        }
-        text == pattern
-    }
-
-    /// Simple regex pattern matching.
-    fn regex_match(&self, text: &str, pattern: &str) -> bool {
-        // Simplified regex matching for common patterns
-        if pattern.ends_with('*') {
-            let prefix = &pattern[..pattern.len() - 1];
-            return text.starts_with(prefix);
-        }
-        text.contains(pattern)
-    }
-
     /// Logs a pattern match to the audit trail.
     fn log_pattern_match(
         &self,
@@ -823,23 +1247,36 @@ impl SabotageDetector {
         Ok(())
     }
 
-    /// Anchors the event to Googolswarm blockchain.
-    fn anchor_to_googolswarm(&self, event: &SabotageEvent) -> SabotageDetectorResult<String> {
-        let txid = self
-            .googolswarm_client
-            .anchor_event(event)
-            .map_err(|e| SabotageDetectorError::GoogolswarmAnchorError(e.to_string()))?;
+    /// Attempts every due outbox entry against the Googolswarm client and
+    /// applies `update_event_with_anchor` for each txid received. Anchoring
+    /// failures are rescheduled with backoff, not returned as errors.
+    pub fn process_anchor_outbox(
+        &self,
+        now: DateTime<Utc>,
+    ) -> SabotageDetectorResult<DrainReport> {
+        let sink = self.anchor_write_back();
+        AnchorOutbox::drain_shared(&self.anchor_outbox, &self.googolswarm_client, &sink, now)
+            .map_err(SabotageDetectorError::AnchorOutboxError)
+    }
 
-        Ok(txid)
+    /// Events still waiting for a Googolswarm txid, oldest first.
+    pub fn pending_anchors(&self) -> SabotageDetectorResult<Vec<OutboxEntry>> {
+        Ok(self.lock_outbox()?.entries().to_vec())
     }
 
-    /// Updates the event record with the anchor transaction ID.
-    fn update_event_with_anchor(&self, event: &SabotageEvent) -> SabotageDetectorResult<()> {
-        self.qpu_datashard
-            .update_record_anchor(event.event_id, event.googolswarm_anchor_txid.as_ref().unwrap())
-            .map_err(|e| SabotageDetectorError::DatashardWriteError(e.to_string()))?;
+    /// Locks the outbox shared with the anchor worker.
+    fn lock_outbox(&self) -> SabotageDetectorResult<MutexGuard<'_, AnchorOutbox>> {
+        self.anchor_outbox
+            .lock()
+            .map_err(|e| SabotageDetectorError::AnchorOutboxError(e.to_string()))
+    }
 
-        Ok(())
+    /// Write-back target for txids, usable from the anchor worker thread.
+    fn anchor_write_back(&self) -> AnchorWriteBack {
+        AnchorWriteBack {
+            qpu_datashard: self.qpu_datashard.clone(),
+            donutloop_logger: self.donutloop_logger.clone(),
+        }
     }
 
     /// Logs the event to DonutLoop audit trail.
@@ -891,6 +1328,47 @@ impl SabotageDetector {
     }
 }
 
+impl AnchorSink for SabotageDetector {
+    fn apply_anchor(&self, event_id: u64, txid: &str) -> Result<(), String> {
+        self.anchor_write_back().apply_anchor(event_id, txid)
+    }
+}
+
+/// Writes anchor txids back into QPU.Datashard and the DonutLoop trail.
+struct AnchorWriteBack {
+    qpu_datashard: Arc<QpuDatashard>,
+    donutloop_logger: Arc<DonutLoopLogger>,
+}
+
+impl AnchorWriteBack {
+    /// Updates the event record with the anchor transaction ID.
+    fn update_event_with_anchor(&self, event_id: u64, txid: &str) -> SabotageDetectorResult<()> {
+        self.qpu_datashard
+            .update_record_anchor(event_id, txid)
+            .map_err(|e| SabotageDetectorError::DatashardWriteError(e.to_string()))?;
+
+        self.donutloop_logger
+            .log_entry(
+                "SABOTAGEEVENT_ANCHORED",
+                &serde_json::json!({
+                    "event_id": event_id,
+                    "googolswarm_anchor": txid,
+                    "timestamp": Utc::now().to_rfc3339(),
+                }),
+            )
+            .map_err(|e| SabotageDetectorError::DonutLoopLogError(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+impl AnchorSink for AnchorWriteBack {
+    fn apply_anchor(&self, event_id: u64, txid: &str) -> Result<(), String> {
+        self.update_event_with_anchor(event_id, txid)
+            .map_err(|e| e.to_string())
+    }
+}
+
 // =============================================================================
 // UNIT TESTS
 // =============================================================================
@@ -898,6 +1376,8 @@ impl SabotageDetector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::guards::anchor_outbox::MockAnchorService;
+    use crate::guards::blacklist_shard::CompiledPattern;
     use crate::test_utils::{
         mock_brainidentity, mock_did_keypair, mock_donutloop, mock_googolswarm, mock_qpu_datashard,
     };
@@ -923,7 +1403,6 @@ mod tests {
 
     #[test]
     fn test_blacklist_pattern_matching_eq() {
-        let detector = create_test_detector();
         let pattern = BlacklistPattern {
             metric: "blacklist_pattern".into(),
             domain: "host".into(),
@@ -935,13 +1414,13 @@ mod tests {
             source: "test".into(),
         };
 
-        assert!(detector.matches_pattern("QConLocus", &pattern));
-        assert!(!detector.matches_pattern("OtherCrate", &pattern));
+        let compiled = CompiledPattern::compile(&pattern).unwrap();
+        assert!(compiled.is_match("QConLocus"));
+        assert!(!compiled.is_match("OtherCrate"));
     }
 
     #[test]
     fn test_blacklist_pattern_matching_prefix() {
-        let detector = create_test_detector();
         let pattern = BlacklistPattern {
             metric: "blacklist_pattern".into(),
             domain: "host".into(),
@@ -953,8 +1432,9 @@ mod tests {
             source: "test".into(),
         };
 
-        assert!(detector.matches_pattern("QConLocus_orchestrator", &pattern));
-        assert!(!detector.matches_pattern("OtherCrate_symbol", &pattern));
+        let compiled = CompiledPattern::compile(&pattern).unwrap();
+        assert!(compiled.is_match("QConLocus_orchestrator"));
+        assert!(!compiled.is_match("OtherCrate_symbol"));
     }
 
     #[test]
@@ -982,6 +1462,356 @@ mod tests {
         let total = scalar.compute_total();
         assert_eq!(total, 1.0); // Should be clamped to max 1.0
     }
+
+    #[test]
+    fn test_trace_diff_reports_weight_and_action_changes() {
+        let contribution = |weight: f64| TierContribution {
+            tier: 2,
+            component: "blacklist".into(),
+            check: "blacklist_pattern".into(),
+            fired: true,
+            rule_id: Some("blacklist_match".into()),
+            weight,
+            matched_pattern: None,
+            observed: vec![],
+            explanation: String::new(),
+        };
+        let trace = |version: &str, weight: f64, action: SabotageAction| EvaluationTrace {
+            shard_version: version.into(),
+            contributions: vec![contribution(weight)],
+            risk: SabotageRiskScalar::new(),
+            action,
+            action_reason: String::new(),
+        };
+
+        let a = trace("1.0.0", 0.30, SabotageAction::LogOnly);
+        assert!(diff_traces(&a, &a.clone()).is_empty());
+
+        let b = trace("1.1.0", 0.80, SabotageAction::Deny);
+        let diffs = diff_traces(&a, &b);
+        assert_eq!(diffs.len(), 3);
+        assert!(diffs.iter().any(|d| d.starts_with("blacklist_pattern")));
+    }
+
+    const SHARD: &str =
+        include_str!("../../../../Config/Sovereignty/policies/bostrom-blacklist-v1.aln");
+
+    /// Host-signed proposal whose only risk factor is its identifier.
+    fn proposal(detector: &SabotageDetector, identifier: &str) -> EvolutionProposal {
+        EvolutionProposal {
+            identifier: identifier.into(),
+            signer_did: detector.host_did_keypair.did(),
+            commit_metadata: None,
+            firmware_update: None,
+            accesses_inner_devices: false,
+            has_guarded_path: true,
+            references_quarantined_sko: false,
+            ..Default::default()
+        }
+    }
+
+    fn calm_telemetry() -> BiophysicalTelemetry {
+        BiophysicalTelemetry {
+            eeg_stress_ratio: 0.2,
+            hrv_anomaly_index: 0.1,
+            nanoswarm_density: 0.1,
+            sleep_corridor_violated: false,
+            ..Default::default()
+        }
+    }
+
+    fn shard_with_blacklist_weight(version: &str, weight: f64) -> BostromBlacklistShard {
+        let mut shard = BostromBlacklistShard::parse_aln(SHARD).unwrap();
+        shard.version = version.into();
+        for rule in shard.risk_rules.iter_mut().filter(|r| r.rule_id == "blacklist_match") {
+            rule.weight = weight;
+        }
+        shard
+    }
+
+    #[test]
+    fn test_traced_evaluation_explains_a_blacklisted_proposal() {
+        let detector = create_test_detector();
+        let trace = detector
+            .evaluate_proposal_traced(&proposal(&detector, "QConLocus"), &calm_telemetry())
+            .unwrap();
+
+        assert_eq!(trace.shard_version, "1.0.0");
+        let blacklist = trace
+            .contributions
+            .iter()
+            .find(|c| c.check == "blacklist_pattern")
+            .unwrap();
+        assert!(blacklist.fired);
+        assert_eq!(blacklist.tier, 2);
+        assert_eq!(blacklist.rule_id.as_deref(), Some("blacklist_match"));
+        assert_eq!(blacklist.weight, 0.30);
+        assert_eq!(blacklist.matched_pattern.as_ref().unwrap().pattern, "QConLocus");
+        for check in ["untrusted_signer", "firmware_not_did_signed", "eeg_hrv_stress_spike", "ghost_access_path"] {
+            assert!(!trace.contributions.iter().any(|c| c.check == check && c.fired), "{}", check);
+        }
+        assert!((trace.risk.blacklist - 0.30).abs() < 1e-9);
+        assert_eq!(trace.action, SabotageAction::LogOnly);
+        assert_eq!(trace.action_reason, "below all thresholds");
+
+        let clean = detector
+            .evaluate_proposal_traced(&proposal(&detector, "sovereign_module"), &calm_telemetry())
+            .unwrap();
+        assert!(clean.contributions.iter().all(|c| c.matched_pattern.is_none()));
+    }
+
+    #[test]
+    fn test_replay_reproduces_a_recorded_decision() {
+        let detector = create_test_detector();
+        let p = proposal(&detector, "QConLocus_orchestrator");
+        let telemetry = calm_telemetry();
+        let trace = detector.evaluate_proposal_traced(&p, &telemetry).unwrap();
+        let record = detector.record_decision(&p, &telemetry, &trace);
+
+        let outcome = detector
+            .replay_decision(&record, &BostromBlacklistShard::parse_aln(SHARD).unwrap())
+            .unwrap();
+        assert!(outcome.identical, "{:?}", outcome.differences);
+        assert_eq!(outcome.replayed, trace);
+
+        // A same-version shard with different weights is reported, not hidden.
+        let drifted = detector
+            .replay_decision(&record, &shard_with_blacklist_weight("1.0.0", 0.5))
+            .unwrap();
+        assert!(!drifted.identical);
+        assert!(drifted.differences.iter().any(|d| d.starts_with("blacklist_pattern")));
+
+        let err = detector
+            .replay_decision(&record, &shard_with_blacklist_weight("2.0.0", 0.30))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SabotageDetectorError::ShardVersionMismatch { ref expected, ref actual }
+                if expected == "1.0.0" && actual == "2.0.0"
+        ));
+    }
+
+    #[test]
+    fn test_what_if_reports_an_action_change() {
+        let detector = create_test_detector();
+        let p = proposal(&detector, "QConLocus");
+        let report = detector
+            .what_if(&p, &calm_telemetry(), &shard_with_blacklist_weight("1.1.0", 0.75))
+            .unwrap();
+
+        assert_eq!(report.current.action, SabotageAction::LogOnly);
+        assert_eq!(report.candidate.action, SabotageAction::Deny);
+        assert_eq!(report.candidate.action_reason, "sabotage_risk >= 0.70");
+        assert!(report.action_changed);
+        assert!((report.total_delta - 0.45).abs() < 1e-9);
+        assert!(report.differences.contains(&"shard_version: 1.0.0 -> 1.1.0".to_string()));
+        assert!(report.differences.iter().any(|d| d.starts_with("action:")));
+
+        // Same shard: nothing changes.
+        let same = detector
+            .what_if(&p, &calm_telemetry(), &shard_with_blacklist_weight("1.0.0", 0.30))
+            .unwrap();
+        assert!(!same.action_changed);
+        assert!(same.differences.is_empty());
+    }
+
+    #[test]
+    fn test_what_if_uses_the_candidate_thresholds() {
+        let detector = create_test_detector();
+        let p = proposal(&detector, "QConLocus");
+        let mut candidate = BostromBlacklistShard::parse_aln(SHARD).unwrap();
+        candidate.version = "1.1.0".into();
+        for t in candidate.thresholds.iter_mut().filter(|t| t.threshold_id == "federal_toy_block") {
+            t.value = 0.25;
+        }
+        let report = detector.what_if(&p, &calm_telemetry(), &candidate).unwrap();
+
+        // Same weights, lower cut-off: only the action moves.
+        assert!(report.total_delta.abs() < 1e-12);
+        assert_eq!(report.current.action, SabotageAction::LogOnly);
+        assert_eq!(report.candidate.action, SabotageAction::Deny);
+        assert_eq!(report.candidate.action_reason, "sabotage_risk >= 0.25");
+        assert!(report.action_changed);
+
+        // Without threshold rows nothing but logging is possible.
+        candidate.thresholds.clear();
+        let risk = SabotageRiskScalar { total: 1.0, integrity: 1.0, ..SabotageRiskScalar::new() };
+        assert_eq!(
+            SabotageDetector::determine_action(&candidate, &risk),
+            (SabotageAction::LogOnly, "below all thresholds".to_string())
+        );
+    }
+
+    fn detector_with_anchor_service(service: Arc<MockAnchorService>) -> SabotageDetector {
+        SabotageDetector::new(
+            PathBuf::from("test_data/bostrom-blacklist-v1.aln"),
+            mock_brainidentity(),
+            mock_did_keypair(),
+            mock_qpu_datashard(),
+            mock_donutloop(),
+            service,
+        )
+        .expect("Failed to create test detector")
+    }
+
+    /// Raises a Deny event for a blacklisted proposal.
+    fn raise_event(detector: &mut SabotageDetector) -> SabotageEvent {
+        let p = proposal(detector, "QConLocus");
+        let (risk, _) = detector.evaluate_proposal(&p, &calm_telemetry()).unwrap();
+        detector
+            .generate_sabotage_event(&risk, SabotageAction::Deny, &p)
+            .unwrap()
+    }
+
+    fn outbox_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "sabotage_outbox_{}_{}.jsonl",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_event_is_anchored_and_written_back_when_online() {
+        let service = Arc::new(MockAnchorService::new());
+        let mut detector = detector_with_anchor_service(service.clone());
+
+        let event = raise_event(&mut detector);
+        assert_eq!(
+            event.googolswarm_anchor_txid,
+            Some(MockAnchorService::txid_for(event.event_id))
+        );
+        assert_eq!(service.calls(), 1);
+        assert!(detector.pending_anchors().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_offline_event_stays_queued_until_service_returns() {
+        let service = Arc::new(MockAnchorService::new());
+        service.set_offline(true);
+        let mut detector = detector_with_anchor_service(service.clone());
+
+        let event = raise_event(&mut detector);
+        assert_eq!(event.googolswarm_anchor_txid, None);
+        let pending = detector.pending_anchors().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event.event_id, event.event_id);
+        assert_eq!(pending[0].attempts, 1);
+        assert!(pending[0].last_error.as_deref().unwrap().contains("offline"));
+
+        // Not yet due: the backoff holds the entry back.
+        let report = detector.process_anchor_outbox(Utc::now()).unwrap();
+        assert!(report.anchored.is_empty());
+        assert_eq!(report.pending, 1);
+
+        service.set_offline(false);
+        let later = Utc::now() + chrono::Duration::hours(1);
+        let report = detector.process_anchor_outbox(later).unwrap();
+        assert_eq!(
+            report.anchored,
+            vec![(event.event_id, MockAnchorService::txid_for(event.event_id))]
+        );
+        assert_eq!(report.pending, 0);
+        assert!(detector.pending_anchors().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_pending_anchors_survive_a_restart() {
+        let path = outbox_path("restart");
+        let service = Arc::new(MockAnchorService::new());
+        service.set_offline(true);
+
+        let event = {
+            let mut detector = detector_with_anchor_service(service.clone())
+                .with_anchor_outbox(&path)
+                .unwrap();
+            raise_event(&mut detector)
+        };
+
+        let reloaded = AnchorOutbox::open(&path).unwrap();
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded.entries()[0].event.event_id, event.event_id);
+
+        service.set_offline(false);
+        let detector = detector_with_anchor_service(service)
+            .with_anchor_outbox(&path)
+            .unwrap();
+        let report = detector
+            .process_anchor_outbox(Utc::now() + chrono::Duration::hours(1))
+            .unwrap();
+        assert_eq!(report.anchored.len(), 1);
+        assert!(AnchorOutbox::open(&path).unwrap().is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_background_worker_drains_the_outbox() {
+        let service = Arc::new(MockAnchorService::new());
+        service.set_offline(true);
+        let mut detector = detector_with_anchor_service(service.clone())
+            .with_anchor_retry_policy(RetryPolicy {
+                base_delay_ms: 1,
+                max_delay_ms: 5,
+            })
+            .unwrap();
+        raise_event(&mut detector);
+        assert_eq!(detector.pending_anchors().unwrap().len(), 1);
+
+        detector.start_anchor_worker(Duration::from_millis(2));
+        service.set_offline(false);
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !detector.pending_anchors().unwrap().is_empty() {
+            assert!(std::time::Instant::now() < deadline, "worker never drained the outbox");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        detector.stop_anchor_worker();
+    }
+
+    /// Anchor client that blocks until released.
+    struct GatedAnchorClient {
+        gate: Mutex<std::sync::mpsc::Receiver<()>>,
+    }
+
+    impl AnchorClient for GatedAnchorClient {
+        fn anchor(&self, event: &SabotageEvent) -> Result<String, String> {
+            self.gate.lock().unwrap().recv().map_err(|e| e.to_string())?;
+            Ok(MockAnchorService::txid_for(event.event_id))
+        }
+    }
+
+    #[test]
+    fn test_a_hung_anchor_client_neither_blocks_events_nor_holds_the_outbox() {
+        let (release, gate) = std::sync::mpsc::channel();
+        let mut detector = SabotageDetector::new(
+            PathBuf::from("test_data/bostrom-blacklist-v1.aln"),
+            mock_brainidentity(),
+            mock_did_keypair(),
+            mock_qpu_datashard(),
+            mock_donutloop(),
+            GatedAnchorClient { gate: Mutex::new(gate) },
+        )
+        .unwrap()
+        .with_inline_anchor_timeout(Duration::from_millis(20));
+
+        let event = raise_event(&mut detector);
+        assert_eq!(event.googolswarm_anchor_txid, None);
+
+        // The attempt is still in flight: the outbox stays usable and a
+        // drain pass skips the claimed entry instead of calling again.
+        let pending = detector.pending_anchors().unwrap();
+        assert_eq!((pending.len(), pending[0].attempts), (1, 0));
+        let report = detector.process_anchor_outbox(Utc::now()).unwrap();
+        assert!(report.anchored.is_empty() && report.failed.is_empty());
+
+        release.send(()).unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !detector.pending_anchors().unwrap().is_empty() {
+            assert!(std::time::Instant::now() < deadline, "attempt never settled");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
 }
 
 // =============================================================================
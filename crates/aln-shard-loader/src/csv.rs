@@ -0,0 +1,126 @@
+use crate::error::{ShardErrorKind, ShardErrors};
+
+/// One logical record: the 1-based line it starts on and its cells.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Record {
+    pub line: usize,
+    pub cells: Vec<String>,
+}
+
+impl Record {
+    pub fn first(&self) -> &str {
+        self.cells.first().map(String::as_str).unwrap_or("")
+    }
+}
+
+/// Split one CSV line. Unquoted cells are trimmed, quoted cells are kept
+/// verbatim (`""` inside quotes is a literal quote, a bare `""` cell is
+/// empty). A `"` inside an unquoted cell or text after a closing quote is
+/// rejected. `UnterminatedQuote` means a quote is still open, so a caller
+/// may join the next physical line and retry.
+pub fn split_row(line: &str) -> Result<Vec<String>, ShardErrorKind> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    cell.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if cell.trim().is_empty() && !quoted => {
+                cell.clear();
+                quoted = true;
+                in_quotes = true;
+            }
+            '"' => return Err(ShardErrorKind::StrayQuote { cell: cells.len() + 1 }),
+            ',' if !in_quotes => {
+                cells.push(finish(&mut cell, quoted));
+                quoted = false;
+            }
+            c if quoted && !in_quotes => {
+                if !c.is_whitespace() {
+                    return Err(ShardErrorKind::TextAfterQuote { cell: cells.len() + 1 });
+                }
+            }
+            _ => cell.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(ShardErrorKind::UnterminatedQuote);
+    }
+    cells.push(finish(&mut cell, quoted));
+    Ok(cells)
+}
+
+fn finish(cell: &mut String, quoted: bool) -> String {
+    let out = if quoted { cell.clone() } else { cell.trim().to_string() };
+    cell.clear();
+    out
+}
+
+/// Split a shard into records, skipping blank lines and `#` comments and
+/// joining physical lines while a quoted cell is open.
+pub(crate) fn records(text: &str, errors: &mut ShardErrors) -> Vec<Record> {
+    let mut out = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+
+    for (idx, raw) in text.lines().enumerate() {
+        let raw = raw.trim_end_matches('\r');
+        let (line, buf) = match pending.take() {
+            Some((start, mut buf)) => {
+                buf.push('\n');
+                buf.push_str(raw);
+                (start, buf)
+            }
+            None => {
+                let t = raw.trim();
+                if t.is_empty() || t.starts_with('#') {
+                    continue;
+                }
+                (idx + 1, raw.to_string())
+            }
+        };
+        match split_row(&buf) {
+            Ok(cells) => out.push(Record { line, cells }),
+            Err(ShardErrorKind::UnterminatedQuote) => pending = Some((line, buf)),
+            Err(kind) => errors.push(line, kind),
+        }
+    }
+    if let Some((line, _)) = pending {
+        errors.push(line, ShardErrorKind::UnterminatedQuote);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_row_quotes_and_empties() {
+        assert_eq!(
+            split_row(r#"session_id,xr,session,,,"",file32"#).unwrap(),
+            vec!["session_id", "xr", "session", "", "", "", "file32"]
+        );
+        assert_eq!(split_row(r#"a,"x,y ""z""",b"#).unwrap(), vec!["a", r#"x,y "z""#, "b"]);
+        assert_eq!(split_row(r#"a, "q" ,b"#).unwrap(), vec!["a", "q", "b"]);
+        assert_eq!(split_row(r#"a,"open"#), Err(ShardErrorKind::UnterminatedQuote));
+        assert_eq!(split_row(r#"a,b",c"#), Err(ShardErrorKind::StrayQuote { cell: 2 }));
+        assert_eq!(split_row(r#"a,"b"c,d"#), Err(ShardErrorKind::TextAfterQuote { cell: 2 }));
+
+        let mut errors = ShardErrors::default();
+        let recs = records("a,\"one\ntwo\",b\n\n# note\nc\nd,e\"\n", &mut errors);
+        assert_eq!(errors.iter().map(|e| e.line).collect::<Vec<_>>(), vec![6]);
+        assert_eq!(recs.len(), 2);
+        assert_eq!(recs[0].cells, vec!["a", "one\ntwo", "b"]);
+        assert_eq!((recs[1].line, recs[1].first()), (5, "c"));
+    }
+}
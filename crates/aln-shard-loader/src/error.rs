@@ -0,0 +1,134 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// What went wrong on a shard line (or, for line 0, in the shard as a whole).
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ShardErrorKind {
+    #[error("expected `aln,<shard-id>` directive")]
+    MissingDirective,
+    #[error("expected header `{expected}`, found `{found}`")]
+    UnexpectedHeader { expected: String, found: String },
+    #[error("expected {expected} cells, found {found}")]
+    CellCount { expected: usize, found: usize },
+    #[error("unterminated quoted cell")]
+    UnterminatedQuote,
+    #[error("stray `\"` in unquoted cell {cell}")]
+    StrayQuote { cell: usize },
+    #[error("text after closing quote in cell {cell}")]
+    TextAfterQuote { cell: usize },
+    #[error("empty metric name")]
+    EmptyMetric,
+    #[error("metric `{metric}` already defined on line {first_line}")]
+    DuplicateMetric { metric: String, first_line: usize },
+    #[error("threshold `{0}` is not a number")]
+    InvalidThreshold(String),
+    #[error("unknown op `{0}` (expected max, min, eq or empty)")]
+    UnknownOp(String),
+    #[error("`{metric}` has op `{op}` but no threshold")]
+    MissingThreshold { metric: String, op: String },
+    #[error("unknown record `{0}` (expected SECTION, ROW or FOOTER)")]
+    UnknownRecord(String),
+    #[error("ROW before any SECTION")]
+    RowOutsideSection,
+    #[error("SECTION without a name")]
+    UnnamedSection,
+    #[error("section `{name}` already opened on line {first_line}")]
+    DuplicateSection { name: String, first_line: usize },
+    #[error("no schema registered for shard `{0}`")]
+    UnknownSchema(String),
+    #[error("required metric `{0}` is missing")]
+    MissingMetric(String),
+    #[error("metric `{0}` is not declared by the schema")]
+    UnlistedMetric(String),
+    #[error("`{metric}` unit `{found}` not in {expected:?}")]
+    UnitNotAllowed { metric: String, found: String, expected: Vec<String> },
+    #[error("`{metric}` op `{op}` is not in the schema vocabulary")]
+    OpNotAllowed { metric: String, op: String },
+    #[error("required section `{0}` is missing")]
+    MissingSection(String),
+    #[error("row kind `{0}` is not in the schema vocabulary")]
+    KindNotAllowed(String),
+}
+
+/// A single parse or validation failure. `line` is 1-based; 0 means the
+/// error concerns the shard as a whole (e.g. a missing required metric).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShardError {
+    pub line: usize,
+    pub kind: ShardErrorKind,
+}
+
+impl ShardError {
+    pub fn new(line: usize, kind: ShardErrorKind) -> Self {
+        ShardError { line, kind }
+    }
+}
+
+impl fmt::Display for ShardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "line {}: {}", self.line, self.kind)
+        }
+    }
+}
+
+impl std::error::Error for ShardError {}
+
+/// Every error found in one pass, in line order. Loaders keep going after
+/// the first bad row so a broken shard is reported in full.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ShardErrors(pub Vec<ShardError>);
+
+impl ShardErrors {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ShardError> {
+        self.0.iter()
+    }
+
+    pub(crate) fn push(&mut self, line: usize, kind: ShardErrorKind) {
+        self.0.push(ShardError::new(line, kind));
+    }
+
+    pub(crate) fn into_result<T>(mut self, value: T) -> Result<T, ShardErrors> {
+        if self.0.is_empty() {
+            Ok(value)
+        } else {
+            self.0.sort_by_key(|e| e.line);
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ShardErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", e)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ShardErrors {}
+
+/// Failure loading a shard file from disk.
+#[derive(Debug, Error)]
+pub enum LoadError {
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{}:\n{errors}", path.display())]
+    Invalid { path: PathBuf, errors: ShardErrors },
+}
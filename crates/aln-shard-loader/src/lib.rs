@@ -0,0 +1,29 @@
+//! Typed loader for the CSV-style ALN shard files under `aln/`.
+//!
+//! Two layouts are supported:
+//! - table shards: an `aln,<shard-id>` directive, a header row and data rows.
+//!   The common `metric,domain,module,op,threshold,unit,source` header gets
+//!   the typed [`MetricShard`] view (`shard.threshold("xr_visual_joules")`).
+//! - SECTION/ROW shards such as `policy-newrow-shark.aln` and the
+//!   `xr-grid.*` files, loaded as [`SectionShard`].
+//!
+//! [`SchemaRegistry`] validates parsed shards against registered schemas
+//! (required metrics, units, op vocabulary). All errors carry the 1-based
+//! line they were found on.
+//!
+//! [`split_row`] is the single-line CSV splitter behind all of the above,
+//! exported for loaders of other CSV-style shards.
+
+mod csv;
+pub mod error;
+pub mod metric;
+pub mod schema;
+pub mod section;
+pub mod table;
+
+pub use csv::split_row;
+pub use error::{LoadError, ShardError, ShardErrorKind, ShardErrors};
+pub use metric::{MetricRow, MetricShard, Op, METRIC_HEADER};
+pub use schema::{MetricSchema, MetricSpec, SchemaRegistry, SectionSchema};
+pub use section::{Section, SectionRow, SectionShard};
+pub use table::{TableRow, TableShard};
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ShardErrorKind, ShardErrors};
+use crate::table::TableShard;
+
+/// Header every metric shard must carry, in this order.
+pub const METRIC_HEADER: [&str; 7] = ["metric", "domain", "module", "op", "threshold", "unit", "source"];
+
+/// Comparison a metric row imposes on observed values. An empty `op` cell
+/// declares a value (a weight, a total) rather than a bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Op {
+    Max,
+    Min,
+    Eq,
+}
+
+impl Op {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Op::Max => "max",
+            Op::Min => "min",
+            Op::Eq => "eq",
+        }
+    }
+
+    /// Whether `observed` satisfies this op against `threshold`.
+    pub fn admits(&self, threshold: f64, observed: f64) -> bool {
+        match self {
+            Op::Max => observed <= threshold,
+            Op::Min => observed >= threshold,
+            Op::Eq => (observed - threshold).abs() <= 1e-9,
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Op {
+    type Err = ShardErrorKind;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "max" => Ok(Op::Max),
+            "min" => Ok(Op::Min),
+            "eq" => Ok(Op::Eq),
+            _ => Err(ShardErrorKind::UnknownOp(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricRow {
+    pub line: usize,
+    pub metric: String,
+    pub domain: String,
+    pub module: String,
+    pub op: Option<Op>,
+    pub threshold: Option<f64>,
+    pub unit: String,
+    pub source: String,
+}
+
+/// Typed `metric,domain,module,op,threshold,unit,source` shard.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricShard {
+    pub id: String,
+    rows: Vec<MetricRow>,
+    #[serde(skip)]
+    index: HashMap<String, usize>,
+}
+
+impl MetricShard {
+    /// Parse shard text, reporting every bad line rather than the first.
+    pub fn parse(text: &str) -> Result<Self, ShardErrors> {
+        let mut errors = ShardErrors::default();
+        let Some(table) = TableShard::parse_lenient(text, &mut errors) else {
+            return Err(errors);
+        };
+        Self::build(table, errors)
+    }
+
+    pub fn from_table(table: TableShard) -> Result<Self, ShardErrors> {
+        Self::build(table, ShardErrors::default())
+    }
+
+    fn build(table: TableShard, mut errors: ShardErrors) -> Result<Self, ShardErrors> {
+        if table.header != METRIC_HEADER {
+            errors.push(
+                table.header_line,
+                ShardErrorKind::UnexpectedHeader { expected: METRIC_HEADER.join(","), found: table.header.join(",") },
+            );
+            return Err(errors);
+        }
+
+        let mut rows: Vec<MetricRow> = Vec::with_capacity(table.rows.len());
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for r in table.rows {
+            let c = &r.cells;
+            if c[0].is_empty() {
+                errors.push(r.line, ShardErrorKind::EmptyMetric);
+                continue;
+            }
+            let op = if c[3].is_empty() {
+                None
+            } else {
+                match c[3].parse::<Op>() {
+                    Ok(op) => Some(op),
+                    Err(kind) => {
+                        errors.push(r.line, kind);
+                        continue;
+                    }
+                }
+            };
+            let threshold = if c[4].is_empty() {
+                None
+            } else {
+                match c[4].parse::<f64>() {
+                    Ok(v) if v.is_finite() => Some(v),
+                    _ => {
+                        errors.push(r.line, ShardErrorKind::InvalidThreshold(c[4].clone()));
+                        continue;
+                    }
+                }
+            };
+            if let (Some(op), None) = (op, threshold) {
+                errors.push(r.line, ShardErrorKind::MissingThreshold { metric: c[0].clone(), op: op.to_string() });
+                continue;
+            }
+            if let Some(&first) = index.get(&c[0]) {
+                let first_line = rows[first].line;
+                errors.push(r.line, ShardErrorKind::DuplicateMetric { metric: c[0].clone(), first_line });
+                continue;
+            }
+            index.insert(c[0].clone(), rows.len());
+            rows.push(MetricRow {
+                line: r.line,
+                metric: c[0].clone(),
+                domain: c[1].clone(),
+                module: c[2].clone(),
+                op,
+                threshold,
+                unit: c[5].clone(),
+                source: c[6].clone(),
+            });
+        }
+        errors.into_result(MetricShard { id: table.id, rows, index })
+    }
+
+    pub fn rows(&self) -> &[MetricRow] {
+        &self.rows
+    }
+
+    pub fn row(&self, metric: &str) -> Option<&MetricRow> {
+        self.index.get(metric).map(|&i| &self.rows[i])
+    }
+
+    pub fn threshold(&self, metric: &str) -> Option<f64> {
+        self.row(metric).and_then(|r| r.threshold)
+    }
+
+    pub fn op(&self, metric: &str) -> Option<Op> {
+        self.row(metric).and_then(|r| r.op)
+    }
+
+    pub fn unit(&self, metric: &str) -> Option<&str> {
+        self.row(metric).map(|r| r.unit.as_str())
+    }
+
+    /// Whether `observed` is within the metric's bound. `None` when the
+    /// metric is absent or only declares a value.
+    pub fn admits(&self, metric: &str, observed: f64) -> Option<bool> {
+        let row = self.row(metric)?;
+        Some(row.op?.admits(row.threshold?, observed))
+    }
+
+    /// Rows enforced by a given guard module, e.g. `xr.budget.guard`.
+    pub fn module_rows<'a>(&'a self, module: &'a str) -> impl Iterator<Item = &'a MetricRow> + 'a {
+        self.rows.iter().filter(move |r| r.module == module)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_shard_lookups() {
+        let shard = MetricShard::parse(include_str!("../../../aln/xr.multimodal.budget.v1.aln")).unwrap();
+        assert_eq!(shard.id, "xr.multimodal.budget.v1");
+        assert_eq!(shard.threshold("xr_visual_joules"), Some(24000.0));
+        assert_eq!(shard.op("xr_visual_joules"), Some(Op::Max));
+        assert_eq!(shard.op("host_limit_joules"), None);
+        assert_eq!(shard.unit("bci_stim_joules"), Some("J"));
+        assert_eq!(shard.admits("bci_stim_joules", 9000.0), Some(false));
+        assert_eq!(shard.admits("host_limit_joules", 1.0), None);
+        assert_eq!(shard.module_rows("xr.budget.guard").count(), 3);
+
+        let session = MetricShard::parse(include_str!("../../../aln/xr.corridor.session.v1.aln")).unwrap();
+        assert_eq!(session.threshold("session_id"), None);
+        assert_eq!(session.row("session_id").map(|r| r.line), Some(4));
+    }
+
+    #[test]
+    fn test_reports_every_bad_line() {
+        let text = "aln,t.v1\nmetric,domain,module,op,threshold,unit,source\n\
+                    a,x,m,max,1.0,J,f\n\
+                    b,x,m,under,1.0,J,f\n\
+                    c,x,m,max,lots,J,f\n\
+                    a,x,m,min,2.0,J,f\n\
+                    d,x,m,min,,J,f\n\
+                    e,x,m\n";
+        let errs = MetricShard::parse(text).unwrap_err();
+        let lines: Vec<usize> = errs.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![4, 5, 6, 7, 8]);
+        assert_eq!(errs.0[2].kind, ShardErrorKind::DuplicateMetric { metric: "a".into(), first_line: 3 });
+        assert!(errs.to_string().starts_with("line 4: unknown op `under`"));
+    }
+}
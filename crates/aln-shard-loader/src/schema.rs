@@ -0,0 +1,324 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{LoadError, ShardError, ShardErrorKind, ShardErrors};
+use crate::metric::{MetricShard, Op};
+use crate::section::SectionShard;
+
+/// A metric the schema requires, with the units it may be expressed in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSpec {
+    pub metric: String,
+    /// Accepted units; empty accepts any.
+    pub units: Vec<String>,
+    /// Whether the row must carry a threshold.
+    pub threshold_required: bool,
+}
+
+/// Expected shape of one metric shard id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSchema {
+    pub shard_id: String,
+    pub required: Vec<MetricSpec>,
+    /// Ops rows may use. Rows with an empty op are always accepted.
+    pub ops: Vec<Op>,
+    /// Whether rows not listed in `required` are accepted.
+    pub allow_unlisted: bool,
+}
+
+impl MetricSchema {
+    pub fn new(shard_id: &str, ops: &[Op]) -> Self {
+        MetricSchema { shard_id: shard_id.to_string(), required: Vec::new(), ops: ops.to_vec(), allow_unlisted: false }
+    }
+
+    /// Require `metric` with a threshold in one of `units`.
+    pub fn require(mut self, metric: &str, units: &[&str]) -> Self {
+        self.required.push(MetricSpec {
+            metric: metric.to_string(),
+            units: units.iter().map(|u| u.to_string()).collect(),
+            threshold_required: true,
+        });
+        self
+    }
+
+    /// Require `metric` to be declared, with or without a threshold.
+    pub fn declare(mut self, metric: &str) -> Self {
+        self.required.push(MetricSpec { metric: metric.to_string(), units: Vec::new(), threshold_required: false });
+        self
+    }
+
+    pub fn allow_unlisted(mut self) -> Self {
+        self.allow_unlisted = true;
+        self
+    }
+
+    pub fn validate(&self, shard: &MetricShard) -> Vec<ShardError> {
+        let mut errors = Vec::new();
+        for spec in &self.required {
+            let Some(row) = shard.row(&spec.metric) else {
+                errors.push(ShardError::new(0, ShardErrorKind::MissingMetric(spec.metric.clone())));
+                continue;
+            };
+            if !spec.units.is_empty() && !spec.units.contains(&row.unit) {
+                errors.push(ShardError::new(
+                    row.line,
+                    ShardErrorKind::UnitNotAllowed {
+                        metric: row.metric.clone(),
+                        found: row.unit.clone(),
+                        expected: spec.units.clone(),
+                    },
+                ));
+            }
+            if spec.threshold_required && row.threshold.is_none() {
+                errors.push(ShardError::new(
+                    row.line,
+                    ShardErrorKind::MissingThreshold {
+                        metric: row.metric.clone(),
+                        op: row.op.map(|o| o.to_string()).unwrap_or_default(),
+                    },
+                ));
+            }
+        }
+        for row in shard.rows() {
+            if let Some(op) = row.op {
+                if !self.ops.contains(&op) {
+                    errors.push(ShardError::new(
+                        row.line,
+                        ShardErrorKind::OpNotAllowed { metric: row.metric.clone(), op: op.to_string() },
+                    ));
+                }
+            }
+            if !self.allow_unlisted && !self.required.iter().any(|s| s.metric == row.metric) {
+                errors.push(ShardError::new(row.line, ShardErrorKind::UnlistedMetric(row.metric.clone())));
+            }
+        }
+        errors
+    }
+}
+
+/// Expected shape of one SECTION/ROW shard id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionSchema {
+    pub shard_id: String,
+    pub required_sections: Vec<String>,
+    /// Row kinds (`scalar`, `flag`, ...) rows may use; empty accepts any.
+    pub kinds: Vec<String>,
+}
+
+impl SectionSchema {
+    pub fn new(shard_id: &str, required_sections: &[&str], kinds: &[&str]) -> Self {
+        SectionSchema {
+            shard_id: shard_id.to_string(),
+            required_sections: required_sections.iter().map(|s| s.to_string()).collect(),
+            kinds: kinds.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    pub fn validate(&self, shard: &SectionShard) -> Vec<ShardError> {
+        let mut errors: Vec<ShardError> = self
+            .required_sections
+            .iter()
+            .filter(|name| shard.section(name).is_none())
+            .map(|name| ShardError::new(0, ShardErrorKind::MissingSection(name.clone())))
+            .collect();
+        if !self.kinds.is_empty() {
+            for row in shard.sections.iter().flat_map(|s| &s.rows) {
+                if !self.kinds.iter().any(|k| k == row.kind()) {
+                    errors.push(ShardError::new(row.line, ShardErrorKind::KindNotAllowed(row.kind().to_string())));
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Schemas keyed by shard id. Loading through the registry parses and then
+/// validates, so callers only ever see shards that match their schema.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    metrics: BTreeMap<String, MetricSchema>,
+    sections: BTreeMap<String, SectionSchema>,
+}
+
+impl SchemaRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schemas for the metric shards shipped under `aln/`.
+    pub fn builtin() -> Self {
+        use Op::{Eq, Max, Min};
+
+        let mut reg = SchemaRegistry::new();
+        reg.register_metric(
+            MetricSchema::new("xr.multimodal.budget.v1", &[Max])
+                .require("host_limit_joules", &["J"])
+                .require("xr_visual_joules", &["J"])
+                .require("bci_stim_joules", &["J"]),
+        );
+        reg.register_metric(
+            MetricSchema::new("xr.neurorights.v1", &[Max])
+                .require("autonomy_weight", &["arb"])
+                .require("covert_mod_weight", &["arb"])
+                .require("sleep_safety_weight", &["arb"])
+                .require("max_weight_sum", &["arb"]),
+        );
+        reg.register_metric(
+            MetricSchema::new("xr.corridor.session.v1", &[Min])
+                .require("xr_risk_margin", &["arb"])
+                .declare("session_id"),
+        );
+        reg.register_metric(
+            MetricSchema::new("xr.corridor.host.v1", &[Max, Min])
+                .require("xr_risk", &["arb"])
+                .require("hrv_ms", &["ms"])
+                .require("thermo_c", &["C"])
+                .require("duty_fraction", &["fraction"])
+                .require("fov_deg", &["deg"])
+                .require("fps", &["Hz"]),
+        );
+        reg.register_metric(
+            MetricSchema::new("xr.blink.corridor.v1", &[Max])
+                .require("blink_z", &["SD"])
+                .require("cog_load_idx", &["fraction"]),
+        );
+        reg.register_metric(
+            MetricSchema::new("xr.chat.grammar.v1", &[Max])
+                .require("hallucination_risk", &["fraction"])
+                .require("manipulation_risk", &["fraction"])
+                .require("privacy_risk", &["fraction"]),
+        );
+        reg.register_metric(
+            MetricSchema::new("xr.device.capability.v1", &[Max, Eq])
+                .require("display_nits", &["nits"])
+                .require("haptics_band_hz", &["Hz"])
+                .require("xr_safe", &["bool"]),
+        );
+        reg.register_metric(MetricSchema::new("xr.evidence.policy.v1", &[Min]).require("min_satisfied", &["count"]));
+        reg.register_metric(
+            MetricSchema::new("xr.guard.metrics.v1", &[Max]).require("xr_guard_denied_ratio", &["fraction"]),
+        );
+        reg.register_section(SectionSchema::new(
+            "policy-newrow-shark",
+            &["DOMAIN", "DENY-STATES", "RUNTIME-RULES"],
+            &["scalar", "condition"],
+        ));
+        reg
+    }
+
+    /// Register (or replace) the schema for `schema.shard_id`.
+    pub fn register_metric(&mut self, schema: MetricSchema) {
+        self.metrics.insert(schema.shard_id.clone(), schema);
+    }
+
+    pub fn register_section(&mut self, schema: SectionSchema) {
+        self.sections.insert(schema.shard_id.clone(), schema);
+    }
+
+    pub fn metric_schema(&self, shard_id: &str) -> Option<&MetricSchema> {
+        self.metrics.get(shard_id)
+    }
+
+    pub fn section_schema(&self, shard_id: &str) -> Option<&SectionSchema> {
+        self.sections.get(shard_id)
+    }
+
+    /// Validate an already-parsed metric shard against its registered schema.
+    pub fn validate_metric(&self, shard: &MetricShard) -> Result<(), ShardErrors> {
+        let errors = match self.metrics.get(&shard.id) {
+            Some(schema) => schema.validate(shard),
+            None => vec![ShardError::new(0, ShardErrorKind::UnknownSchema(shard.id.clone()))],
+        };
+        ShardErrors(errors).into_result(())
+    }
+
+    pub fn validate_section(&self, shard: &SectionShard) -> Result<(), ShardErrors> {
+        let errors = match self.sections.get(&shard.id) {
+            Some(schema) => schema.validate(shard),
+            None => vec![ShardError::new(0, ShardErrorKind::UnknownSchema(shard.id.clone()))],
+        };
+        ShardErrors(errors).into_result(())
+    }
+
+    /// Parse and validate metric shard text.
+    pub fn parse_metric(&self, text: &str) -> Result<MetricShard, ShardErrors> {
+        let shard = MetricShard::parse(text)?;
+        self.validate_metric(&shard)?;
+        Ok(shard)
+    }
+
+    pub fn parse_section(&self, default_id: &str, text: &str) -> Result<SectionShard, ShardErrors> {
+        let shard = SectionShard::parse(default_id, text)?;
+        self.validate_section(&shard)?;
+        Ok(shard)
+    }
+
+    pub fn load_metric_file(&self, path: impl AsRef<Path>) -> Result<MetricShard, LoadError> {
+        let path = path.as_ref();
+        let text = read(path)?;
+        self.parse_metric(&text).map_err(|errors| LoadError::Invalid { path: path.to_path_buf(), errors })
+    }
+
+    /// Load a SECTION/ROW file; the file stem is the id unless the shard
+    /// names its own `destination-path`.
+    pub fn load_section_file(&self, path: impl AsRef<Path>) -> Result<SectionShard, LoadError> {
+        let path = path.as_ref();
+        let text = read(path)?;
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        self.parse_section(stem, &text).map_err(|errors| LoadError::Invalid { path: path.to_path_buf(), errors })
+    }
+}
+
+fn read(path: &Path) -> Result<String, LoadError> {
+    fs::read_to_string(path).map_err(|source| LoadError::Io { path: path.to_path_buf(), source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIPPED: [&str; 9] = [
+        include_str!("../../../aln/xr.multimodal.budget.v1.aln"),
+        include_str!("../../../aln/xr.neurorights.v1.aln"),
+        include_str!("../../../aln/xr.corridor.session.v1.aln"),
+        include_str!("../../../aln/xr.corridor.host.v1.aln"),
+        include_str!("../../../aln/xr.blink.corridor.v1.aln"),
+        include_str!("../../../aln/xr.chat.grammar.v1.aln"),
+        include_str!("../../../aln/xr.device.capability.v1.aln"),
+        include_str!("../../../aln/xr.evidence.policy.v1.aln"),
+        include_str!("../../../aln/xr.guard.metrics.v1.aln"),
+    ];
+
+    #[test]
+    fn test_builtin_schemas_accept_shipped_shards() {
+        let reg = SchemaRegistry::builtin();
+        for text in SHIPPED {
+            if let Err(e) = reg.parse_metric(text) {
+                panic!("{}", e);
+            }
+        }
+        reg.parse_section("policy-newrow-shark", include_str!("../../../policy-newrow-shark.aln")).unwrap();
+    }
+
+    #[test]
+    fn test_schema_violations_carry_lines() {
+        let reg = SchemaRegistry::builtin();
+        let text = "aln,xr.multimodal.budget.v1\nmetric,domain,module,op,threshold,unit,source\n\
+                    host_limit_joules,host,xr.budget.guard,,72000.0,J,file32\n\
+                    xr_visual_joules,host,xr.budget.guard,min,24.0,kJ,file32\n\
+                    extra,host,xr.budget.guard,,1,J,file32\n";
+        let errs = reg.parse_metric(text).unwrap_err();
+        let got: Vec<(usize, &ShardErrorKind)> = errs.iter().map(|e| (e.line, &e.kind)).collect();
+        assert_eq!(got.len(), 4);
+        assert_eq!(got[0], (0, &ShardErrorKind::MissingMetric("bci_stim_joules".into())));
+        assert!(matches!(got[1], (4, ShardErrorKind::UnitNotAllowed { .. })));
+        assert!(matches!(got[2], (4, ShardErrorKind::OpNotAllowed { .. })));
+        assert_eq!(got[3], (5, &ShardErrorKind::UnlistedMetric("extra".into())));
+
+        let unknown = reg.parse_metric("aln,nope.v1\nmetric,domain,module,op,threshold,unit,source\n").unwrap_err();
+        assert_eq!(unknown.0[0].kind, ShardErrorKind::UnknownSchema("nope.v1".into()));
+    }
+}
@@ -0,0 +1,208 @@
+use serde::Serialize;
+
+use crate::csv::records;
+use crate::error::{ShardErrorKind, ShardErrors};
+
+/// One `ROW,...` record. Cells follow the
+/// `entity,field,kind,key,value,datatype,constraints...,notes` convention,
+/// but the tail is free-form (enum rows list their variants inline).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionRow {
+    pub line: usize,
+    pub cells: Vec<String>,
+}
+
+impl SectionRow {
+    fn get(&self, i: usize) -> &str {
+        self.cells.get(i).map(String::as_str).unwrap_or("")
+    }
+
+    pub fn entity(&self) -> &str {
+        self.get(0)
+    }
+
+    pub fn field(&self) -> &str {
+        self.get(1)
+    }
+
+    /// `scalar`, `enum`, `flag`, `condition`, `expression`, ...
+    pub fn kind(&self) -> &str {
+        self.get(2)
+    }
+
+    pub fn key(&self) -> &str {
+        self.get(3)
+    }
+
+    pub fn value(&self) -> &str {
+        self.get(4)
+    }
+
+    /// Cells after `value`: datatype, constraints / enum variants, notes.
+    pub fn tail(&self) -> &[String] {
+        self.cells.get(5..).unwrap_or(&[])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Section {
+    pub name: String,
+    pub line: usize,
+    pub rows: Vec<SectionRow>,
+}
+
+/// `SECTION,NAME` / `ROW,...` shard, as in `policy-newrow-shark.aln` and the
+/// `xr-grid.*` files. A ROW ending in a trailing comma continues on the next
+/// line(s), which is how long quoted conditions are written.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionShard {
+    pub id: String,
+    /// From a `destination-path,<file>` preamble line, if present.
+    pub destination: Option<String>,
+    /// From a `QPU.Datashard ...` preamble line, if present.
+    pub title: Option<String>,
+    /// The `path,entitytype,...` column header, if present.
+    pub header: Option<Vec<String>>,
+    pub sections: Vec<Section>,
+    pub footer: Option<String>,
+}
+
+impl SectionShard {
+    /// Parse shard text. The id is taken from the `destination-path`
+    /// preamble (minus `.aln`) when there is one, else `default_id`.
+    pub fn parse(default_id: &str, text: &str) -> Result<Self, ShardErrors> {
+        let mut errors = ShardErrors::default();
+        let mut shard = SectionShard {
+            id: default_id.to_string(),
+            destination: None,
+            title: None,
+            header: None,
+            sections: Vec::new(),
+            footer: None,
+        };
+
+        for rec in records(text, &mut errors) {
+            let first = rec.first();
+            if shard.sections.is_empty() && first != "SECTION" {
+                if first == "aln" {
+                    continue;
+                } else if first.ends_with("destination-path") {
+                    let dest = rec.cells.get(1).cloned().unwrap_or_default();
+                    shard.id = dest.trim_end_matches(".aln").to_string();
+                    shard.destination = Some(dest);
+                    continue;
+                } else if let Some(rest) = first.strip_prefix("QPU.Datashard") {
+                    let mut parts = vec![rest.trim().to_string()];
+                    parts.extend(rec.cells[1..].iter().cloned());
+                    shard.title = Some(parts.join(", ").trim_start_matches([',', ' ']).to_string());
+                    continue;
+                } else if first == "path" {
+                    shard.header = Some(rec.cells);
+                    continue;
+                } else if first == "ROW" {
+                    errors.push(rec.line, ShardErrorKind::RowOutsideSection);
+                    continue;
+                }
+            }
+
+            match first {
+                "SECTION" => {
+                    let name = rec.cells.get(1).cloned().unwrap_or_default();
+                    if name.is_empty() {
+                        errors.push(rec.line, ShardErrorKind::UnnamedSection);
+                    } else if let Some(prev) = shard.section(&name) {
+                        errors.push(rec.line, ShardErrorKind::DuplicateSection { name, first_line: prev.line });
+                    } else {
+                        shard.sections.push(Section { name, line: rec.line, rows: Vec::new() });
+                    }
+                }
+                "ROW" => match shard.sections.last_mut() {
+                    Some(section) => section.rows.push(SectionRow { line: rec.line, cells: rec.cells[1..].to_vec() }),
+                    None => errors.push(rec.line, ShardErrorKind::RowOutsideSection),
+                },
+                "FOOTER" => shard.footer = rec.cells.get(1).cloned(),
+                _ => {
+                    let last = shard.sections.last_mut().and_then(|s| s.rows.last_mut());
+                    match last {
+                        Some(row) if row.cells.last().is_some_and(String::is_empty) => {
+                            row.cells.pop();
+                            row.cells.extend(rec.cells);
+                        }
+                        _ => errors.push(rec.line, ShardErrorKind::UnknownRecord(first.to_string())),
+                    }
+                }
+            }
+        }
+        errors.into_result(shard)
+    }
+
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    /// First row in `section` with the given key.
+    pub fn row(&self, section: &str, key: &str) -> Option<&SectionRow> {
+        self.section(section)?.rows.iter().find(|r| r.key() == key)
+    }
+
+    pub fn value(&self, section: &str, key: &str) -> Option<&str> {
+        self.row(section, key).map(SectionRow::value)
+    }
+
+    /// Every value under a repeated key, e.g. the `agentid` rows.
+    pub fn values(&self, section: &str, key: &str) -> Vec<&str> {
+        self.section(section)
+            .map(|s| s.rows.iter().filter(|r| r.key() == key).map(SectionRow::value).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn scalar_f64(&self, section: &str, key: &str) -> Option<f64> {
+        self.value(section, key)?.parse().ok()
+    }
+
+    pub fn flag(&self, section: &str, key: &str) -> Option<bool> {
+        self.value(section, key)?.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newrow_shark_continuations() {
+        let shard = SectionShard::parse("policy-newrow-shark", include_str!("../../../policy-newrow-shark.aln")).unwrap();
+        assert_eq!(shard.id, "policy-newrow-shark");
+        let names: Vec<&str> = shard.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["DOMAIN", "DENY-STATES", "RUNTIME-RULES"]);
+        assert_eq!(shard.value("DENY-STATES", "deny_states"), Some("CAP_STATE|CONSENT_STATE|QPU_SAFETY|WALLET|HUD"));
+
+        let rule = shard.row("RUNTIME-RULES", "denySymbolBinding").unwrap();
+        assert_eq!(rule.line, 9);
+        assert_eq!(rule.value(), "forbidbind if symbol_prefix in HUD_,WALLET_,QPU_,CAP_,CONSENT_");
+        assert_eq!(rule.tail(), ["string", "readonly"]);
+    }
+
+    #[test]
+    fn test_grid_preamble_and_lookups() {
+        let shard =
+            SectionShard::parse("unused", include_str!("../../../aln/xr-grid.quantum-roaming-debug.v1.aln")).unwrap();
+        assert_eq!(shard.id, "xr-grid.quantum-roaming-debug.v1");
+        assert_eq!(shard.title.as_deref(), Some("Quantum roaming vs observer_only state debug"));
+        assert_eq!(shard.header.as_ref().map(Vec::len), Some(8));
+        assert_eq!(shard.scalar_f64("DERIVED", "rmax"), Some(0.35));
+        assert_eq!(shard.flag("NEURORIGHTS-GUARDS", "mentalprivacy"), Some(true));
+        assert_eq!(shard.footer.as_deref(), Some("END-OF-SHARD"));
+
+        let bench =
+            SectionShard::parse("unused", include_str!("../../../aln/xr-benchmark.phoenix.reference.v1.aln")).unwrap();
+        let all = bench.row("ASSERTIONS", "all_match").unwrap();
+        assert_eq!(all.value(), "E_match AND Dr_match AND C_match");
+        assert_eq!(all.tail(), ["string", "readonly", "Pass/fail"]);
+        assert_eq!(bench.title.as_deref().map(|t| t.ends_with("(E, Dr, C)")), Some(true));
+
+        let agents =
+            SectionShard::parse("unused", include_str!("../../../aln/xr-grid.agent-dev-tunnels.v2.aln")).unwrap();
+        assert_eq!(agents.values("AGENT-PROFILES", "agentid"), vec!["mistral", "qwen", "perplexity", "grok"]);
+    }
+}
@@ -0,0 +1,83 @@
+use serde::Serialize;
+
+use crate::csv::{records, Record};
+use crate::error::{ShardErrorKind, ShardErrors};
+
+/// One data row of a table shard, with the line it came from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableRow {
+    pub line: usize,
+    pub cells: Vec<String>,
+}
+
+/// Generic `aln,<id>` / header / rows shard, e.g. `xr.stack.retrievals.v1`.
+/// `MetricShard` is the typed view over the `metric,...` header.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableShard {
+    pub id: String,
+    pub header: Vec<String>,
+    pub header_line: usize,
+    pub rows: Vec<TableRow>,
+}
+
+impl TableShard {
+    pub fn parse(text: &str) -> Result<Self, ShardErrors> {
+        let mut errors = ShardErrors::default();
+        match Self::parse_lenient(text, &mut errors) {
+            Some(table) => errors.into_result(table),
+            None => Err(errors),
+        }
+    }
+
+    /// Parse what can be parsed: malformed rows are recorded in `errors`
+    /// and dropped. `None` only when the directive itself is missing.
+    pub(crate) fn parse_lenient(text: &str, errors: &mut ShardErrors) -> Option<Self> {
+        let recs = records(text, errors);
+        let mut iter = recs.into_iter();
+
+        let id = match iter.next() {
+            Some(Record { cells, .. }) if cells.len() == 2 && cells[0] == "aln" && !cells[1].is_empty() => {
+                cells[1].clone()
+            }
+            Some(r) => {
+                errors.push(r.line, ShardErrorKind::MissingDirective);
+                return None;
+            }
+            None => {
+                errors.push(0, ShardErrorKind::MissingDirective);
+                return None;
+            }
+        };
+        let (header_line, header) = match iter.next() {
+            Some(r) => (r.line, r.cells),
+            None => (0, Vec::new()),
+        };
+
+        let mut rows = Vec::new();
+        for r in iter {
+            if r.cells.len() != header.len() {
+                errors.push(r.line, ShardErrorKind::CellCount { expected: header.len(), found: r.cells.len() });
+                continue;
+            }
+            rows.push(TableRow { line: r.line, cells: r.cells });
+        }
+        Some(TableShard { id, header, header_line, rows })
+    }
+
+    pub fn column(&self, name: &str) -> Option<usize> {
+        self.header.iter().position(|h| h == name)
+    }
+
+    /// Cell of `row` under column `name`.
+    pub fn cell<'a>(&self, row: &'a TableRow, name: &str) -> Option<&'a str> {
+        self.column(name).and_then(|i| row.cells.get(i)).map(String::as_str)
+    }
+
+    /// Rows whose `column` equals `value`.
+    pub fn rows_where<'a>(&'a self, column: &str, value: &'a str) -> impl Iterator<Item = &'a TableRow> + 'a {
+        let idx = self.column(column);
+        self.rows
+            .iter()
+            .filter(move |r| idx.and_then(|i| r.cells.get(i)).map(String::as_str) == Some(value))
+    }
+}
@@ -1,55 +1,69 @@
+use std::sync::OnceLock;
 use std::time::SystemTime;
 
 use crate::{BioscaleUpgradeStore, HostBudget, UpgradeDecision, UpgradeDescriptor};
 use reality_os::crypto_env::CryptoAwareEnv;
-use reality_os::crypto_patterns::CryptoPatternSet;
+use reality_os::crypto_posture::{
+    AlgorithmFamily, CryptoPostureEngine, CryptoPostureVerdict, DependencySurface,
+};
+
+/// The v1 posture engine, built once: construction compiles every pattern.
+fn v1_engine() -> &'static CryptoPostureEngine {
+    static ENGINE: OnceLock<CryptoPostureEngine> = OnceLock::new();
+    ENGINE.get_or_init(CryptoPostureEngine::v1)
+}
 
 /// Helper trait to detect crypto usage from descriptors.
 pub trait CryptoProfileExt {
     fn uses_blake3(&self) -> bool;
     fn uses_sha3(&self) -> bool;
+    /// Descriptor metadata scanned alongside the dependency list.
+    fn descriptor_strings(&self) -> Vec<String>;
 }
 
 impl CryptoProfileExt for UpgradeDescriptor {
     fn uses_blake3(&self) -> bool {
-        let p = CryptoPatternSet::v1();
-        let mut found = false;
-        let meta = [
-            self.github_repo_slug.as_str(),
-            self.ota_manifest_path.as_str(),
-            self.display_name.as_str(),
-            self.version.as_str(),
-        ];
-        for m in meta {
-            if p.is_blake(m) {
-                found = true;
-                break;
-            }
-        }
-        found
+        let engine = v1_engine();
+        self.descriptor_strings()
+            .iter()
+            .any(|m| engine.classify(m).contains(&AlgorithmFamily::Blake))
     }
 
     fn uses_sha3(&self) -> bool {
-        let p = CryptoPatternSet::v1();
-        let mut found = false;
-        let meta = [
-            self.github_repo_slug.as_str(),
-            self.ota_manifest_path.as_str(),
-            self.display_name.as_str(),
-            self.version.as_str(),
-        ];
-        for m in meta {
-            if p.is_sha3(m) {
-                found = true;
-                break;
-            }
-        }
-        found
+        let engine = v1_engine();
+        self.descriptor_strings()
+            .iter()
+            .any(|m| engine.classify(m).contains(&AlgorithmFamily::Sha3))
+    }
+
+    fn descriptor_strings(&self) -> Vec<String> {
+        vec![
+            self.github_repo_slug.clone(),
+            self.ota_manifest_path.clone(),
+            self.display_name.clone(),
+            self.version.clone(),
+        ]
     }
 }
 
+/// Run the posture engine over an upgrade's descriptor plus its full
+/// dependency surface (Cargo.lock crate names and symbols).
+pub fn crypto_posture_verdict(
+    upgrade: &UpgradeDescriptor,
+    deps: &DependencySurface,
+    env: &CryptoAwareEnv,
+    corridor: &str,
+) -> CryptoPostureVerdict {
+    let surface = deps
+        .clone()
+        .with_descriptor_strings(upgrade.descriptor_strings());
+    let evidence_tags: Vec<String> = upgrade.evidence.tags.iter().cloned().collect();
+    env.posture.evaluate(corridor, &surface, &evidence_tags)
+}
+
 /// Evaluate an upgrade under HostBudget + crypto posture.
-/// Any BLAKE or SHA3 usage is rejected unconditionally.
+/// Only descriptor metadata is scanned; prefer `evaluate_with_crypto_posture`
+/// when the upgrade's Cargo.lock is available.
 pub fn evaluate_with_crypto_gate<S: BioscaleUpgradeStore>(
     store: S,
     host: HostBudget,
@@ -57,16 +71,29 @@ pub fn evaluate_with_crypto_gate<S: BioscaleUpgradeStore>(
     start: SystemTime,
     env: &CryptoAwareEnv,
 ) -> UpgradeDecision {
-    if upgrade.uses_blake3() && !env.permits_blake() {
-        return UpgradeDecision::Denied {
-            reason: "BLAKE-family cryptography not permitted in this corridor".into(),
-        };
-    }
+    evaluate_with_crypto_posture(
+        store,
+        host,
+        upgrade,
+        &DependencySurface::default(),
+        start,
+        env,
+    )
+}
 
-    if upgrade.uses_sha3() && !env.permits_sha3() {
-        return UpgradeDecision::Denied {
-            reason: "SHA3-family cryptography not permitted in this corridor".into(),
-        };
+/// Evaluate an upgrade under HostBudget + crypto posture, scanning the
+/// descriptor and the full dependency surface in the env's default corridor.
+pub fn evaluate_with_crypto_posture<S: BioscaleUpgradeStore>(
+    store: S,
+    host: HostBudget,
+    upgrade: UpgradeDescriptor,
+    deps: &DependencySurface,
+    start: SystemTime,
+    env: &CryptoAwareEnv,
+) -> UpgradeDecision {
+    let verdict = crypto_posture_verdict(&upgrade, deps, env, env.posture.default_corridor());
+    if let Some(reason) = verdict.deny_reason() {
+        return UpgradeDecision::Denied { reason };
     }
 
     store.evaluate_upgrade(host, upgrade, start)
@@ -1,22 +1,35 @@
 use std::time::SystemTime;
-use bioscale_upgrade_store::{BioscaleUpgradeStore, HostBudget, UpgradeDecision, UpgradeDescriptor};
-use reality_os_cargo_env_descriptor::{describe_cargo_env, CargoEnvDescriptor};
+use crate::cryptogate::CryptoProfileExt;
+use crate::{BioscaleUpgradeStore, HostBudget, UpgradeDecision, UpgradeDescriptor};
+use reality_os::blake_env::BlakeAwareEnv;
+use reality_os::crypto_posture::{AlgorithmFamily, DependencySurface};
 
 /// Evaluate an upgrade with both HostBudget and Blake3 posture enforced.
+/// The Blake decision comes from the env's posture engine, including any
+/// evidence-tag exception and the env's required evidence tag.
 pub fn evaluate_with_env_and_blake<S: BioscaleUpgradeStore>(
     store: &S,
     host: HostBudget,
     upgrade: UpgradeDescriptor,
     requested_start: SystemTime,
-) -> (UpgradeDecision, CargoEnvDescriptor) {
-    let env = describe_cargo_env().expect("CargoEnvDescriptor required");
+) -> (UpgradeDecision, BlakeAwareEnv) {
+    let env = BlakeAwareEnv::current();
     let evidence_tags: Vec<String> = upgrade.evidence.tags.iter().cloned().collect();
 
-    // Blake gate: deny if this descriptor intersects BlakePatternSet without proper evidence.
-    if !env.permits_blake3(&evidence_tags) && upgrade.crypto_profile.uses_blake3 {
+    let surface = DependencySurface::default().with_descriptor_strings(upgrade.descriptor_strings());
+    let uses_blake = env
+        .verdict(&surface, &evidence_tags)
+        .findings
+        .iter()
+        .any(|f| f.family == AlgorithmFamily::Blake);
+
+    if uses_blake && !env.permits_blake3(&evidence_tags) {
         return (
             UpgradeDecision::Denied {
-                reason: "Blake3 use is not permitted by CargoEnvDescriptor posture".into(),
+                reason: format!(
+                    "Blake3 use is not permitted by crypto posture {}",
+                    env.pattern_set_version()
+                ),
             },
             env,
         );
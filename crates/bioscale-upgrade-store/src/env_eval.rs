@@ -1,7 +1,9 @@
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use crate::{BioscaleUpgradeStore, HostBudget, UpgradeDecision, UpgradeDescriptor};
 use reality_os::cargo_env::{describe_cargo_env, CargoEnvDescriptor};
+use reality_os::supply_chain_audit::{SupplyChainAuditReport, SupplyChainAuditor};
 
 /// Evaluate an upgrade under both HostBudget and CargoEnvDescriptor envelopes.
 pub fn evaluate_with_env<S: BioscaleUpgradeStore>(
@@ -14,3 +16,33 @@ pub fn evaluate_with_env<S: BioscaleUpgradeStore>(
     let decision = store.evaluate_upgrade(host, upgrade, requested_start);
     (decision, env)
 }
+
+/// Evaluate an upgrade only if its workspace passes the offline supply-chain audit.
+/// The audit report is returned alongside the decision for the admission log.
+pub fn evaluate_with_supply_chain_audit<S: BioscaleUpgradeStore>(
+    store: &S,
+    host: HostBudget,
+    upgrade: UpgradeDescriptor,
+    requested_start: SystemTime,
+    workspace_root: &Path,
+    lab_root: PathBuf,
+) -> (UpgradeDecision, SupplyChainAuditReport) {
+    let env = describe_cargo_env();
+    let report = SupplyChainAuditor::from_env(&env, lab_root).audit_workspace(workspace_root);
+    if !report.passed() {
+        let first = &report.violations[0];
+        return (
+            UpgradeDecision::Denied {
+                reason: format!(
+                    "supply-chain audit failed with {} violation(s); first: {:?} on {}: {}",
+                    report.violations.len(),
+                    first.rule,
+                    first.package,
+                    first.detail
+                ),
+            },
+            report,
+        );
+    }
+    (store.evaluate_upgrade(host, upgrade, requested_start), report)
+}
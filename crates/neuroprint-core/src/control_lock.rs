@@ -13,13 +13,24 @@ pub struct ControlLockResult {
     pub reason: String,
 }
 
+/// Constraint-pressure level above which a window can count as control-lock.
+pub const DEFAULT_LOCK_CP: f64 = 0.7;
+
 pub fn evaluate_lock(
     elasticity: &ElasticityResult,
     inputs: &LockInputs
 ) -> ControlLockResult {
+    evaluate_lock_with(elasticity, inputs, DEFAULT_LOCK_CP)
+}
+
+pub fn evaluate_lock_with(
+    elasticity: &ElasticityResult,
+    inputs: &LockInputs,
+    cp_threshold: f64
+) -> ControlLockResult {
 
     let lock = elasticity.rec_bounded < 0.0
-        && inputs.cp > 0.7
+        && inputs.cp > cp_threshold
         && inputs.delta_bioload <= 0.0;
 
     let reason = if lock {
@@ -0,0 +1,411 @@
+use serde::{Serialize, Deserialize};
+use std::collections::VecDeque;
+use super::repair_elasticity::{compute_elasticity, ElasticityResult, WindowMetrics};
+use super::control_lock::DEFAULT_LOCK_CP;
+
+/// Hysteresis settings for the control-lock monitor.
+/// A window "qualifies" when cp is above `entry_cp`, bioload did not improve
+/// and repair throughput did not respond (`rec_bounded <= 0`; a flat window
+/// under sustained pressure counts, unlike the one-shot `evaluate_lock`).
+/// An episode opens after `min_lock_windows` qualifying windows in a row and
+/// closes after `min_release_windows` windows in a row with cp below
+/// `exit_cp` or positive bioload improvement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockMonitorConfig {
+    pub entry_cp: f64,
+    pub exit_cp: f64,
+    pub min_lock_windows: u32,
+    pub min_release_windows: u32,
+    /// Number of recent cp values kept for trend reporting.
+    pub history_len: usize,
+}
+
+impl Default for LockMonitorConfig {
+    fn default() -> Self {
+        LockMonitorConfig {
+            entry_cp: DEFAULT_LOCK_CP,
+            exit_cp: 0.55,
+            min_lock_windows: 3,
+            min_release_windows: 2,
+            history_len: 64,
+        }
+    }
+}
+
+/// One window's evidence: the metrics seen and what they produced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowEvidence {
+    pub window_index: u64,
+    pub window_start_ms: u64,
+    pub metrics: WindowMetrics,
+    pub elasticity: ElasticityResult,
+    pub delta_bioload: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockEpisode {
+    pub start_window: u64,
+    pub start_ms: u64,
+    /// `None` while the episode is still open.
+    pub end_window: Option<u64>,
+    pub end_ms: Option<u64>,
+    pub peak_cp: f64,
+    pub min_rec: f64,
+    /// The qualifying windows that opened the episode.
+    pub cause: Vec<WindowEvidence>,
+}
+
+impl LockEpisode {
+    pub fn is_open(&self) -> bool {
+        self.end_window.is_none()
+    }
+
+    pub fn duration_windows(&self) -> Option<u64> {
+        self.end_window.map(|end| end - self.start_window)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LockState {
+    Clear,
+    /// Qualifying windows seen, not yet long enough to open an episode.
+    Pending,
+    Locked,
+    /// Locked, but release windows are accumulating.
+    Releasing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorStatus {
+    pub window_index: u64,
+    pub state: LockState,
+    pub cp: f64,
+    pub rec_bounded: Option<f64>,
+    pub episode_opened: bool,
+    pub episode_closed: bool,
+}
+
+/// Tracks constraint pressure across successive windows and logs
+/// persistent control-lock episodes. Advisory only; it never actuates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlLockMonitor {
+    pub config: LockMonitorConfig,
+    pub state: LockState,
+    pub episodes: Vec<LockEpisode>,
+    cp_history: VecDeque<f64>,
+    prev: Option<WindowMetrics>,
+    next_index: u64,
+    pending: Vec<WindowEvidence>,
+    release_run: u32,
+}
+
+impl ControlLockMonitor {
+    pub fn new(config: LockMonitorConfig) -> Self {
+        ControlLockMonitor {
+            config,
+            state: LockState::Clear,
+            episodes: Vec::new(),
+            cp_history: VecDeque::new(),
+            prev: None,
+            next_index: 0,
+            pending: Vec::new(),
+            release_run: 0,
+        }
+    }
+
+    /// Feed the next window. The first window only seeds the baseline,
+    /// since elasticity needs a previous window to compare against.
+    pub fn observe(
+        &mut self,
+        window_start_ms: u64,
+        metrics: WindowMetrics,
+        delta_bioload: f64
+    ) -> MonitorStatus {
+        let window_index = self.next_index;
+        self.next_index += 1;
+
+        let prev = match self.prev.replace(metrics.clone()) {
+            Some(p) => p,
+            None => {
+                let cp = compute_elasticity(&metrics, &metrics).cp_curr;
+                self.push_cp(cp);
+                return self.status(window_index, cp, None, false, false);
+            }
+        };
+
+        let elasticity = compute_elasticity(&prev, &metrics);
+        let cp = elasticity.cp_curr;
+        let rec = elasticity.rec_bounded;
+        self.push_cp(cp);
+
+        let qualifies = rec <= 0.0 && cp > self.config.entry_cp && delta_bioload <= 0.0;
+        let releasing = cp < self.config.exit_cp || delta_bioload > 0.0;
+        let evidence = WindowEvidence {
+            window_index,
+            window_start_ms,
+            metrics,
+            elasticity,
+            delta_bioload,
+        };
+
+        let mut opened = false;
+        let mut closed = false;
+
+        match self.state {
+            LockState::Clear | LockState::Pending => {
+                if qualifies {
+                    self.pending.push(evidence);
+                    self.state = LockState::Pending;
+                    if self.pending.len() as u32 >= self.config.min_lock_windows.max(1) {
+                        self.open_episode();
+                        opened = true;
+                    }
+                } else {
+                    self.pending.clear();
+                    self.state = LockState::Clear;
+                }
+            }
+            LockState::Locked | LockState::Releasing => {
+                if let Some(ep) = self.episodes.last_mut() {
+                    ep.peak_cp = ep.peak_cp.max(cp);
+                    ep.min_rec = ep.min_rec.min(rec);
+                }
+                if releasing {
+                    self.release_run += 1;
+                    self.state = LockState::Releasing;
+                    if self.release_run >= self.config.min_release_windows.max(1) {
+                        if let Some(ep) = self.episodes.last_mut() {
+                            ep.end_window = Some(window_index);
+                            ep.end_ms = Some(window_start_ms);
+                        }
+                        self.release_run = 0;
+                        self.state = LockState::Clear;
+                        closed = true;
+                    }
+                } else {
+                    // Between exit_cp and entry_cp the lock holds (hysteresis band).
+                    self.release_run = 0;
+                    self.state = LockState::Locked;
+                }
+            }
+        }
+
+        self.status(window_index, cp, Some(rec), opened, closed)
+    }
+
+    fn open_episode(&mut self) {
+        let cause = std::mem::take(&mut self.pending);
+        let first = &cause[0];
+        let peak_cp = cause.iter().map(|e| e.elasticity.cp_curr).fold(f64::MIN, f64::max);
+        let min_rec = cause.iter().map(|e| e.elasticity.rec_bounded).fold(f64::MAX, f64::min);
+        self.episodes.push(LockEpisode {
+            start_window: first.window_index,
+            start_ms: first.window_start_ms,
+            end_window: None,
+            end_ms: None,
+            peak_cp,
+            min_rec,
+            cause,
+        });
+        self.release_run = 0;
+        self.state = LockState::Locked;
+    }
+
+    fn push_cp(&mut self, cp: f64) {
+        self.cp_history.push_back(cp);
+        while self.cp_history.len() > self.config.history_len {
+            self.cp_history.pop_front();
+        }
+    }
+
+    fn status(
+        &self,
+        window_index: u64,
+        cp: f64,
+        rec_bounded: Option<f64>,
+        episode_opened: bool,
+        episode_closed: bool
+    ) -> MonitorStatus {
+        MonitorStatus {
+            window_index,
+            state: self.state,
+            cp,
+            rec_bounded,
+            episode_opened,
+            episode_closed,
+        }
+    }
+
+    /// Recent constraint pressure, oldest first.
+    pub fn cp_history(&self) -> &VecDeque<f64> {
+        &self.cp_history
+    }
+
+    /// Mean cp over the retained history.
+    pub fn cp_mean(&self) -> f64 {
+        if self.cp_history.is_empty() {
+            return 0.0;
+        }
+        self.cp_history.iter().sum::<f64>() / self.cp_history.len() as f64
+    }
+
+    pub fn open_episode_ref(&self) -> Option<&LockEpisode> {
+        self.episodes.last().filter(|e| e.is_open())
+    }
+
+    pub fn closed_episodes(&self) -> impl Iterator<Item = &LockEpisode> {
+        self.episodes.iter().filter(|e| !e.is_open())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Window whose constraint pressure is `cp` and repair throughput `rt`.
+    fn window(cp: f64, rt: f64) -> WindowMetrics {
+        WindowMetrics {
+            fear: cp,
+            block_rate: cp,
+            force_repair_rate: cp,
+            repair_rate: rt,
+            clean_tech_rate: rt,
+            support_rate: rt,
+        }
+    }
+
+    fn cp_of(level: f64) -> f64 {
+        compute_elasticity(&window(level, 0.5), &window(level, 0.5)).cp_curr
+    }
+
+    /// Seed plus `n` flat high-pressure windows: rec == 0, no bioload change.
+    fn locked_monitor(config: LockMonitorConfig) -> ControlLockMonitor {
+        let mut mon = ControlLockMonitor::new(config);
+        mon.observe(0, window(0.9, 0.5), 0.0);
+        for i in 1..=mon.config.min_lock_windows as u64 {
+            mon.observe(i * 1_000, window(0.9, 0.5), 0.0);
+        }
+        assert_eq!(mon.state, LockState::Locked);
+        mon
+    }
+
+    #[test]
+    fn first_window_only_seeds_the_baseline() {
+        let mut mon = ControlLockMonitor::new(LockMonitorConfig::default());
+        let status = mon.observe(0, window(0.9, 0.5), 0.0);
+        assert_eq!(status.state, LockState::Clear);
+        assert_eq!(status.rec_bounded, None);
+        assert_eq!(mon.cp_history().len(), 1);
+    }
+
+    #[test]
+    fn lock_opens_after_min_lock_windows_in_a_row() {
+        let mut mon = ControlLockMonitor::new(LockMonitorConfig::default());
+        mon.observe(0, window(0.9, 0.5), 0.0);
+        assert_eq!(mon.observe(1_000, window(0.9, 0.5), 0.0).state, LockState::Pending);
+        assert_eq!(mon.observe(2_000, window(0.9, 0.5), 0.0).state, LockState::Pending);
+
+        let status = mon.observe(3_000, window(0.9, 0.5), 0.0);
+        assert_eq!(status.state, LockState::Locked);
+        assert!(status.episode_opened);
+
+        let ep = mon.open_episode_ref().unwrap();
+        assert_eq!((ep.start_window, ep.start_ms), (1, 1_000));
+        assert_eq!(ep.cause.len(), 3);
+        assert_eq!(ep.min_rec, 0.0);
+    }
+
+    #[test]
+    fn a_broken_run_resets_the_pending_count() {
+        let mut mon = ControlLockMonitor::new(LockMonitorConfig::default());
+        mon.observe(0, window(0.9, 0.5), 0.0);
+        mon.observe(1_000, window(0.9, 0.5), 0.0);
+        mon.observe(2_000, window(0.9, 0.5), 0.0);
+        // Bioload improved: not a qualifying window.
+        assert_eq!(mon.observe(3_000, window(0.9, 0.5), 0.1).state, LockState::Clear);
+        assert_eq!(mon.observe(4_000, window(0.9, 0.5), 0.0).state, LockState::Pending);
+        assert_eq!(mon.observe(5_000, window(0.9, 0.5), 0.0).state, LockState::Pending);
+        assert!(mon.observe(6_000, window(0.9, 0.5), 0.0).episode_opened);
+        assert_eq!(mon.episodes[0].start_window, 4);
+    }
+
+    #[test]
+    fn repair_response_does_not_qualify() {
+        let mut mon = ControlLockMonitor::new(LockMonitorConfig::default());
+        mon.observe(0, window(0.85, 0.3), 0.0);
+        for i in 1..6 {
+            // Pressure rising but repair throughput rising faster: rec > 0.
+            let status = mon.observe(i, window(0.85 + 0.01 * i as f64, 0.3 + 0.05 * i as f64), 0.0);
+            assert!(status.rec_bounded.unwrap() > 0.0);
+            assert_eq!(status.state, LockState::Clear);
+        }
+    }
+
+    #[test]
+    fn entry_threshold_is_strict() {
+        let at_entry = LockMonitorConfig { entry_cp: cp_of(0.8), ..LockMonitorConfig::default() };
+        let mut mon = ControlLockMonitor::new(at_entry);
+        for i in 0..6 {
+            mon.observe(i, window(0.8, 0.5), 0.0);
+        }
+        assert_eq!(mon.state, LockState::Clear);
+        assert!(mon.episodes.is_empty());
+
+        let just_below = LockMonitorConfig { entry_cp: cp_of(0.8) - 1e-9, ..LockMonitorConfig::default() };
+        let mut mon = ControlLockMonitor::new(just_below);
+        for i in 0..4 {
+            mon.observe(i, window(0.8, 0.5), 0.0);
+        }
+        assert_eq!(mon.state, LockState::Locked);
+    }
+
+    #[test]
+    fn hysteresis_band_holds_the_lock() {
+        let mut mon = locked_monitor(LockMonitorConfig::default());
+        // Below entry_cp (0.7) but above exit_cp (0.55): still locked.
+        for i in 10..15 {
+            assert_eq!(mon.observe(i, window(0.6, 0.5), 0.0).state, LockState::Locked);
+        }
+        assert!(mon.open_episode_ref().is_some());
+    }
+
+    #[test]
+    fn release_needs_consecutive_windows_below_exit() {
+        let mut mon = locked_monitor(LockMonitorConfig::default());
+        assert_eq!(mon.observe(10_000, window(0.3, 0.5), 0.0).state, LockState::Releasing);
+        // Back into the band: the release run starts over.
+        assert_eq!(mon.observe(11_000, window(0.6, 0.5), 0.0).state, LockState::Locked);
+        assert_eq!(mon.observe(12_000, window(0.3, 0.5), 0.0).state, LockState::Releasing);
+
+        let status = mon.observe(13_000, window(0.3, 0.5), 0.0);
+        assert_eq!(status.state, LockState::Clear);
+        assert!(status.episode_closed);
+
+        let ep = mon.closed_episodes().next().unwrap();
+        assert_eq!((ep.end_window, ep.end_ms), (Some(7), Some(13_000)));
+        assert_eq!(ep.duration_windows(), Some(6));
+        assert!(mon.open_episode_ref().is_none());
+    }
+
+    #[test]
+    fn exit_threshold_is_strict_and_bioload_improvement_releases() {
+        let config = LockMonitorConfig { exit_cp: cp_of(0.5), ..LockMonitorConfig::default() };
+        let mut mon = locked_monitor(config);
+        for i in 10..14 {
+            assert_eq!(mon.observe(i, window(0.5, 0.5), 0.0).state, LockState::Locked);
+        }
+        // Still at the exit level, but bioload is improving.
+        assert_eq!(mon.observe(20, window(0.5, 0.5), 0.2).state, LockState::Releasing);
+        assert!(mon.observe(21, window(0.9, 0.5), 0.2).episode_closed);
+    }
+
+    #[test]
+    fn episode_tracks_peak_pressure_and_history_is_bounded() {
+        let config = LockMonitorConfig { history_len: 4, ..LockMonitorConfig::default() };
+        let mut mon = locked_monitor(config);
+        mon.observe(10, window(1.0, 0.5), 0.0);
+        assert_eq!(mon.open_episode_ref().unwrap().peak_cp, cp_of(1.0));
+        assert_eq!(mon.cp_history().len(), 4);
+        assert!((mon.cp_mean() - (3.0 * cp_of(0.9) + cp_of(1.0)) / 4.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,317 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::{BiophysicalEnvelopeSnapshot, NatureTokenRuleSet, NeuroprintView};
+
+// ============ NORMALIZATION PROFILE ============
+// Per-subject baseline ranges used by TreeOfLifeView. Version 0 is the
+// population default (60–120 BPM, 0–80 ms HRV, 1 h session, 1000 events).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NormalizationProfile {
+    pub subject_id: String,
+    pub version: u32,
+    pub hr_rest_bpm: f32,          // maps to 0.0 strain
+    pub hr_max_bpm: f32,           // maps to 1.0 strain
+    pub hrv_max_ms: f32,           // maps to 1.0 reserve
+    pub hydration_min: f32,        // raw hydration index mapped to 0.0
+    pub hydration_max: f32,        // raw hydration index mapped to 1.0
+    pub session_window_secs: f32,
+    pub evolve_event_scale: f32,
+    pub calibration_samples: usize, // 0 for the population default
+}
+
+impl NormalizationProfile {
+    pub fn population_default() -> Self {
+        Self {
+            subject_id: String::new(),
+            version: 0,
+            hr_rest_bpm: 60.0,
+            hr_max_bpm: 120.0,
+            hrv_max_ms: 80.0,
+            hydration_min: 0.0,
+            hydration_max: 1.0,
+            session_window_secs: 3600.0,
+            evolve_event_scale: 1000.0,
+            calibration_samples: 0,
+        }
+    }
+
+    pub fn normalize_heart_rate(&self, bpm: f32) -> f32 {
+        (bpm - self.hr_rest_bpm) / (self.hr_max_bpm - self.hr_rest_bpm)
+    }
+
+    pub fn normalize_hrv(&self, ms: f32) -> f32 {
+        ms / self.hrv_max_ms
+    }
+
+    pub fn normalize_hydration(&self, raw: f32) -> f32 {
+        ((raw - self.hydration_min) / (self.hydration_max - self.hydration_min)).clamp(0.0, 1.0)
+    }
+
+    fn validate(&self) -> Result<(), CalibrationError> {
+        let ok = self.hr_max_bpm > self.hr_rest_bpm
+            && self.hrv_max_ms > 0.0
+            && self.hydration_max > self.hydration_min
+            && self.session_window_secs > 0.0
+            && self.evolve_event_scale > 0.0;
+        if ok { Ok(()) } else { Err(CalibrationError::DegenerateRange) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationError {
+    TooFewSamples { have: usize, need: usize },
+    DegenerateRange,               // e.g. flat HR during calibration
+    NonMonotonicVersion { subject_id: String, latest: u32, attempted: u32 },
+    UnknownVersion { subject_id: String, version: u32 },
+    Io(String),
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::TooFewSamples { have, need } => {
+                write!(f, "calibration needs {} samples, have {}", need, have)
+            }
+            CalibrationError::DegenerateRange => write!(f, "calibration produced a degenerate range"),
+            CalibrationError::NonMonotonicVersion { subject_id, latest, attempted } => write!(
+                f,
+                "profile version {} for {} is not newer than {}",
+                attempted, subject_id, latest
+            ),
+            CalibrationError::UnknownVersion { subject_id, version } => {
+                write!(f, "no profile version {} for {}", version, subject_id)
+            }
+            CalibrationError::Io(e) => write!(f, "profile store I/O: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CalibrationError {}
+
+// ============ CALIBRATION SESSION ============
+// Collects resting/baseline snapshots and learns robust ranges (5th/95th percentiles).
+#[derive(Debug, Clone, Default)]
+pub struct CalibrationSession {
+    pub subject_id: String,
+    pub min_samples: usize,
+    heart_rate: Vec<f32>,
+    hrv: Vec<f32>,
+    hydration: Vec<f32>,
+}
+
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let idx = ((sorted.len() - 1) as f32 * p).round() as usize;
+    sorted[idx.min(sorted.len() - 1)]
+}
+
+fn sorted(xs: &[f32]) -> Vec<f32> {
+    let mut v = xs.to_vec();
+    v.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    v
+}
+
+impl CalibrationSession {
+    pub fn new(subject_id: &str, min_samples: usize) -> Self {
+        Self { subject_id: subject_id.to_string(), min_samples, ..Default::default() }
+    }
+
+    pub fn record(&mut self, snapshot: &BiophysicalEnvelopeSnapshot) {
+        self.heart_rate.push(snapshot.heart_rate);
+        self.hrv.push(snapshot.heart_rate_variability);
+        if let Some(h) = snapshot.hydration {
+            self.hydration.push(h);
+        }
+    }
+
+    pub fn samples(&self) -> usize {
+        self.heart_rate.len()
+    }
+
+    /// Fit a profile. Session window and event scale keep the population
+    /// defaults; hydration falls back to [0,1] if it was never measured.
+    pub fn fit(&self, version: u32) -> Result<NormalizationProfile, CalibrationError> {
+        if self.samples() < self.min_samples.max(2) {
+            return Err(CalibrationError::TooFewSamples { have: self.samples(), need: self.min_samples.max(2) });
+        }
+        let base = NormalizationProfile::population_default();
+        let hr = sorted(&self.heart_rate);
+        let hrv = sorted(&self.hrv);
+
+        let hr_rest = percentile(&hr, 0.05);
+        // Strain ceiling: at least the population span above the subject's rest.
+        let hr_max = percentile(&hr, 0.95).max(hr_rest + (base.hr_max_bpm - base.hr_rest_bpm));
+        let (hydration_min, hydration_max) = if self.hydration.len() >= 2 {
+            let h = sorted(&self.hydration);
+            (percentile(&h, 0.05), percentile(&h, 0.95))
+        } else {
+            (base.hydration_min, base.hydration_max)
+        };
+
+        let profile = NormalizationProfile {
+            subject_id: self.subject_id.clone(),
+            version,
+            hr_rest_bpm: hr_rest,
+            hr_max_bpm: hr_max,
+            hrv_max_ms: percentile(&hrv, 0.95),
+            hydration_min,
+            hydration_max,
+            calibration_samples: self.samples(),
+            ..base
+        };
+        profile.validate()?;
+        Ok(profile)
+    }
+}
+
+// ============ PROFILE STORE ============
+// Versioned, forward-only profiles per subject_id; persisted as JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileStore {
+    pub profiles: BTreeMap<String, Vec<NormalizationProfile>>,
+}
+
+impl ProfileStore {
+    /// Version 0 is the population default, so every stored version must
+    /// be newer than both it and the subject's latest profile.
+    pub fn insert(&mut self, profile: NormalizationProfile) -> Result<(), CalibrationError> {
+        profile.validate()?;
+        let latest = self.profiles.get(&profile.subject_id).and_then(|v| v.last()).map_or(0, |p| p.version);
+        if profile.version <= latest {
+            return Err(CalibrationError::NonMonotonicVersion {
+                subject_id: profile.subject_id.clone(),
+                latest,
+                attempted: profile.version,
+            });
+        }
+        self.profiles.entry(profile.subject_id.clone()).or_default().push(profile);
+        Ok(())
+    }
+
+    /// Latest profile for a subject, or the population default.
+    pub fn current(&self, subject_id: &str) -> NormalizationProfile {
+        self.profiles
+            .get(subject_id)
+            .and_then(|v| v.last())
+            .cloned()
+            .unwrap_or_else(NormalizationProfile::population_default)
+    }
+
+    pub fn get(&self, subject_id: &str, version: u32) -> Result<NormalizationProfile, CalibrationError> {
+        if version == 0 {
+            return Ok(NormalizationProfile::population_default());
+        }
+        self.profiles
+            .get(subject_id)
+            .and_then(|v| v.iter().find(|p| p.version == version))
+            .cloned()
+            .ok_or_else(|| CalibrationError::UnknownVersion { subject_id: subject_id.to_string(), version })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CalibrationError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| CalibrationError::Io(e.to_string()))?;
+        serde_json::from_str(&raw).map_err(|e| CalibrationError::Io(e.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), CalibrationError> {
+        let raw = serde_json::to_string_pretty(self).map_err(|e| CalibrationError::Io(e.to_string()))?;
+        std::fs::write(path, raw).map_err(|e| CalibrationError::Io(e.to_string()))
+    }
+}
+
+// ============ HISTORICAL RECOMPUTE ============
+// Re-derive past neuroprints under another profile version. Original
+// timestamps are preserved; output is advisory-only like every NeuroprintView.
+pub fn recompute_history(
+    history: &[(u64, BiophysicalEnvelopeSnapshot)],
+    subject_id: &str,
+    rules: &NatureTokenRuleSet,
+    profile: &NormalizationProfile,
+) -> Vec<NeuroprintView> {
+    history
+        .iter()
+        .map(|(timestamp_ms, snapshot)| {
+            let mut view = NeuroprintView::with_profile(snapshot, subject_id, rules, profile);
+            view.timestamp_ms = *timestamp_ms;
+            view
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{default_rule_set, CapabilityTier, TreeOfLifeView};
+
+    fn snap(hr: f32, hrv: f32, hydration: Option<f32>) -> BiophysicalEnvelopeSnapshot {
+        BiophysicalEnvelopeSnapshot {
+            heart_rate: hr,
+            heart_rate_variability: hrv,
+            eeg_alpha: 0.5,
+            eeg_beta: 0.5,
+            eeg_gamma: 0.5,
+            eeg_alpha_cve: 0.5,
+            eda: 0.2,
+            motion_accel: 0.1,
+            roh: 0.05,
+            capability_tier: CapabilityTier::CapGeneralUse,
+            evolve_index: 10,
+            session_epoch: 60,
+            hydration,
+        }
+    }
+
+    #[test]
+    fn test_calibrated_profile_changes_normalization() {
+        // Athlete-like baseline: resting HR ~48, HRV up to ~110 ms.
+        let mut cal = CalibrationSession::new("self-bostrom", 10);
+        for i in 0..20 {
+            cal.record(&snap(48.0 + i as f32 * 0.5, 90.0 + i as f32, Some(0.40 + i as f32 * 0.01)));
+        }
+        let profile = cal.fit(1).unwrap();
+        assert!(profile.hr_rest_bpm < 60.0);
+        assert!(profile.hrv_max_ms > 80.0);
+
+        // HR 75 sits inside the population range, so the default blood axis
+        // is not saturated and the lower calibrated resting HR must show.
+        let s = snap(75.0, 100.0, Some(0.50));
+        let default_tree = TreeOfLifeView::from_snapshot(&s);
+        let calibrated = TreeOfLifeView::from_snapshot_with_profile(&s, &profile);
+        assert!(default_tree.blood > 0.0 && default_tree.blood < 1.0);
+        assert!(calibrated.blood < default_tree.blood);
+        assert!(calibrated.oxygen < default_tree.oxygen);
+        assert!(calibrated.h2o > 0.0 && calibrated.h2o < 1.0);
+    }
+
+    #[test]
+    fn test_store_is_forward_only_and_recomputes_history() {
+        let mut store = ProfileStore::default();
+        let mut cal = CalibrationSession::new("self-bostrom", 2);
+        cal.record(&snap(50.0, 60.0, None));
+        cal.record(&snap(58.0, 95.0, None));
+        // Version 0 would be shadowed by the population default.
+        assert!(matches!(
+            store.insert(cal.fit(0).unwrap()),
+            Err(CalibrationError::NonMonotonicVersion { latest: 0, attempted: 0, .. })
+        ));
+        assert!(store.profiles.is_empty());
+        store.insert(cal.fit(2).unwrap()).unwrap();
+        assert!(matches!(
+            store.insert(cal.fit(1).unwrap()),
+            Err(CalibrationError::NonMonotonicVersion { .. })
+        ));
+
+        let history = vec![(1_000, snap(70.0, 40.0, None)), (2_000, snap(80.0, 30.0, None))];
+        let v0 = recompute_history(&history, "self-bostrom", default_rule_set(), &store.get("self-bostrom", 0).unwrap());
+        let v2 = recompute_history(&history, "self-bostrom", default_rule_set(), &store.current("self-bostrom"));
+        assert_eq!(v2[1].timestamp_ms, 2_000);
+        assert_eq!(v0[0].profile_version, 0);
+        assert_eq!(v2[0].profile_version, 2);
+        assert!(v2[0].tree_of_life.blood != v0[0].tree_of_life.blood);
+    }
+}
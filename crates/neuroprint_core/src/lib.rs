@@ -1,6 +1,12 @@
 use serde::{Serialize, Deserialize};
 use std::time::SystemTime;
-use std::fmt;
+
+pub mod calibration;
+pub mod rules;
+pub mod session;
+pub use calibration::{CalibrationSession, NormalizationProfile, ProfileStore};
+pub use rules::{default_rule_set, NatureTokenRuleSet, RuleLoadError};
+pub use session::{NeuroprintSession, NeuroprintSessionConfig, TrendToken};
 
 // ============ CORE INVARIENTS ============
 // RoH ≤ 0.3 is hard-bound; all outputs must reflect this ceiling
@@ -23,6 +29,8 @@ pub struct BiophysicalEnvelopeSnapshot {
     pub capability_tier: CapabilityTier,
     pub evolve_index: u64,         // Event count since session start
     pub session_epoch: u64,        // Seconds since session start
+    #[serde(default)]
+    pub hydration: Option<f32>,    // Hydration index, raw (e.g. bioimpedance); None = not measured
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -52,7 +60,7 @@ pub struct TreeOfLifeView {
     pub blood: f32,      // 1.0 - normalized_heart_rate (strain)
     pub oxygen: f32,     // normalized_heart_rate_variability
     pub wave: f32,       // avg(eeg_alpha, eeg_beta, eeg_gamma, eeg_alpha_cve)
-    pub h2o: f32,        // fixed neutral (0.5) until hydration axis defined
+    pub h2o: f32,        // normalized hydration; neutral 0.5 when not measured
     pub time: f32,       // normalized session_epoch / max_session_window
     pub decay: f32,      // roh / 0.3 (clamped)
     pub lifeforce: f32,  // 1.0 - decay
@@ -68,23 +76,34 @@ pub struct TreeOfLifeView {
 
 impl TreeOfLifeView {
     pub fn from_snapshot(snapshot: &BiophysicalEnvelopeSnapshot) -> Self {
+        Self::from_snapshot_with_profile(snapshot, &NormalizationProfile::population_default())
+    }
+
+    /// Normalize against a subject's calibration profile instead of population ranges.
+    pub fn from_snapshot_with_profile(
+        snapshot: &BiophysicalEnvelopeSnapshot,
+        profile: &NormalizationProfile,
+    ) -> Self {
         // Normalize inputs
-        let normalized_hr = (snapshot.heart_rate - 60.0) / 60.0; // 60–120 BPM range
-        let normalized_hrv = snapshot.heart_rate_variability / 80.0; // 0–80 ms
+        let normalized_hr = profile.normalize_heart_rate(snapshot.heart_rate);
+        let normalized_hrv = profile.normalize_hrv(snapshot.heart_rate_variability);
         let normalized_eeg_alpha = snapshot.eeg_alpha;
         let normalized_eeg_beta = snapshot.eeg_beta;
         let normalized_eeg_gamma = snapshot.eeg_gamma;
         let normalized_eeg_alpha_cve = snapshot.eeg_alpha_cve;
         let normalized_eda = snapshot.eda;
         let normalized_motion = snapshot.motion_accel;
-        let normalized_epoch = (snapshot.session_epoch as f32) / 3600.0; // 1hr max session
-        let normalized_evolve = (snapshot.evolve_index as f32) / 1000.0; // 0–1000 events
+        let normalized_epoch = (snapshot.session_epoch as f32) / profile.session_window_secs;
+        let normalized_evolve = (snapshot.evolve_index as f32) / profile.evolve_event_scale;
 
         // Compute TREE assets (pure functions, no side effects)
         let blood = 1.0 - normalized_hr.max(0.0).min(1.0); // Inverse strain
         let oxygen = normalized_hrv.max(0.0).min(1.0); // Direct reserve
         let wave = (normalized_eeg_alpha + normalized_eeg_beta + normalized_eeg_gamma + normalized_eeg_alpha_cve) / 4.0;
-        let h2o = 0.5; // Neutral placeholder
+        let h2o = snapshot
+            .hydration
+            .map(|h| profile.normalize_hydration(h))
+            .unwrap_or(0.5); // Neutral when not measured
         let time = normalized_epoch.max(0.0).min(1.0);
         let decay = (snapshot.roh / 0.3).min(1.0); // Clamped ceiling
         let lifeforce = 1.0 - decay;
@@ -130,6 +149,8 @@ pub struct NatureToken {
 pub struct NeuroprintView {
     pub timestamp_ms: u64,
     pub subject_id: String,
+    #[serde(default)]
+    pub profile_version: u32,      // 0 = population default ranges
     pub tree_of_life: TreeOfLifeView,
     pub nature_tokens: Vec<NatureToken>,
     pub diagnostics: NeuroprintDiagnostics,
@@ -145,9 +166,28 @@ pub struct NeuroprintDiagnostics {
 
 impl NeuroprintView {
     pub fn new(snapshot: &BiophysicalEnvelopeSnapshot, subject_id: &str) -> Self {
-        let tree_of_life = TreeOfLifeView::from_snapshot(snapshot);
+        Self::with_rules(snapshot, subject_id, default_rule_set())
+    }
 
-        let nature_tokens = Self::infer_nature_tokens(&tree_of_life);
+    /// Same as `new`, but evaluates a caller-supplied (already validated) rule set.
+    pub fn with_rules(
+        snapshot: &BiophysicalEnvelopeSnapshot,
+        subject_id: &str,
+        rules: &NatureTokenRuleSet,
+    ) -> Self {
+        Self::with_profile(snapshot, subject_id, rules, &NormalizationProfile::population_default())
+    }
+
+    /// Build a view normalized under a specific calibration profile version.
+    pub fn with_profile(
+        snapshot: &BiophysicalEnvelopeSnapshot,
+        subject_id: &str,
+        rules: &NatureTokenRuleSet,
+        profile: &NormalizationProfile,
+    ) -> Self {
+        let tree_of_life = TreeOfLifeView::from_snapshot_with_profile(snapshot, profile);
+
+        let nature_tokens = rules.evaluate(&tree_of_life);
 
         let diagnostics = Self::generate_diagnostics(&tree_of_life, &nature_tokens);
 
@@ -157,6 +197,7 @@ impl NeuroprintView {
                 .unwrap()
                 .as_millis() as u64,
             subject_id: subject_id.to_string(),
+            profile_version: profile.version,
             tree_of_life,
             nature_tokens,
             diagnostics,
@@ -164,76 +205,8 @@ impl NeuroprintView {
     }
 
     fn infer_nature_tokens(tree: &TreeOfLifeView) -> Vec<NatureToken> {
-        let mut tokens = Vec::new();
-
-        // CALM_STABLE: high lifeforce, high oxygen, low decay, low fear/pain
-        if tree.lifeforce > 0.7 && tree.oxygen > 0.7 && tree.decay < 0.3 && tree.fear < 0.3 && tree.pain < 0.3 {
-            tokens.push(NatureToken {
-                label: "CALM_STABLE".to_string(),
-                confidence: 0.9,
-                evidence: vec![
-                    "lifeforce > 0.7".to_string(),
-                    "oxygen > 0.7".to_string(),
-                    "decay < 0.3".to_string(),
-                    "fear < 0.3".to_string(),
-                    "pain < 0.3".to_string(),
-                ],
-            });
-        }
-
-        // OVERLOADED: decay > 0.6, power > 0.7, lifeforce < 0.3
-        if tree.decay > 0.6 && tree.power > 0.7 && tree.lifeforce < 0.3 {
-            tokens.push(NatureToken {
-                label: "OVERLOADED".to_string(),
-                confidence: 0.85,
-                evidence: vec![
-                    "decay > 0.6".to_string(),
-                    "power > 0.7".to_string(),
-                    "lifeforce < 0.3".to_string(),
-                ],
-            });
-        }
-
-        // RECOVERY: decay down 0.2+, lifeforce up 0.2+, fear/pain down
-        // (In practice, this requires historical context — here we simulate one-step delta)
-        // For single snapshot, we use heuristic: decay > 0.4 but < 0.6 and lifeforce > 0.4
-        if tree.decay > 0.4 && tree.decay < 0.6 && tree.lifeforce > 0.4 && tree.fear < 0.4 {
-            tokens.push(NatureToken {
-                label: "RECOVERY".to_string(),
-                confidence: 0.7,
-                evidence: vec![
-                    "decay in [0.4, 0.6]".to_string(),
-                    "lifeforce > 0.4".to_string(),
-                    "fear < 0.4".to_string(),
-                ],
-            });
-        }
-
-        // UNFAIR_DRAIN: low lifeforce + high power + high tech + low brain
-        // (simulated: low lifeforce, high power, high tech, low brain)
-        if tree.lifeforce < 0.3 && tree.power > 0.7 && tree.tech > 0.7 && tree.brain < 0.4 {
-            tokens.push(NatureToken {
-                label: "UNFAIR_DRAIN".to_string(),
-                confidence: 0.8,
-                evidence: vec![
-                    "lifeforce < 0.3".to_string(),
-                    "power > 0.7".to_string(),
-                    "tech > 0.7".to_string(),
-                    "brain < 0.4".to_string(),
-                ],
-            });
-        }
-
-        // Default
-        if tokens.is_empty() {
-            tokens.push(NatureToken {
-                label: "BALANCED".to_string(),
-                confidence: 0.6,
-                evidence: vec!["no strong pattern detected".to_string()],
-            });
-        }
-
-        tokens
+        // Thresholds live in policy/nature-tokens.aln (NATURE-TOKENS-SNAPSHOT).
+        default_rule_set().evaluate(tree)
     }
 
     fn generate_diagnostics(tree: &TreeOfLifeView, tokens: &[NatureToken]) -> NeuroprintDiagnostics {
@@ -281,6 +254,7 @@ mod tests {
             capability_tier: CapabilityTier::CapAugmentedCitizen,
             evolve_index: 150,
             session_epoch: 1800,
+            hydration: None,
         };
 
         let tree = TreeOfLifeView::from_snapshot(&snapshot);
@@ -307,14 +281,15 @@ mod tests {
             capability_tier: CapabilityTier::CapModelOnly,
             evolve_index: 950,
             session_epoch: 3500,
+            hydration: None,
         };
 
         let tree = TreeOfLifeView::from_snapshot(&snapshot);
         let tokens = NeuroprintView::infer_nature_tokens(&tree);
 
         assert!(tokens.iter().any(|t| t.label == "OVERLOADED"));
-        assert!(tokens.iter().any(|t| t.label == "BALANCED"));
-        assert!(tokens.len() >= 1);
+        // BALANCED is only emitted when no other token fires.
+        assert!(!tokens.iter().any(|t| t.label == "BALANCED"));
     }
 
     #[test]
@@ -328,17 +303,18 @@ mod tests {
             eeg_alpha_cve: 0.5,
             eda: 0.2,
             motion_accel: 0.1,
-            roh: 0.10,
+            roh: 0.05,
             capability_tier: CapabilityTier::CapAugmentedCitizen,
             evolve_index: 50,
             session_epoch: 1200,
+            hydration: None,
         };
 
         let nv = NeuroprintView::new(&snapshot, "self-bostrom");
 
         assert_eq!(nv.subject_id, "self-bostrom");
-        assert!(nv.tree_of_life.decay <= 0.334);
-        assert!(nv.tree_of_life.lifeforce >= 0.666);
+        assert!(nv.tree_of_life.decay < 0.3);
+        assert!(nv.tree_of_life.lifeforce > 0.7);
         assert!(nv.diagnostics.sovereignty_integrity);
         assert_eq!(nv.nature_tokens.len(), 1);
         assert_eq!(nv.nature_tokens[0].label, "CALM_STABLE");
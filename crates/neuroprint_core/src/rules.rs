@@ -0,0 +1,759 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::{NatureToken, TreeOfLifeView};
+
+// ============ DECLARATIVE NATURE-TOKEN RULES ============
+// Loaded from the NATURE-TOKENS-SNAPSHOT section of policy/nature-tokens.aln.
+// Evaluation is a pure function of one TreeOfLifeView; advisory-only.
+
+pub const SNAPSHOT_SECTION: &str = "NATURE-TOKENS-SNAPSHOT";
+/// Windowed definitions of the same tokens; their axis thresholds must
+/// agree with the snapshot section.
+pub const WINDOWED_SECTION: &str = "NATURE-TOKENS";
+
+/// Shipped policy, embedded so the default rule set needs no I/O.
+const DEFAULT_POLICY: &str = include_str!("../../../policy/nature-tokens.aln");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TreeAxis {
+    Blood, Oxygen, Wave, H2o, Time, Decay, Lifeforce, Nano,
+    Brain, Smart, Evolve, Power, Tech, Fear, Pain,
+}
+
+impl TreeAxis {
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s.trim().to_ascii_uppercase().as_str() {
+            "BLOOD" => TreeAxis::Blood,
+            "OXYGEN" => TreeAxis::Oxygen,
+            "WAVE" => TreeAxis::Wave,
+            "H2O" => TreeAxis::H2o,
+            "TIME" => TreeAxis::Time,
+            "DECAY" => TreeAxis::Decay,
+            "LIFEFORCE" => TreeAxis::Lifeforce,
+            "NANO" => TreeAxis::Nano,
+            "BRAIN" => TreeAxis::Brain,
+            "SMART" => TreeAxis::Smart,
+            "EVOLVE" => TreeAxis::Evolve,
+            "POWER" => TreeAxis::Power,
+            "TECH" => TreeAxis::Tech,
+            "FEAR" => TreeAxis::Fear,
+            "PAIN" => TreeAxis::Pain,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TreeAxis::Blood => "blood",
+            TreeAxis::Oxygen => "oxygen",
+            TreeAxis::Wave => "wave",
+            TreeAxis::H2o => "h2o",
+            TreeAxis::Time => "time",
+            TreeAxis::Decay => "decay",
+            TreeAxis::Lifeforce => "lifeforce",
+            TreeAxis::Nano => "nano",
+            TreeAxis::Brain => "brain",
+            TreeAxis::Smart => "smart",
+            TreeAxis::Evolve => "evolve",
+            TreeAxis::Power => "power",
+            TreeAxis::Tech => "tech",
+            TreeAxis::Fear => "fear",
+            TreeAxis::Pain => "pain",
+        }
+    }
+
+    pub fn value(&self, tree: &TreeOfLifeView) -> f32 {
+        match self {
+            TreeAxis::Blood => tree.blood,
+            TreeAxis::Oxygen => tree.oxygen,
+            TreeAxis::Wave => tree.wave,
+            TreeAxis::H2o => tree.h2o,
+            TreeAxis::Time => tree.time,
+            TreeAxis::Decay => tree.decay,
+            TreeAxis::Lifeforce => tree.lifeforce,
+            TreeAxis::Nano => tree.nano,
+            TreeAxis::Brain => tree.brain,
+            TreeAxis::Smart => tree.smart,
+            TreeAxis::Evolve => tree.evolve,
+            TreeAxis::Power => tree.power,
+            TreeAxis::Tech => tree.tech,
+            TreeAxis::Fear => tree.fear,
+            TreeAxis::Pain => tree.pain,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareOp {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl CompareOp {
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s.trim() {
+            ">" => CompareOp::Gt,
+            ">=" => CompareOp::Ge,
+            "<" => CompareOp::Lt,
+            "<=" => CompareOp::Le,
+            _ => return None,
+        })
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+        }
+    }
+
+    fn holds(&self, lhs: f32, rhs: f32) -> bool {
+        match self {
+            CompareOp::Gt => lhs > rhs,
+            CompareOp::Ge => lhs >= rhs,
+            CompareOp::Lt => lhs < rhs,
+            CompareOp::Le => lhs <= rhs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AxisCondition {
+    pub axis: TreeAxis,
+    pub op: CompareOp,
+    pub threshold: f32,
+}
+
+impl AxisCondition {
+    pub fn holds(&self, tree: &TreeOfLifeView) -> bool {
+        self.op.holds(self.axis.value(tree), self.threshold)
+    }
+
+    pub fn evidence(&self) -> String {
+        format!("{} {} {}", self.axis.name(), self.op.symbol(), self.threshold)
+    }
+}
+
+/// Admissible interval on one axis: (axis, lo, hi, lo_inclusive, hi_inclusive).
+type AxisInterval = (TreeAxis, f32, f32, bool, bool);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatureTokenRule {
+    pub label: String,
+    pub confidence: f32,
+    pub conditions: Vec<AxisCondition>, // all must hold
+    pub evidence: Vec<String>,          // empty = generated from conditions
+    pub line: usize,
+}
+
+impl NatureTokenRule {
+    pub fn matches(&self, tree: &TreeOfLifeView) -> bool {
+        self.conditions.iter().all(|c| c.holds(tree))
+    }
+
+    fn to_token(&self) -> NatureToken {
+        let evidence = if self.evidence.is_empty() {
+            self.conditions.iter().map(AxisCondition::evidence).collect()
+        } else {
+            self.evidence.clone()
+        };
+        NatureToken { label: self.label.clone(), confidence: self.confidence, evidence }
+    }
+
+    /// Per-axis admissible intervals implied by the conditions.
+    fn intervals(&self) -> Vec<AxisInterval> {
+        let mut out: Vec<AxisInterval> = Vec::new();
+        for c in &self.conditions {
+            let idx = match out.iter().position(|(a, ..)| *a == c.axis) {
+                Some(i) => i,
+                None => {
+                    out.push((c.axis, f32::NEG_INFINITY, f32::INFINITY, true, true));
+                    out.len() - 1
+                }
+            };
+            let iv = &mut out[idx];
+            match c.op {
+                CompareOp::Gt | CompareOp::Ge => {
+                    let incl = c.op == CompareOp::Ge;
+                    if c.threshold > iv.1 || (c.threshold == iv.1 && !incl) {
+                        iv.1 = c.threshold;
+                        iv.3 = incl;
+                    }
+                }
+                CompareOp::Lt | CompareOp::Le => {
+                    let incl = c.op == CompareOp::Le;
+                    if c.threshold < iv.2 || (c.threshold == iv.2 && !incl) {
+                        iv.2 = c.threshold;
+                        iv.4 = incl;
+                    }
+                }
+            }
+        }
+        out.sort_by_key(|(a, ..)| *a as u8);
+        out
+    }
+}
+
+/// True if some TREE view satisfies both regions. An axis constrained in
+/// only one region is unbounded in the other.
+fn regions_intersect(a: &[AxisInterval], b: &[AxisInterval]) -> bool {
+    a.iter().all(|&(axis, lo1, hi1, lo1_inc, hi1_inc)| {
+        let Some(&(_, lo2, hi2, lo2_inc, hi2_inc)) = b.iter().find(|iv| iv.0 == axis) else {
+            return true;
+        };
+        let (lo, lo_inc) = match lo1.partial_cmp(&lo2) {
+            Some(std::cmp::Ordering::Greater) => (lo1, lo1_inc),
+            Some(std::cmp::Ordering::Less) => (lo2, lo2_inc),
+            _ => (lo1, lo1_inc && lo2_inc),
+        };
+        let (hi, hi_inc) = match hi1.partial_cmp(&hi2) {
+            Some(std::cmp::Ordering::Less) => (hi1, hi1_inc),
+            Some(std::cmp::Ordering::Greater) => (hi2, hi2_inc),
+            _ => (hi1, hi1_inc && hi2_inc),
+        };
+        lo < hi || (lo == hi && lo_inc && hi_inc)
+    })
+}
+
+/// `(line, token, axis, is_min, threshold)` from the windowed section.
+type WindowedThreshold = (usize, String, TreeAxis, bool, f32);
+
+/// Every `THRESH <AXIS>_MIN` or `<AXIS>_MAX` row of the windowed section.
+/// Thresholds on derived quantities (e.g. `DECAY_DROP_MIN`) are skipped.
+fn windowed_thresholds(text: &str) -> Result<Vec<WindowedThreshold>, RuleLoadError> {
+    let mut out = Vec::new();
+    let mut in_section = false;
+    let mut token: Option<String> = None;
+    for (idx, raw) in text.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = strip_comment(raw).trim();
+        if let Some(name) = line.strip_prefix("SECTION,") {
+            in_section = name.trim() == WINDOWED_SECTION;
+            token = None;
+            continue;
+        }
+        let Some(row) = line.strip_prefix("ROW,").filter(|_| in_section) else { continue };
+        let parts: Vec<&str> = row.split(',').map(str::trim).collect();
+        match parts.as_slice() {
+            ["ID", label] => token = Some(label.to_string()),
+            ["THRESH", name, value] => {
+                let (axis, is_min) = match (name.strip_suffix("_MIN"), name.strip_suffix("_MAX")) {
+                    (Some(a), _) => (a, true),
+                    (_, Some(a)) => (a, false),
+                    _ => continue,
+                };
+                if let (Some(label), Some(axis)) = (&token, TreeAxis::parse(axis)) {
+                    out.push((line_no, label.clone(), axis, is_min, parse_f32(line_no, value)?));
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefaultToken {
+    pub label: String,
+    pub confidence: f32,
+    pub evidence: String,
+}
+
+/// Two tokens declared (`ROW,OVERLAPS,A,B`) as allowed to fire together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowedOverlap {
+    pub a: String,
+    pub b: String,
+    pub line: usize,
+}
+
+impl AllowedOverlap {
+    fn covers(&self, x: &str, y: &str) -> bool {
+        (self.a == x && self.b == y) || (self.a == y && self.b == x)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatureTokenRuleSet {
+    pub version: u32,
+    pub rules: Vec<NatureTokenRule>,
+    pub default: Option<DefaultToken>,
+    #[serde(default)]
+    pub allowed_overlaps: Vec<AllowedOverlap>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleLoadError {
+    SectionMissing,
+    UnknownAxis { line: usize, axis: String },
+    UnknownOp { line: usize, op: String },
+    BadNumber { line: usize, value: String },
+    ConditionOutsideToken { line: usize },
+    EmptyRule { line: usize, label: String },
+    DuplicateToken { line: usize, label: String },
+    /// A rule's conditions on one axis admit no value.
+    Unsatisfiable { line: usize, label: String, axis: &'static str },
+    /// Two rules have identical admissible regions, so the later one is a
+    /// duplicate. Rejected even when the pair may overlap.
+    DuplicateConditions { line: usize, label: String, same_as: String },
+    /// Two rules admit a common TREE view but are not declared with
+    /// `ROW,OVERLAPS`.
+    OverlappingRules { line: usize, label: String, overlaps: String },
+    /// An `OVERLAPS` row names a token that is not defined.
+    UnknownToken { line: usize, label: String },
+    /// A snapshot condition disagrees with the same token's threshold in
+    /// the windowed section.
+    ThresholdMismatch { line: usize, label: String, axis: &'static str, windowed: f32, snapshot: f32 },
+    Malformed { line: usize, text: String },
+}
+
+impl fmt::Display for RuleLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleLoadError::SectionMissing => write!(f, "section {} not found", SNAPSHOT_SECTION),
+            RuleLoadError::UnknownAxis { line, axis } => write!(f, "line {}: unknown TREE axis '{}'", line, axis),
+            RuleLoadError::UnknownOp { line, op } => write!(f, "line {}: unknown operator '{}'", line, op),
+            RuleLoadError::BadNumber { line, value } => write!(f, "line {}: invalid number '{}'", line, value),
+            RuleLoadError::ConditionOutsideToken { line } => write!(f, "line {}: COND/EVIDENCE before any TOKEN", line),
+            RuleLoadError::EmptyRule { line, label } => write!(f, "line {}: token {} has no conditions", line, label),
+            RuleLoadError::DuplicateToken { line, label } => write!(f, "line {}: token {} defined twice", line, label),
+            RuleLoadError::Unsatisfiable { line, label, axis } => {
+                write!(f, "line {}: token {} can never fire (conditions on {} are contradictory)", line, label, axis)
+            }
+            RuleLoadError::DuplicateConditions { line, label, same_as } => {
+                write!(f, "line {}: token {} has the same conditions as {}", line, label, same_as)
+            }
+            RuleLoadError::OverlappingRules { line, label, overlaps } => write!(
+                f,
+                "line {}: token {} overlaps {}; declare ROW,OVERLAPS,{},{} if both may fire",
+                line, label, overlaps, overlaps, label
+            ),
+            RuleLoadError::UnknownToken { line, label } => write!(f, "line {}: unknown token {}", line, label),
+            RuleLoadError::ThresholdMismatch { line, label, axis, windowed, snapshot } => write!(
+                f,
+                "line {}: token {} {} threshold {} disagrees with {} in section {}",
+                line, label, axis, snapshot, windowed, WINDOWED_SECTION
+            ),
+            RuleLoadError::Malformed { line, text } => write!(f, "line {}: malformed row '{}'", line, text),
+        }
+    }
+}
+
+impl std::error::Error for RuleLoadError {}
+
+/// Drop a `#` comment. Only a `#` at the start of the line or after
+/// whitespace starts one, so values such as `EVIDENCE,ticket#12` survive.
+fn strip_comment(raw: &str) -> &str {
+    let mut after_space = true;
+    for (i, ch) in raw.char_indices() {
+        if ch == '#' && after_space {
+            return &raw[..i];
+        }
+        after_space = ch.is_whitespace();
+    }
+    raw
+}
+
+fn parse_f32(line: usize, s: &str) -> Result<f32, RuleLoadError> {
+    s.trim()
+        .parse::<f32>()
+        .map_err(|_| RuleLoadError::BadNumber { line, value: s.trim().to_string() })
+}
+
+impl NatureTokenRuleSet {
+    /// Parse and validate the snapshot section of an ALN policy document.
+    pub fn from_aln(text: &str) -> Result<Self, RuleLoadError> {
+        let mut in_section = false;
+        let mut seen_section = false;
+        let mut version = 0;
+        let mut rules: Vec<NatureTokenRule> = Vec::new();
+        let mut default = None;
+        let mut allowed_overlaps = Vec::new();
+
+        for (idx, raw) in text.lines().enumerate() {
+            let line_no = idx + 1;
+            let line = strip_comment(raw).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("SECTION,") {
+                in_section = name.trim() == SNAPSHOT_SECTION;
+                seen_section |= in_section;
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+            let row = match line.strip_prefix("ROW,") {
+                Some(r) => r,
+                None => return Err(RuleLoadError::Malformed { line: line_no, text: line.to_string() }),
+            };
+            let (key, rest) = row.split_once(',').unwrap_or((row, ""));
+            match key.trim() {
+                "VERSION" => {
+                    version = rest.trim().parse().map_err(|_| RuleLoadError::BadNumber {
+                        line: line_no,
+                        value: rest.trim().to_string(),
+                    })?
+                }
+                "NOTE" => {}
+                "TOKEN" => {
+                    let (label, conf) = rest
+                        .split_once(',')
+                        .ok_or_else(|| RuleLoadError::Malformed { line: line_no, text: line.to_string() })?;
+                    let label = label.trim().to_string();
+                    if rules.iter().any(|r| r.label == label) {
+                        return Err(RuleLoadError::DuplicateToken { line: line_no, label });
+                    }
+                    rules.push(NatureTokenRule {
+                        label,
+                        confidence: parse_f32(line_no, conf)?,
+                        conditions: Vec::new(),
+                        evidence: Vec::new(),
+                        line: line_no,
+                    });
+                }
+                "COND" => {
+                    let parts: Vec<&str> = rest.split(',').collect();
+                    if parts.len() != 3 {
+                        return Err(RuleLoadError::Malformed { line: line_no, text: line.to_string() });
+                    }
+                    let axis = TreeAxis::parse(parts[0]).ok_or_else(|| RuleLoadError::UnknownAxis {
+                        line: line_no,
+                        axis: parts[0].trim().to_string(),
+                    })?;
+                    let op = CompareOp::parse(parts[1]).ok_or_else(|| RuleLoadError::UnknownOp {
+                        line: line_no,
+                        op: parts[1].trim().to_string(),
+                    })?;
+                    let threshold = parse_f32(line_no, parts[2])?;
+                    rules
+                        .last_mut()
+                        .ok_or(RuleLoadError::ConditionOutsideToken { line: line_no })?
+                        .conditions
+                        .push(AxisCondition { axis, op, threshold });
+                }
+                "OVERLAPS" => {
+                    let (a, b) = rest
+                        .split_once(',')
+                        .ok_or_else(|| RuleLoadError::Malformed { line: line_no, text: line.to_string() })?;
+                    allowed_overlaps.push(AllowedOverlap {
+                        a: a.trim().to_string(),
+                        b: b.trim().to_string(),
+                        line: line_no,
+                    });
+                }
+                "EVIDENCE" => {
+                    rules
+                        .last_mut()
+                        .ok_or(RuleLoadError::ConditionOutsideToken { line: line_no })?
+                        .evidence
+                        .push(rest.trim().to_string());
+                }
+                "DEFAULT" => {
+                    let parts: Vec<&str> = rest.splitn(3, ',').collect();
+                    if parts.len() != 3 {
+                        return Err(RuleLoadError::Malformed { line: line_no, text: line.to_string() });
+                    }
+                    default = Some(DefaultToken {
+                        label: parts[0].trim().to_string(),
+                        confidence: parse_f32(line_no, parts[1])?,
+                        evidence: parts[2].trim().to_string(),
+                    });
+                }
+                _ => return Err(RuleLoadError::Malformed { line: line_no, text: line.to_string() }),
+            }
+        }
+
+        if !seen_section {
+            return Err(RuleLoadError::SectionMissing);
+        }
+        let set = Self { version, rules, default, allowed_overlaps };
+        set.validate()?;
+        set.check_windowed_agreement(&windowed_thresholds(text)?)?;
+        Ok(set)
+    }
+
+    /// Every `<AXIS>_MIN`/`_MAX` threshold of a token in the windowed
+    /// section must equal the snapshot condition on that axis and side.
+    fn check_windowed_agreement(&self, windowed: &[WindowedThreshold]) -> Result<(), RuleLoadError> {
+        for (line, label, axis, is_min, value) in windowed {
+            let Some(rule) = self.rules.iter().find(|r| r.label == *label) else { continue };
+            let lower = |op: CompareOp| matches!(op, CompareOp::Gt | CompareOp::Ge);
+            for c in rule.conditions.iter().filter(|c| c.axis == *axis && lower(c.op) == *is_min) {
+                if c.threshold != *value {
+                    return Err(RuleLoadError::ThresholdMismatch {
+                        line: *line,
+                        label: label.clone(),
+                        axis: axis.name(),
+                        windowed: *value,
+                        snapshot: c.threshold,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject empty, contradictory, duplicate and overlapping rules. Two
+    /// rules may overlap only if an `OVERLAPS` row declares the pair
+    /// (e.g. OVERLOADED and UNFAIR_DRAIN, which fire together).
+    pub fn validate(&self) -> Result<(), RuleLoadError> {
+        for o in &self.allowed_overlaps {
+            for label in [&o.a, &o.b] {
+                if !self.rules.iter().any(|r| r.label == *label) {
+                    return Err(RuleLoadError::UnknownToken { line: o.line, label: label.clone() });
+                }
+            }
+        }
+        let mut regions: Vec<(&NatureTokenRule, Vec<AxisInterval>)> = Vec::new();
+        for rule in &self.rules {
+            if rule.conditions.is_empty() {
+                return Err(RuleLoadError::EmptyRule { line: rule.line, label: rule.label.clone() });
+            }
+            let ivs = rule.intervals();
+            for (axis, lo, hi, lo_inc, hi_inc) in &ivs {
+                if lo > hi || (lo == hi && !(*lo_inc && *hi_inc)) {
+                    return Err(RuleLoadError::Unsatisfiable {
+                        line: rule.line,
+                        label: rule.label.clone(),
+                        axis: axis.name(),
+                    });
+                }
+            }
+            if let Some((other, _)) = regions.iter().find(|(_, r)| *r == ivs) {
+                return Err(RuleLoadError::DuplicateConditions {
+                    line: rule.line,
+                    label: rule.label.clone(),
+                    same_as: other.label.clone(),
+                });
+            }
+            let overlapping = regions.iter().find(|(other, r)| {
+                regions_intersect(r, &ivs) && !self.allowed_overlaps.iter().any(|o| o.covers(&other.label, &rule.label))
+            });
+            if let Some((other, _)) = overlapping {
+                return Err(RuleLoadError::OverlappingRules {
+                    line: rule.line,
+                    label: rule.label.clone(),
+                    overlaps: other.label.clone(),
+                });
+            }
+            regions.push((rule, ivs));
+        }
+        Ok(())
+    }
+
+    /// Evaluate every rule against one TREE view, in declaration order.
+    pub fn evaluate(&self, tree: &TreeOfLifeView) -> Vec<NatureToken> {
+        let mut tokens: Vec<NatureToken> = self
+            .rules
+            .iter()
+            .filter(|r| r.matches(tree))
+            .map(NatureTokenRule::to_token)
+            .collect();
+        if tokens.is_empty() {
+            if let Some(d) = &self.default {
+                tokens.push(NatureToken {
+                    label: d.label.clone(),
+                    confidence: d.confidence,
+                    evidence: vec![d.evidence.clone()],
+                });
+            }
+        }
+        tokens
+    }
+}
+
+/// Rule set shipped in policy/nature-tokens.aln, parsed once.
+pub fn default_rule_set() -> &'static NatureTokenRuleSet {
+    static RULES: OnceLock<NatureTokenRuleSet> = OnceLock::new();
+    RULES.get_or_init(|| {
+        NatureTokenRuleSet::from_aln(DEFAULT_POLICY).expect("policy/nature-tokens.aln must validate")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BiophysicalEnvelopeSnapshot, CapabilityTier};
+
+    // Golden reference: the hardcoded predicates the rule set replaced.
+    fn hardcoded_tokens(tree: &TreeOfLifeView) -> Vec<NatureToken> {
+        let mut tokens = Vec::new();
+        let tok = |label: &str, confidence: f32, evidence: &[&str]| NatureToken {
+            label: label.to_string(),
+            confidence,
+            evidence: evidence.iter().map(|s| s.to_string()).collect(),
+        };
+        if tree.lifeforce > 0.7 && tree.oxygen > 0.7 && tree.decay < 0.3 && tree.fear < 0.3 && tree.pain < 0.3 {
+            tokens.push(tok("CALM_STABLE", 0.9, &["lifeforce > 0.7", "oxygen > 0.7", "decay < 0.3", "fear < 0.3", "pain < 0.3"]));
+        }
+        if tree.decay > 0.6 && tree.power > 0.7 && tree.lifeforce < 0.3 {
+            tokens.push(tok("OVERLOADED", 0.85, &["decay > 0.6", "power > 0.7", "lifeforce < 0.3"]));
+        }
+        if tree.decay > 0.4 && tree.decay < 0.6 && tree.lifeforce > 0.4 && tree.fear < 0.4 {
+            tokens.push(tok("RECOVERY", 0.7, &["decay in [0.4, 0.6]", "lifeforce > 0.4", "fear < 0.4"]));
+        }
+        if tree.lifeforce < 0.3 && tree.power > 0.7 && tree.tech > 0.7 && tree.brain < 0.4 {
+            tokens.push(tok("UNFAIR_DRAIN", 0.8, &["lifeforce < 0.3", "power > 0.7", "tech > 0.7", "brain < 0.4"]));
+        }
+        if tokens.is_empty() {
+            tokens.push(tok("BALANCED", 0.6, &["no strong pattern detected"]));
+        }
+        tokens
+    }
+
+    fn assert_parity(tree: &TreeOfLifeView) {
+        let got = default_rule_set().evaluate(tree);
+        let want = hardcoded_tokens(tree);
+        assert_eq!(got.len(), want.len(), "tree {:?}", tree);
+        for (g, w) in got.iter().zip(want.iter()) {
+            assert_eq!(g.label, w.label);
+            assert_eq!(g.confidence, w.confidence);
+            assert_eq!(g.evidence, w.evidence);
+        }
+    }
+
+    #[test]
+    fn test_golden_parity_over_snapshot_grid() {
+        let tiers = [
+            CapabilityTier::CapModelOnly,
+            CapabilityTier::CapLabBench,
+            CapabilityTier::CapGeneralUse,
+            CapabilityTier::CapAugmentedCitizen,
+            CapabilityTier::CapSovereignKernel,
+        ];
+        for tier in tiers.iter() {
+            for hr in [55.0f32, 70.0, 90.0, 110.0, 125.0] {
+                for hrv in [10.0f32, 40.0, 70.0] {
+                    for roh in [0.0f32, 0.05, 0.10, 0.15, 0.20, 0.25, 0.30] {
+                        for arousal in [0.0f32, 0.3, 0.6, 0.9] {
+                            let snapshot = BiophysicalEnvelopeSnapshot {
+                                heart_rate: hr,
+                                heart_rate_variability: hrv,
+                                eeg_alpha: 0.5,
+                                eeg_beta: 0.5,
+                                eeg_gamma: 0.5,
+                                eeg_alpha_cve: 0.5,
+                                eda: arousal,
+                                motion_accel: arousal,
+                                roh,
+                                capability_tier: tier.clone(),
+                                evolve_index: 500,
+                                session_epoch: 1800,
+                                hydration: None,
+                            };
+                            assert_parity(&TreeOfLifeView::from_snapshot(&snapshot));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_load_time_validation() {
+        let unknown = "SECTION,NATURE-TOKENS-SNAPSHOT\nROW,TOKEN,X,0.5\nROW,COND,MOOD,>,0.1\n";
+        assert_eq!(
+            NatureTokenRuleSet::from_aln(unknown).unwrap_err(),
+            RuleLoadError::UnknownAxis { line: 3, axis: "MOOD".to_string() }
+        );
+
+        let contradictory = "SECTION,NATURE-TOKENS-SNAPSHOT\nROW,TOKEN,X,0.5\nROW,COND,DECAY,>,0.6\nROW,COND,DECAY,<,0.3\n";
+        assert!(matches!(
+            NatureTokenRuleSet::from_aln(contradictory),
+            Err(RuleLoadError::Unsatisfiable { line: 2, .. })
+        ));
+
+        let duplicate = "SECTION,NATURE-TOKENS-SNAPSHOT\nROW,TOKEN,A,0.5\nROW,COND,FEAR,>,0.5\nROW,TOKEN,B,0.6\nROW,COND,FEAR,>,0.5\n";
+        assert!(matches!(
+            NatureTokenRuleSet::from_aln(duplicate),
+            Err(RuleLoadError::DuplicateConditions { line: 4, .. })
+        ));
+
+        let overlapping = "SECTION,NATURE-TOKENS-SNAPSHOT\nROW,TOKEN,A,0.5\nROW,COND,FEAR,>,0.5\nROW,TOKEN,B,0.6\nROW,COND,FEAR,>,0.7\n";
+        assert_eq!(
+            NatureTokenRuleSet::from_aln(overlapping).unwrap_err(),
+            RuleLoadError::OverlappingRules { line: 4, label: "B".to_string(), overlaps: "A".to_string() }
+        );
+
+        // Touching at an open bound is not an overlap.
+        let adjacent = "SECTION,NATURE-TOKENS-SNAPSHOT\nROW,TOKEN,A,0.5\nROW,COND,FEAR,>=,0.5\nROW,TOKEN,B,0.6\nROW,COND,FEAR,<,0.5\n";
+        assert!(NatureTokenRuleSet::from_aln(adjacent).is_ok());
+        let closed = adjacent.replace("FEAR,<,", "FEAR,<=,");
+        assert!(matches!(
+            NatureTokenRuleSet::from_aln(&closed),
+            Err(RuleLoadError::OverlappingRules { .. })
+        ));
+
+        let unknown_pair = format!("{}ROW,OVERLAPS,A,C\n", overlapping);
+        assert!(matches!(
+            NatureTokenRuleSet::from_aln(&unknown_pair),
+            Err(RuleLoadError::UnknownToken { label, .. }) if label == "C"
+        ));
+
+        // A declared overlap loads, and both tokens fire where they meet.
+        let declared = format!("{}ROW,OVERLAPS,B,A\n", overlapping);
+        let set = NatureTokenRuleSet::from_aln(&declared).unwrap();
+        let mut tree = TreeOfLifeView::from_snapshot(&BiophysicalEnvelopeSnapshot {
+            heart_rate: 70.0,
+            heart_rate_variability: 40.0,
+            eeg_alpha: 0.5,
+            eeg_beta: 0.5,
+            eeg_gamma: 0.5,
+            eeg_alpha_cve: 0.5,
+            eda: 0.3,
+            motion_accel: 0.3,
+            roh: 0.1,
+            capability_tier: CapabilityTier::CapGeneralUse,
+            evolve_index: 500,
+            session_epoch: 1800,
+            hydration: None,
+        });
+        tree.fear = 0.8;
+        let labels: Vec<String> = set.evaluate(&tree).into_iter().map(|t| t.label).collect();
+        assert_eq!(labels, vec!["A".to_string(), "B".to_string()]);
+    }
+
+    #[test]
+    fn test_windowed_thresholds_must_agree() {
+        let snapshot = "SECTION,NATURE-TOKENS-SNAPSHOT\nROW,TOKEN,CALM,0.9\nROW,COND,OXYGEN,>,0.70\nROW,COND,DECAY,<,0.30\n";
+        let windowed = |oxygen: &str, decay: &str| {
+            format!(
+                "SECTION,NATURE-TOKENS\nROW,ID,CALM\nROW,THRESH,OXYGEN_MIN,{}\nROW,THRESH,DECAY_MAX,{}\n\
+                 ROW,THRESH,DECAY_DROP_MIN,0.10\nROW,WHEN,\n  AVG_WINDOW(TREE.OXYGEN) >= OXYGEN_MIN\n{}",
+                oxygen, decay, snapshot
+            )
+        };
+        assert!(NatureTokenRuleSet::from_aln(&windowed("0.70", "0.30")).is_ok());
+        assert_eq!(
+            NatureTokenRuleSet::from_aln(&windowed("0.60", "0.30")).unwrap_err(),
+            RuleLoadError::ThresholdMismatch {
+                line: 3,
+                label: "CALM".to_string(),
+                axis: "oxygen",
+                windowed: 0.60,
+                snapshot: 0.70,
+            }
+        );
+        assert!(matches!(
+            NatureTokenRuleSet::from_aln(&windowed("0.70", "0.40")),
+            Err(RuleLoadError::ThresholdMismatch { line: 4, axis: "decay", .. })
+        ));
+    }
+
+    #[test]
+    fn test_hash_only_starts_a_comment_after_whitespace() {
+        let text = "# header\nSECTION,NATURE-TOKENS-SNAPSHOT\nROW,TOKEN,A,0.5   # trailing note\n\
+                    ROW,COND,FEAR,>,0.5\nROW,EVIDENCE,see ticket#12\n";
+        let set = NatureTokenRuleSet::from_aln(text).unwrap();
+        assert_eq!(set.rules[0].confidence, 0.5);
+        assert_eq!(set.rules[0].evidence, vec!["see ticket#12".to_string()]);
+    }
+}
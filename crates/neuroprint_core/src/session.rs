@@ -0,0 +1,378 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::{BiophysicalEnvelopeSnapshot, NatureToken, NeuroprintView, TreeOfLifeView};
+
+// ============ SESSION CONFIG ============
+// Defaults mirror policy/nature-tokens.aln (WINDOW_EPOCHS, RECOVERY thresholds),
+// expressed in snapshots rather than seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeuroprintSessionConfig {
+    pub recent_window: usize,          // WINDOW_EPOCHS_RECENT
+    pub past_window: usize,            // WINDOW_EPOCHS_PAST
+    pub overload_fraction_min: f32,    // fraction of recent window that must be OVERLOADED
+    pub overload_clear_fraction: f32,  // below this the sustained episode ends (hysteresis)
+    pub decay_drop_min: f32,
+    pub lifeforce_rise_min: f32,
+    pub fear_drop_min: f32,
+    pub pain_drop_min: f32,
+}
+
+impl Default for NeuroprintSessionConfig {
+    fn default() -> Self {
+        Self {
+            recent_window: 300,
+            past_window: 300,
+            overload_fraction_min: 0.80,
+            overload_clear_fraction: 0.50,
+            decay_drop_min: 0.10,
+            lifeforce_rise_min: 0.10,
+            fear_drop_min: 0.10,
+            pain_drop_min: 0.10,
+        }
+    }
+}
+
+// ============ WINDOW STATISTICS ============
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+pub struct AxisStats {
+    pub mean: f32,
+    pub std_dev: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WindowStats {
+    pub samples: usize,
+    pub decay: AxisStats,
+    pub lifeforce: AxisStats,
+    pub fear: AxisStats,
+    pub pain: AxisStats,
+    pub power: AxisStats,
+    pub overload_fraction: f32,
+}
+
+fn axis_stats<'a, I: Iterator<Item = &'a SessionSample>>(it: I, f: fn(&TreeOfLifeView) -> f32) -> AxisStats {
+    let xs: Vec<f32> = it.map(|s| f(&s.tree)).collect();
+    if xs.is_empty() {
+        return AxisStats::default();
+    }
+    let n = xs.len() as f32;
+    let mean = xs.iter().sum::<f32>() / n;
+    let var = xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / n;
+    AxisStats { mean, std_dev: var.sqrt() }
+}
+
+fn window_stats(samples: &[&SessionSample]) -> WindowStats {
+    if samples.is_empty() {
+        return WindowStats::default();
+    }
+    let overloaded = samples.iter().filter(|s| s.overloaded).count();
+    WindowStats {
+        samples: samples.len(),
+        decay: axis_stats(samples.iter().copied(), |t| t.decay),
+        lifeforce: axis_stats(samples.iter().copied(), |t| t.lifeforce),
+        fear: axis_stats(samples.iter().copied(), |t| t.fear),
+        pain: axis_stats(samples.iter().copied(), |t| t.pain),
+        power: axis_stats(samples.iter().copied(), |t| t.power),
+        overload_fraction: overloaded as f32 / samples.len() as f32,
+    }
+}
+
+// ============ TREND TOKENS ============
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendToken {
+    pub token: NatureToken,
+    pub onset_epoch: u64,
+    pub offset_epoch: Option<u64>, // None while still active
+}
+
+#[derive(Debug, Clone)]
+struct SessionSample {
+    session_epoch: u64,
+    tree: TreeOfLifeView,
+    overloaded: bool,
+}
+
+// ============ SESSION ============
+// Stateful, advisory-only: keeps sliding windows of TREE views and derives
+// trend tokens. Never actuates; the only output is data.
+#[derive(Debug, Clone)]
+pub struct NeuroprintSession {
+    pub subject_id: String,
+    pub config: NeuroprintSessionConfig,
+    history: VecDeque<SessionSample>,
+    active_overload: Option<TrendToken>,
+    active_recovery: Option<TrendToken>,
+    closed: Vec<TrendToken>,
+    last_epoch: u64,
+}
+
+impl NeuroprintSession {
+    pub fn new(subject_id: &str, config: NeuroprintSessionConfig) -> Self {
+        Self {
+            subject_id: subject_id.to_string(),
+            history: VecDeque::with_capacity(config.recent_window + config.past_window),
+            config,
+            active_overload: None,
+            active_recovery: None,
+            closed: Vec::new(),
+            last_epoch: 0,
+        }
+    }
+
+    /// Ingest one snapshot; returns the trend tokens active after this step.
+    pub fn ingest(&mut self, snapshot: &BiophysicalEnvelopeSnapshot) -> Vec<TrendToken> {
+        let tree = TreeOfLifeView::from_snapshot(snapshot);
+        let overloaded = NeuroprintView::infer_nature_tokens(&tree)
+            .iter()
+            .any(|t| t.label == "OVERLOADED");
+        self.last_epoch = snapshot.session_epoch;
+        self.history.push_back(SessionSample { session_epoch: snapshot.session_epoch, tree, overloaded });
+        while self.history.len() > self.config.recent_window + self.config.past_window {
+            self.history.pop_front();
+        }
+
+        let (past, recent) = self.split_windows();
+        let recent_stats = window_stats(&recent);
+        let past_stats = window_stats(&past);
+        // Onset = first overloaded sample of the current run in the recent window.
+        let run_onset = recent
+            .iter()
+            .rev()
+            .take_while(|s| s.overloaded)
+            .last()
+            .map(|s| s.session_epoch)
+            .unwrap_or(self.last_epoch);
+
+        self.update_overload(&recent_stats, run_onset);
+        self.update_recovery(&past_stats, &recent_stats);
+
+        self.active_tokens()
+    }
+
+    fn split_windows(&self) -> (Vec<&SessionSample>, Vec<&SessionSample>) {
+        let n = self.history.len();
+        let recent_len = n.min(self.config.recent_window);
+        let split = n - recent_len;
+        let past = self.history.iter().take(split).collect();
+        let recent = self.history.iter().skip(split).collect();
+        (past, recent)
+    }
+
+    /// Fraction of the configured window that is actually filled.
+    fn coverage(samples: usize, window: usize) -> f32 {
+        if window == 0 {
+            return 0.0;
+        }
+        (samples as f32 / window as f32).min(1.0)
+    }
+
+    fn update_overload(&mut self, recent: &WindowStats, run_onset: u64) {
+        let coverage = Self::coverage(recent.samples, self.config.recent_window);
+        match &mut self.active_overload {
+            None if coverage >= 1.0 && recent.overload_fraction >= self.config.overload_fraction_min => {
+                let stability = 1.0 - (recent.decay.std_dev + recent.power.std_dev).min(1.0);
+                self.active_overload = Some(TrendToken {
+                    token: NatureToken {
+                        label: "OVERLOADED_SUSTAINED".to_string(),
+                        confidence: (recent.overload_fraction * stability).clamp(0.0, 1.0),
+                        evidence: vec![
+                            format!("overload_fraction {:.2} >= {:.2}", recent.overload_fraction, self.config.overload_fraction_min),
+                            format!("avg decay {:.2} (sd {:.2})", recent.decay.mean, recent.decay.std_dev),
+                            format!("avg power {:.2} (sd {:.2})", recent.power.mean, recent.power.std_dev),
+                        ],
+                    },
+                    onset_epoch: run_onset,
+                    offset_epoch: None,
+                });
+            }
+            Some(active) if recent.overload_fraction < self.config.overload_clear_fraction => {
+                let mut done = active.clone();
+                done.offset_epoch = Some(self.last_epoch);
+                self.closed.push(done);
+                self.active_overload = None;
+            }
+            Some(active) => {
+                let stability = 1.0 - (recent.decay.std_dev + recent.power.std_dev).min(1.0);
+                active.token.confidence = (recent.overload_fraction * stability).clamp(0.0, 1.0);
+            }
+            None => {}
+        }
+    }
+
+    fn update_recovery(&mut self, past: &WindowStats, recent: &WindowStats) {
+        let cfg = &self.config;
+        let previously_overloaded = past.overload_fraction >= cfg.overload_fraction_min
+            || self.closed.iter().any(|t| t.token.label == "OVERLOADED_SUSTAINED");
+        let full = Self::coverage(past.samples, cfg.past_window) >= 1.0
+            && Self::coverage(recent.samples, cfg.recent_window) >= 1.0;
+
+        let decay_drop = past.decay.mean - recent.decay.mean;
+        let lifeforce_rise = recent.lifeforce.mean - past.lifeforce.mean;
+        let fear_drop = past.fear.mean - recent.fear.mean;
+        let pain_drop = past.pain.mean - recent.pain.mean;
+
+        let recovering = full
+            && previously_overloaded
+            && self.active_overload.is_none()
+            && decay_drop >= cfg.decay_drop_min
+            && lifeforce_rise >= cfg.lifeforce_rise_min
+            && fear_drop >= cfg.fear_drop_min
+            && pain_drop >= cfg.pain_drop_min;
+
+        if recovering {
+            // Confidence: weakest margin over its threshold, discounted by recent-window noise.
+            let margin = [
+                decay_drop / cfg.decay_drop_min,
+                lifeforce_rise / cfg.lifeforce_rise_min,
+                fear_drop / cfg.fear_drop_min,
+                pain_drop / cfg.pain_drop_min,
+            ]
+            .iter()
+            .fold(f32::INFINITY, |a, b| a.min(*b));
+            let noise = (recent.decay.std_dev + recent.lifeforce.std_dev) / 2.0;
+            let confidence = ((margin / 2.0).min(1.0) * (1.0 - noise.min(1.0))).clamp(0.0, 1.0);
+            let token = NatureToken {
+                label: "RECOVERY".to_string(),
+                confidence,
+                evidence: vec![
+                    format!("decay drop {:.2} >= {:.2}", decay_drop, cfg.decay_drop_min),
+                    format!("lifeforce rise {:.2} >= {:.2}", lifeforce_rise, cfg.lifeforce_rise_min),
+                    format!("fear drop {:.2} >= {:.2}", fear_drop, cfg.fear_drop_min),
+                    format!("pain drop {:.2} >= {:.2}", pain_drop, cfg.pain_drop_min),
+                    "previously OVERLOADED".to_string(),
+                ],
+            };
+            match &mut self.active_recovery {
+                Some(active) => active.token = token,
+                None => {
+                    self.active_recovery = Some(TrendToken {
+                        token,
+                        onset_epoch: self.last_epoch,
+                        offset_epoch: None,
+                    })
+                }
+            }
+        } else if let Some(mut done) = self.active_recovery.take() {
+            done.offset_epoch = Some(self.last_epoch);
+            self.closed.push(done);
+        }
+    }
+
+    pub fn active_tokens(&self) -> Vec<TrendToken> {
+        self.active_overload
+            .iter()
+            .chain(self.active_recovery.iter())
+            .cloned()
+            .collect()
+    }
+
+    /// Episodes that have ended, in closing order.
+    pub fn closed_tokens(&self) -> &[TrendToken] {
+        &self.closed
+    }
+
+    /// Current recent-window statistics.
+    pub fn recent_stats(&self) -> WindowStats {
+        window_stats(&self.split_windows().1)
+    }
+
+    /// One `.evolve.jsonl` line describing the session's current trend state.
+    pub fn to_evolve_jsonl_line(&self, timestamp_ms: u64) -> String {
+        let record = SessionEvolveRecord {
+            kind: "neuroprint.session.trend".to_string(),
+            timestamp_ms,
+            subject_id: self.subject_id.clone(),
+            session_epoch: self.last_epoch,
+            active: self.active_tokens(),
+            recent_window: self.recent_stats(),
+            advisory_only: true,
+            non_actuating: true,
+        };
+        serde_json::to_string(&record).unwrap_or_default()
+    }
+}
+
+// ============ EXPORT RECORD ============
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEvolveRecord {
+    pub kind: String,
+    pub timestamp_ms: u64,
+    pub subject_id: String,
+    pub session_epoch: u64,
+    pub active: Vec<TrendToken>,
+    pub recent_window: WindowStats,
+    pub advisory_only: bool,  // always true
+    pub non_actuating: bool,  // always true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CapabilityTier;
+
+    fn snap(epoch: u64, roh: f32, hr: f32, eda: f32, motion: f32) -> BiophysicalEnvelopeSnapshot {
+        BiophysicalEnvelopeSnapshot {
+            heart_rate: hr,
+            heart_rate_variability: 40.0,
+            eeg_alpha: 0.5,
+            eeg_beta: 0.5,
+            eeg_gamma: 0.5,
+            eeg_alpha_cve: 0.5,
+            eda,
+            motion_accel: motion,
+            roh,
+            capability_tier: CapabilityTier::CapGeneralUse,
+            evolve_index: epoch,
+            session_epoch: epoch,
+            hydration: None,
+        }
+    }
+
+    fn small_config() -> NeuroprintSessionConfig {
+        NeuroprintSessionConfig { recent_window: 10, past_window: 10, ..Default::default() }
+    }
+
+    #[test]
+    fn test_sustained_overload_onset_and_offset() {
+        let mut session = NeuroprintSession::new("self-bostrom", small_config());
+        for e in 0..10 {
+            session.ingest(&snap(e, 0.28, 118.0, 0.9, 0.9));
+        }
+        let active = session.active_tokens();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].token.label, "OVERLOADED_SUSTAINED");
+        assert_eq!(active[0].onset_epoch, 0);
+
+        for e in 10..20 {
+            session.ingest(&snap(e, 0.05, 70.0, 0.1, 0.1));
+        }
+        let closed = session.closed_tokens();
+        assert!(closed.iter().any(|t| t.token.label == "OVERLOADED_SUSTAINED" && t.offset_epoch.is_some()));
+    }
+
+    #[test]
+    fn test_recovery_requires_history() {
+        let mut session = NeuroprintSession::new("self-bostrom", small_config());
+        // Calm-only history never yields RECOVERY.
+        for e in 0..20 {
+            let active = session.ingest(&snap(e, 0.15, 80.0, 0.3, 0.2));
+            assert!(active.iter().all(|t| t.token.label != "RECOVERY"));
+        }
+
+        let mut session = NeuroprintSession::new("self-bostrom", small_config());
+        for e in 0..10 {
+            session.ingest(&snap(e, 0.28, 118.0, 0.9, 0.9));
+        }
+        let mut saw_recovery = false;
+        for e in 10..20 {
+            let active = session.ingest(&snap(e, 0.05, 70.0, 0.1, 0.1));
+            saw_recovery |= active.iter().any(|t| t.token.label == "RECOVERY");
+        }
+        assert!(saw_recovery);
+
+        let line = session.to_evolve_jsonl_line(0);
+        assert!(line.contains("\"advisory_only\":true"));
+        assert!(!line.contains('\n'));
+    }
+}
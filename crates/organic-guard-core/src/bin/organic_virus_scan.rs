@@ -0,0 +1,307 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Parser;
+use organic_guard_core::{aln_schema_fields, DbDiagnostic, SignatureDatabase};
+use serde::Serialize;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "organic-virus-scan",
+    about = "Scan a crate's ALN schema and module tree against organic virus signature packs (CI gate)."
+)]
+struct Args {
+    /// Signature pack (.json or .aln); repeat to merge several packs.
+    #[arg(long = "signatures", required = true)]
+    signatures: Vec<PathBuf>,
+
+    /// Crate root containing src/ (and optionally Cargo.toml).
+    #[arg(long = "crate")]
+    crate_dir: PathBuf,
+
+    /// ALN schema files to scan for forbidden fields.
+    #[arg(long)]
+    schema: Vec<PathBuf>,
+
+    /// Only apply signatures scoped to this jurisdiction particle.
+    #[arg(long)]
+    jurisdiction: Option<String>,
+
+    /// Write the JSON report here instead of stdout.
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Finding {
+    sig_id: String,
+    kind: &'static str,
+    /// Module path or field name that matched.
+    subject: String,
+    location: String,
+}
+
+#[derive(Serialize)]
+struct ScanReport {
+    crate_name: String,
+    packs: Vec<(String, u32)>,
+    signatures_loaded: usize,
+    modules_scanned: usize,
+    fields_scanned: usize,
+    findings: Vec<Finding>,
+    diagnostics: Vec<DbDiagnostic>,
+    blocked: bool,
+}
+
+/// Exit status: no findings and no conflicts.
+const EXIT_CLEAN: u8 = 0;
+/// Exit status: findings or pack conflicts; the report says which.
+const EXIT_BLOCKED: u8 = 1;
+/// Exit status: a pack, schema or output file could not be read or written.
+const EXIT_ERROR: u8 = 2;
+
+fn main() -> ExitCode {
+    ExitCode::from(run(&Args::parse()))
+}
+
+fn run(args: &Args) -> u8 {
+    // All packs are loaded together so bundles can reference particles in other files.
+    let mut db = SignatureDatabase::new();
+    if let Err(e) = db.load_files(&args.signatures) {
+        eprintln!("organic-virus-scan: {}", e);
+        return EXIT_ERROR;
+    }
+    let jurisdiction = args.jurisdiction.as_deref();
+
+    let crate_name = crate_name(&args.crate_dir);
+    let modules = module_tree(&args.crate_dir, &crate_name);
+    let mut findings = Vec::new();
+    for (module_path, file) in &modules {
+        // Prefixes may target the crate name or any individual module segment.
+        let mut subjects: Vec<&str> = module_path.split("::").collect();
+        subjects.push(module_path);
+        let mut seen = Vec::new();
+        for subject in subjects {
+            for sig in db.module_hits(subject, jurisdiction) {
+                if !seen.contains(&sig.sig_id) {
+                    seen.push(sig.sig_id.clone());
+                    findings.push(Finding {
+                        sig_id: sig.sig_id.clone(),
+                        kind: "forbidden_module",
+                        subject: module_path.clone(),
+                        location: file.display().to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut fields_scanned = 0;
+    for schema in &args.schema {
+        let fields = match fs::read_to_string(schema)
+            .map_err(|e| e.to_string())
+            .and_then(|s| aln_schema_fields(&s).map_err(|e| e.to_string()))
+        {
+            Ok(f) => f,
+            Err(e) => {
+                eprintln!("organic-virus-scan: {}: {}", schema.display(), e);
+                return EXIT_ERROR;
+            }
+        };
+        fields_scanned += fields.len();
+        for field in fields {
+            for sig in db.field_hits(&field, jurisdiction) {
+                findings.push(Finding {
+                    sig_id: sig.sig_id.clone(),
+                    kind: "forbidden_field",
+                    subject: field.clone(),
+                    location: schema.display().to_string(),
+                });
+            }
+        }
+    }
+
+    // Static scan only: impact thresholds need telemetry and are not evaluated here.
+    let blocked = !findings.is_empty() || db.has_conflicts();
+    let report = ScanReport {
+        crate_name,
+        packs: db.packs.clone(),
+        signatures_loaded: db.len(),
+        modules_scanned: modules.len(),
+        fields_scanned,
+        findings,
+        diagnostics: db.diagnostics.clone(),
+        blocked,
+    };
+
+    let json = serde_json::to_string_pretty(&report).unwrap_or_else(|_| "{}".into());
+    match &args.out {
+        Some(path) => {
+            if let Err(e) = fs::write(path, &json) {
+                eprintln!("organic-virus-scan: {}: {}", path.display(), e);
+                return EXIT_ERROR;
+            }
+        }
+        None => println!("{}", json),
+    }
+
+    if blocked {
+        eprintln!(
+            "organic-virus-scan: BLOCKED ({} findings, conflicts: {})",
+            report.findings.len(),
+            db.has_conflicts()
+        );
+        EXIT_BLOCKED
+    } else {
+        EXIT_CLEAN
+    }
+}
+
+/// `[package].name` from Cargo.toml, falling back to the directory name.
+fn crate_name(dir: &Path) -> String {
+    let from_manifest = fs::read_to_string(dir.join("Cargo.toml")).ok().and_then(|raw| {
+        let mut in_package = false;
+        for line in raw.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_package = line == "[package]";
+            } else if in_package && line.starts_with("name") {
+                return line.split('=').nth(1).map(|v| v.trim().trim_matches('"').to_string());
+            }
+        }
+        None
+    });
+    from_manifest
+        .unwrap_or_else(|| dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default())
+        .replace('-', "_")
+}
+
+/// Module paths derived from the file layout under src/.
+fn module_tree(dir: &Path, crate_name: &str) -> Vec<(String, PathBuf)> {
+    let src = dir.join("src");
+    let mut out = Vec::new();
+    let mut stack = vec![src.clone()];
+    while let Some(d) = stack.pop() {
+        let entries = match fs::read_dir(&d) {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            // `file_type` does not follow symlinks.
+            let file_type = match entry.file_type() {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            if file_type.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            if !file_type.is_file() || path.extension().and_then(|e| e.to_str()) != Some("rs") {
+                continue;
+            }
+            let rel = path.strip_prefix(&src).unwrap_or(&path).with_extension("");
+            let mut segments: Vec<String> = rel.iter().map(|s| s.to_string_lossy().into_owned()).collect();
+            if matches!(segments.last().map(String::as_str), Some("lib" | "main" | "mod")) {
+                segments.pop();
+            }
+            let mut module = crate_name.to_string();
+            for s in segments {
+                module.push_str("::");
+                module.push_str(&s);
+            }
+            out.push((module, path));
+        }
+    }
+    out.sort();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scratch crate with one module and one schema, removed on drop.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str, module: &str, schema: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("organic-virus-scan-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(dir.join("src")).unwrap();
+            fs::write(dir.join("Cargo.toml"), "[package]\nname = \"scanned-crate\"\n").unwrap();
+            fs::write(dir.join("src/lib.rs"), "").unwrap();
+            fs::write(dir.join(format!("src/{}.rs", module)), "").unwrap();
+            fs::write(dir.join("schema.aln"), schema).unwrap();
+            Self(dir)
+        }
+
+        fn args(&self, packs: &[PathBuf]) -> Args {
+            let mut argv = vec!["organic-virus-scan".to_string()];
+            for p in packs {
+                argv.push("--signatures".into());
+                argv.push(p.display().to_string());
+            }
+            for (flag, value) in [
+                ("--crate", self.0.display().to_string()),
+                ("--schema", self.0.join("schema.aln").display().to_string()),
+                ("--out", self.0.join("report.json").display().to_string()),
+            ] {
+                argv.push(flag.into());
+                argv.push(value);
+            }
+            Args::try_parse_from(argv).unwrap()
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn shipped_packs() -> Vec<PathBuf> {
+        let particles = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../qpudatashards/particles");
+        vec![
+            particles.join("organic.virus.signature.schema.v1.aln"),
+            particles.join("OrganicVirusSignaturesPhoenix2026v1.aln"),
+        ]
+    }
+
+    #[test]
+    fn clean_crate_exits_zero() {
+        let scratch = Scratch::new("clean", "telemetry", "record Sample {\n  heart_rate f32\n}\n");
+        assert_eq!(run(&scratch.args(&shipped_packs())), EXIT_CLEAN);
+        let report = fs::read_to_string(scratch.0.join("report.json")).unwrap();
+        assert!(report.contains("\"signatures_loaded\": 3"));
+    }
+
+    #[test]
+    fn findings_exit_one() {
+        let scratch = Scratch::new("module", "direct_actuation", "record Sample {\n  heart_rate f32\n}\n");
+        assert_eq!(run(&scratch.args(&shipped_packs())), EXIT_BLOCKED);
+
+        let scratch = Scratch::new("field", "telemetry", "record Sample {\n  torque f32\n}\n");
+        assert_eq!(run(&scratch.args(&shipped_packs())), EXIT_BLOCKED);
+        let report = fs::read_to_string(scratch.0.join("report.json")).unwrap();
+        assert!(report.contains("PHX-NEURORIGHTS-LEAK-2026-01"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_directories_are_not_followed() {
+        let scratch = Scratch::new("symlink", "telemetry", "record Sample {\n  heart_rate f32\n}\n");
+        let outside = Scratch::new("symlink-target", "direct_actuation", "");
+        std::os::unix::fs::symlink(outside.0.join("src"), scratch.0.join("src/linked")).unwrap();
+        assert!(module_tree(&scratch.0, "scanned_crate").iter().all(|(m, _)| !m.contains("linked")));
+        assert_eq!(run(&scratch.args(&shipped_packs())), EXIT_CLEAN);
+    }
+
+    #[test]
+    fn unreadable_or_unresolved_packs_exit_two() {
+        let scratch = Scratch::new("errors", "telemetry", "");
+        assert_eq!(run(&scratch.args(&[scratch.0.join("missing.aln")])), EXIT_ERROR);
+        // The bundle alone cannot resolve the signature defined in the schema file.
+        assert_eq!(run(&scratch.args(&shipped_packs()[1..])), EXIT_ERROR);
+    }
+}
@@ -0,0 +1,273 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::virus::{evaluate_against_signatures, ImpactProfile, VirusCheckResult, VirusSignature};
+
+/// One normalized [0.0, 1.0] telemetry sample recorded while a module runs
+/// in a sandbox or replay. Higher is worse on every channel.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TelemetrySample {
+    pub t_ms: u64,
+    /// Modeled overuse / injury risk.
+    pub risk: f32,
+    /// Neuromotor duty-cycle fraction.
+    pub duty_cycle: f32,
+    /// Normalized fatigue index.
+    pub fatigue: f32,
+    /// Normalized device-hours / energy draw (inverse of EcoImpactScore).
+    pub eco_load: f32,
+}
+
+/// A recorded run: baseline ("before") or with the candidate module ("after").
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct TelemetryTrace {
+    pub label: String,
+    pub samples: Vec<TelemetrySample>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EstimatorConfig {
+    /// Minimum samples per trace.
+    pub min_samples: usize,
+    /// Two-sided normal quantile for the bounds (1.96 ≈ 95%).
+    pub z: f32,
+}
+
+impl Default for EstimatorConfig {
+    fn default() -> Self {
+        EstimatorConfig { min_samples: 8, z: 1.96 }
+    }
+}
+
+/// Estimated worsening with confidence bounds. All three profiles are
+/// clamped to [0.0, 1.0]; improvements estimate as 0.0.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ImpactEstimate {
+    pub point: ImpactProfile,
+    pub lower: ImpactProfile,
+    pub upper: ImpactProfile,
+    pub z: f32,
+    pub samples_before: usize,
+    pub samples_after: usize,
+}
+
+impl ImpactEstimate {
+    /// Dimensions where the caller's claim is below the estimate's lower bound.
+    pub fn under_reported(&self, claimed: &ImpactProfile) -> Vec<&'static str> {
+        let mut out = Vec::new();
+        let dims = [
+            ("delta_risk", claimed.delta_risk, self.lower.delta_risk),
+            ("delta_duty_cycle", claimed.delta_duty_cycle, self.lower.delta_duty_cycle),
+            ("delta_fatigue", claimed.delta_fatigue, self.lower.delta_fatigue),
+            ("delta_eco", claimed.delta_eco, self.lower.delta_eco),
+        ];
+        for (name, claim, lower) in dims {
+            if claim < lower {
+                out.push(name);
+            }
+        }
+        out
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EstimationError {
+    TooFewSamples { trace: String, have: usize, need: usize },
+    /// A sample channel outside [0.0, 1.0] or not finite.
+    OutOfRange { trace: String, t_ms: u64, channel: &'static str, value: f32 },
+}
+
+impl fmt::Display for EstimationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EstimationError::TooFewSamples { trace, have, need } => {
+                write!(f, "trace `{}` has {} samples, need {}", trace, have, need)
+            }
+            EstimationError::OutOfRange { trace, t_ms, channel, value } => {
+                write!(f, "trace `{}` at t={}ms: {}={} outside [0,1]", trace, t_ms, channel, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EstimationError {}
+
+fn channels(s: &TelemetrySample) -> [(&'static str, f32); 4] {
+    [
+        ("risk", s.risk),
+        ("duty_cycle", s.duty_cycle),
+        ("fatigue", s.fatigue),
+        ("eco_load", s.eco_load),
+    ]
+}
+
+fn validate(trace: &TelemetryTrace, need: usize) -> Result<(), EstimationError> {
+    if trace.samples.len() < need {
+        return Err(EstimationError::TooFewSamples {
+            trace: trace.label.clone(),
+            have: trace.samples.len(),
+            need,
+        });
+    }
+    for s in &trace.samples {
+        for (channel, value) in channels(s) {
+            if !value.is_finite() || !(0.0..=1.0).contains(&value) {
+                return Err(EstimationError::OutOfRange {
+                    trace: trace.label.clone(),
+                    t_ms: s.t_ms,
+                    channel,
+                    value,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Mean and sample variance of channel `k` over a trace.
+fn moments(trace: &TelemetryTrace, k: usize) -> (f64, f64) {
+    let n = trace.samples.len() as f64;
+    let xs = trace.samples.iter().map(|s| channels(s)[k].1 as f64);
+    let mean = xs.clone().sum::<f64>() / n;
+    let var = xs.map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+    (mean, var)
+}
+
+/// Derive an `ImpactProfile` from before/after traces instead of trusting
+/// caller-supplied deltas: difference of means per channel, with Welch
+/// standard errors for the bounds.
+pub fn estimate_impact(
+    before: &TelemetryTrace,
+    after: &TelemetryTrace,
+    config: &EstimatorConfig,
+) -> Result<ImpactEstimate, EstimationError> {
+    let need = config.min_samples.max(2);
+    validate(before, need)?;
+    validate(after, need)?;
+
+    let mut point = [0.0f32; 4];
+    let mut lower = [0.0f32; 4];
+    let mut upper = [0.0f32; 4];
+    for k in 0..4 {
+        let (mb, vb) = moments(before, k);
+        let (ma, va) = moments(after, k);
+        let delta = ma - mb;
+        let se = (vb / before.samples.len() as f64 + va / after.samples.len() as f64).sqrt();
+        let half = config.z as f64 * se;
+        point[k] = delta.clamp(0.0, 1.0) as f32;
+        lower[k] = (delta - half).clamp(0.0, 1.0) as f32;
+        upper[k] = (delta + half).clamp(0.0, 1.0) as f32;
+    }
+    let profile = |d: [f32; 4]| ImpactProfile {
+        delta_risk: d[0],
+        delta_duty_cycle: d[1],
+        delta_fatigue: d[2],
+        delta_eco: d[3],
+    };
+
+    Ok(ImpactEstimate {
+        point: profile(point),
+        lower: profile(lower),
+        upper: profile(upper),
+        z: config.z,
+        samples_before: before.samples.len(),
+        samples_after: after.samples.len(),
+    })
+}
+
+/// Which impact values signature thresholds are checked against.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ImpactMode {
+    /// Caller-supplied deltas, as `evaluate_against_signatures` does today.
+    Claimed,
+    /// Per dimension, the larger of the claim and the estimate's upper bound,
+    /// so under-reporting cannot slip under a threshold.
+    EstimatedUpperBound,
+}
+
+/// The impact profile a given mode evaluates.
+pub fn effective_impact(claimed: &ImpactProfile, estimate: &ImpactEstimate, mode: ImpactMode) -> ImpactProfile {
+    match mode {
+        ImpactMode::Claimed => claimed.clone(),
+        ImpactMode::EstimatedUpperBound => ImpactProfile {
+            delta_risk: claimed.delta_risk.max(estimate.upper.delta_risk),
+            delta_duty_cycle: claimed.delta_duty_cycle.max(estimate.upper.delta_duty_cycle),
+            delta_fatigue: claimed.delta_fatigue.max(estimate.upper.delta_fatigue),
+            delta_eco: claimed.delta_eco.max(estimate.upper.delta_eco),
+        },
+    }
+}
+
+/// `evaluate_against_signatures` with the impact chosen by `mode`.
+pub fn evaluate_with_estimate(
+    claimed: &ImpactProfile,
+    estimate: &ImpactEstimate,
+    mode: ImpactMode,
+    module_name: &str,
+    present_fields: &[&str],
+    signatures: &[VirusSignature],
+) -> VirusCheckResult {
+    let impact = effective_impact(claimed, estimate, mode);
+    evaluate_against_signatures(&impact, module_name, present_fields, signatures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::virus::ThreatClass;
+
+    fn trace(label: &str, duty: f32, n: usize) -> TelemetryTrace {
+        TelemetryTrace {
+            label: label.into(),
+            samples: (0..n)
+                .map(|i| TelemetrySample {
+                    t_ms: i as u64 * 1000,
+                    risk: 0.1,
+                    duty_cycle: duty + if i % 2 == 0 { 0.02 } else { -0.02 },
+                    fatigue: 0.2,
+                    eco_load: 0.3,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_upper_bound_mode_catches_under_reporting() {
+        let est = estimate_impact(&trace("before", 0.30, 20), &trace("after", 0.45, 20), &EstimatorConfig::default())
+            .unwrap();
+        assert!(est.lower.delta_duty_cycle > 0.1 && est.upper.delta_duty_cycle < 0.2);
+        assert!(est.lower.delta_risk == 0.0 && est.upper.delta_risk == 0.0);
+
+        let claimed = ImpactProfile { delta_risk: 0.0, delta_duty_cycle: 0.01, delta_fatigue: 0.0, delta_eco: 0.0 };
+        assert_eq!(est.under_reported(&claimed), vec!["delta_duty_cycle"]);
+
+        let sig = VirusSignature {
+            sig_id: "DUTY-10".into(),
+            description: String::new(),
+            class: ThreatClass::OverloadEnvelope,
+            forbidden_fields: vec![],
+            forbidden_modules: vec![],
+            impact_thresholds: ImpactProfile { delta_risk: 0.0, delta_duty_cycle: 0.10, delta_fatigue: 0.0, delta_eco: 0.0 },
+            jurisdiction_particle: String::new(),
+        };
+        let sigs = [sig];
+        assert!(!evaluate_with_estimate(&claimed, &est, ImpactMode::Claimed, "m", &[], &sigs).blocked);
+        assert!(evaluate_with_estimate(&claimed, &est, ImpactMode::EstimatedUpperBound, "m", &[], &sigs).blocked);
+    }
+
+    #[test]
+    fn test_rejects_short_or_out_of_range_traces() {
+        let cfg = EstimatorConfig::default();
+        assert!(matches!(
+            estimate_impact(&trace("before", 0.3, 3), &trace("after", 0.3, 20), &cfg),
+            Err(EstimationError::TooFewSamples { .. })
+        ));
+        let mut bad = trace("after", 0.3, 20);
+        bad.samples[4].fatigue = 1.5;
+        assert!(matches!(
+            estimate_impact(&trace("before", 0.3, 20), &bad, &cfg),
+            Err(EstimationError::OutOfRange { channel: "fatigue", .. })
+        ));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod virus;
+pub mod signature_db;
+pub mod impact_estimator;
 
 pub use crate::virus::{
     ImpactProfile,
@@ -7,3 +9,25 @@ pub use crate::virus::{
     VirusCheckResult,
     evaluate_against_signatures,
 };
+
+pub use crate::signature_db::{
+    DbDiagnostic,
+    SignatureDatabase,
+    SignatureDbError,
+    SignatureEntry,
+    SignaturePack,
+    aln_schema_fields,
+    parse_aln_pack,
+};
+
+pub use crate::impact_estimator::{
+    EstimationError,
+    EstimatorConfig,
+    ImpactEstimate,
+    ImpactMode,
+    TelemetrySample,
+    TelemetryTrace,
+    effective_impact,
+    estimate_impact,
+    evaluate_with_estimate,
+};
@@ -0,0 +1,836 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::virus::{ImpactProfile, ThreatClass, VirusCheckResult, VirusSignature};
+
+/// A versioned bundle of signatures, as shipped in JSON or ALN particle form.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SignaturePack {
+    pub pack_id: String,
+    pub version: u32,
+    /// Default jurisdiction for signatures that do not name their own.
+    #[serde(default)]
+    pub jurisdiction_particle: String,
+    pub signatures: Vec<VirusSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignatureDbError {
+    Io(String),
+    Json(String),
+    /// ALN parse failure with 1-based line number.
+    Aln { line: usize, message: String },
+    /// Bundle references a signature particle that none of the loaded files define.
+    MissingSignature(String),
+    /// Two loaded files define the same particle name with different content.
+    DuplicateParticle(String),
+    /// Error raised while reading one pack file.
+    InFile { path: String, error: Box<SignatureDbError> },
+}
+
+impl fmt::Display for SignatureDbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureDbError::Io(e) => write!(f, "io: {}", e),
+            SignatureDbError::Json(e) => write!(f, "json: {}", e),
+            SignatureDbError::Aln { line, message } => write!(f, "aln line {}: {}", line, message),
+            SignatureDbError::MissingSignature(id) => write!(f, "bundle references unknown signature {}", id),
+            SignatureDbError::DuplicateParticle(name) => {
+                write!(f, "particle {} is defined twice with different content", name)
+            }
+            SignatureDbError::InFile { path, error } => write!(f, "{}: {}", path, error),
+        }
+    }
+}
+
+impl std::error::Error for SignatureDbError {}
+
+/// Problems found while merging packs. Conflicts should fail CI; shadowed
+/// and superseded entries are informational.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DbDiagnostic {
+    /// Same sig_id and pack version but different content.
+    Conflict { sig_id: String, existing_pack: String, incoming_pack: String },
+    /// Older pack version replaced by a newer one.
+    Superseded { sig_id: String, old_version: u32, new_version: u32 },
+    /// Every match of `sig_id` is already caught by `shadowed_by`.
+    Shadowed { sig_id: String, shadowed_by: String },
+}
+
+impl DbDiagnostic {
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, DbDiagnostic::Conflict { .. })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SignatureEntry {
+    pub signature: VirusSignature,
+    pub pack_id: String,
+    pub pack_version: u32,
+}
+
+/// Character trie over `forbidden_modules` prefixes.
+#[derive(Clone, Debug, Default)]
+struct PrefixTrie {
+    nodes: Vec<TrieNode>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TrieNode {
+    children: BTreeMap<char, usize>,
+    sigs: Vec<usize>,
+}
+
+impl PrefixTrie {
+    fn insert(&mut self, prefix: &str, sig: usize) {
+        if self.nodes.is_empty() {
+            self.nodes.push(TrieNode::default());
+        }
+        let mut at = 0;
+        for c in prefix.chars() {
+            at = match self.nodes[at].children.get(&c) {
+                Some(&next) => next,
+                None => {
+                    self.nodes.push(TrieNode::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[at].children.insert(c, next);
+                    next
+                }
+            };
+        }
+        self.nodes[at].sigs.push(sig);
+    }
+
+    /// Signatures with any prefix of `name` registered.
+    fn matches(&self, name: &str, out: &mut Vec<usize>) {
+        let mut at = match self.nodes.first() {
+            Some(_) => 0,
+            None => return,
+        };
+        out.extend(&self.nodes[at].sigs);
+        for c in name.chars() {
+            match self.nodes[at].children.get(&c) {
+                Some(&next) => {
+                    at = next;
+                    out.extend(&self.nodes[at].sigs);
+                }
+                None => return,
+            }
+        }
+    }
+}
+
+/// Merged, indexed signature set used by CI and pre-OTA checks in place of
+/// linear scans over `&[VirusSignature]`.
+#[derive(Clone, Debug, Default)]
+pub struct SignatureDatabase {
+    entries: Vec<SignatureEntry>,
+    by_id: HashMap<String, usize>,
+    module_trie: PrefixTrie,
+    field_index: HashMap<String, Vec<usize>>,
+    pub packs: Vec<(String, u32)>,
+    pub diagnostics: Vec<DbDiagnostic>,
+}
+
+impl SignatureDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a pack from `.json` or `.aln` (by extension) and merge it.
+    pub fn load_file(&mut self, path: &Path) -> Result<(), SignatureDbError> {
+        self.load_files(&[path])
+    }
+
+    /// Load several `.json` / `.aln` packs and merge them. ALN bundle
+    /// references are resolved only after every file has been read, so a
+    /// bundle may list signature particles defined in another file.
+    pub fn load_files<P: AsRef<Path>>(&mut self, paths: &[P]) -> Result<(), SignatureDbError> {
+        let mut json_packs = Vec::new();
+        let mut aln_files = Vec::new();
+        for path in paths {
+            let path = path.as_ref();
+            let in_file = |error: SignatureDbError| SignatureDbError::InFile {
+                path: path.display().to_string(),
+                error: Box::new(error),
+            };
+            let raw = std::fs::read_to_string(path).map_err(|e| in_file(SignatureDbError::Io(e.to_string())))?;
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("json") => json_packs.push(
+                    serde_json::from_str::<SignaturePack>(&raw)
+                        .map_err(|e| in_file(SignatureDbError::Json(e.to_string())))?,
+                ),
+                _ => aln_files.push(parse_aln_file(&raw).map_err(in_file)?),
+            }
+        }
+        let aln_packs = resolve_aln_files(aln_files)?;
+        for pack in json_packs.into_iter().chain(aln_packs) {
+            self.merge(pack);
+        }
+        Ok(())
+    }
+
+    /// In-memory equivalent of `load_files` for ALN sources.
+    pub fn merge_aln_sources(&mut self, sources: &[&str]) -> Result<(), SignatureDbError> {
+        let files = sources
+            .iter()
+            .map(|src| parse_aln_file(src))
+            .collect::<Result<Vec<_>, _>>()?;
+        for pack in resolve_aln_files(files)? {
+            self.merge(pack);
+        }
+        Ok(())
+    }
+
+    /// Merge a pack. Per sig_id the highest pack version wins; equal versions
+    /// with different content are recorded as conflicts and the existing entry kept.
+    pub fn merge(&mut self, pack: SignaturePack) {
+        self.packs.push((pack.pack_id.clone(), pack.version));
+        for mut sig in pack.signatures {
+            if sig.jurisdiction_particle.is_empty() {
+                sig.jurisdiction_particle = pack.jurisdiction_particle.clone();
+            }
+            let incoming = SignatureEntry {
+                signature: sig,
+                pack_id: pack.pack_id.clone(),
+                pack_version: pack.version,
+            };
+            match self.by_id.get(&incoming.signature.sig_id).copied() {
+                None => {
+                    self.by_id.insert(incoming.signature.sig_id.clone(), self.entries.len());
+                    self.entries.push(incoming);
+                }
+                Some(i) => {
+                    let existing = &self.entries[i];
+                    if incoming.pack_version > existing.pack_version {
+                        self.diagnostics.push(DbDiagnostic::Superseded {
+                            sig_id: incoming.signature.sig_id.clone(),
+                            old_version: existing.pack_version,
+                            new_version: incoming.pack_version,
+                        });
+                        self.entries[i] = incoming;
+                    } else if incoming.pack_version == existing.pack_version
+                        && incoming.signature != existing.signature
+                    {
+                        self.diagnostics.push(DbDiagnostic::Conflict {
+                            sig_id: incoming.signature.sig_id.clone(),
+                            existing_pack: existing.pack_id.clone(),
+                            incoming_pack: incoming.pack_id.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        self.reindex();
+    }
+
+    fn reindex(&mut self) {
+        self.module_trie = PrefixTrie::default();
+        self.field_index.clear();
+        for (i, e) in self.entries.iter().enumerate() {
+            for prefix in &e.signature.forbidden_modules {
+                self.module_trie.insert(prefix, i);
+            }
+            for field in &e.signature.forbidden_fields {
+                self.field_index.entry(field.clone()).or_default().push(i);
+            }
+        }
+        self.diagnostics.retain(|d| !matches!(d, DbDiagnostic::Shadowed { .. }));
+        let shadowed = self.find_shadowed();
+        self.diagnostics.extend(shadowed);
+    }
+
+    fn find_shadowed(&self) -> Vec<DbDiagnostic> {
+        let mut out = Vec::new();
+        for (i, a) in self.entries.iter().enumerate() {
+            for (j, b) in self.entries.iter().enumerate() {
+                if i == j || !shadows(&b.signature, &a.signature) {
+                    continue;
+                }
+                // Identical signatures shadow each other; report only one direction.
+                if shadows(&a.signature, &b.signature) && a.signature.sig_id < b.signature.sig_id {
+                    continue;
+                }
+                out.push(DbDiagnostic::Shadowed {
+                    sig_id: a.signature.sig_id.clone(),
+                    shadowed_by: b.signature.sig_id.clone(),
+                });
+                break;
+            }
+        }
+        out
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, sig_id: &str) -> Option<&SignatureEntry> {
+        self.by_id.get(sig_id).map(|&i| &self.entries[i])
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        self.diagnostics.iter().any(DbDiagnostic::is_conflict)
+    }
+
+    fn in_scope(&self, i: usize, jurisdiction: Option<&str>) -> bool {
+        let particle = &self.entries[i].signature.jurisdiction_particle;
+        match jurisdiction {
+            None => true,
+            Some(j) => particle.is_empty() || particle == "*" || particle == j,
+        }
+    }
+
+    /// Signatures whose forbidden_modules match `module_name`.
+    pub fn module_hits(&self, module_name: &str, jurisdiction: Option<&str>) -> Vec<&VirusSignature> {
+        let mut idx = Vec::new();
+        self.module_trie.matches(module_name, &mut idx);
+        self.collect(idx, jurisdiction)
+    }
+
+    /// Signatures whose forbidden_fields include `field`.
+    pub fn field_hits(&self, field: &str, jurisdiction: Option<&str>) -> Vec<&VirusSignature> {
+        let idx = self.field_index.get(field).cloned().unwrap_or_default();
+        self.collect(idx, jurisdiction)
+    }
+
+    fn collect(&self, mut idx: Vec<usize>, jurisdiction: Option<&str>) -> Vec<&VirusSignature> {
+        idx.sort_unstable();
+        idx.dedup();
+        idx.into_iter()
+            .filter(|&i| self.in_scope(i, jurisdiction))
+            .map(|i| &self.entries[i].signature)
+            .collect()
+    }
+
+    /// Indexed equivalent of `evaluate_against_signatures`, limited to
+    /// signatures in scope for `jurisdiction` (`None` = all).
+    pub fn evaluate(
+        &self,
+        impact: &ImpactProfile,
+        module_name: &str,
+        present_fields: &[&str],
+        jurisdiction: Option<&str>,
+    ) -> VirusCheckResult {
+        let mut hits = vec![false; self.entries.len()];
+        let mut idx = Vec::new();
+        self.module_trie.matches(module_name, &mut idx);
+        for f in present_fields {
+            if let Some(v) = self.field_index.get(*f) {
+                idx.extend(v);
+            }
+        }
+        for i in idx {
+            hits[i] = true;
+        }
+        for (i, e) in self.entries.iter().enumerate() {
+            if !hits[i] && crate::virus::impact_exceeds_thresholds(impact, &e.signature.impact_thresholds) {
+                hits[i] = true;
+            }
+        }
+
+        let mut violated: Vec<String> = hits
+            .iter()
+            .enumerate()
+            .filter(|(i, hit)| **hit && self.in_scope(*i, jurisdiction))
+            .map(|(i, _)| self.entries[i].signature.sig_id.clone())
+            .collect();
+        violated.sort();
+
+        VirusCheckResult {
+            blocked: !violated.is_empty(),
+            violated_signatures: violated,
+        }
+    }
+}
+
+/// `b` shadows `a` when it has the same class and jurisdiction and would
+/// fire on everything `a` fires on.
+fn shadows(b: &VirusSignature, a: &VirusSignature) -> bool {
+    if a.class != b.class || a.jurisdiction_particle != b.jurisdiction_particle {
+        return false;
+    }
+    let modules = a
+        .forbidden_modules
+        .iter()
+        .all(|m| b.forbidden_modules.iter().any(|p| m.starts_with(p.as_str())));
+    let fields = a.forbidden_fields.iter().all(|f| b.forbidden_fields.contains(f));
+    let dim = |ta: f32, tb: f32| ta <= 0.0 || (tb > 0.0 && tb <= ta);
+    let t = (&a.impact_thresholds, &b.impact_thresholds);
+    modules
+        && fields
+        && dim(t.0.delta_risk, t.1.delta_risk)
+        && dim(t.0.delta_duty_cycle, t.1.delta_duty_cycle)
+        && dim(t.0.delta_fatigue, t.1.delta_fatigue)
+        && dim(t.0.delta_eco, t.1.delta_eco)
+}
+
+// ---------------------------------------------------------------------------
+// ALN particle form (see qpudatashards/particles/organic.virus.signature.schema.v1.aln)
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+enum AlnValue {
+    Str(String),
+    List(Vec<String>),
+    Num(f32),
+}
+
+struct AlnBlock {
+    kind: String,
+    name: String,
+    line: usize,
+    fields: BTreeMap<String, AlnValue>,
+}
+
+fn parse_blocks(src: &str) -> Result<Vec<AlnBlock>, SignatureDbError> {
+    let err = |line: usize, message: String| SignatureDbError::Aln { line, message };
+    let mut blocks = Vec::new();
+    let mut current: Option<AlnBlock> = None;
+    let mut lines = src.lines().enumerate().map(|(i, l)| (i + 1, l));
+
+    while let Some((n, raw)) = lines.next() {
+        let line = strip_comment(raw).trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "}" {
+            match current.take() {
+                Some(b) => blocks.push(b),
+                None => return Err(err(n, "unmatched `}`".into())),
+            }
+            continue;
+        }
+        if let Some(head) = line.strip_suffix('{') {
+            if current.is_some() {
+                return Err(err(n, "nested block".into()));
+            }
+            let mut parts = head.split_whitespace();
+            let kind = parts.next().unwrap_or_default().to_string();
+            let name = parts.next().ok_or_else(|| err(n, "block without a name".into()))?;
+            current = Some(AlnBlock { kind, name: name.to_string(), line: n, fields: BTreeMap::new() });
+            continue;
+        }
+        let block = current.as_mut().ok_or_else(|| err(n, format!("statement outside a block: {}", line)))?;
+        let (key, rest) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| err(n, format!("missing value for `{}`", line)))?;
+        let mut rest = rest.trim().to_string();
+
+        // Lists may span lines until the closing `]`.
+        if rest.starts_with('[') {
+            while !rest.contains(']') {
+                let (_, more) = lines.next().ok_or_else(|| err(n, "unterminated list".into()))?;
+                rest.push(' ');
+                rest.push_str(strip_comment(more).trim());
+            }
+        }
+        block.fields.insert(key.to_string(), parse_value(&rest).map_err(|m| err(n, m))?);
+    }
+    if let Some(b) = current {
+        return Err(err(b.line, format!("block `{}` is not closed", b.name)));
+    }
+    Ok(blocks)
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_str = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_str = !in_str,
+            '#' if !in_str => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(v: &str) -> Result<AlnValue, String> {
+    if let Some(inner) = v.strip_prefix('[') {
+        let inner = inner.trim_end().strip_suffix(']').ok_or("list must end with `]`")?;
+        let items = inner
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(unquote)
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok(AlnValue::List(items));
+    }
+    if let Ok(n) = v.parse::<f32>() {
+        return Ok(AlnValue::Num(n));
+    }
+    unquote(v).map(AlnValue::Str)
+}
+
+/// Quoted string, or a bare identifier such as a schema or type name.
+fn unquote(s: &str) -> Result<String, String> {
+    if let Some(inner) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        return Ok(inner.to_string());
+    }
+    if !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+        return Ok(s.to_string());
+    }
+    Err(format!("cannot parse value `{}`", s))
+}
+
+fn parse_threat_class(s: &str) -> Option<ThreatClass> {
+    Some(match s {
+        "NeurorightsBreach" => ThreatClass::NeurorightsBreach,
+        "OverloadEnvelope" => ThreatClass::OverloadEnvelope,
+        "EcoRegression" => ThreatClass::EcoRegression,
+        "IllicitCommerceBridge" => ThreatClass::IllicitCommerceBridge,
+        "SchemaActuationLeak" => ThreatClass::SchemaActuationLeak,
+        _ => return None,
+    })
+}
+
+/// Trailing `.vN` of a particle name, e.g. `...PHX.2026.v1` -> 1.
+fn version_suffix(name: &str) -> Option<u32> {
+    name.rsplit('.').next()?.strip_prefix('v')?.parse().ok()
+}
+
+fn signature_from_block(b: &AlnBlock) -> Result<VirusSignature, SignatureDbError> {
+    let err = |message: String| SignatureDbError::Aln { line: b.line, message };
+    let s = |k: &str| match b.fields.get(k) {
+        Some(AlnValue::Str(v)) => Ok(v.clone()),
+        _ => Err(err(format!("{}: `{}` must be a string", b.name, k))),
+    };
+    let list = |k: &str| match b.fields.get(k) {
+        Some(AlnValue::List(v)) => Ok(v.clone()),
+        None => Ok(Vec::new()),
+        _ => Err(err(format!("{}: `{}` must be a list", b.name, k))),
+    };
+    let num = |k: &str| match b.fields.get(k) {
+        Some(AlnValue::Num(v)) => Ok(*v),
+        None => Ok(0.0),
+        _ => Err(err(format!("{}: `{}` must be a number", b.name, k))),
+    };
+    let class_raw = s("threat_class")?;
+    Ok(VirusSignature {
+        sig_id: s("sig_id")?,
+        description: s("description").unwrap_or_default(),
+        class: parse_threat_class(&class_raw).ok_or_else(|| err(format!("unknown threat_class {}", class_raw)))?,
+        forbidden_fields: list("forbidden_fields")?,
+        forbidden_modules: list("forbidden_modules")?,
+        impact_thresholds: ImpactProfile {
+            delta_risk: num("delta_risk_max")?,
+            delta_duty_cycle: num("delta_duty_cycle_max")?,
+            delta_fatigue: num("delta_fatigue_max")?,
+            delta_eco: num("delta_eco_max")?,
+        },
+        jurisdiction_particle: s("jurisdiction_particle").unwrap_or_default(),
+    })
+}
+
+/// Bundle particle whose signature references are resolved against every loaded file.
+struct AlnBundle {
+    pack_id: String,
+    version: u32,
+    jurisdiction: String,
+    refs: Vec<String>,
+}
+
+/// Signature particles (in file order) and the optional bundle of one ALN file.
+struct AlnFile {
+    particles: Vec<(String, VirusSignature)>,
+    bundle: Option<AlnBundle>,
+}
+
+fn parse_aln_file(src: &str) -> Result<AlnFile, SignatureDbError> {
+    let blocks = parse_blocks(src)?;
+    let mut particles = Vec::new();
+    let mut bundle = None;
+
+    for b in blocks.iter().filter(|b| b.kind == "particle") {
+        if b.fields.contains_key("bundle_id") {
+            bundle = Some(b);
+        } else if matches!(b.fields.get("schema"), Some(AlnValue::Str(s)) if s == "OrganicVirusSignature")
+            || b.fields.contains_key("sig_id")
+        {
+            particles.push((b.name.clone(), signature_from_block(b)?));
+        }
+    }
+
+    let bundle = bundle.map(|b| {
+        let get_str = |k: &str| match b.fields.get(k) {
+            Some(AlnValue::Str(v)) => v.clone(),
+            _ => String::new(),
+        };
+        AlnBundle {
+            pack_id: get_str("bundle_id"),
+            version: match b.fields.get("version") {
+                Some(AlnValue::Num(v)) => *v as u32,
+                _ => version_suffix(&b.name).unwrap_or(0),
+            },
+            jurisdiction: get_str("jurisdiction"),
+            refs: match b.fields.get("signatures") {
+                Some(AlnValue::List(v)) => v.clone(),
+                _ => particles.iter().map(|(name, _)| name.clone()).collect(),
+            },
+        }
+    });
+    Ok(AlnFile { particles, bundle })
+}
+
+/// One pack per bundle, resolved against the particles of every file. Particles
+/// from bundle-less files that no bundle references form one unnamed pack.
+fn resolve_aln_files(files: Vec<AlnFile>) -> Result<Vec<SignaturePack>, SignatureDbError> {
+    let mut pool: BTreeMap<String, VirusSignature> = BTreeMap::new();
+    for (name, sig) in files.iter().flat_map(|f| f.particles.iter()) {
+        match pool.get(name) {
+            Some(existing) if existing != sig => return Err(SignatureDbError::DuplicateParticle(name.clone())),
+            Some(_) => {}
+            None => {
+                pool.insert(name.clone(), sig.clone());
+            }
+        }
+    }
+
+    let mut packs = Vec::new();
+    let mut referenced = Vec::new();
+    for bundle in files.iter().filter_map(|f| f.bundle.as_ref()) {
+        let signatures = bundle
+            .refs
+            .iter()
+            .map(|r| pool.get(r).cloned().ok_or_else(|| SignatureDbError::MissingSignature(r.clone())))
+            .collect::<Result<Vec<_>, _>>()?;
+        referenced.extend(bundle.refs.iter().cloned());
+        packs.push(SignaturePack {
+            pack_id: bundle.pack_id.clone(),
+            version: bundle.version,
+            jurisdiction_particle: bundle.jurisdiction.clone(),
+            signatures,
+        });
+    }
+
+    let mut loose = Vec::new();
+    for (name, sig) in files.iter().filter(|f| f.bundle.is_none()).flat_map(|f| f.particles.iter()) {
+        if !referenced.contains(name) && !loose.iter().any(|(n, _)| n == name) {
+            loose.push((name.clone(), sig.clone()));
+        }
+    }
+    if !loose.is_empty() {
+        packs.push(SignaturePack {
+            pack_id: String::new(),
+            version: 0,
+            jurisdiction_particle: String::new(),
+            signatures: loose.into_iter().map(|(_, sig)| sig).collect(),
+        });
+    }
+    Ok(packs)
+}
+
+/// Parse a single ALN file holding `OrganicVirusSignature` particles and,
+/// optionally, one bundle particle (`bundle_id`, `jurisdiction`, `signatures`,
+/// `version`). Without a bundle every signature particle in the file forms the
+/// pack. Bundle references must resolve within this file; use
+/// `SignatureDatabase::load_files` for bundles that span files.
+pub fn parse_aln_pack(src: &str) -> Result<SignaturePack, SignatureDbError> {
+    let file = parse_aln_file(src)?;
+    if file.bundle.is_none() && file.particles.is_empty() {
+        return Ok(SignaturePack {
+            pack_id: String::new(),
+            version: 0,
+            jurisdiction_particle: String::new(),
+            signatures: Vec::new(),
+        });
+    }
+    Ok(resolve_aln_files(vec![file])?.remove(0))
+}
+
+/// Field names declared by an ALN schema: the first token of each line in
+/// `record` blocks and the keys of `particle` blocks.
+pub fn aln_schema_fields(src: &str) -> Result<Vec<String>, SignatureDbError> {
+    let mut fields = Vec::new();
+    for (n, raw) in src.lines().enumerate() {
+        let line = strip_comment(raw).trim();
+        if line.is_empty() || line == "}" || line.ends_with('{') || line.starts_with('"') || line.starts_with(']') {
+            continue;
+        }
+        match line.split_whitespace().next() {
+            Some(tok) if tok.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => fields.push(tok.to_string()),
+            Some(_) => {}
+            None => return Err(SignatureDbError::Aln { line: n + 1, message: "empty statement".into() }),
+        }
+    }
+    fields.sort();
+    fields.dedup();
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHX: &str = r#"
+particle organic.virus.signature.PHX.NeurorightsLeak.2026.v1 {
+  schema OrganicVirusSignature
+  sig_id               "PHX-NEURORIGHTS-LEAK-2026-01"
+  description          "Blocks direct actuation."
+  threat_class         "NeurorightsBreach"
+  forbidden_fields     ["torque", "current",
+                        "stimulation"]
+  forbidden_modules    ["unsafe_neuro", "direct_actuation"]
+  delta_risk_max       0.0
+  delta_duty_cycle_max 0.0
+  delta_fatigue_max    0.0
+  delta_eco_max        0.0
+  jurisdiction_particle "policy.jurisdiction.us-az-maricopa-phoenix.v1"
+}
+
+particle organic.virus.signature.bundle.PHX.2026.v2 {
+  bundle_id   "OrganicVirusSignaturesPhoenix2026"
+  jurisdiction "policy.jurisdiction.us-az-maricopa-phoenix.v1"
+  signatures  ["organic.virus.signature.PHX.NeurorightsLeak.2026.v1"]
+}
+"#;
+
+    fn narrow(sig_id: &str, modules: &[&str]) -> VirusSignature {
+        VirusSignature {
+            sig_id: sig_id.into(),
+            description: String::new(),
+            class: ThreatClass::NeurorightsBreach,
+            forbidden_fields: vec!["torque".into()],
+            forbidden_modules: modules.iter().map(|m| m.to_string()).collect(),
+            impact_thresholds: ImpactProfile { delta_risk: 0.0, delta_duty_cycle: 0.0, delta_fatigue: 0.0, delta_eco: 0.0 },
+            jurisdiction_particle: "policy.jurisdiction.us-az-maricopa-phoenix.v1".into(),
+        }
+    }
+
+    #[test]
+    fn test_aln_pack_indexes_and_scopes() {
+        let pack = parse_aln_pack(PHX).unwrap();
+        assert_eq!(pack.version, 2);
+        assert_eq!(pack.signatures[0].forbidden_fields.len(), 3);
+
+        let mut db = SignatureDatabase::new();
+        db.merge(pack);
+        let zero = ImpactProfile { delta_risk: 0.0, delta_duty_cycle: 0.0, delta_fatigue: 0.0, delta_eco: 0.0 };
+        let phx = Some("policy.jurisdiction.us-az-maricopa-phoenix.v1");
+
+        assert!(db.evaluate(&zero, "unsafe_neuro_bridge", &[], phx).blocked);
+        assert!(db.evaluate(&zero, "safe_mod", &["current"], phx).blocked);
+        assert!(!db.evaluate(&zero, "safe_mod", &["heart_rate"], phx).blocked);
+        assert!(!db.evaluate(&zero, "unsafe_neuro_bridge", &[], Some("policy.jurisdiction.eu.v1")).blocked);
+    }
+
+    #[test]
+    fn test_merge_reports_conflicts_and_shadowing() {
+        let mut db = SignatureDatabase::new();
+        db.merge(parse_aln_pack(PHX).unwrap());
+        db.merge(SignaturePack {
+            pack_id: "local".into(),
+            version: 2,
+            jurisdiction_particle: String::new(),
+            signatures: vec![
+                narrow("PHX-NEURORIGHTS-LEAK-2026-01", &["other"]),
+                narrow("PHX-NARROW", &["unsafe_neuro_v2"]),
+            ],
+        });
+        assert!(db.has_conflicts());
+        assert!(db.diagnostics.contains(&DbDiagnostic::Shadowed {
+            sig_id: "PHX-NARROW".into(),
+            shadowed_by: "PHX-NEURORIGHTS-LEAK-2026-01".into(),
+        }));
+    }
+
+    const SHIPPED_SCHEMA: &str = include_str!("../../../qpudatashards/particles/organic.virus.signature.schema.v1.aln");
+    const SHIPPED_PACK: &str = include_str!("../../../qpudatashards/particles/OrganicVirusSignaturesPhoenix2026v1.aln");
+
+    #[test]
+    fn test_shipped_pack_resolves_across_files() {
+        let mut db = SignatureDatabase::new();
+        db.merge_aln_sources(&[SHIPPED_SCHEMA, SHIPPED_PACK]).unwrap();
+        assert_eq!(db.packs, vec![("OrganicVirusSignaturesPhoenix2026v1".to_string(), 1)]);
+        assert_eq!(db.len(), 3);
+        assert!(db.diagnostics.is_empty(), "{:?}", db.diagnostics);
+        assert_eq!(db.get("PHX-NEURORIGHTS-LEAK-2026-01").unwrap().pack_version, 1);
+
+        let overload = ImpactProfile { delta_risk: 0.0, delta_duty_cycle: 0.2, delta_fatigue: 0.0, delta_eco: 0.0 };
+        let result = db.evaluate(&overload, "telemetry", &[], None);
+        assert_eq!(result.violated_signatures, vec!["PHX-OVERLOAD-ENVELOPE-2026-01".to_string()]);
+
+        // Order of files does not matter; a bundle on its own does not resolve.
+        let mut reversed = SignatureDatabase::new();
+        reversed.merge_aln_sources(&[SHIPPED_PACK, SHIPPED_SCHEMA]).unwrap();
+        assert_eq!(reversed.len(), 3);
+        assert_eq!(
+            SignatureDatabase::new().merge_aln_sources(&[SHIPPED_PACK]),
+            Err(SignatureDbError::MissingSignature(
+                "organic.virus.signature.PHX.NeurorightsLeak.2026.v1".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_particles_must_agree() {
+        let edited = PHX.replace("Blocks direct actuation.", "Something else.");
+        assert_eq!(
+            SignatureDatabase::new().merge_aln_sources(&[PHX, edited.as_str()]),
+            Err(SignatureDbError::DuplicateParticle("organic.virus.signature.PHX.NeurorightsLeak.2026.v1".into()))
+        );
+    }
+
+    #[test]
+    fn test_json_pack_loads_with_pack_jurisdiction() {
+        let pack = SignaturePack {
+            pack_id: "json-pack".into(),
+            version: 3,
+            jurisdiction_particle: "policy.jurisdiction.eu.v1".into(),
+            signatures: vec![VirusSignature { jurisdiction_particle: String::new(), ..narrow("EU-1", &["eu_bad"]) }],
+        };
+        let path = std::env::temp_dir().join(format!("signature-db-{}.json", std::process::id()));
+        std::fs::write(&path, serde_json::to_string(&pack).unwrap()).unwrap();
+        let mut db = SignatureDatabase::new();
+        let loaded = db.load_file(&path);
+        let _ = std::fs::remove_file(&path);
+        loaded.unwrap();
+
+        assert_eq!(db.packs, vec![("json-pack".to_string(), 3)]);
+        let entry = db.get("EU-1").unwrap();
+        assert_eq!(entry.signature.jurisdiction_particle, "policy.jurisdiction.eu.v1");
+        assert_eq!(db.module_hits("eu_bad_mod", Some("policy.jurisdiction.eu.v1")).len(), 1);
+        assert!(db.module_hits("eu_bad_mod", Some("policy.jurisdiction.us.v1")).is_empty());
+
+        let bad = std::env::temp_dir().join(format!("signature-db-bad-{}.json", std::process::id()));
+        std::fs::write(&bad, "{").unwrap();
+        let err = SignatureDatabase::new().load_file(&bad).unwrap_err();
+        let _ = std::fs::remove_file(&bad);
+        assert!(matches!(err, SignatureDbError::InFile { ref error, .. } if matches!(**error, SignatureDbError::Json(_))));
+    }
+
+    #[test]
+    fn test_newer_pack_version_supersedes() {
+        let pack = |version: u32, modules: &[&str]| SignaturePack {
+            pack_id: "local".into(),
+            version,
+            jurisdiction_particle: String::new(),
+            signatures: vec![narrow("LOCAL-1", modules)],
+        };
+        let mut db = SignatureDatabase::new();
+        db.merge(pack(1, &["old_mod"]));
+        db.merge(pack(2, &["new_mod"]));
+        assert_eq!(
+            db.diagnostics,
+            vec![DbDiagnostic::Superseded { sig_id: "LOCAL-1".into(), old_version: 1, new_version: 2 }]
+        );
+        assert_eq!(db.get("LOCAL-1").unwrap().pack_version, 2);
+        assert!(db.module_hits("old_mod", None).is_empty());
+        assert_eq!(db.module_hits("new_mod", None).len(), 1);
+
+        // An older pack arriving later changes nothing.
+        db.merge(pack(1, &["old_mod"]));
+        assert_eq!(db.get("LOCAL-1").unwrap().pack_version, 2);
+        assert_eq!(db.diagnostics.len(), 1);
+        assert!(!db.has_conflicts());
+    }
+}
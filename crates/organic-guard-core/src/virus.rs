@@ -65,7 +65,7 @@ fn any_forbidden_field_present(present_fields: &[&str], forbidden: &[String]) ->
     present_fields.iter().any(|f| forbidden.iter().any(|ff| ff == f))
 }
 
-fn impact_exceeds_thresholds(impact: &ImpactProfile, thresholds: &ImpactProfile) -> bool {
+pub(crate) fn impact_exceeds_thresholds(impact: &ImpactProfile, thresholds: &ImpactProfile) -> bool {
     // Conservative interpretation: any delta >= threshold is considered a violation. [file:5]
     (thresholds.delta_risk > 0.0 && impact.delta_risk >= thresholds.delta_risk)
         || (thresholds.delta_duty_cycle > 0.0 && impact.delta_duty_cycle >= thresholds.delta_duty_cycle)
@@ -2,23 +2,19 @@ use std::time::Duration;
 use crate::cargoenv::{CargoEnvDescriptor, BioscaleEnvEnvelope};
 use bioscale_upgrade_store::{HostBudget, EvidenceBundle};
 use bioscale_upgrade_store::defaults::DEFAULTBIOPHYSEVIDENCE;
+use crate::crypto_posture::{
+    AlgorithmFamily, CryptoPostureEngine, CryptoPostureVerdict, DependencySurface,
+};
 
-/// Blake-family crypto posture for this host.
-#[derive(Clone, Debug)]
-pub struct BlakeCryptoPosture {
-    /// If false, any Blake3-related compile, run, or OTA is forbidden.
-    pub blake3_allowed: bool,
-    /// Optional evidence tag that must appear in an upgrade's EvidenceBundle.
-    pub required_evidence_tag: Option<&'static str>,
-    /// Version of the static denylist in force.
-    pub pattern_set_version: &'static str,
-}
-
-/// Extend the core environment descriptor with Blake posture.
+/// Extend the core environment descriptor with the host's crypto posture.
+/// Blake-family decisions come from `posture`; there is no separate flag.
 #[derive(Clone, Debug)]
 pub struct BlakeAwareEnv {
     pub env: CargoEnvDescriptor,
-    pub blake: BlakeCryptoPosture,
+    pub posture: CryptoPostureEngine,
+    /// Optional evidence tag that must appear in an upgrade's EvidenceBundle
+    /// before Blake3 is considered at all.
+    pub required_evidence_tag: Option<&'static str>,
 }
 
 impl BlakeAwareEnv {
@@ -36,24 +32,75 @@ impl BlakeAwareEnv {
         };
 
         let env = CargoEnvDescriptor::new_default(bioscale);
-        let blake = BlakeCryptoPosture {
-            blake3_allowed: false,
+        Self {
+            env,
+            posture: CryptoPostureEngine::v1(),
             required_evidence_tag: None,
-            pattern_set_version: "blake-patterns-v1",
-        };
+        }
+    }
 
-        Self { env, blake }
+    /// Version of the posture table in force for the default corridor.
+    pub fn pattern_set_version(&self) -> &str {
+        self.posture.table_version(self.posture.default_corridor())
     }
 
     /// Does this env permit Blake3 given the upgrade's evidence tags?
-    pub fn permits_blake3(&self, tags: &[&str]) -> bool {
-        if !self.blake.blake3_allowed {
-            return false;
-        }
-        if let Some(required) = self.blake.required_evidence_tag {
-            tags.iter().any(|t| *t == required)
-        } else {
-            true
+    /// When `required_evidence_tag` is set it must be among `tags`, whatever
+    /// the corridor table says.
+    pub fn permits_blake3<T: AsRef<str>>(&self, tags: &[T]) -> bool {
+        if let Some(required) = self.required_evidence_tag {
+            if !tags.iter().any(|t| t.as_ref() == required) {
+                return false;
+            }
         }
+        let tags: Vec<String> = tags.iter().map(|t| t.as_ref().to_string()).collect();
+        self.posture
+            .permits(self.posture.default_corridor(), AlgorithmFamily::Blake, &tags)
+    }
+
+    /// Full posture verdict for an upgrade's dependency surface in the default corridor.
+    pub fn verdict<T: AsRef<str>>(&self, surface: &DependencySurface, tags: &[T]) -> CryptoPostureVerdict {
+        let tags: Vec<String> = tags.iter().map(|t| t.as_ref().to_string()).collect();
+        self.posture
+            .evaluate(self.posture.default_corridor(), surface, &tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto_posture::{CorridorPostureTable, PostureAction, PostureRule};
+
+    fn env_with_blake_exception() -> BlakeAwareEnv {
+        let mut env = BlakeAwareEnv::current();
+        env.posture = CryptoPostureEngine::new(
+            vec![CorridorPostureTable {
+                corridor: "bci-host".into(),
+                version: "test".into(),
+                rules: vec![PostureRule::deny(AlgorithmFamily::Blake).with_exception("blake3-audited")],
+                default_action: PostureAction::Allow,
+            }],
+            "bci-host",
+        );
+        env
+    }
+
+    #[test]
+    fn current_env_denies_blake3() {
+        let env = BlakeAwareEnv::current();
+        assert!(!env.permits_blake3::<&str>(&[]));
+        assert_eq!(env.pattern_set_version(), "crypto-posture-v1");
+    }
+
+    #[test]
+    fn required_evidence_tag_is_enforced() {
+        let mut env = env_with_blake_exception();
+        assert!(env.permits_blake3(&["blake3-audited"]));
+
+        env.required_evidence_tag = Some("lab-signoff");
+        assert!(!env.permits_blake3(&["blake3-audited"]));
+        assert!(env.permits_blake3(&["blake3-audited", "lab-signoff"]));
+        // The tag alone does not override the corridor table.
+        assert!(!env.permits_blake3(&["lab-signoff"]));
     }
 }
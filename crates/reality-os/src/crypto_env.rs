@@ -1,42 +1,38 @@
 use crate::cargoenv::CargoEnvDescriptor;
+use crate::crypto_posture::{AlgorithmFamily, CryptoPostureEngine};
 
-/// Crypto posture for this host: BLAKE-family and SHA3-family forbidden.
-#[derive(Clone, Debug)]
-pub struct CryptoPosture {
-    /// If false, *any* BLAKE-family compile/run/OTA is forbidden.
-    pub blake_allowed: bool,
-    /// If false, *any* SHA3-family compile/run/OTA is forbidden.
-    pub sha3_allowed: bool,
-    /// Versioned denylist pattern set identifier.
-    pub patternset_version: &'static str,
-}
-
+/// Host environment plus its crypto posture. Every BLAKE/SHA3 answer is read
+/// from `posture`; v1 forbids both families in the default corridor.
 #[derive(Clone, Debug)]
 pub struct CryptoAwareEnv {
     pub env: CargoEnvDescriptor,
-    pub crypto: CryptoPosture,
+    pub posture: CryptoPostureEngine,
 }
 
 impl CryptoAwareEnv {
     pub fn current() -> Self {
         let env = CargoEnvDescriptor::new_default_bioscale(); // existing constructor.[file:40][file:31]
 
-        let crypto = CryptoPosture {
-            blake_allowed: false,
-            sha3_allowed: false,
-            patternset_version: "crypto-patterns-v1",
-        };
-
-        Self { env, crypto }
+        Self { env, posture: CryptoPostureEngine::v1() }
     }
 
-    /// Hard policy: no BLAKE-family allowed.
+    /// Is any BLAKE-family use allowed in the default corridor without exceptions?
     pub fn permits_blake(&self) -> bool {
-        self.crypto.blake_allowed
+        self.permits(AlgorithmFamily::Blake)
     }
 
-    /// Hard policy: no SHA3-family allowed.
+    /// Is any SHA3-family use allowed in the default corridor without exceptions?
     pub fn permits_sha3(&self) -> bool {
-        self.crypto.sha3_allowed
+        self.permits(AlgorithmFamily::Sha3)
+    }
+
+    /// Version of the posture table in force for the default corridor.
+    pub fn pattern_set_version(&self) -> &str {
+        self.posture.table_version(self.posture.default_corridor())
+    }
+
+    fn permits(&self, family: AlgorithmFamily) -> bool {
+        self.posture
+            .permits(self.posture.default_corridor(), family, &[])
     }
 }
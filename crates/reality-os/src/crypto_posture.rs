@@ -0,0 +1,443 @@
+use regex::Regex;
+
+/// Cryptographic algorithm families the posture engine can gate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AlgorithmFamily {
+    Blake,
+    Sha2,
+    Sha3,
+    Keccak,
+    Argon2,
+    Scrypt,
+    Md5,
+    Sha1,
+    Ed25519,
+    Secp256k1,
+}
+
+impl AlgorithmFamily {
+    pub const ALL: [AlgorithmFamily; 10] = [
+        AlgorithmFamily::Blake,
+        AlgorithmFamily::Sha2,
+        AlgorithmFamily::Sha3,
+        AlgorithmFamily::Keccak,
+        AlgorithmFamily::Argon2,
+        AlgorithmFamily::Scrypt,
+        AlgorithmFamily::Md5,
+        AlgorithmFamily::Sha1,
+        AlgorithmFamily::Ed25519,
+        AlgorithmFamily::Secp256k1,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlgorithmFamily::Blake => "BLAKE",
+            AlgorithmFamily::Sha2 => "SHA-2",
+            AlgorithmFamily::Sha3 => "SHA-3",
+            AlgorithmFamily::Keccak => "Keccak",
+            AlgorithmFamily::Argon2 => "Argon2",
+            AlgorithmFamily::Scrypt => "scrypt",
+            AlgorithmFamily::Md5 => "MD5",
+            AlgorithmFamily::Sha1 => "SHA-1",
+            AlgorithmFamily::Ed25519 => "Ed25519",
+            AlgorithmFamily::Secp256k1 => "secp256k1",
+        }
+    }
+
+    /// Case-insensitive pattern matched against crate names, symbols and descriptors.
+    fn pattern(&self) -> &'static str {
+        match self {
+            AlgorithmFamily::Blake => r"(?i)blake3?|blake2[bs]?",
+            // The separator/boundary after `sha3` keeps SHA-2 names like
+            // `sha384` from matching.
+            AlgorithmFamily::Sha3 => r"(?i)sha3(?:[-_ ]?(?:224|256|384|512))?(?:[-_ ]|\b)",
+            AlgorithmFamily::Sha2 => r"(?i)\bsha2\b|sha[-_]?(224|256|384|512)\b",
+            AlgorithmFamily::Keccak => r"(?i)keccak|tiny[-_]keccak",
+            AlgorithmFamily::Argon2 => r"(?i)argon2(i|d|id)?",
+            AlgorithmFamily::Scrypt => r"(?i)\bscrypt\b",
+            AlgorithmFamily::Md5 => r"(?i)\bmd-?5\b",
+            AlgorithmFamily::Sha1 => r"(?i)\bsha-?1(_smol)?\b",
+            AlgorithmFamily::Ed25519 => r"(?i)ed25519",
+            AlgorithmFamily::Secp256k1 => r"(?i)secp256k1|\bk256\b",
+        }
+    }
+}
+
+/// What a corridor does when a family is found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PostureAction {
+    Allow,
+    Deny,
+}
+
+/// One row of a corridor's allow/deny table.
+#[derive(Clone, Debug)]
+pub struct PostureRule {
+    pub family: AlgorithmFamily,
+    pub action: PostureAction,
+    /// Evidence tags that turn a Deny into an Allow for this family.
+    pub exception_evidence_tags: Vec<String>,
+}
+
+impl PostureRule {
+    pub fn allow(family: AlgorithmFamily) -> Self {
+        Self { family, action: PostureAction::Allow, exception_evidence_tags: Vec::new() }
+    }
+
+    pub fn deny(family: AlgorithmFamily) -> Self {
+        Self { family, action: PostureAction::Deny, exception_evidence_tags: Vec::new() }
+    }
+
+    pub fn with_exception(mut self, tag: &str) -> Self {
+        self.exception_evidence_tags.push(tag.to_string());
+        self
+    }
+}
+
+/// Declarative per-corridor posture table.
+#[derive(Clone, Debug)]
+pub struct CorridorPostureTable {
+    pub corridor: String,
+    pub version: String,
+    pub rules: Vec<PostureRule>,
+    /// Action for families with no explicit rule.
+    pub default_action: PostureAction,
+}
+
+impl CorridorPostureTable {
+    pub fn rule_for(&self, family: AlgorithmFamily) -> Option<&PostureRule> {
+        self.rules.iter().find(|r| r.family == family)
+    }
+}
+
+/// Where in an upgrade a family was detected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FindingSource {
+    /// Package name from Cargo.lock.
+    LockedCrate,
+    /// Symbol path (e.g. `blake3::hash`).
+    Symbol,
+    /// Descriptor metadata (repo slug, manifest path, display name, version).
+    Descriptor,
+}
+
+/// One detected algorithm use and the table's disposition for it.
+#[derive(Clone, Debug)]
+pub struct CryptoFinding {
+    pub family: AlgorithmFamily,
+    pub source: FindingSource,
+    pub matched: String,
+    pub action: PostureAction,
+    /// Evidence tag that granted an exception, if any.
+    pub exception_tag: Option<String>,
+}
+
+/// Structured posture verdict for one upgrade in one corridor.
+#[derive(Clone, Debug)]
+pub struct CryptoPostureVerdict {
+    pub corridor: String,
+    pub table_version: String,
+    pub findings: Vec<CryptoFinding>,
+}
+
+impl CryptoPostureVerdict {
+    pub fn is_denied(&self) -> bool {
+        self.findings.iter().any(|f| f.action == PostureAction::Deny)
+    }
+
+    pub fn denied_families(&self) -> Vec<AlgorithmFamily> {
+        let mut fams: Vec<AlgorithmFamily> = self
+            .findings
+            .iter()
+            .filter(|f| f.action == PostureAction::Deny)
+            .map(|f| f.family)
+            .collect();
+        fams.sort();
+        fams.dedup();
+        fams
+    }
+
+    /// Human-readable denial reason suitable for `UpgradeDecision::Denied`.
+    pub fn deny_reason(&self) -> Option<String> {
+        if !self.is_denied() {
+            return None;
+        }
+        let fams: Vec<&str> = self.denied_families().iter().map(|f| f.as_str()).collect();
+        let first = self
+            .findings
+            .iter()
+            .find(|f| f.action == PostureAction::Deny)
+            .map(|f| f.matched.as_str())
+            .unwrap_or_default();
+        Some(format!(
+            "{} cryptography not permitted in corridor '{}' ({}; first match: {})",
+            fams.join(", "),
+            self.corridor,
+            self.table_version,
+            first
+        ))
+    }
+}
+
+/// Everything an upgrade pulls in, as seen by the posture engine.
+#[derive(Clone, Debug, Default)]
+pub struct DependencySurface {
+    pub locked_crates: Vec<String>,
+    pub symbols: Vec<String>,
+    pub descriptor_strings: Vec<String>,
+}
+
+impl DependencySurface {
+    /// Collect package names from the `[[package]]` entries of a Cargo.lock.
+    pub fn from_cargo_lock(lock: &str) -> Self {
+        let mut locked_crates = Vec::new();
+        let mut in_package = false;
+        for line in lock.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_package = line == "[[package]]";
+                continue;
+            }
+            if !in_package {
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("name") {
+                let value = rest.trim_start().trim_start_matches('=').trim().trim_matches('"');
+                if !value.is_empty() {
+                    locked_crates.push(value.to_string());
+                }
+            }
+        }
+        Self { locked_crates, ..Default::default() }
+    }
+
+    pub fn with_symbols<I: IntoIterator<Item = String>>(mut self, symbols: I) -> Self {
+        self.symbols.extend(symbols);
+        self
+    }
+
+    pub fn with_descriptor_strings<I: IntoIterator<Item = String>>(mut self, strings: I) -> Self {
+        self.descriptor_strings.extend(strings);
+        self
+    }
+}
+
+/// Single crypto posture engine shared by every upgrade / env gate.
+#[derive(Clone, Debug)]
+pub struct CryptoPostureEngine {
+    patterns: Vec<(AlgorithmFamily, Regex)>,
+    tables: Vec<CorridorPostureTable>,
+    default_corridor: String,
+}
+
+impl CryptoPostureEngine {
+    pub fn new(tables: Vec<CorridorPostureTable>, default_corridor: &str) -> Self {
+        let patterns = AlgorithmFamily::ALL
+            .iter()
+            .map(|f| (*f, Regex::new(f.pattern()).expect("valid algorithm family regex")))
+            .collect();
+        Self { patterns, tables, default_corridor: default_corridor.to_string() }
+    }
+
+    /// Host default: BLAKE and SHA3 families forbidden in the `bci-host` corridor.
+    pub fn v1() -> Self {
+        let bci = CorridorPostureTable {
+            corridor: "bci-host".into(),
+            version: "crypto-posture-v1".into(),
+            rules: vec![
+                PostureRule::deny(AlgorithmFamily::Blake),
+                PostureRule::deny(AlgorithmFamily::Sha3),
+            ],
+            default_action: PostureAction::Allow,
+        };
+        Self::new(vec![bci], "bci-host")
+    }
+
+    pub fn default_corridor(&self) -> &str {
+        &self.default_corridor
+    }
+
+    pub fn table(&self, corridor: &str) -> Option<&CorridorPostureTable> {
+        self.tables.iter().find(|t| t.corridor == corridor)
+    }
+
+    /// Version of the corridor's table, or `unknown-corridor`.
+    pub fn table_version(&self, corridor: &str) -> &str {
+        self.table(corridor)
+            .map(|t| t.version.as_str())
+            .unwrap_or("unknown-corridor")
+    }
+
+    /// Families detected in a single string.
+    pub fn classify(&self, s: &str) -> Vec<AlgorithmFamily> {
+        self.patterns
+            .iter()
+            .filter(|(_, re)| re.is_match(s))
+            .map(|(f, _)| *f)
+            .collect()
+    }
+
+    /// Does the corridor permit this family given the upgrade's evidence tags?
+    /// Unknown corridors fail closed.
+    pub fn permits(&self, corridor: &str, family: AlgorithmFamily, evidence_tags: &[String]) -> bool {
+        self.disposition(corridor, family, evidence_tags).0 == PostureAction::Allow
+    }
+
+    fn disposition(
+        &self,
+        corridor: &str,
+        family: AlgorithmFamily,
+        evidence_tags: &[String],
+    ) -> (PostureAction, Option<String>) {
+        let table = match self.table(corridor) {
+            Some(t) => t,
+            None => return (PostureAction::Deny, None),
+        };
+        match table.rule_for(family) {
+            Some(rule) if rule.action == PostureAction::Deny => {
+                match rule
+                    .exception_evidence_tags
+                    .iter()
+                    .find(|t| evidence_tags.iter().any(|e| e == *t))
+                {
+                    Some(tag) => (PostureAction::Allow, Some(tag.clone())),
+                    None => (PostureAction::Deny, None),
+                }
+            }
+            Some(rule) => (rule.action.clone(), None),
+            None => (table.default_action.clone(), None),
+        }
+    }
+
+    /// Scan an upgrade's full dependency surface against a corridor table.
+    pub fn evaluate(
+        &self,
+        corridor: &str,
+        surface: &DependencySurface,
+        evidence_tags: &[String],
+    ) -> CryptoPostureVerdict {
+        let table_version = self.table_version(corridor).to_string();
+
+        let sources = surface
+            .locked_crates
+            .iter()
+            .map(|s| (FindingSource::LockedCrate, s))
+            .chain(surface.symbols.iter().map(|s| (FindingSource::Symbol, s)))
+            .chain(surface.descriptor_strings.iter().map(|s| (FindingSource::Descriptor, s)));
+
+        let mut findings = Vec::new();
+        for (source, s) in sources {
+            for family in self.classify(s) {
+                let (action, exception_tag) = self.disposition(corridor, family, evidence_tags);
+                findings.push(CryptoFinding {
+                    family,
+                    source: source.clone(),
+                    matched: s.clone(),
+                    action,
+                    exception_tag,
+                });
+            }
+        }
+
+        CryptoPostureVerdict { corridor: corridor.to_string(), table_version, findings }
+    }
+}
+
+impl Default for CryptoPostureEngine {
+    fn default() -> Self {
+        Self::v1()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(t: &[&str]) -> Vec<String> {
+        t.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn sha3_pattern_does_not_match_sha2_names() {
+        let engine = CryptoPostureEngine::v1();
+        for sha3 in ["sha3", "sha3-256", "SHA3_512", "sha3::Sha3_256", "tiny sha3 384", "sha3_hasher"] {
+            assert!(engine.classify(sha3).contains(&AlgorithmFamily::Sha3), "{}", sha3);
+        }
+        for sha2 in ["sha384", "SHA384", "sha2::Sha384", "sha-384", "hmac-sha384"] {
+            let families = engine.classify(sha2);
+            assert!(!families.contains(&AlgorithmFamily::Sha3), "{}", sha2);
+            assert!(families.contains(&AlgorithmFamily::Sha2), "{}", sha2);
+        }
+    }
+
+    #[test]
+    fn classify_covers_the_legacy_blake_names() {
+        let engine = CryptoPostureEngine::v1();
+        for name in ["blake3", "blake2b", "BLAKE2s", "blake3::hash"] {
+            assert_eq!(engine.classify(name), vec![AlgorithmFamily::Blake], "{}", name);
+        }
+        assert!(engine.classify("serde_json").is_empty());
+    }
+
+    #[test]
+    fn cargo_lock_surface_reads_package_names_only() {
+        let lock = r#"
+version = 3
+
+[[package]]
+name = "blake3"
+version = "1.5.0"
+dependencies = [
+ "name",
+]
+
+[[package]]
+name = "sha2"
+version = "0.10.8"
+
+[metadata]
+name = "not-a-package"
+"#;
+        let surface = DependencySurface::from_cargo_lock(lock);
+        assert_eq!(surface.locked_crates, vec!["blake3".to_string(), "sha2".to_string()]);
+    }
+
+    #[test]
+    fn v1_denies_blake_and_sha3_across_the_surface() {
+        let engine = CryptoPostureEngine::v1();
+        let surface = DependencySurface::from_cargo_lock("[[package]]\nname = \"sha2\"\n")
+            .with_symbols(vec!["sha3::Sha3_256::digest".to_string()])
+            .with_descriptor_strings(vec!["acme/blake3-ota".to_string()]);
+        let verdict = engine.evaluate("bci-host", &surface, &[]);
+
+        assert!(verdict.is_denied());
+        assert_eq!(verdict.denied_families(), vec![AlgorithmFamily::Blake, AlgorithmFamily::Sha3]);
+        let sha3 = verdict.findings.iter().find(|f| f.family == AlgorithmFamily::Sha3).unwrap();
+        assert_eq!(sha3.source, FindingSource::Symbol);
+        let sha2 = verdict.findings.iter().find(|f| f.family == AlgorithmFamily::Sha2).unwrap();
+        assert_eq!(sha2.action, PostureAction::Allow);
+        assert!(verdict.deny_reason().unwrap().contains("crypto-posture-v1"));
+    }
+
+    #[test]
+    fn evidence_tags_grant_exceptions_and_unknown_corridors_fail_closed() {
+        let table = CorridorPostureTable {
+            corridor: "lab".into(),
+            version: "lab-v1".into(),
+            rules: vec![PostureRule::deny(AlgorithmFamily::Blake).with_exception("blake3-audited")],
+            default_action: PostureAction::Deny,
+        };
+        let engine = CryptoPostureEngine::new(vec![table], "lab");
+        let surface = DependencySurface::default().with_symbols(vec!["blake3::hash".to_string()]);
+
+        assert!(engine.evaluate("lab", &surface, &[]).is_denied());
+        let verdict = engine.evaluate("lab", &surface, &tags(&["blake3-audited"]));
+        assert!(!verdict.is_denied());
+        assert_eq!(verdict.findings[0].exception_tag.as_deref(), Some("blake3-audited"));
+
+        // No rule for Argon2: the table default applies.
+        assert!(!engine.permits("lab", AlgorithmFamily::Argon2, &[]));
+        assert!(!engine.permits("elsewhere", AlgorithmFamily::Sha2, &[]));
+        assert_eq!(engine.evaluate("elsewhere", &surface, &[]).table_version, "unknown-corridor");
+    }
+}
@@ -0,0 +1,617 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cargo_env::{CargoEnvDescriptor, CargoSupplyChainPolicy, RustToolchainEnvelope};
+
+/// Index URLs accepted for the `crates.io-index` alias in `allowed_registries`.
+const CRATES_IO_INDEX_URLS: [&str; 2] = [
+    "https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io",
+];
+
+/// Coverage caveat attached to every report.
+const LOCAL_MANIFEST_NOTE: &str = "build.rs and proc-macro rules cover local workspace manifests only; \
+     registry and git packages in Cargo.lock are not inspected for build scripts or proc macros";
+
+/// Which policy rule a violation breaks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupplyChainRule {
+    RegistryNotAllowed,
+    GitDependencyForbidden,
+    GitOrgNotAllowed,
+    PathDependencyOutsideLab,
+    MaxTransitiveDepsExceeded,
+    BuildScriptForbidden,
+    ProcMacroForbidden,
+    /// Lock source with a prefix other than `registry+`, `sparse+` or `git+`.
+    UnknownSourceKind,
+    UnparseableInput,
+}
+
+/// One rule violation, tied to the package that caused it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SupplyChainViolation {
+    pub rule: SupplyChainRule,
+    pub package: String,
+    pub version: Option<String>,
+    pub detail: String,
+}
+
+/// Machine-readable audit result consumed by upgrade admission.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SupplyChainAuditReport {
+    pub workspace_root: String,
+    pub locked_packages: usize,
+    pub transitive_deps: usize,
+    pub violations: Vec<SupplyChainViolation>,
+    /// Limits of what the audit checked, for whoever reads the report.
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
+
+impl SupplyChainAuditReport {
+    pub fn passed(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".into())
+    }
+}
+
+/// One `[[package]]` entry from Cargo.lock.
+#[derive(Clone, Debug, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// `None` for workspace / path packages.
+    pub source: Option<String>,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CargoLockFile {
+    #[serde(default, rename = "package")]
+    packages: Vec<LockedPackage>,
+}
+
+/// What the auditor needs from a local Cargo.toml.
+#[derive(Clone, Debug)]
+pub struct LocalManifest {
+    pub package: String,
+    pub manifest_path: PathBuf,
+    pub has_build_script: bool,
+    pub is_proc_macro: bool,
+    /// Resolved paths of every `path = "..."` dependency.
+    pub path_dependencies: Vec<(String, PathBuf)>,
+}
+
+impl LocalManifest {
+    /// Parse a Cargo.toml; `build.rs` is detected either from `package.build`
+    /// or from the file existing next to the manifest.
+    pub fn parse(manifest_path: &Path) -> Result<Option<Self>, String> {
+        let raw = fs::read_to_string(manifest_path)
+            .map_err(|e| format!("{}: {}", manifest_path.display(), e))?;
+        let doc: toml::Value =
+            toml::from_str(&raw).map_err(|e| format!("{}: {}", manifest_path.display(), e))?;
+
+        // Virtual workspace manifests have no [package].
+        let package = match doc.get("package") {
+            Some(p) => p,
+            None => return Ok(None),
+        };
+        let name = package
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+
+        let has_build_script = match package.get("build") {
+            Some(toml::Value::Boolean(b)) => *b,
+            Some(toml::Value::String(_)) => true,
+            _ => dir.join("build.rs").exists(),
+        };
+        let is_proc_macro = doc
+            .get("lib")
+            .and_then(|l| l.get("proc-macro").or_else(|| l.get("proc_macro")))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let mut path_dependencies = Vec::new();
+        for table in ["dependencies", "dev-dependencies", "build-dependencies"] {
+            if let Some(deps) = doc.get(table).and_then(|d| d.as_table()) {
+                for (dep, spec) in deps {
+                    if let Some(p) = spec.get("path").and_then(|p| p.as_str()) {
+                        path_dependencies.push((dep.clone(), dir.join(p)));
+                    }
+                }
+            }
+        }
+
+        Ok(Some(Self {
+            package: name,
+            manifest_path: manifest_path.to_path_buf(),
+            has_build_script,
+            is_proc_macro,
+            path_dependencies,
+        }))
+    }
+}
+
+/// Offline auditor for a workspace against `CargoSupplyChainPolicy`
+/// and the build-time parts of `RustToolchainEnvelope`.
+pub struct SupplyChainAuditor<'a> {
+    pub policy: &'a CargoSupplyChainPolicy,
+    pub toolchain: &'a RustToolchainEnvelope,
+    /// Root under which path dependencies are permitted.
+    pub lab_root: PathBuf,
+}
+
+impl<'a> SupplyChainAuditor<'a> {
+    pub fn from_env(env: &'a CargoEnvDescriptor, lab_root: PathBuf) -> Self {
+        Self { policy: &env.supply_chain, toolchain: &env.rust, lab_root }
+    }
+
+    /// Audit `<workspace_root>/Cargo.lock` plus every Cargo.toml under the root.
+    pub fn audit_workspace(&self, workspace_root: &Path) -> SupplyChainAuditReport {
+        let mut violations = Vec::new();
+
+        let lock = match fs::read_to_string(workspace_root.join("Cargo.lock")) {
+            Ok(raw) => raw,
+            Err(e) => {
+                violations.push(unparseable("Cargo.lock", e.to_string()));
+                String::new()
+            }
+        };
+
+        let mut manifests = Vec::new();
+        for path in find_manifests(workspace_root) {
+            match LocalManifest::parse(&path) {
+                Ok(Some(m)) => manifests.push(m),
+                Ok(None) => {}
+                Err(e) => violations.push(unparseable(&path.display().to_string(), e)),
+            }
+        }
+
+        let mut report = self.audit(&lock, &manifests);
+        report.workspace_root = workspace_root.display().to_string();
+        violations.append(&mut report.violations);
+        report.violations = violations;
+        report
+    }
+
+    /// Evaluate every rule over already-loaded lock text and manifests.
+    pub fn audit(&self, cargo_lock: &str, manifests: &[LocalManifest]) -> SupplyChainAuditReport {
+        let mut violations = Vec::new();
+
+        let packages = match toml::from_str::<CargoLockFile>(cargo_lock) {
+            Ok(lock) => lock.packages,
+            Err(e) => {
+                if !cargo_lock.is_empty() {
+                    violations.push(unparseable("Cargo.lock", e.to_string()));
+                }
+                Vec::new()
+            }
+        };
+
+        let mut transitive = 0usize;
+        for pkg in &packages {
+            let source = match &pkg.source {
+                Some(s) => s,
+                None => continue,
+            };
+            transitive += 1;
+
+            if let Some(url) = source.strip_prefix("git+") {
+                if self.policy.forbid_git_deps {
+                    violations.push(violation(
+                        SupplyChainRule::GitDependencyForbidden,
+                        pkg,
+                        format!("git source {}", url),
+                    ));
+                } else if !self.git_org_allowed(url) {
+                    violations.push(violation(
+                        SupplyChainRule::GitOrgNotAllowed,
+                        pkg,
+                        format!("git source {} is outside allowed orgs {:?}", url, self.policy.allowed_orgs),
+                    ));
+                }
+            } else if source.starts_with("registry+") || source.starts_with("sparse+") {
+                if !self.registry_allowed(source) {
+                    violations.push(violation(
+                        SupplyChainRule::RegistryNotAllowed,
+                        pkg,
+                        format!("registry {} not in {:?}", source, self.policy.allowed_registries),
+                    ));
+                }
+            } else {
+                // Fail closed on source kinds the policy has no rule for.
+                violations.push(violation(
+                    SupplyChainRule::UnknownSourceKind,
+                    pkg,
+                    format!("unrecognised lock source {}", source),
+                ));
+            }
+        }
+
+        if transitive > self.policy.max_transitive_deps as usize {
+            violations.push(SupplyChainViolation {
+                rule: SupplyChainRule::MaxTransitiveDepsExceeded,
+                package: "*".into(),
+                version: None,
+                detail: format!(
+                    "{} external packages locked, limit is {}",
+                    transitive, self.policy.max_transitive_deps
+                ),
+            });
+        }
+
+        // Fail closed: a lab root or dependency path that does not resolve
+        // cannot be shown to be inside the lab.
+        let lab_root = fs::canonicalize(&self.lab_root).ok();
+        for m in manifests {
+            if !self.toolchain.allow_build_rs && m.has_build_script {
+                violations.push(local_violation(
+                    SupplyChainRule::BuildScriptForbidden,
+                    m,
+                    "build scripts are not allowed by RustToolchainEnvelope".into(),
+                ));
+            }
+            if !self.toolchain.allow_proc_macros && m.is_proc_macro {
+                violations.push(local_violation(
+                    SupplyChainRule::ProcMacroForbidden,
+                    m,
+                    "proc-macro crates are not allowed by RustToolchainEnvelope".into(),
+                ));
+            }
+            if self.policy.forbid_path_deps_outside_lab {
+                for (dep, path) in &m.path_dependencies {
+                    let detail = match (&lab_root, fs::canonicalize(path)) {
+                        (Some(root), Ok(resolved)) if resolved.starts_with(root) => continue,
+                        (Some(root), Ok(resolved)) => format!(
+                            "path dependency `{}` -> {} is outside {}",
+                            dep,
+                            resolved.display(),
+                            root.display()
+                        ),
+                        (Some(_), Err(e)) => {
+                            format!("path dependency `{}` -> {} does not resolve: {}", dep, path.display(), e)
+                        }
+                        (None, _) => format!(
+                            "lab root {} does not resolve; path dependency `{}` cannot be checked",
+                            self.lab_root.display(),
+                            dep
+                        ),
+                    };
+                    violations.push(local_violation(SupplyChainRule::PathDependencyOutsideLab, m, detail));
+                }
+            }
+        }
+
+        SupplyChainAuditReport {
+            workspace_root: String::new(),
+            locked_packages: packages.len(),
+            transitive_deps: transitive,
+            violations,
+            notes: vec![LOCAL_MANIFEST_NOTE.to_string()],
+        }
+    }
+
+    /// Exact match of the source's index URL against `allowed_registries`.
+    /// Entries are either full index URLs or the `crates.io-index` alias.
+    fn registry_allowed(&self, source: &str) -> bool {
+        let url = match registry_url(source) {
+            Some(url) => url,
+            None => return false,
+        };
+        self.policy.allowed_registries.iter().any(|r| {
+            if r.contains("://") {
+                r.trim_end_matches('/') == url
+            } else {
+                r == "crates.io-index" && CRATES_IO_INDEX_URLS.contains(&url)
+            }
+        })
+    }
+
+    /// The URL's host must be `github.com` and its first path segment must
+    /// equal one of `allowed_orgs`.
+    fn git_org_allowed(&self, url: &str) -> bool {
+        match github_org(url) {
+            Some(org) => self.policy.allowed_orgs.iter().any(|o| o == org),
+            None => false,
+        }
+    }
+}
+
+fn violation(rule: SupplyChainRule, pkg: &LockedPackage, detail: String) -> SupplyChainViolation {
+    SupplyChainViolation {
+        rule,
+        package: pkg.name.clone(),
+        version: Some(pkg.version.clone()),
+        detail,
+    }
+}
+
+fn local_violation(rule: SupplyChainRule, m: &LocalManifest, detail: String) -> SupplyChainViolation {
+    SupplyChainViolation {
+        rule,
+        package: m.package.clone(),
+        version: None,
+        detail: format!("{} ({})", detail, m.manifest_path.display()),
+    }
+}
+
+fn unparseable(what: &str, err: String) -> SupplyChainViolation {
+    SupplyChainViolation {
+        rule: SupplyChainRule::UnparseableInput,
+        package: what.to_string(),
+        version: None,
+        detail: err,
+    }
+}
+
+/// Index URL of a `registry+` / `sparse+` lock source, without a trailing
+/// slash. Sparse URLs keep their `sparse+` prefix, as in Cargo's config.
+fn registry_url(source: &str) -> Option<&str> {
+    let url = if let Some(rest) = source.strip_prefix("registry+") {
+        rest
+    } else if source.starts_with("sparse+") {
+        source
+    } else {
+        return None;
+    };
+    Some(url.trim_end_matches('/'))
+}
+
+/// Org of a GitHub git source, e.g. `https://github.com/<org>/<repo>?rev=...#<sha>`.
+/// `None` for any other host, userinfo-spoofed hosts included.
+fn github_org(url: &str) -> Option<&str> {
+    let (scheme, rest) = url.split_once("://")?;
+    if !matches!(scheme, "https" | "http" | "ssh" | "git") {
+        return None;
+    }
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let (authority, path) = rest.split_once('/')?;
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    if !host.eq_ignore_ascii_case("github.com") {
+        return None;
+    }
+    path.split('/').next().filter(|org| !org.is_empty())
+}
+
+/// Every Cargo.toml under `root`, skipping `target/`, hidden directories and
+/// symlinked directories (which could otherwise form a cycle).
+fn find_manifests(root: &Path) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            // `file_type` does not follow symlinks.
+            let file_type = match entry.file_type() {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+            if file_type.is_dir() {
+                if name != "target" && !name.starts_with('.') {
+                    stack.push(path);
+                }
+            } else if name == "Cargo.toml" {
+                out.push(path);
+            }
+        }
+    }
+    out.sort();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> CargoSupplyChainPolicy {
+        CargoSupplyChainPolicy {
+            allowed_orgs: vec!["Doctor0Evil".into()],
+            allowed_registries: vec!["crates.io-index".into()],
+            forbid_git_deps: false,
+            forbid_path_deps_outside_lab: true,
+            max_transitive_deps: 3,
+        }
+    }
+
+    fn toolchain() -> RustToolchainEnvelope {
+        RustToolchainEnvelope {
+            channel: "stable".into(),
+            min_version_semver: "1.76.0".into(),
+            max_version_semver: None,
+            allowed_targets: vec![],
+            forbid_unsafe_code: true,
+            allow_proc_macros: false,
+            allow_build_rs: false,
+        }
+    }
+
+    fn lock(sources: &[(&str, &str)]) -> String {
+        sources
+            .iter()
+            .map(|(name, source)| {
+                format!("[[package]]\nname = \"{}\"\nversion = \"1.0.0\"\nsource = \"{}\"\n\n", name, source)
+            })
+            .collect()
+    }
+
+    fn rules(report: &SupplyChainAuditReport) -> Vec<SupplyChainRule> {
+        report.violations.iter().map(|v| v.rule.clone()).collect()
+    }
+
+    /// Scratch directory under the system temp dir, removed on drop.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("supply-chain-audit-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn registries_are_matched_exactly() {
+        let policy = policy();
+        let toolchain = toolchain();
+        let auditor = SupplyChainAuditor { policy: &policy, toolchain: &toolchain, lab_root: PathBuf::from("/") };
+
+        let ok = lock(&[
+            ("serde", "registry+https://github.com/rust-lang/crates.io-index"),
+            ("regex", "sparse+https://index.crates.io/"),
+        ]);
+        assert!(auditor.audit(&ok, &[]).passed());
+
+        let evil = lock(&[
+            ("serde", "registry+https://evil.example/?index.crates.io"),
+            ("regex", "registry+https://evil.example/crates.io-index"),
+            ("toml", "sparse+https://index.crates.io.evil.example/"),
+        ]);
+        let report = auditor.audit(&evil, &[]);
+        assert_eq!(rules(&report), vec![SupplyChainRule::RegistryNotAllowed; 3]);
+
+        let mut by_url = policy.clone();
+        by_url.allowed_registries = vec!["https://mirror.lab.example/index/".into()];
+        let auditor = SupplyChainAuditor { policy: &by_url, toolchain: &toolchain, lab_root: PathBuf::from("/") };
+        let mirror = lock(&[("serde", "registry+https://mirror.lab.example/index")]);
+        assert!(auditor.audit(&mirror, &[]).passed());
+    }
+
+    #[test]
+    fn git_sources_and_transitive_limit() {
+        let policy = policy();
+        let toolchain = toolchain();
+        let auditor = SupplyChainAuditor { policy: &policy, toolchain: &toolchain, lab_root: PathBuf::from("/") };
+        let raw = lock(&[
+            ("a", "git+https://github.com/Doctor0Evil/a?rev=1#abc"),
+            ("b", "git+https://github.com/someone/b#def"),
+            ("c", "registry+https://github.com/rust-lang/crates.io-index"),
+            ("d", "registry+https://github.com/rust-lang/crates.io-index"),
+        ]);
+        let report = auditor.audit(&raw, &[]);
+        assert_eq!(
+            rules(&report),
+            vec![SupplyChainRule::GitOrgNotAllowed, SupplyChainRule::MaxTransitiveDepsExceeded]
+        );
+        assert_eq!(report.transitive_deps, 4);
+
+        let spoofed = lock(&[
+            ("e", "git+https://evil.example/github.com/Doctor0Evil/x#1"),
+            ("f", "git+https://github.com.evil.example/Doctor0Evil/x#2"),
+            ("g", "git+https://github.com@evil.example/Doctor0Evil/x#3"),
+            ("h", "git+https://github.com/Doctor0Evil-fork/x#4"),
+        ]);
+        assert_eq!(rules(&auditor.audit(&spoofed, &[]))[..4], [SupplyChainRule::GitOrgNotAllowed; 4]);
+        let ssh = lock(&[("i", "git+ssh://git@github.com/Doctor0Evil/x#5")]);
+        assert!(auditor.audit(&ssh, &[]).passed());
+
+        let mut strict = policy.clone();
+        strict.forbid_git_deps = true;
+        let auditor = SupplyChainAuditor { policy: &strict, toolchain: &toolchain, lab_root: PathBuf::from("/") };
+        assert_eq!(auditor.audit(&raw, &[]).violations[0].rule, SupplyChainRule::GitDependencyForbidden);
+    }
+
+    #[test]
+    fn path_dependencies_fail_closed() {
+        let scratch = Scratch::new("paths");
+        let lab = scratch.0.join("lab");
+        fs::create_dir_all(lab.join("inside")).unwrap();
+        fs::create_dir_all(scratch.0.join("outside")).unwrap();
+
+        let policy = policy();
+        let toolchain = toolchain();
+        let auditor = SupplyChainAuditor { policy: &policy, toolchain: &toolchain, lab_root: lab.clone() };
+        let manifest = LocalManifest {
+            package: "app".into(),
+            manifest_path: lab.join("Cargo.toml"),
+            has_build_script: false,
+            is_proc_macro: false,
+            path_dependencies: vec![
+                ("inside".into(), lab.join("inside")),
+                ("escape".into(), lab.join("../outside")),
+                ("missing".into(), lab.join("../x")),
+            ],
+        };
+        let report = auditor.audit("", std::slice::from_ref(&manifest));
+        let flagged: Vec<&str> = report.violations.iter().map(|v| v.detail.as_str()).collect();
+        assert_eq!(flagged.len(), 2, "{:?}", flagged);
+        assert!(flagged[0].contains("`escape`") && flagged[0].contains("is outside"));
+        assert!(flagged[1].contains("`missing`") && flagged[1].contains("does not resolve"));
+
+        let auditor = SupplyChainAuditor { policy: &policy, toolchain: &toolchain, lab_root: scratch.0.join("nope") };
+        assert_eq!(auditor.audit("", &[manifest]).violations.len(), 3);
+    }
+
+    #[test]
+    fn workspace_audit_reads_local_manifests_and_notes_coverage() {
+        let scratch = Scratch::new("workspace");
+        let root = &scratch.0;
+        fs::create_dir_all(root.join("macros")).unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"app\"\nversion = \"0.1.0\"\n").unwrap();
+        fs::write(root.join("build.rs"), "fn main() {}\n").unwrap();
+        fs::write(
+            root.join("macros/Cargo.toml"),
+            "[package]\nname = \"macros\"\nversion = \"0.1.0\"\n\n[lib]\nproc-macro = true\n",
+        )
+        .unwrap();
+        fs::write(root.join("Cargo.lock"), lock(&[("serde", "registry+https://github.com/rust-lang/crates.io-index")]))
+            .unwrap();
+
+        let policy = policy();
+        let toolchain = toolchain();
+        let auditor = SupplyChainAuditor { policy: &policy, toolchain: &toolchain, lab_root: root.clone() };
+        let report = auditor.audit_workspace(root);
+        assert_eq!(
+            rules(&report),
+            vec![SupplyChainRule::BuildScriptForbidden, SupplyChainRule::ProcMacroForbidden]
+        );
+        assert_eq!(report.locked_packages, 1);
+        assert_eq!(report.notes, vec![LOCAL_MANIFEST_NOTE.to_string()]);
+        assert!(report.to_json().contains("local workspace manifests only"));
+    }
+
+    #[test]
+    fn unknown_source_kinds_fail_closed() {
+        let policy = policy();
+        let toolchain = toolchain();
+        let auditor = SupplyChainAuditor { policy: &policy, toolchain: &toolchain, lab_root: PathBuf::from("/") };
+        let raw = lock(&[("serde", "local-registry+file:///vendor")]);
+        assert_eq!(rules(&auditor.audit(&raw, &[])), vec![SupplyChainRule::UnknownSourceKind]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn manifest_walk_skips_symlinked_directories() {
+        let scratch = Scratch::new("symlinks");
+        let root = &scratch.0;
+        fs::create_dir_all(root.join("member")).unwrap();
+        fs::write(root.join("member/Cargo.toml"), "[package]\nname = \"member\"\n").unwrap();
+        std::os::unix::fs::symlink(root, root.join("member/loop")).unwrap();
+
+        assert_eq!(find_manifests(root), vec![root.join("member/Cargo.toml")]);
+    }
+}
@@ -0,0 +1,516 @@
+use serde::{Deserialize, Serialize};
+use crate::did::DidUri;
+use crate::aln::shards::eco_metrics_host_budget::{EcoAttestation, EcoImpactScore};
+use crate::aln::shards::organic_cpu_validator::ValidatorSetShard;
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier};
+use sha2::{Digest, Sha256};
+
+/// Attestations expire after 90 days
+pub const ATTESTATION_VALIDITY_DAYS: i64 = 90;
+
+/// Canonical payload schema tag (bump if the field order changes)
+const PAYLOAD_SCHEMA: &str = "eco-attestation.v2";
+
+/// Build the canonical payload an attester signs for a validator's eco-impact score.
+/// Fields are fixed-order and fixed-precision so every party hashes the same bytes.
+/// `last_calculated` is left out: recomputing a score from identical metrics
+/// must produce the same payload.
+pub fn canonical_score_payload(validator_did: &DidUri, score: &EcoImpactScore) -> String {
+    format!(
+        "{}|{}|{:.6}|{:.6}|{:.6}|{:.6}|{:.6}",
+        PAYLOAD_SCHEMA,
+        serde_json::to_string(validator_did).unwrap_or_default(),
+        score.value,
+        score.gco2_per_joule,
+        score.renewable_energy_percentage,
+        score.hardware_toxicity_score,
+        score.cooling_efficiency,
+    )
+}
+
+/// SHA-256 (hex) of the canonical payload
+pub fn score_payload_hash(validator_did: &DidUri, score: &EcoImpactScore) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(canonical_score_payload(validator_did, score).as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Third-party auditor allowed to attest eco-metrics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowedAttester {
+    /// Attester DID
+    pub attester_did: DidUri,
+    /// ed25519 public key (hex, 32 bytes)
+    pub public_key_hex: String,
+    /// Human-readable auditor name
+    pub name: String,
+}
+
+/// Attester allowlist (append-only; revocation is a new list version)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AttesterAllowlist {
+    pub attesters: Vec<AllowedAttester>,
+    pub version: u32,
+}
+
+impl AttesterAllowlist {
+    /// Look up an attester by DID
+    pub fn get(&self, attester_did: &DidUri) -> Option<&AllowedAttester> {
+        self.attesters.iter().find(|a| a.attester_did == *attester_did)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttestationError {
+    /// Attester DID is not on the allowlist
+    AttesterNotAllowed,
+    /// Allowlisted public key could not be parsed
+    InvalidAttesterKey,
+    /// Signature is missing or not 64 bytes of hex
+    InvalidSignatureFormat,
+    /// Signature does not verify over the canonical payload
+    SignatureMismatch,
+    /// Stored payload hash does not match the embedded eco-impact score
+    PayloadHashMismatch,
+    /// Attestation has passed its expiry time
+    Expired,
+    /// Attestation was invalidated by a registry sweep
+    Invalidated,
+}
+
+/// Why an attestation was invalidated
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum InvalidationReason {
+    Expired,
+    MetricsChanged,
+    AttesterRemoved,
+    ValidatorDeactivated,
+}
+
+/// Invalidation record (append-only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationInvalidation {
+    pub attestation_id: String,
+    pub validator_did: DidUri,
+    pub reason: InvalidationReason,
+    pub invalidated_at: DateTime<Utc>,
+    pub row_anchor_height: u64,
+}
+
+/// A validator claiming an eco floor that no current attestation backs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnbackedFloorClaim {
+    pub validator_did: DidUri,
+    /// Eco-impact score the validator claims
+    pub claimed_value: f32,
+    /// Floor being claimed (e.g. 0.86 for health validation)
+    pub floor: f32,
+    /// Most recent attestation for the validator, if any
+    pub latest_attestation_id: Option<String>,
+    pub reason: String,
+}
+
+/// Issue a signed attestation for a validator's current eco-impact score
+pub fn issue_attestation(
+    attestation_id: String,
+    validator_did: &DidUri,
+    attester_did: &DidUri,
+    score: &EcoImpactScore,
+    attester_keypair: &Keypair,
+    now: DateTime<Utc>,
+    height: u64,
+) -> EcoAttestation {
+    let payload = canonical_score_payload(validator_did, score);
+    let signature = attester_keypair.sign(payload.as_bytes());
+    EcoAttestation {
+        attestation_id,
+        validator_did: validator_did.clone(),
+        attester_did: attester_did.clone(),
+        eco_impact_score: score.clone(),
+        attestation_time: now,
+        expiry_time: now + Duration::days(ATTESTATION_VALIDITY_DAYS),
+        row_anchor_height: height,
+        is_valid: true,
+        score_payload_hash: score_payload_hash(validator_did, score),
+        attester_signature: hex::encode(signature.to_bytes()),
+    }
+}
+
+/// Verify an attestation's signature against the allowlist, its validity flag and its expiry
+pub fn verify_attestation(
+    attestation: &EcoAttestation,
+    allowlist: &AttesterAllowlist,
+    now: DateTime<Utc>,
+) -> Result<(), AttestationError> {
+    if !attestation.is_valid {
+        return Err(AttestationError::Invalidated);
+    }
+
+    let attester = allowlist
+        .get(&attestation.attester_did)
+        .ok_or(AttestationError::AttesterNotAllowed)?;
+
+    let key_bytes = hex::decode(&attester.public_key_hex)
+        .map_err(|_| AttestationError::InvalidAttesterKey)?;
+    let public_key =
+        PublicKey::from_bytes(&key_bytes).map_err(|_| AttestationError::InvalidAttesterKey)?;
+
+    let sig_bytes = hex::decode(&attestation.attester_signature)
+        .map_err(|_| AttestationError::InvalidSignatureFormat)?;
+    if sig_bytes.len() != 64 {
+        return Err(AttestationError::InvalidSignatureFormat);
+    }
+    let signature =
+        Signature::from_bytes(&sig_bytes).map_err(|_| AttestationError::InvalidSignatureFormat)?;
+
+    if attestation.score_payload_hash
+        != score_payload_hash(&attestation.validator_did, &attestation.eco_impact_score)
+    {
+        return Err(AttestationError::PayloadHashMismatch);
+    }
+
+    let payload = canonical_score_payload(&attestation.validator_did, &attestation.eco_impact_score);
+    public_key
+        .verify(payload.as_bytes(), &signature)
+        .map_err(|_| AttestationError::SignatureMismatch)?;
+
+    if now >= attestation.expiry_time {
+        return Err(AttestationError::Expired);
+    }
+    Ok(())
+}
+
+/// Eco attestation registry shard (append-only attestations + invalidation log)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct EcoAttestationRegistry {
+    pub allowlist: AttesterAllowlist,
+    pub attestations: Vec<EcoAttestation>,
+    pub invalidations: Vec<AttestationInvalidation>,
+    pub last_updated_height: u64,
+}
+
+impl EcoAttestationRegistry {
+    pub fn new(allowlist: AttesterAllowlist) -> Self {
+        Self {
+            allowlist,
+            ..Default::default()
+        }
+    }
+
+    /// Verify and append an attestation (forward-only).
+    /// Previous attestations for the same validator stay in the log but are no longer current.
+    pub fn submit(
+        &mut self,
+        attestation: EcoAttestation,
+        now: DateTime<Utc>,
+        height: u64,
+    ) -> Result<(), AttestationError> {
+        verify_attestation(&attestation, &self.allowlist, now)?;
+        self.attestations.push(attestation);
+        self.last_updated_height = height;
+        Ok(())
+    }
+
+    /// Latest attestation (valid or not) for a validator
+    pub fn latest_for_validator(&self, validator_did: &DidUri) -> Option<&EcoAttestation> {
+        self.attestations
+            .iter()
+            .rev()
+            .find(|a| a.validator_did == *validator_did)
+    }
+
+    /// Latest attestation for a validator that is still valid at `now`
+    pub fn current_for_validator(
+        &self,
+        validator_did: &DidUri,
+        now: DateTime<Utc>,
+    ) -> Option<&EcoAttestation> {
+        self.attestations
+            .iter()
+            .rev()
+            .find(|a| a.validator_did == *validator_did && a.is_valid_at(now))
+    }
+
+    /// Invalidate attestations that expired, whose attester left the allowlist,
+    /// whose validator was deactivated, or whose validator's live eco metrics no
+    /// longer match the attested payload.
+    /// Returns the invalidation records appended by this sweep.
+    pub fn sweep(
+        &mut self,
+        validators: &ValidatorSetShard,
+        now: DateTime<Utc>,
+        height: u64,
+    ) -> Vec<AttestationInvalidation> {
+        let mut appended = Vec::new();
+        for att in self.attestations.iter_mut().filter(|a| a.is_valid) {
+            let reason = if now >= att.expiry_time {
+                Some(InvalidationReason::Expired)
+            } else if self.allowlist.get(&att.attester_did).is_none() {
+                Some(InvalidationReason::AttesterRemoved)
+            } else {
+                // The latest entry decides: superseded entries are inactive by design.
+                let latest = validators
+                    .validators
+                    .iter()
+                    .rev()
+                    .find(|v| v.validator_did == att.validator_did);
+                match latest {
+                    Some(v) if !v.is_active => Some(InvalidationReason::ValidatorDeactivated),
+                    Some(v)
+                        if score_payload_hash(&v.validator_did, &v.eco_impact_score)
+                            != att.score_payload_hash =>
+                    {
+                        Some(InvalidationReason::MetricsChanged)
+                    }
+                    _ => None,
+                }
+            };
+
+            if let Some(reason) = reason {
+                att.is_valid = false;
+                appended.push(AttestationInvalidation {
+                    attestation_id: att.attestation_id.clone(),
+                    validator_did: att.validator_did.clone(),
+                    reason,
+                    invalidated_at: now,
+                    row_anchor_height: height,
+                });
+            }
+        }
+        if !appended.is_empty() {
+            self.invalidations.extend(appended.iter().cloned());
+            self.last_updated_height = height;
+        }
+        appended
+    }
+
+    /// Report active validators whose eco score claims `floor` but have no current
+    /// attestation backing that score.
+    pub fn unbacked_floor_claims(
+        &self,
+        validators: &ValidatorSetShard,
+        floor: f32,
+        now: DateTime<Utc>,
+    ) -> Vec<UnbackedFloorClaim> {
+        validators
+            .validators
+            .iter()
+            .filter(|v| v.is_active && v.eco_impact_score.meets_floor(floor))
+            .filter_map(|v| {
+                let latest = self.latest_for_validator(&v.validator_did);
+                let reason = match self.current_for_validator(&v.validator_did, now) {
+                    None => match latest {
+                        None => "no attestation on record".to_string(),
+                        Some(_) => "latest attestation expired or invalidated".to_string(),
+                    },
+                    Some(att) if !att.eco_impact_score.meets_floor(floor) => format!(
+                        "attested score {:.3} is below floor {:.2}",
+                        att.eco_impact_score.value, floor
+                    ),
+                    Some(att)
+                        if att.score_payload_hash
+                            != score_payload_hash(&v.validator_did, &v.eco_impact_score) =>
+                    {
+                        "live metrics differ from attested payload".to_string()
+                    }
+                    Some(_) => return None,
+                };
+                Some(UnbackedFloorClaim {
+                    validator_did: v.validator_did.clone(),
+                    claimed_value: v.eco_impact_score.value,
+                    floor,
+                    latest_attestation_id: latest.map(|a| a.attestation_id.clone()),
+                    reason,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aln::shards::organic_cpu_validator::{OrganicCpuValidator, ValidatorType};
+    use chrono::TimeZone;
+    use ed25519_dalek::SecretKey;
+
+    fn did(s: &str) -> DidUri {
+        serde_json::from_value(serde_json::Value::String(s.to_string())).unwrap()
+    }
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        let public = PublicKey::from(&secret);
+        Keypair { secret, public }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn allowlist(attester: &Keypair) -> AttesterAllowlist {
+        AttesterAllowlist {
+            attesters: vec![AllowedAttester {
+                attester_did: did("did:aln:auditor:1"),
+                public_key_hex: hex::encode(attester.public.to_bytes()),
+                name: "Auditor One".to_string(),
+            }],
+            version: 1,
+        }
+    }
+
+    fn score() -> EcoImpactScore {
+        EcoImpactScore::calculate(0.5, 90.0, 0.1, 0.9)
+    }
+
+    fn attest(keypair: &Keypair, score: &EcoImpactScore) -> EcoAttestation {
+        issue_attestation(
+            "att-1".to_string(),
+            &did("did:aln:validator:1"),
+            &did("did:aln:auditor:1"),
+            score,
+            keypair,
+            now(),
+            100,
+        )
+    }
+
+    fn validator(score: EcoImpactScore) -> ValidatorSetShard {
+        let mut shard = ValidatorSetShard::default();
+        shard.append_validator(
+            OrganicCpuValidator {
+                validator_did: did("did:aln:validator:1"),
+                name: "lab rig".to_string(),
+                validator_type: ValidatorType::LabRig,
+                jurisdiction: "US-AZ".to_string(),
+                energy_envelope_joules_per_day: 1_000,
+                current_energy_usage_joules: 0,
+                eco_impact_score: score,
+                risk_of_harm: 0.1,
+                ker_scoreboard_id: None,
+                uptime_percentage: 99.0,
+                missed_precommits: 0,
+                roh_compliance_score: 0.99,
+                hardware_attestation_hash: String::new(),
+                row_admission_height: 1,
+                last_heartbeat: now(),
+                is_active: true,
+                is_validating: true,
+            },
+            1,
+        );
+        shard
+    }
+
+    #[test]
+    fn issued_attestation_verifies_and_is_current() {
+        let attester = keypair(1);
+        let mut registry = EcoAttestationRegistry::new(allowlist(&attester));
+        let att = attest(&attester, &score());
+
+        assert_eq!(verify_attestation(&att, &registry.allowlist, now()), Ok(()));
+        registry.submit(att, now(), 101).unwrap();
+        assert!(registry.current_for_validator(&did("did:aln:validator:1"), now()).is_some());
+        assert_eq!(registry.last_updated_height, 101);
+    }
+
+    #[test]
+    fn verification_rejects_unlisted_forged_tampered_and_expired() {
+        let attester = keypair(1);
+        let list = allowlist(&attester);
+
+        let mut unlisted = attest(&attester, &score());
+        unlisted.attester_did = did("did:aln:auditor:2");
+        assert_eq!(verify_attestation(&unlisted, &list, now()), Err(AttestationError::AttesterNotAllowed));
+
+        // Listed DID, but signed with a key the allowlist does not hold.
+        let forged = attest(&keypair(2), &score());
+        assert_eq!(verify_attestation(&forged, &list, now()), Err(AttestationError::SignatureMismatch));
+
+        let mut tampered = attest(&attester, &score());
+        tampered.eco_impact_score.value = 0.99;
+        assert_eq!(verify_attestation(&tampered, &list, now()), Err(AttestationError::PayloadHashMismatch));
+
+        let att = attest(&attester, &score());
+        let later = now() + Duration::days(ATTESTATION_VALIDITY_DAYS);
+        assert_eq!(verify_attestation(&att, &list, later), Err(AttestationError::Expired));
+
+        let mut invalidated = attest(&attester, &score());
+        invalidated.is_valid = false;
+        assert_eq!(verify_attestation(&invalidated, &list, now()), Err(AttestationError::Invalidated));
+    }
+
+    #[test]
+    fn payload_ignores_calculation_time() {
+        let a = score();
+        let mut b = a.clone();
+        b.last_calculated = a.last_calculated + Duration::hours(6);
+        let v = did("did:aln:validator:1");
+        assert_eq!(score_payload_hash(&v, &a), score_payload_hash(&v, &b));
+    }
+
+    #[test]
+    fn sweep_invalidates_only_on_real_changes() {
+        let attester = keypair(1);
+        let mut registry = EcoAttestationRegistry::new(allowlist(&attester));
+        registry.submit(attest(&attester, &score()), now(), 101).unwrap();
+
+        // Same metrics recalculated later: not a change.
+        let mut recomputed = score();
+        recomputed.last_calculated = now() + Duration::hours(1);
+        assert!(registry.sweep(&validator(recomputed), now(), 102).is_empty());
+
+        let changed = EcoImpactScore::calculate(0.5, 60.0, 0.1, 0.9);
+        let swept = registry.sweep(&validator(changed), now(), 103);
+        assert_eq!(swept.len(), 1);
+        assert_eq!(swept[0].reason, InvalidationReason::MetricsChanged);
+        assert_eq!(registry.invalidations.len(), 1);
+        // Already invalid: a second sweep appends nothing.
+        assert!(registry.sweep(&validator(score()), now(), 104).is_empty());
+    }
+
+    #[test]
+    fn sweep_reports_expiry_and_removed_attesters() {
+        let attester = keypair(1);
+        let mut registry = EcoAttestationRegistry::new(allowlist(&attester));
+        registry.submit(attest(&attester, &score()), now(), 101).unwrap();
+        let later = now() + Duration::days(ATTESTATION_VALIDITY_DAYS + 1);
+        assert_eq!(registry.sweep(&validator(score()), later, 102)[0].reason, InvalidationReason::Expired);
+
+        let mut registry = EcoAttestationRegistry::new(allowlist(&attester));
+        registry.submit(attest(&attester, &score()), now(), 101).unwrap();
+        registry.allowlist = AttesterAllowlist { attesters: Vec::new(), version: 2 };
+        assert_eq!(
+            registry.sweep(&validator(score()), now(), 102)[0].reason,
+            InvalidationReason::AttesterRemoved
+        );
+    }
+
+    #[test]
+    fn sweep_invalidates_deactivated_validators() {
+        let attester = keypair(1);
+        let mut registry = EcoAttestationRegistry::new(allowlist(&attester));
+        registry.submit(attest(&attester, &score()), now(), 101).unwrap();
+
+        let mut validators = validator(score());
+        validators.validators[0].is_active = false;
+        let swept = registry.sweep(&validators, now(), 102);
+        assert_eq!(swept.len(), 1);
+        assert_eq!(swept[0].reason, InvalidationReason::ValidatorDeactivated);
+        assert!(registry.current_for_validator(&did("did:aln:validator:1"), now()).is_none());
+    }
+
+    #[test]
+    fn unbacked_claims_are_reported() {
+        let attester = keypair(1);
+        let mut registry = EcoAttestationRegistry::new(allowlist(&attester));
+        let validators = validator(score());
+        let claims = registry.unbacked_floor_claims(&validators, 0.86, now());
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].latest_attestation_id, None);
+
+        registry.submit(attest(&attester, &score()), now(), 101).unwrap();
+        assert!(registry.unbacked_floor_claims(&validators, 0.86, now()).is_empty());
+    }
+}
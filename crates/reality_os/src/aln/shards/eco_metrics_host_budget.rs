@@ -150,11 +150,22 @@ pub struct EcoAttestation {
     pub row_anchor_height: u64,
     /// Forward-only: true if currently valid
     pub is_valid: bool,
+    /// SHA-256 (hex) of the canonical eco-score payload this attestation covers
+    #[serde(default)]
+    pub score_payload_hash: String,
+    /// Attester ed25519 signature (hex) over the canonical eco-score payload
+    #[serde(default)]
+    pub attester_signature: String,
 }
 
 impl EcoAttestation {
     /// Check if attestation is still valid (not expired)
     pub fn is_currently_valid(&self) -> bool {
-        self.is_valid && Utc::now() < self.expiry_time
+        self.is_valid_at(Utc::now())
+    }
+
+    /// Check if attestation is valid at a given time
+    pub fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        self.is_valid && now < self.expiry_time
     }
 }
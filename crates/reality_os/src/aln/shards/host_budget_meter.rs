@@ -0,0 +1,511 @@
+use serde::{Deserialize, Serialize};
+use crate::did::DidUri;
+use crate::aln::shards::eco_metrics_host_budget::HostBudget;
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use std::fmt;
+
+/// Metered resource classes tracked by a HostBudget
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum MeteredResource {
+    /// Energy (millijoules)
+    Energy,
+    /// Compute (gas units)
+    Compute,
+    /// Bandwidth (bytes)
+    Bandwidth,
+}
+
+/// A single usage event reported by a host device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageEvent {
+    /// Unique event ID (UUID v7)
+    pub event_id: String,
+    /// Citizen DID
+    pub citizen_did: DidUri,
+    /// Host device DID that consumed the resource
+    pub host_device_did: DidUri,
+    /// Resource consumed
+    pub resource: MeteredResource,
+    /// Amount consumed, in the resource's budget unit
+    pub amount: u64,
+    /// Timestamp of consumption
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Soft-warning and hard-deny thresholds (percent of daily budget)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeterThresholds {
+    /// Warning thresholds, ascending (e.g. 75%, 90%)
+    pub soft_warning_pct: Vec<f32>,
+    /// Usage that would exceed this percentage is denied (normally 100%)
+    pub hard_deny_pct: f32,
+}
+
+impl Default for MeterThresholds {
+    fn default() -> Self {
+        Self {
+            soft_warning_pct: vec![75.0, 90.0],
+            hard_deny_pct: 100.0,
+        }
+    }
+}
+
+/// Outcome of metering a single usage event
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum MeterDecision {
+    /// Usage recorded, below all warning thresholds
+    Allowed,
+    /// Usage recorded, but a warning threshold was crossed by this event
+    SoftWarning {
+        resource: MeteredResource,
+        usage_pct: f32,
+        threshold_pct: f32,
+    },
+    /// Usage rejected: it would exhaust the budget
+    Denied {
+        resource: MeteredResource,
+        reason: String,
+    },
+}
+
+impl MeterDecision {
+    /// True if the usage was debited from the budget
+    pub fn is_recorded(&self) -> bool {
+        !matches!(self, MeterDecision::Denied { .. })
+    }
+}
+
+/// Audit log entry: one per metered event (append-only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageAuditEntry {
+    /// The usage event as reported
+    pub event: UsageEvent,
+    /// Decision applied to the event
+    pub decision: MeterDecision,
+    /// ROW anchor height of the budget the event was metered against
+    pub budget_anchor_height: u64,
+    /// Usage counter for the resource after the event was applied
+    pub usage_after: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum MeterError {
+    /// No current budget for the citizen/device pair
+    NoCurrentBudget,
+}
+
+impl fmt::Display for MeterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeterError::NoCurrentBudget => write!(f, "no current budget for the citizen/device pair"),
+        }
+    }
+}
+
+impl std::error::Error for MeterError {}
+
+/// Host budget metering service.
+/// Budgets are append-only: rollover creates a new record and retires the old one.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HostBudgetMeter {
+    pub budgets: Vec<HostBudget>,
+    pub audit_log: Vec<UsageAuditEntry>,
+    pub thresholds: MeterThresholds,
+}
+
+impl HostBudgetMeter {
+    pub fn new(thresholds: MeterThresholds) -> Self {
+        Self {
+            budgets: Vec::new(),
+            audit_log: Vec::new(),
+            thresholds,
+        }
+    }
+
+    /// Register a budget (forward-only, retires previous current budget for the pair)
+    pub fn register_budget(&mut self, budget: HostBudget) {
+        for b in self.budgets.iter_mut() {
+            if b.citizen_did == budget.citizen_did
+                && b.host_device_did == budget.host_device_did
+            {
+                b.is_current = false;
+            }
+        }
+        self.budgets.push(budget);
+    }
+
+    /// Get the current budget for a citizen/device pair
+    pub fn current_budget(&self, citizen_did: &DidUri, host_device_did: &DidUri) -> Option<&HostBudget> {
+        self.budgets.iter().rev().find(|b| {
+            b.is_current && b.citizen_did == *citizen_did && b.host_device_did == *host_device_did
+        })
+    }
+
+    /// The budget for the day `at` falls in: the newest record for the
+    /// pair with the earliest reset time after `at`. A late event that
+    /// arrives after a rollover is debited against the day it happened on.
+    fn budget_at_mut(
+        &mut self,
+        citizen_did: &DidUri,
+        host_device_did: &DidUri,
+        at: DateTime<Utc>,
+    ) -> Option<&mut HostBudget> {
+        let is_pair = |b: &HostBudget| b.citizen_did == *citizen_did && b.host_device_did == *host_device_did;
+        let day_end = self
+            .budgets
+            .iter()
+            .filter(|b| is_pair(b) && at < b.budget_reset_time)
+            .map(|b| b.budget_reset_time)
+            .min()?;
+        self.budgets
+            .iter_mut()
+            .rev()
+            .find(|b| is_pair(b) && b.budget_reset_time == day_end)
+    }
+
+    /// Meter a usage event against the budget for the day it happened on.
+    /// Rolls the budget over first if `budget_reset_time` has passed.
+    pub fn record_usage(&mut self, event: UsageEvent, height: u64) -> Result<MeterDecision, MeterError> {
+        self.rollover_due(event.timestamp, height);
+
+        let thresholds = self.thresholds.clone();
+        if self.current_budget(&event.citizen_did, &event.host_device_did).is_none() {
+            return Err(MeterError::NoCurrentBudget);
+        }
+        let budget = self
+            .budget_at_mut(&event.citizen_did, &event.host_device_did, event.timestamp)
+            .ok_or(MeterError::NoCurrentBudget)?;
+
+        let (used, limit) = usage_and_limit(budget, event.resource);
+        let after = used.saturating_add(event.amount);
+        let pct_before = usage_percentage(used, limit);
+        let pct_after = usage_percentage(after, limit);
+
+        // The hard deny is decided in integers; f32 percentages round large
+        // limits (e.g. 100_000_001 / 100_000_000 == 100.0) and would overdraft.
+        let exceeds_cap = used as u128 + event.amount as u128 > hard_cap(limit, thresholds.hard_deny_pct);
+        let decision = if limit == 0 || exceeds_cap {
+            MeterDecision::Denied {
+                resource: event.resource,
+                reason: format!(
+                    "{:?} budget exhausted: {} + {} exceeds daily limit {}",
+                    event.resource, used, event.amount, limit
+                ),
+            }
+        } else {
+            // Report the highest warning threshold crossed by this event.
+            let crossed = thresholds
+                .soft_warning_pct
+                .iter()
+                .copied()
+                .filter(|t| pct_before < *t && pct_after >= *t)
+                .fold(None, |acc: Option<f32>, t| Some(acc.map_or(t, |a| a.max(t))));
+            match crossed {
+                Some(threshold_pct) => MeterDecision::SoftWarning {
+                    resource: event.resource,
+                    usage_pct: pct_after,
+                    threshold_pct,
+                },
+                None => MeterDecision::Allowed,
+            }
+        };
+
+        if decision.is_recorded() {
+            debit(budget, event.resource, event.amount);
+        }
+        let usage_after = usage_and_limit(budget, event.resource).0;
+        let budget_anchor_height = budget.row_anchor_height;
+
+        self.audit_log.push(UsageAuditEntry {
+            event,
+            decision: decision.clone(),
+            budget_anchor_height,
+            usage_after,
+        });
+        Ok(decision)
+    }
+
+    /// Roll over every current budget whose reset time has passed.
+    /// New records keep the same daily limits and reset at the next UTC midnight.
+    pub fn rollover_due(&mut self, now: DateTime<Utc>, height: u64) -> usize {
+        let due: Vec<HostBudget> = self
+            .budgets
+            .iter()
+            .filter(|b| b.is_current && now >= b.budget_reset_time)
+            .cloned()
+            .collect();
+
+        for base in &due {
+            let mut next = HostBudget::create_new_budget(
+                base,
+                base.daily_energy_budget_mj,
+                base.daily_compute_budget,
+                base.daily_bandwidth_budget_bytes,
+                height.max(base.row_anchor_height + 1),
+            );
+            next.budget_reset_time = next_utc_midnight(now);
+            self.register_budget(next);
+        }
+        due.len()
+    }
+
+    /// Get audit entries for a citizen
+    pub fn audit_for_citizen(&self, citizen_did: &DidUri) -> Vec<&UsageAuditEntry> {
+        self.audit_log
+            .iter()
+            .filter(|e| e.event.citizen_did == *citizen_did)
+            .collect()
+    }
+
+    /// Get budget history (oldest first) for a citizen/device pair
+    pub fn budget_history(&self, citizen_did: &DidUri, host_device_did: &DidUri) -> Vec<&HostBudget> {
+        self.budgets
+            .iter()
+            .filter(|b| b.citizen_did == *citizen_did && b.host_device_did == *host_device_did)
+            .collect()
+    }
+}
+
+fn usage_and_limit(budget: &HostBudget, resource: MeteredResource) -> (u64, u64) {
+    match resource {
+        MeteredResource::Energy => (budget.current_energy_usage_mj, budget.daily_energy_budget_mj),
+        MeteredResource::Compute => (budget.current_compute_usage, budget.daily_compute_budget),
+        MeteredResource::Bandwidth => (
+            budget.current_bandwidth_usage_bytes,
+            budget.daily_bandwidth_budget_bytes,
+        ),
+    }
+}
+
+fn debit(budget: &mut HostBudget, resource: MeteredResource, amount: u64) {
+    let counter = match resource {
+        MeteredResource::Energy => &mut budget.current_energy_usage_mj,
+        MeteredResource::Compute => &mut budget.current_compute_usage,
+        MeteredResource::Bandwidth => &mut budget.current_bandwidth_usage_bytes,
+    };
+    *counter = counter.saturating_add(amount);
+}
+
+/// Highest usage allowed under `hard_deny_pct`, in budget units.
+fn hard_cap(limit: u64, hard_deny_pct: f32) -> u128 {
+    let basis_points = (hard_deny_pct.max(0.0) * 100.0).round() as u128;
+    limit as u128 * basis_points / 10_000
+}
+
+fn usage_percentage(used: u64, limit: u64) -> f32 {
+    if limit == 0 {
+        return 100.0;
+    }
+    (used as f32 / limit as f32) * 100.0
+}
+
+/// Next UTC midnight strictly after `now`
+pub fn next_utc_midnight(now: DateTime<Utc>) -> DateTime<Utc> {
+    let tomorrow = now.date_naive() + Duration::days(1);
+    tomorrow.and_time(NaiveTime::MIN).and_utc()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn did(s: &str) -> DidUri {
+        serde_json::from_value(serde_json::Value::String(s.to_string())).unwrap()
+    }
+
+    fn t(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, hour, 0, 0).unwrap()
+    }
+
+    fn budget(energy_mj: u64, reset: DateTime<Utc>, height: u64) -> HostBudget {
+        HostBudget {
+            citizen_did: did("did:aln:citizen:1"),
+            host_device_did: did("did:aln:device:1"),
+            daily_energy_budget_mj: energy_mj,
+            current_energy_usage_mj: 0,
+            daily_compute_budget: 1_000,
+            current_compute_usage: 0,
+            daily_bandwidth_budget_bytes: 1_000,
+            current_bandwidth_usage_bytes: 0,
+            budget_reset_time: reset,
+            row_anchor_height: height,
+            is_current: true,
+        }
+    }
+
+    fn usage(id: &str, amount: u64, at: DateTime<Utc>) -> UsageEvent {
+        UsageEvent {
+            event_id: id.to_string(),
+            citizen_did: did("did:aln:citizen:1"),
+            host_device_did: did("did:aln:device:1"),
+            resource: MeteredResource::Energy,
+            amount,
+            timestamp: at,
+        }
+    }
+
+    fn meter(energy_mj: u64) -> HostBudgetMeter {
+        let mut meter = HostBudgetMeter::new(MeterThresholds::default());
+        meter.register_budget(budget(energy_mj, t(23), 10));
+        meter
+    }
+
+    fn energy_used(meter: &HostBudgetMeter) -> u64 {
+        meter
+            .current_budget(&did("did:aln:citizen:1"), &did("did:aln:device:1"))
+            .unwrap()
+            .current_energy_usage_mj
+    }
+
+    #[test]
+    fn soft_warnings_fire_once_per_threshold() {
+        let mut meter = meter(1_000);
+        assert_eq!(meter.record_usage(usage("a", 700, t(1)), 11).unwrap(), MeterDecision::Allowed);
+
+        match meter.record_usage(usage("b", 100, t(2)), 11).unwrap() {
+            MeterDecision::SoftWarning { threshold_pct, .. } => assert_eq!(threshold_pct, 75.0),
+            other => panic!("expected 75% warning, got {:?}", other),
+        }
+        // Still between 75% and 90%: no repeat warning.
+        assert_eq!(meter.record_usage(usage("c", 50, t(3)), 11).unwrap(), MeterDecision::Allowed);
+
+        match meter.record_usage(usage("d", 100, t(4)), 11).unwrap() {
+            MeterDecision::SoftWarning { threshold_pct, usage_pct, .. } => {
+                assert_eq!(threshold_pct, 90.0);
+                assert_eq!(usage_pct, 95.0);
+            }
+            other => panic!("expected 90% warning, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn crossing_several_thresholds_reports_the_highest() {
+        let mut meter = meter(1_000);
+        match meter.record_usage(usage("a", 950, t(1)), 11).unwrap() {
+            MeterDecision::SoftWarning { threshold_pct, .. } => assert_eq!(threshold_pct, 90.0),
+            other => panic!("expected 90% warning, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn hard_deny_allows_exactly_the_limit() {
+        let mut meter = meter(1_000);
+        assert!(meter.record_usage(usage("a", 1_000, t(1)), 11).unwrap().is_recorded());
+        let denied = meter.record_usage(usage("b", 1, t(2)), 11).unwrap();
+        assert!(matches!(denied, MeterDecision::Denied { resource: MeteredResource::Energy, .. }));
+        assert_eq!(energy_used(&meter), 1_000);
+    }
+
+    #[test]
+    fn hard_deny_holds_at_large_limits() {
+        // 100_000_001 / 100_000_000 rounds to exactly 100.0 in f32.
+        let mut large = meter(100_000_000);
+        assert!(large.record_usage(usage("a", 100_000_000, t(1)), 11).unwrap().is_recorded());
+        assert!(!large.record_usage(usage("b", 1, t(2)), 11).unwrap().is_recorded());
+        assert_eq!(energy_used(&large), 100_000_000);
+
+        let mut maxed = meter(u64::MAX);
+        assert!(maxed.record_usage(usage("a", u64::MAX, t(1)), 11).unwrap().is_recorded());
+        assert!(!maxed.record_usage(usage("b", 1, t(2)), 11).unwrap().is_recorded());
+    }
+
+    #[test]
+    fn zero_limit_denies_and_missing_budget_errors() {
+        let mut meter = meter(0);
+        assert!(!meter.record_usage(usage("a", 1, t(1)), 11).unwrap().is_recorded());
+
+        let mut empty = HostBudgetMeter::default();
+        assert!(matches!(
+            empty.record_usage(usage("a", 1, t(1)), 11),
+            Err(MeterError::NoCurrentBudget)
+        ));
+    }
+
+    #[test]
+    fn rollover_retires_the_old_budget_and_resets_usage() {
+        let mut meter = meter(1_000);
+        meter.record_usage(usage("a", 900, t(1)), 11).unwrap();
+
+        // Past the 23:00 reset time: the event is metered against a fresh budget.
+        let next_day = t(23) + Duration::hours(2);
+        assert_eq!(meter.record_usage(usage("b", 500, next_day), 5).unwrap(), MeterDecision::Allowed);
+
+        let history = meter.budget_history(&did("did:aln:citizen:1"), &did("did:aln:device:1"));
+        assert_eq!(history.len(), 2);
+        assert!(!history[0].is_current);
+        assert_eq!(history[0].current_energy_usage_mj, 900);
+
+        let current = history[1];
+        assert!(current.is_current);
+        assert_eq!(current.current_energy_usage_mj, 500);
+        assert_eq!(current.daily_energy_budget_mj, 1_000);
+        // Anchor height never goes backwards even if the caller passes a stale one.
+        assert_eq!(current.row_anchor_height, 11);
+        assert_eq!(current.budget_reset_time, next_utc_midnight(next_day));
+
+        // Not due again until the next midnight.
+        assert_eq!(meter.rollover_due(next_day, 12), 0);
+    }
+
+    #[test]
+    fn late_events_are_debited_to_their_own_day() {
+        let mut meter = meter(1_000);
+        meter.record_usage(usage("a", 900, t(1)), 11).unwrap();
+        let next_day = t(23) + Duration::hours(2);
+        meter.record_usage(usage("b", 100, next_day), 12).unwrap();
+
+        // Reported after the rollover, but consumed before the boundary:
+        // it counts against yesterday's budget, which it would overdraw.
+        let late = meter.record_usage(usage("c", 200, t(22)), 12).unwrap();
+        assert!(!late.is_recorded());
+        let fits = meter.record_usage(usage("d", 50, t(22)), 12).unwrap();
+        assert!(fits.is_recorded());
+
+        let history = meter.budget_history(&did("did:aln:citizen:1"), &did("did:aln:device:1"));
+        assert_eq!(history[0].current_energy_usage_mj, 950);
+        assert_eq!(history[1].current_energy_usage_mj, 100);
+        assert_eq!(meter.audit_log[3].budget_anchor_height, 10);
+    }
+
+    #[test]
+    fn replacing_a_budget_mid_day_meters_against_the_replacement() {
+        let mut meter = meter(1_000);
+        meter.register_budget(budget(2_000, t(23), 11));
+        assert!(meter.record_usage(usage("a", 1_500, t(1)), 11).unwrap().is_recorded());
+        assert_eq!(energy_used(&meter), 1_500);
+    }
+
+    #[test]
+    fn meter_error_displays() {
+        assert_eq!(MeterError::NoCurrentBudget.to_string(), "no current budget for the citizen/device pair");
+    }
+
+    #[test]
+    fn audit_log_records_every_event_including_denials() {
+        let mut meter = meter(1_000);
+        meter.record_usage(usage("a", 800, t(1)), 11).unwrap();
+        meter.record_usage(usage("b", 500, t(2)), 11).unwrap();
+
+        let mut other = usage("c", 10, t(3));
+        other.citizen_did = did("did:aln:citizen:2");
+        other.host_device_did = did("did:aln:device:2");
+        meter.register_budget(HostBudget {
+            citizen_did: did("did:aln:citizen:2"),
+            host_device_did: did("did:aln:device:2"),
+            ..budget(1_000, t(23), 10)
+        });
+        meter.record_usage(other, 11).unwrap();
+
+        assert_eq!(meter.audit_log.len(), 3);
+        let entries = meter.audit_for_citizen(&did("did:aln:citizen:1"));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].event.event_id, "a");
+        assert_eq!(entries[0].usage_after, 800);
+        assert!(matches!(entries[0].decision, MeterDecision::SoftWarning { .. }));
+        assert_eq!(entries[1].event.event_id, "b");
+        assert_eq!(entries[1].usage_after, 800);
+        assert!(!entries[1].decision.is_recorded());
+        assert_eq!(entries[1].budget_anchor_height, 10);
+    }
+}
@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Tolerance for the weights-sum-to-one check.
+const WEIGHT_SUM_EPSILON: f32 = 1e-3;
+
+/// One governance-approved version of the CAC axis weights.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CacWeights {
+    /// Monotonic weight-set version.
+    pub version: u32,
+    /// Weight of the safety axis.
+    pub w_safety: f32,
+    /// Weight of the learning axis.
+    pub w_learning: f32,
+    /// Weight of the care axis.
+    pub w_care: f32,
+    /// First ledger height at which this version applies.
+    pub effective_from_height: u64,
+    /// Governance proposal or vote reference that approved this version.
+    #[serde(default)]
+    pub approved_by: Option<String>,
+}
+
+impl CacWeights {
+    /// The original 0.4 / 0.35 / 0.25 weighting.
+    pub fn genesis() -> Self {
+        Self {
+            version: 1,
+            w_safety: 0.4,
+            w_learning: 0.35,
+            w_care: 0.25,
+            effective_from_height: 0,
+            approved_by: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CacWeightConfigError {
+    Io(String),
+    Parse(String),
+    Empty,
+    NegativeWeight { version: u32 },
+    /// A weight is NaN or infinite.
+    NonFiniteWeight { version: u32 },
+    WeightsDoNotSumToOne { version: u32, sum: f32 },
+    /// Versions and effective heights must both strictly increase.
+    NonMonotonic { version: u32 },
+}
+
+/// Versioned CAC weight table, loaded from governance config
+/// (e.g. `config/realityos/cac_weights.toml`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacWeightConfig {
+    pub weights: Vec<CacWeights>,
+}
+
+impl Default for CacWeightConfig {
+    fn default() -> Self {
+        Self { weights: vec![CacWeights::genesis()] }
+    }
+}
+
+impl CacWeightConfig {
+    pub fn from_toml_str(raw: &str) -> Result<Self, CacWeightConfigError> {
+        let cfg: CacWeightConfig =
+            toml::from_str(raw).map_err(|e| CacWeightConfigError::Parse(e.to_string()))?;
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CacWeightConfigError> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| CacWeightConfigError::Io(e.to_string()))?;
+        Self::from_toml_str(&raw)
+    }
+
+    /// Reject non-finite or negative weights, sums other than 1, and out-of-order versions.
+    pub fn validate(&self) -> Result<(), CacWeightConfigError> {
+        if self.weights.is_empty() {
+            return Err(CacWeightConfigError::Empty);
+        }
+        let mut prev: Option<&CacWeights> = None;
+        for w in &self.weights {
+            if !(w.w_safety.is_finite() && w.w_learning.is_finite() && w.w_care.is_finite()) {
+                return Err(CacWeightConfigError::NonFiniteWeight { version: w.version });
+            }
+            if w.w_safety < 0.0 || w.w_learning < 0.0 || w.w_care < 0.0 {
+                return Err(CacWeightConfigError::NegativeWeight { version: w.version });
+            }
+            let sum = w.w_safety + w.w_learning + w.w_care;
+            if (sum - 1.0).abs() > WEIGHT_SUM_EPSILON {
+                return Err(CacWeightConfigError::WeightsDoNotSumToOne { version: w.version, sum });
+            }
+            if let Some(p) = prev {
+                if w.version <= p.version || w.effective_from_height <= p.effective_from_height {
+                    return Err(CacWeightConfigError::NonMonotonic { version: w.version });
+                }
+            }
+            prev = Some(w);
+        }
+        Ok(())
+    }
+
+    /// Weight set by explicit version.
+    pub fn get(&self, version: u32) -> Option<&CacWeights> {
+        self.weights.iter().find(|w| w.version == version)
+    }
+
+    /// Weight set in force at a ledger height.
+    pub fn active_at(&self, height: u64) -> Option<&CacWeights> {
+        self.weights
+            .iter()
+            .rev()
+            .find(|w| w.effective_from_height <= height)
+    }
+
+    /// Most recent weight set.
+    pub fn latest(&self) -> Option<&CacWeights> {
+        self.weights.last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHIPPED: &str = include_str!("../../../../config/realityos/cac_weights.toml");
+
+    fn table(rows: &str) -> Result<CacWeightConfig, CacWeightConfigError> {
+        CacWeightConfig::from_toml_str(rows)
+    }
+
+    #[test]
+    fn shipped_config_parses_to_genesis() {
+        let cfg = table(SHIPPED).unwrap();
+        let latest = cfg.latest().unwrap();
+        assert_eq!(latest.version, 1);
+        assert_eq!((latest.w_safety, latest.w_learning, latest.w_care), (0.40, 0.35, 0.25));
+        assert_eq!(cfg.active_at(0).unwrap().version, 1);
+    }
+
+    #[test]
+    fn versions_resolve_by_height() {
+        let cfg = table(
+            "[[weights]]\nversion = 1\nw_safety = 0.4\nw_learning = 0.35\nw_care = 0.25\neffective_from_height = 0\n\
+             [[weights]]\nversion = 2\nw_safety = 0.5\nw_learning = 0.25\nw_care = 0.25\neffective_from_height = 100\napproved_by = \"prop-7\"\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.active_at(99).unwrap().version, 1);
+        assert_eq!(cfg.active_at(100).unwrap().version, 2);
+        assert_eq!(cfg.get(2).unwrap().approved_by.as_deref(), Some("prop-7"));
+    }
+
+    #[test]
+    fn invalid_tables_are_rejected() {
+        assert!(matches!(table("weights = []"), Err(CacWeightConfigError::Empty)));
+        assert!(matches!(table("weights = 3"), Err(CacWeightConfigError::Parse(_))));
+        assert!(matches!(
+            table("[[weights]]\nversion = 1\nw_safety = -0.2\nw_learning = 0.7\nw_care = 0.5\neffective_from_height = 0\n"),
+            Err(CacWeightConfigError::NegativeWeight { version: 1 })
+        ));
+        assert!(matches!(
+            table("[[weights]]\nversion = 1\nw_safety = 0.5\nw_learning = 0.5\nw_care = 0.5\neffective_from_height = 0\n"),
+            Err(CacWeightConfigError::WeightsDoNotSumToOne { version: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn non_finite_weights_are_rejected() {
+        for bad in ["nan", "inf", "-inf"] {
+            let raw = format!(
+                "[[weights]]\nversion = 1\nw_safety = {bad}\nw_learning = 0.35\nw_care = 0.25\neffective_from_height = 0\n"
+            );
+            assert!(matches!(table(&raw), Err(CacWeightConfigError::NonFiniteWeight { version: 1 })));
+        }
+    }
+
+    #[test]
+    fn non_monotonic_versions_are_rejected() {
+        let mut cfg = CacWeightConfig::default();
+        let mut next = CacWeights::genesis();
+        next.version = 2;
+        cfg.weights.push(next.clone());
+        assert!(matches!(cfg.validate(), Err(CacWeightConfigError::NonMonotonic { version: 2 })));
+
+        next.effective_from_height = 10;
+        next.version = 1;
+        cfg.weights[1] = next;
+        assert!(matches!(cfg.validate(), Err(CacWeightConfigError::NonMonotonic { version: 1 })));
+    }
+
+    #[test]
+    fn load_reports_missing_files() {
+        let missing = std::env::temp_dir().join("cac-weights-does-not-exist.toml");
+        assert!(matches!(CacWeightConfig::load(&missing), Err(CacWeightConfigError::Io(_))));
+    }
+}
@@ -0,0 +1,410 @@
+use ed25519_dalek::{Keypair, PublicKey, Signature, Signer, Verifier};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use crate::reputation::cac_weights::{CacWeightConfig, CacWeights};
+use crate::reputation::soul_card::SoulReputationCard;
+
+/// A SoulReputationCard snapshot as anchored, with the weight version used
+/// to compute its CAC index and the signer's ed25519 signature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedCardSnapshot {
+    pub card: SoulReputationCard,
+    /// CAC weight version the stored `cac_index` was computed under.
+    pub weights_version: u32,
+    /// SHA-256 (hex) of the canonical snapshot payload.
+    pub payload_hash: String,
+    /// DID of the signer (SovereigntyCore or an accredited issuer).
+    pub signer_did: String,
+    /// Signer public key (hex, 32 bytes). Informational only: verification
+    /// uses the key the trusted-signer allowlist holds for `signer_did`.
+    pub signer_public_key: String,
+    /// ed25519 signature (hex) over the canonical payload.
+    pub signature: String,
+}
+
+/// Badge for one historical snapshot under two weight versions.
+#[derive(Debug, Clone, Serialize)]
+pub struct BadgeComparison {
+    pub last_anchored_height: u64,
+    pub stored_weights_version: u32,
+    pub stored_cac_index: f32,
+    pub stored_grade: &'static str,
+    pub candidate_weights_version: u32,
+    pub candidate_cac_index: f32,
+    pub candidate_grade: &'static str,
+}
+
+impl BadgeComparison {
+    pub fn grade_changed(&self) -> bool {
+        self.stored_grade != self.candidate_grade
+    }
+}
+
+/// Issuer accredited to sign SoulCard snapshots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedSigner {
+    /// Signer DID
+    pub signer_did: String,
+    /// ed25519 public key (hex, 32 bytes)
+    pub public_key_hex: String,
+    /// Human-readable issuer name
+    pub name: String,
+}
+
+/// Trusted-signer allowlist (append-only; revocation is a new list version)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TrustedSignerAllowlist {
+    pub signers: Vec<TrustedSigner>,
+    pub version: u32,
+}
+
+impl TrustedSignerAllowlist {
+    /// Look up a signer by DID
+    pub fn get(&self, signer_did: &str) -> Option<&TrustedSigner> {
+        self.signers.iter().find(|s| s.signer_did == signer_did)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SnapshotError {
+    /// `last_anchored_height` must strictly increase per DID.
+    NonMonotonicHeight { did: String, previous: u64, attempted: u64 },
+    /// The snapshot's `cac_index` does not match its declared weight version.
+    CacIndexMismatch { expected: f32, found: f32 },
+    UnknownWeightsVersion(u32),
+    /// The declared weight version is not the one in force at the snapshot's height.
+    WeightsVersionNotActive { declared: u32, active: Option<u32> },
+    /// `signer_did` is not on the trusted-signer allowlist.
+    SignerNotTrusted(String),
+    /// Allowlisted public key could not be parsed.
+    InvalidSignerKey,
+    InvalidSignature,
+    PayloadHashMismatch,
+    UnknownDid(String),
+}
+
+/// Canonical, fixed-precision payload signed for a snapshot. The DID is
+/// JSON-escaped so a `|` inside it cannot shift the fields that follow.
+pub fn canonical_snapshot_payload(card: &SoulReputationCard, weights_version: u32) -> String {
+    format!(
+        "soul-card.v2|{}|{:.6}|{}|{:.6}|{:.6}|{:.6}|{:.6}|{}|{}|w{}",
+        serde_json::to_string(&card.did).unwrap_or_default(),
+        card.validated_hours,
+        card.missions_completed,
+        card.safety_score,
+        card.learning_score,
+        card.care_score,
+        card.cac_index,
+        card.incidents_reported,
+        card.last_anchored_height,
+        weights_version,
+    )
+}
+
+fn payload_hash(payload: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(payload.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Sign a card snapshot. The card's `cac_index` is recomputed under `weights`
+/// first so the signature always covers a consistent index.
+pub fn sign_snapshot(
+    mut card: SoulReputationCard,
+    weights: &CacWeights,
+    signer_did: &str,
+    keypair: &Keypair,
+) -> SignedCardSnapshot {
+    card.recompute_cac_index_with(weights);
+    let payload = canonical_snapshot_payload(&card, weights.version);
+    let signature = keypair.sign(payload.as_bytes());
+    SignedCardSnapshot {
+        card,
+        weights_version: weights.version,
+        payload_hash: payload_hash(&payload),
+        signer_did: signer_did.to_string(),
+        signer_public_key: hex::encode(keypair.public.to_bytes()),
+        signature: hex::encode(signature.to_bytes()),
+    }
+}
+
+/// Verify a snapshot's hash and its signature under the allowlisted key for
+/// `signer_did`. The key carried in the snapshot is never trusted.
+pub fn verify_snapshot(
+    snapshot: &SignedCardSnapshot,
+    signers: &TrustedSignerAllowlist,
+) -> Result<(), SnapshotError> {
+    let signer = signers
+        .get(&snapshot.signer_did)
+        .ok_or_else(|| SnapshotError::SignerNotTrusted(snapshot.signer_did.clone()))?;
+    let key_bytes = hex::decode(&signer.public_key_hex).map_err(|_| SnapshotError::InvalidSignerKey)?;
+    let public_key = PublicKey::from_bytes(&key_bytes).map_err(|_| SnapshotError::InvalidSignerKey)?;
+
+    let payload = canonical_snapshot_payload(&snapshot.card, snapshot.weights_version);
+    if payload_hash(&payload) != snapshot.payload_hash {
+        return Err(SnapshotError::PayloadHashMismatch);
+    }
+    let sig_bytes = hex::decode(&snapshot.signature).map_err(|_| SnapshotError::InvalidSignature)?;
+    let signature = Signature::from_bytes(&sig_bytes).map_err(|_| SnapshotError::InvalidSignature)?;
+    public_key
+        .verify(payload.as_bytes(), &signature)
+        .map_err(|_| SnapshotError::InvalidSignature)
+}
+
+/// Forward-only snapshot history per DID.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SoulCardSnapshotStore {
+    /// Issuers whose signatures `append` accepts.
+    #[serde(default)]
+    pub signers: TrustedSignerAllowlist,
+    pub history: BTreeMap<String, Vec<SignedCardSnapshot>>,
+}
+
+impl SoulCardSnapshotStore {
+    pub fn new(signers: TrustedSignerAllowlist) -> Self {
+        Self {
+            signers,
+            history: BTreeMap::new(),
+        }
+    }
+
+    /// Append a verified snapshot. Rejects untrusted signers, heights that do
+    /// not strictly increase, weight versions not in force at the snapshot's
+    /// height, and CAC indices that do not match the declared weight version.
+    pub fn append(
+        &mut self,
+        snapshot: SignedCardSnapshot,
+        weights: &CacWeightConfig,
+    ) -> Result<(), SnapshotError> {
+        verify_snapshot(&snapshot, &self.signers)?;
+
+        let w = weights
+            .get(snapshot.weights_version)
+            .ok_or(SnapshotError::UnknownWeightsVersion(snapshot.weights_version))?;
+        let active = weights
+            .active_at(snapshot.card.last_anchored_height)
+            .map(|a| a.version);
+        if active != Some(w.version) {
+            return Err(SnapshotError::WeightsVersionNotActive {
+                declared: w.version,
+                active,
+            });
+        }
+        let mut expected = snapshot.card.clone();
+        expected.recompute_cac_index_with(w);
+        if (expected.cac_index - snapshot.card.cac_index).abs() > f32::EPSILON {
+            return Err(SnapshotError::CacIndexMismatch {
+                expected: expected.cac_index,
+                found: snapshot.card.cac_index,
+            });
+        }
+
+        let entries = self.history.entry(snapshot.card.did.clone()).or_default();
+        if let Some(last) = entries.last() {
+            if snapshot.card.last_anchored_height <= last.card.last_anchored_height {
+                return Err(SnapshotError::NonMonotonicHeight {
+                    did: snapshot.card.did.clone(),
+                    previous: last.card.last_anchored_height,
+                    attempted: snapshot.card.last_anchored_height,
+                });
+            }
+        }
+        entries.push(snapshot);
+        Ok(())
+    }
+
+    /// Full snapshot history for a DID, oldest first.
+    pub fn history_for(&self, did: &str) -> &[SignedCardSnapshot] {
+        self.history.get(did).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Latest snapshot for a DID.
+    pub fn latest(&self, did: &str) -> Option<&SignedCardSnapshot> {
+        self.history_for(did).last()
+    }
+
+    /// Recompute every historical badge for a DID under a candidate weight
+    /// version, alongside the badge each snapshot was anchored with.
+    pub fn recompute_badges(
+        &self,
+        did: &str,
+        candidate: &CacWeights,
+    ) -> Result<Vec<BadgeComparison>, SnapshotError> {
+        let entries = self
+            .history
+            .get(did)
+            .ok_or_else(|| SnapshotError::UnknownDid(did.to_string()))?;
+
+        Ok(entries
+            .iter()
+            .map(|s| {
+                let mut recomputed = s.card.clone();
+                recomputed.recompute_cac_index_with(candidate);
+                BadgeComparison {
+                    last_anchored_height: s.card.last_anchored_height,
+                    stored_weights_version: s.weights_version,
+                    stored_cac_index: s.card.cac_index,
+                    stored_grade: s.card.grade_label(),
+                    candidate_weights_version: candidate.version,
+                    candidate_cac_index: recomputed.cac_index,
+                    candidate_grade: recomputed.grade_label(),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SecretKey;
+
+    const SIGNER: &str = "did:aln:sovereigntycore";
+
+    fn keypair(seed: u8) -> Keypair {
+        let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+        let public = PublicKey::from(&secret);
+        Keypair { secret, public }
+    }
+
+    fn signers(keypair: &Keypair) -> TrustedSignerAllowlist {
+        TrustedSignerAllowlist {
+            signers: vec![TrustedSigner {
+                signer_did: SIGNER.to_string(),
+                public_key_hex: hex::encode(keypair.public.to_bytes()),
+                name: "SovereigntyCore".to_string(),
+            }],
+            version: 1,
+        }
+    }
+
+    fn weights() -> CacWeightConfig {
+        CacWeightConfig {
+            weights: vec![
+                CacWeights::genesis(),
+                CacWeights {
+                    version: 2,
+                    w_safety: 0.5,
+                    w_learning: 0.25,
+                    w_care: 0.25,
+                    effective_from_height: 1_000,
+                    approved_by: Some("prop-7".to_string()),
+                },
+            ],
+        }
+    }
+
+    fn card(height: u64) -> SoulReputationCard {
+        SoulReputationCard {
+            did: "did:aln:citizen:1".to_string(),
+            validated_hours: 120.5,
+            missions_completed: 12,
+            safety_score: 0.9,
+            learning_score: 0.7,
+            care_score: 0.8,
+            cac_index: 0.0,
+            incidents_reported: 1,
+            last_anchored_height: height,
+        }
+    }
+
+    fn signed(height: u64, version: u32, keypair: &Keypair) -> SignedCardSnapshot {
+        sign_snapshot(card(height), weights().get(version).unwrap(), SIGNER, keypair)
+    }
+
+    #[test]
+    fn trusted_snapshots_append_in_height_order() {
+        let key = keypair(1);
+        let mut store = SoulCardSnapshotStore::new(signers(&key));
+        store.append(signed(10, 1, &key), &weights()).unwrap();
+        store.append(signed(1_200, 2, &key), &weights()).unwrap();
+        assert_eq!(store.history_for("did:aln:citizen:1").len(), 2);
+        assert_eq!(store.latest("did:aln:citizen:1").unwrap().weights_version, 2);
+
+        let err = store.append(signed(1_100, 2, &key), &weights()).unwrap_err();
+        assert!(matches!(err, SnapshotError::NonMonotonicHeight { previous: 1_200, attempted: 1_100, .. }));
+    }
+
+    #[test]
+    fn self_signed_snapshots_are_rejected() {
+        let trusted = keypair(1);
+        let forger = keypair(2);
+        let mut store = SoulCardSnapshotStore::new(signers(&trusted));
+
+        // Trusted DID, but a key minted by the forger and carried in the snapshot.
+        let forged = signed(10, 1, &forger);
+        assert_eq!(forged.signer_public_key, hex::encode(forger.public.to_bytes()));
+        assert!(matches!(store.append(forged, &weights()), Err(SnapshotError::InvalidSignature)));
+
+        // A DID nobody accredited.
+        let mut unknown = signed(10, 1, &forger);
+        unknown.signer_did = "did:aln:forger".to_string();
+        assert!(matches!(store.append(unknown, &weights()), Err(SnapshotError::SignerNotTrusted(_))));
+        assert!(store.history.is_empty());
+    }
+
+    #[test]
+    fn tampered_cards_are_rejected() {
+        let key = keypair(1);
+        let mut store = SoulCardSnapshotStore::new(signers(&key));
+
+        let mut tampered = signed(10, 1, &key);
+        tampered.card.safety_score = 1.0;
+        assert!(matches!(store.append(tampered, &weights()), Err(SnapshotError::PayloadHashMismatch)));
+
+        // Re-hashing the edited payload does not help without the signer's key.
+        let mut rehashed = signed(10, 1, &key);
+        rehashed.card.incidents_reported = 0;
+        rehashed.payload_hash = payload_hash(&canonical_snapshot_payload(&rehashed.card, 1));
+        assert!(matches!(store.append(rehashed, &weights()), Err(SnapshotError::InvalidSignature)));
+    }
+
+    #[test]
+    fn weights_version_must_be_active_at_the_snapshot_height() {
+        let key = keypair(1);
+        let mut store = SoulCardSnapshotStore::new(signers(&key));
+
+        let stale = signed(1_500, 1, &key);
+        assert!(matches!(
+            store.append(stale, &weights()),
+            Err(SnapshotError::WeightsVersionNotActive { declared: 1, active: Some(2) })
+        ));
+        let early = signed(500, 2, &key);
+        assert!(matches!(
+            store.append(early, &weights()),
+            Err(SnapshotError::WeightsVersionNotActive { declared: 2, active: Some(1) })
+        ));
+        assert!(matches!(
+            store.append(signed(500, 1, &key), &CacWeightConfig { weights: vec![] }),
+            Err(SnapshotError::UnknownWeightsVersion(1))
+        ));
+    }
+
+    #[test]
+    fn badges_recompute_under_a_candidate_version() {
+        let key = keypair(1);
+        let mut store = SoulCardSnapshotStore::new(signers(&key));
+        store.append(signed(10, 1, &key), &weights()).unwrap();
+
+        let cmp = store.recompute_badges("did:aln:citizen:1", weights().get(2).unwrap()).unwrap();
+        assert_eq!(cmp.len(), 1);
+        assert_eq!(cmp[0].stored_weights_version, 1);
+        assert!((cmp[0].candidate_cac_index - 0.825).abs() < 1e-6);
+        assert!(matches!(
+            store.recompute_badges("did:aln:nobody", &CacWeights::genesis()),
+            Err(SnapshotError::UnknownDid(_))
+        ));
+    }
+
+    #[test]
+    fn did_separators_cannot_shift_payload_fields() {
+        // The `|` inside the DID stays within its quoted field.
+        let mut spliced = card(10);
+        spliced.did = "did:aln:x|120.500000".to_string();
+        let payload = canonical_snapshot_payload(&spliced, 1);
+        assert!(payload.starts_with("soul-card.v2|\"did:aln:x|120.500000\"|"));
+        assert_ne!(payload, canonical_snapshot_payload(&card(10), 1));
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::reputation::cac_weights::CacWeights;
+
 /// Soul-bound reputation snapshot for an augmented citizen.
 /// No monetary fields, forward-only evolution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,14 +30,14 @@ impl SoulReputationCard {
     /// Recompute the composite CAC index from axis scores.
     /// Forward-only: callers are expected to only persist strictly newer snapshots.
     pub fn recompute_cac_index(&mut self) {
-        // Simple weighted average; weights can be made configurable on-chain.
-        let w_safety: f32 = 0.4;
-        let w_learning: f32 = 0.35;
-        let w_care: f32 = 0.25;
+        self.recompute_cac_index_with(&CacWeights::genesis());
+    }
 
-        let raw = w_safety * self.safety_score
-            + w_learning * self.learning_score
-            + w_care * self.care_score;
+    /// Recompute the composite CAC index under a governance weight version.
+    pub fn recompute_cac_index_with(&mut self, weights: &CacWeights) {
+        let raw = weights.w_safety * self.safety_score
+            + weights.w_learning * self.learning_score
+            + weights.w_care * self.care_score;
 
         // Clamp to [0,1] to avoid drift from numerical error.
         self.cac_index = raw.clamp(0.0, 1.0);
@@ -1,10 +1,15 @@
+use thiserror::Error;
+
+use crate::jurisdiction::JurisdictionRuleTable;
 use crate::types::{
     DeviceRole,
     JurisdictionCode,
+    OfflineReconciliationPolicy,
     OfflineServiceClass,
     ProhibitedDataUse,
 };
 use crate::vc::{
+    DiscoveryPolicy,
     NeurorightsEnvelopeCredential,
     OfflineCompanionRightsCredential,
     NeurospaceRadiusCredential,
@@ -17,6 +22,19 @@ pub struct OfflineCompanionShard {
     pub allow_health_access: bool,
     pub allow_civic_basic: bool,
     pub allow_eco_leisure: bool,
+    pub reconciliation_policy: OfflineReconciliationPolicy,
+}
+
+impl OfflineCompanionShard {
+    pub fn allows(&self, class: OfflineServiceClass) -> bool {
+        self.right_to_offline_companion
+            && match class {
+                OfflineServiceClass::EssentialRoute => self.allow_essential_route,
+                OfflineServiceClass::HealthAccess => self.allow_health_access,
+                OfflineServiceClass::CivicBasic => self.allow_civic_basic,
+                OfflineServiceClass::EcoLeisure => self.allow_eco_leisure,
+            }
+    }
 }
 
 impl From<&OfflineCompanionRightsCredential> for OfflineCompanionShard {
@@ -41,38 +59,119 @@ impl From<&OfflineCompanionRightsCredential> for OfflineCompanionShard {
             allow_health_access: allow_health,
             allow_civic_basic: allow_civic,
             allow_eco_leisure: allow_eco,
+            reconciliation_policy: vc.reconciliation_policy,
         }
     }
 }
 
+/// What a host observes about one nearby device when deciding whether it may
+/// discover the host's neurospace.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeviceEncounter {
+    pub role: DeviceRole,
+    pub jurisdiction: JurisdictionCode,
+    pub distance_m: f32,
+    pub line_of_sight: bool,
+    pub manually_paired: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum EncounterDenial {
+    #[error("no jurisdiction rule covers {0}")]
+    UnknownJurisdiction(String),
+    #[error("device distance {0} m is not a finite, non-negative value")]
+    InvalidDistance(f32),
+    #[error("device at {distance_m} m is outside the {limit_m} m radius")]
+    OutOfRadius { distance_m: f32, limit_m: f32 },
+    #[error("device role {0:?} is blocked")]
+    RoleBlocked(DeviceRole),
+    #[error("device role {0:?} is not permitted here")]
+    RoleNotPermitted(DeviceRole),
+    #[error("discovery requires line of sight")]
+    NoLineOfSight,
+    #[error("discovery requires manual pairing")]
+    NotPaired,
+}
+
 #[derive(Clone, Debug)]
 pub struct NeurospaceRadiusShard {
     pub radius_meters: f32,               // neurospaceradius!meters
     pub permitted_device_roles: Vec<DeviceRole>,
     pub blocked_device_roles: Vec<DeviceRole>,
     pub jurisdiction: JurisdictionCode,
+    pub discovery_policy: DiscoveryPolicy,
+    pub rules: JurisdictionRuleTable,
 }
 
 impl NeurospaceRadiusShard {
+    /// Shard governed by [`JurisdictionRuleTable::us_baseline`]; use
+    /// [`with_rules`](Self::with_rules) to apply real rule tables.
     pub fn from_vc(vc: &NeurospaceRadiusCredential, jurisdiction: JurisdictionCode) -> Self {
         Self {
             radius_meters: vc.radius_meters,
             permitted_device_roles: vc.permitted_device_roles.clone(),
             blocked_device_roles: vc.blocked_device_roles.clone(),
             jurisdiction,
+            discovery_policy: vc.discovery_policy,
+            rules: JurisdictionRuleTable::us_baseline(),
         }
     }
 
-    pub fn allow_device(&self, role: DeviceRole, jurisdiction: &JurisdictionCode, distance_m: f32) -> bool {
-        if distance_m > self.radius_meters {
-            return false;
+    pub fn with_rules(mut self, rules: JurisdictionRuleTable) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Credential limits merged with the rules of both the host's and the
+    /// device's jurisdiction; whichever is strictest applies.
+    pub fn evaluate(&self, encounter: &DeviceEncounter) -> Result<(), EncounterDenial> {
+        let rules = self
+            .rules
+            .effective_for(&[self.jurisdiction.clone(), encounter.jurisdiction.clone()])
+            .ok_or_else(|| {
+                let unknown = if self.rules.effective(&self.jurisdiction).is_none() {
+                    &self.jurisdiction
+                } else {
+                    &encounter.jurisdiction
+                };
+                EncounterDenial::UnknownJurisdiction(unknown.0.clone())
+            })?;
+
+        // NaN compares false against the limit, so reject it explicitly.
+        if !encounter.distance_m.is_finite() || encounter.distance_m < 0.0 {
+            return Err(EncounterDenial::InvalidDistance(encounter.distance_m));
+        }
+        let limit_m = self.radius_meters.min(rules.max_radius_meters);
+        if encounter.distance_m > limit_m {
+            return Err(EncounterDenial::OutOfRadius { distance_m: encounter.distance_m, limit_m });
+        }
+        if self.blocked_device_roles.contains(&encounter.role) {
+            return Err(EncounterDenial::RoleBlocked(encounter.role));
         }
-        if self.blocked_device_roles.contains(&role) {
-            return false;
+        if !self.permitted_device_roles.contains(&encounter.role) || !rules.permits_role(encounter.role) {
+            return Err(EncounterDenial::RoleNotPermitted(encounter.role));
         }
-        let jurisdiction_ok = jurisdiction.0.starts_with("US");
-        let role_ok = self.permitted_device_roles.contains(&role);
-        jurisdiction_ok && role_ok
+
+        match self.discovery_policy.stricter(rules.min_discovery_policy) {
+            DiscoveryPolicy::RadiusOnly => Ok(()),
+            DiscoveryPolicy::RadiusPlusLineOfSight if encounter.line_of_sight => Ok(()),
+            DiscoveryPolicy::RadiusPlusLineOfSight => Err(EncounterDenial::NoLineOfSight),
+            DiscoveryPolicy::ManualPairing if encounter.manually_paired => Ok(()),
+            DiscoveryPolicy::ManualPairing => Err(EncounterDenial::NotPaired),
+        }
+    }
+
+    /// Radius-only check: a device with neither line of sight nor pairing,
+    /// so shards requiring either always refuse here.
+    pub fn allow_device(&self, role: DeviceRole, jurisdiction: &JurisdictionCode, distance_m: f32) -> bool {
+        self.evaluate(&DeviceEncounter {
+            role,
+            jurisdiction: jurisdiction.clone(),
+            distance_m,
+            line_of_sight: false,
+            manually_paired: false,
+        })
+        .is_ok()
     }
 }
 
@@ -0,0 +1,433 @@
+//! W3C Verifiable Credentials Data Model 2.0 envelope around the OICL
+//! credential subjects, with issuance and verification.
+
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::SigningKey;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::did::OiclDid;
+use crate::proof::{create_proof, did_key_for, verify_proof, DataIntegrityProof, VerificationKeyResolver};
+use crate::status_list::{StatusList2021Entry, StatusListStore, STATUS_LIST_2021_CONTEXT};
+use crate::vc::CredentialSubject;
+
+pub const VC_V2_CONTEXT: &str = "https://www.w3.org/ns/credentials/v2";
+
+#[derive(Debug, Error)]
+pub enum VcError {
+    #[error("malformed credential JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("credential is not a {expected}")]
+    WrongType { expected: String },
+    #[error("missing @context {0}")]
+    MissingContext(String),
+    #[error("credential has no proof")]
+    MissingProof,
+    #[error("unsupported proof: {0}")]
+    UnsupportedProof(String),
+    #[error("no key for verification method {0}")]
+    UnknownVerificationMethod(String),
+    #[error("verification method {method} is not controlled by issuer {issuer}")]
+    IssuerMismatch { issuer: String, method: String },
+    #[error("issuer {0} is not in the allowed issuers")]
+    UntrustedIssuer(String),
+    #[error("proof signature does not verify")]
+    InvalidSignature,
+    #[error("credential not valid until {0}")]
+    NotYetValid(DateTime<Utc>),
+    #[error("credential expired at {0}")]
+    Expired(DateTime<Utc>),
+    #[error("credential has been revoked")]
+    Revoked,
+    #[error("status list {0} is not available locally")]
+    UnknownStatusList(String),
+    #[error("invalid credential status: {0}")]
+    InvalidStatus(String),
+}
+
+/// `credentialSubject`: the subject's id plus the OICL claims inline.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Subject<S> {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<OiclDid>,
+    #[serde(flatten)]
+    pub claims: S,
+}
+
+/// A verifiable credential carrying one OICL credential subject.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiableCredential<S> {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub types: Vec<String>,
+    pub issuer: OiclDid,
+    pub valid_from: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<DateTime<Utc>>,
+    pub credential_subject: Subject<S>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_status: Option<StatusList2021Entry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<DataIntegrityProof>,
+}
+
+impl<S: CredentialSubject> VerifiableCredential<S> {
+    /// Unsigned credential valid from `valid_from`, with no expiry or status.
+    pub fn new(issuer: OiclDid, subject_id: Option<OiclDid>, claims: S, valid_from: DateTime<Utc>) -> Self {
+        Self {
+            context: vec![VC_V2_CONTEXT.to_string()],
+            id: None,
+            types: vec!["VerifiableCredential".to_string(), S::CREDENTIAL_TYPE.to_string()],
+            issuer,
+            valid_from,
+            valid_until: None,
+            credential_subject: Subject { id: subject_id, claims },
+            credential_status: None,
+            proof: None,
+        }
+    }
+
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    pub fn valid_until(mut self, until: DateTime<Utc>) -> Self {
+        self.valid_until = Some(until);
+        self
+    }
+
+    pub fn with_status(mut self, status: StatusList2021Entry) -> Self {
+        if !self.context.iter().any(|c| c == STATUS_LIST_2021_CONTEXT) {
+            self.context.push(STATUS_LIST_2021_CONTEXT.to_string());
+        }
+        self.credential_status = Some(status);
+        self
+    }
+
+    pub fn claims(&self) -> &S {
+        &self.credential_subject.claims
+    }
+
+    pub fn to_json(&self) -> Result<String, VcError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse a credential; checks that it is a credential of type `S`.
+    pub fn from_json(json: &str) -> Result<Self, VcError> {
+        let vc: Self = serde_json::from_str(json)?;
+        vc.check_shape()?;
+        Ok(vc)
+    }
+
+    fn check_shape(&self) -> Result<(), VcError> {
+        if self.context.first().map(String::as_str) != Some(VC_V2_CONTEXT) {
+            return Err(VcError::MissingContext(VC_V2_CONTEXT.to_string()));
+        }
+        if !self.types.iter().any(|t| t == "VerifiableCredential")
+            || !self.types.iter().any(|t| t == S::CREDENTIAL_TYPE)
+        {
+            return Err(VcError::WrongType { expected: S::CREDENTIAL_TYPE.to_string() });
+        }
+        Ok(())
+    }
+
+    /// The credential as a JSON object without `proof`. Round-tripped through
+    /// text so issuer and verifier canonicalize identical number forms.
+    fn unsecured_document(&self) -> Result<Map<String, Value>, VcError> {
+        let text = serde_json::to_string(self)?;
+        let mut map: Map<String, Value> = serde_json::from_str(&text)?;
+        map.remove("proof");
+        Ok(map)
+    }
+}
+
+/// Signs credentials as one issuer DID with one ed25519 key.
+pub struct CredentialIssuer {
+    pub did: OiclDid,
+    pub verification_method: String,
+    signing_key: SigningKey,
+}
+
+impl CredentialIssuer {
+    pub fn new(did: OiclDid, verification_method: &str, signing_key: SigningKey) -> Self {
+        Self { did, verification_method: verification_method.to_string(), signing_key }
+    }
+
+    /// Issuer identified by the `did:key` of its own key.
+    pub fn did_key(signing_key: SigningKey) -> Self {
+        let (did, method) = did_key_for(&signing_key.verifying_key());
+        Self::new(OiclDid::new(did), &method, signing_key)
+    }
+
+    /// Build and sign a credential for `claims`, valid from `now` for `ttl`
+    /// (no expiry when `None`).
+    pub fn issue<S: CredentialSubject>(
+        &self,
+        subject_id: Option<OiclDid>,
+        claims: S,
+        now: DateTime<Utc>,
+        ttl: Option<Duration>,
+        status: Option<StatusList2021Entry>,
+    ) -> Result<VerifiableCredential<S>, VcError> {
+        let mut vc = VerifiableCredential::new(self.did.clone(), subject_id, claims, now);
+        if let Some(ttl) = ttl {
+            vc = vc.valid_until(now + ttl);
+        }
+        if let Some(status) = status {
+            vc = vc.with_status(status);
+        }
+        self.sign(vc, now)
+    }
+
+    /// Attach a proof to an already-built credential issued by this issuer.
+    pub fn sign<S: CredentialSubject>(
+        &self,
+        mut vc: VerifiableCredential<S>,
+        now: DateTime<Utc>,
+    ) -> Result<VerifiableCredential<S>, VcError> {
+        if vc.issuer != self.did {
+            return Err(VcError::IssuerMismatch {
+                issuer: vc.issuer.as_str().to_string(),
+                method: self.verification_method.clone(),
+            });
+        }
+        vc.proof = None;
+        let doc = vc.unsecured_document()?;
+        vc.proof = Some(create_proof(&doc, &self.signing_key, &self.verification_method, now)?);
+        Ok(vc)
+    }
+}
+
+/// Verifier settings: key resolution, trusted issuers, local status lists,
+/// clock skew.
+pub struct VerificationOptions<'a> {
+    pub keys: &'a dyn VerificationKeyResolver,
+    /// When set, only credentials from these issuer DIDs verify; a valid
+    /// proof from any other issuer is rejected.
+    pub allowed_issuers: Option<&'a [OiclDid]>,
+    /// Required when a credential carries `credentialStatus`.
+    pub status_lists: Option<&'a StatusListStore>,
+    pub clock_skew: Duration,
+}
+
+impl<'a> VerificationOptions<'a> {
+    pub fn new(keys: &'a dyn VerificationKeyResolver) -> Self {
+        Self { keys, allowed_issuers: None, status_lists: None, clock_skew: Duration::seconds(60) }
+    }
+
+    pub fn with_allowed_issuers(mut self, issuers: &'a [OiclDid]) -> Self {
+        self.allowed_issuers = Some(issuers);
+        self
+    }
+
+    pub fn with_status_lists(mut self, store: &'a StatusListStore) -> Self {
+        self.status_lists = Some(store);
+        self
+    }
+}
+
+/// Verify shape, trusted issuer, proof, issuer binding, validity window and
+/// revocation, in that order.
+pub fn verify_credential<S: CredentialSubject>(
+    vc: &VerifiableCredential<S>,
+    options: &VerificationOptions<'_>,
+    now: DateTime<Utc>,
+) -> Result<(), VcError> {
+    verify_against(vc, &vc.unsecured_document()?, options, now)
+}
+
+/// Parse and verify a credential from JSON in one step. The proof is
+/// checked over the JSON as received, so members this crate does not model
+/// (`name`, `description`, ...) stay covered by the signature.
+pub fn verify_credential_json<S: CredentialSubject>(
+    json: &str,
+    options: &VerificationOptions<'_>,
+    now: DateTime<Utc>,
+) -> Result<VerifiableCredential<S>, VcError> {
+    let mut doc: Map<String, Value> = serde_json::from_str(json)?;
+    doc.remove("proof");
+    let vc = VerifiableCredential::<S>::from_json(json)?;
+    verify_against(&vc, &doc, options, now)?;
+    Ok(vc)
+}
+
+fn verify_against<S: CredentialSubject>(
+    vc: &VerifiableCredential<S>,
+    unsecured: &Map<String, Value>,
+    options: &VerificationOptions<'_>,
+    now: DateTime<Utc>,
+) -> Result<(), VcError> {
+    vc.check_shape()?;
+    if let Some(allowed) = options.allowed_issuers {
+        if !allowed.iter().any(|did| did.base() == vc.issuer.base()) {
+            return Err(VcError::UntrustedIssuer(vc.issuer.as_str().to_string()));
+        }
+    }
+    let proof = vc.proof.as_ref().ok_or(VcError::MissingProof)?;
+    let method_did = proof.verification_method.split('#').next().unwrap_or_default();
+    if method_did != vc.issuer.base() {
+        return Err(VcError::IssuerMismatch {
+            issuer: vc.issuer.as_str().to_string(),
+            method: proof.verification_method.clone(),
+        });
+    }
+    verify_proof(unsecured, proof, options.keys)?;
+
+    if now + options.clock_skew < vc.valid_from {
+        return Err(VcError::NotYetValid(vc.valid_from));
+    }
+    if let Some(until) = vc.valid_until {
+        if now - options.clock_skew > until {
+            return Err(VcError::Expired(until));
+        }
+    }
+
+    if let Some(status) = &vc.credential_status {
+        let store = options
+            .status_lists
+            .ok_or_else(|| VcError::UnknownStatusList(status.status_list_credential.clone()))?;
+        if store.is_revoked(status)? {
+            return Err(VcError::Revoked);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::DidKeyResolver;
+    use crate::status_list::StatusList;
+    use crate::types::{OfflineReconciliationPolicy, OfflineServiceClass};
+    use crate::vc::OfflineCompanionRightsCredential;
+
+    const LIST_URL: &str = "https://issuer.example/status/1";
+
+    fn issuer(seed: u8) -> CredentialIssuer {
+        CredentialIssuer::did_key(SigningKey::from_bytes(&[seed; 32]))
+    }
+
+    fn claims() -> OfflineCompanionRightsCredential {
+        OfflineCompanionRightsCredential {
+            right_to_offline_companion: true,
+            offline_mode_permissions: vec![OfflineServiceClass::EssentialRoute, OfflineServiceClass::HealthAccess],
+            reconciliation_policy: OfflineReconciliationPolicy::DeferredSync,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn subject() -> Option<OiclDid> {
+        Some(OiclDid::new("did:example:citizen"))
+    }
+
+    #[test]
+    fn test_issued_credential_verifies() {
+        let vc = issuer(1).issue(subject(), claims(), now(), Some(Duration::days(30)), None).unwrap();
+        assert_eq!(vc.types, vec!["VerifiableCredential", "OfflineCompanionRightsCredential"]);
+        assert_eq!(vc.valid_until, Some(now() + Duration::days(30)));
+        let options = VerificationOptions::new(&DidKeyResolver);
+        verify_credential(&vc, &options, now()).unwrap();
+
+        let parsed: VerifiableCredential<OfflineCompanionRightsCredential> =
+            verify_credential_json(&vc.to_json().unwrap(), &options, now()).unwrap();
+        assert_eq!(parsed, vc);
+    }
+
+    #[test]
+    fn test_validity_window_with_clock_skew() {
+        let vc = issuer(1).issue(subject(), claims(), now(), Some(Duration::hours(1)), None).unwrap();
+        let options = VerificationOptions::new(&DidKeyResolver);
+        let until = now() + Duration::hours(1);
+
+        verify_credential(&vc, &options, until + Duration::seconds(60)).unwrap();
+        assert!(matches!(
+            verify_credential(&vc, &options, until + Duration::seconds(61)),
+            Err(VcError::Expired(t)) if t == until
+        ));
+        verify_credential(&vc, &options, now() - Duration::seconds(60)).unwrap();
+        assert!(matches!(
+            verify_credential(&vc, &options, now() - Duration::seconds(61)),
+            Err(VcError::NotYetValid(_))
+        ));
+    }
+
+    #[test]
+    fn test_tampering_breaks_the_proof() {
+        let vc = issuer(1).issue(subject(), claims(), now(), None, None).unwrap();
+        let options = VerificationOptions::new(&DidKeyResolver);
+
+        let mut edited = vc.clone();
+        edited.credential_subject.claims.offline_mode_permissions.push(OfflineServiceClass::EcoLeisure);
+        assert!(matches!(verify_credential(&edited, &options, now()), Err(VcError::InvalidSignature)));
+
+        // Members outside the model are covered when verifying the JSON.
+        let mut json: Value = serde_json::from_str(&vc.to_json().unwrap()).unwrap();
+        json["name"] = Value::String("injected".into());
+        assert!(matches!(
+            verify_credential_json::<OfflineCompanionRightsCredential>(&json.to_string(), &options, now()),
+            Err(VcError::InvalidSignature)
+        ));
+
+        let mut unsigned = vc.clone();
+        unsigned.proof = None;
+        assert!(matches!(verify_credential(&unsigned, &options, now()), Err(VcError::MissingProof)));
+    }
+
+    #[test]
+    fn test_proof_must_come_from_the_issuer() {
+        let vc = issuer(1).issue(subject(), claims(), now(), None, None).unwrap();
+        let options = VerificationOptions::new(&DidKeyResolver);
+
+        let mut claimed = vc.clone();
+        claimed.issuer = issuer(2).did;
+        assert!(matches!(verify_credential(&claimed, &options, now()), Err(VcError::IssuerMismatch { .. })));
+        assert!(matches!(issuer(2).sign(vc, now()), Err(VcError::IssuerMismatch { .. })));
+    }
+
+    #[test]
+    fn test_only_allowed_issuers_verify() {
+        let vc = issuer(1).issue(subject(), claims(), now(), None, None).unwrap();
+        let trusted = [issuer(1).did];
+        verify_credential(&vc, &VerificationOptions::new(&DidKeyResolver).with_allowed_issuers(&trusted), now())
+            .unwrap();
+
+        // Correctly signed, but by an issuer the verifier does not trust.
+        let other = [issuer(2).did];
+        assert!(matches!(
+            verify_credential(&vc, &VerificationOptions::new(&DidKeyResolver).with_allowed_issuers(&other), now()),
+            Err(VcError::UntrustedIssuer(_))
+        ));
+    }
+
+    #[test]
+    fn test_revoked_credential_is_rejected() {
+        let status = StatusList2021Entry::revocation(LIST_URL, 3);
+        let vc = issuer(1).issue(subject(), claims(), now(), None, Some(status)).unwrap();
+        assert!(vc.context.iter().any(|c| c == STATUS_LIST_2021_CONTEXT));
+
+        let keys = DidKeyResolver;
+        assert!(matches!(
+            verify_credential(&vc, &VerificationOptions::new(&keys), now()),
+            Err(VcError::UnknownStatusList(_))
+        ));
+
+        let mut store = StatusListStore::new();
+        store.insert(LIST_URL, StatusList::new(0));
+        verify_credential(&vc, &VerificationOptions::new(&keys).with_status_lists(&store), now()).unwrap();
+
+        store.get_mut(LIST_URL).unwrap().set(3, true).unwrap();
+        assert!(matches!(
+            verify_credential(&vc, &VerificationOptions::new(&keys).with_status_lists(&store), now()),
+            Err(VcError::Revoked)
+        ));
+    }
+}
@@ -1,4 +1,7 @@
-#[derive(Clone, Debug, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct OiclDid(String);
 
 impl OiclDid {
@@ -9,7 +12,36 @@ impl OiclDid {
         Self(s)
     }
 
+    /// Non-panicking `new`, used when reading credentials from JSON.
+    pub fn parse(did: impl Into<String>) -> Result<Self, String> {
+        let s = did.into();
+        if s.starts_with("did:") && s.len() > 4 {
+            Ok(Self(s))
+        } else {
+            Err(format!("invalid DID format: {}", s))
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// The DID without any `#fragment`.
+    pub fn base(&self) -> &str {
+        self.0.split('#').next().unwrap_or(&self.0)
+    }
+}
+
+impl TryFrom<String> for OiclDid {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(s)
+    }
+}
+
+impl From<OiclDid> for String {
+    fn from(did: OiclDid) -> Self {
+        did.0
+    }
 }
@@ -0,0 +1,297 @@
+//! Deterministic 2D device-encounter simulator for exercising
+//! `NeurospaceRadiusShard` discovery decisions: devices move in straight
+//! lines past a host, walls block line of sight.
+
+use crate::aln_bindings::{DeviceEncounter, EncounterDenial, NeurospaceRadiusShard};
+use crate::types::{DeviceRole, JurisdictionCode};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn distance(self, other: Point) -> f32 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+    }
+}
+
+/// Opaque segment between two points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Wall {
+    pub a: Point,
+    pub b: Point,
+}
+
+impl Wall {
+    pub fn new(a: Point, b: Point) -> Self {
+        Self { a, b }
+    }
+
+    /// Whether the segment `p`–`q` crosses this wall (touching counts).
+    pub fn blocks(&self, p: Point, q: Point) -> bool {
+        fn orient(a: Point, b: Point, c: Point) -> f32 {
+            (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+        }
+        fn on_segment(a: Point, b: Point, c: Point) -> bool {
+            c.x >= a.x.min(b.x) && c.x <= a.x.max(b.x) && c.y >= a.y.min(b.y) && c.y <= a.y.max(b.y)
+        }
+        let (d1, d2) = (orient(self.a, self.b, p), orient(self.a, self.b, q));
+        let (d3, d4) = (orient(p, q, self.a), orient(p, q, self.b));
+        if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+            return true;
+        }
+        (d1 == 0.0 && on_segment(self.a, self.b, p))
+            || (d2 == 0.0 && on_segment(self.a, self.b, q))
+            || (d3 == 0.0 && on_segment(p, q, self.a))
+            || (d4 == 0.0 && on_segment(p, q, self.b))
+    }
+}
+
+/// A device moving at constant velocity (m/s).
+#[derive(Clone, Debug)]
+pub struct SimDevice {
+    pub id: String,
+    pub role: DeviceRole,
+    pub jurisdiction: JurisdictionCode,
+    pub position: Point,
+    pub velocity: Point,
+    pub manually_paired: bool,
+}
+
+impl SimDevice {
+    pub fn new(id: &str, role: DeviceRole, jurisdiction: &str, position: Point) -> Self {
+        Self {
+            id: id.to_string(),
+            role,
+            jurisdiction: JurisdictionCode(jurisdiction.to_string()),
+            position,
+            velocity: Point::new(0.0, 0.0),
+            manually_paired: false,
+        }
+    }
+
+    pub fn moving(mut self, vx: f32, vy: f32) -> Self {
+        self.velocity = Point::new(vx, vy);
+        self
+    }
+
+    pub fn paired(mut self) -> Self {
+        self.manually_paired = true;
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncounterEvent {
+    pub t_s: f32,
+    pub device_id: String,
+    pub encounter: DeviceEncounter,
+    pub outcome: Result<(), EncounterDenial>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SimReport {
+    pub events: Vec<EncounterEvent>,
+}
+
+impl SimReport {
+    pub fn for_device<'a>(&'a self, device_id: &'a str) -> impl Iterator<Item = &'a EncounterEvent> + 'a {
+        self.events.iter().filter(move |e| e.device_id == device_id)
+    }
+
+    pub fn ever_admitted(&self, device_id: &str) -> bool {
+        self.for_device(device_id).any(|e| e.outcome.is_ok())
+    }
+
+    pub fn first_admission(&self, device_id: &str) -> Option<f32> {
+        self.for_device(device_id).find(|e| e.outcome.is_ok()).map(|e| e.t_s)
+    }
+
+    pub fn denials(&self, device_id: &str) -> Vec<EncounterDenial> {
+        self.for_device(device_id).filter_map(|e| e.outcome.clone().err()).collect()
+    }
+}
+
+/// Host at a fixed position evaluating every device once per tick.
+pub struct EncounterSimulator {
+    pub shard: NeurospaceRadiusShard,
+    pub host: Point,
+    pub walls: Vec<Wall>,
+    pub devices: Vec<SimDevice>,
+    t_s: f32,
+}
+
+impl EncounterSimulator {
+    pub fn new(shard: NeurospaceRadiusShard, host: Point) -> Self {
+        Self { shard, host, walls: Vec::new(), devices: Vec::new(), t_s: 0.0 }
+    }
+
+    pub fn with_wall(mut self, wall: Wall) -> Self {
+        self.walls.push(wall);
+        self
+    }
+
+    pub fn with_device(mut self, device: SimDevice) -> Self {
+        self.devices.push(device);
+        self
+    }
+
+    pub fn time_s(&self) -> f32 {
+        self.t_s
+    }
+
+    pub fn line_of_sight(&self, to: Point) -> bool {
+        !self.walls.iter().any(|w| w.blocks(self.host, to))
+    }
+
+    /// Evaluate every device at the current time, then advance by `dt_s`.
+    pub fn step(&mut self, dt_s: f32) -> Vec<EncounterEvent> {
+        let events: Vec<EncounterEvent> = self
+            .devices
+            .iter()
+            .map(|d| {
+                let encounter = DeviceEncounter {
+                    role: d.role,
+                    jurisdiction: d.jurisdiction.clone(),
+                    distance_m: self.host.distance(d.position),
+                    line_of_sight: self.line_of_sight(d.position),
+                    manually_paired: d.manually_paired,
+                };
+                EncounterEvent {
+                    t_s: self.t_s,
+                    device_id: d.id.clone(),
+                    outcome: self.shard.evaluate(&encounter),
+                    encounter,
+                }
+            })
+            .collect();
+        for d in &mut self.devices {
+            d.position.x += d.velocity.x * dt_s;
+            d.position.y += d.velocity.y * dt_s;
+        }
+        self.t_s += dt_s;
+        events
+    }
+
+    pub fn run(&mut self, steps: usize, dt_s: f32) -> SimReport {
+        let mut report = SimReport::default();
+        for _ in 0..steps {
+            report.events.extend(self.step(dt_s));
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jurisdiction::{JurisdictionRule, JurisdictionRuleTable};
+    use crate::vc::DiscoveryPolicy;
+
+    fn shard(policy: DiscoveryPolicy, rules: JurisdictionRuleTable) -> NeurospaceRadiusShard {
+        NeurospaceRadiusShard {
+            radius_meters: 20.0,
+            permitted_device_roles: vec![DeviceRole::CivicTerminal, DeviceRole::TransitHub, DeviceRole::PersonalDevice],
+            blocked_device_roles: vec![DeviceRole::AdTech],
+            jurisdiction: JurisdictionCode("US-AZ-PHX".to_string()),
+            discovery_policy: policy,
+            rules,
+        }
+    }
+
+    fn phoenix_rules() -> JurisdictionRuleTable {
+        let mut t = JurisdictionRuleTable::new();
+        t.insert(JurisdictionRule::new("US").block(&[DeviceRole::NonCivicProfiling]));
+        t.insert(JurisdictionRule::new("US-AZ").max_radius(15.0));
+        t.insert(
+            JurisdictionRule::new("US-AZ-PHX")
+                .allow(&[DeviceRole::CivicTerminal, DeviceRole::TransitHub, DeviceRole::EmergencyService])
+                .max_radius(25.0),
+        );
+        t
+    }
+
+    #[test]
+    fn test_approaching_device_is_admitted_at_strictest_radius() {
+        let mut sim = EncounterSimulator::new(shard(DiscoveryPolicy::RadiusOnly, phoenix_rules()), Point::new(0.0, 0.0))
+            .with_device(SimDevice::new("kiosk", DeviceRole::CivicTerminal, "US-AZ-PHX", Point::new(30.0, 0.0)).moving(-1.0, 0.0))
+            .with_device(SimDevice::new("phone", DeviceRole::PersonalDevice, "US-AZ-PHX", Point::new(5.0, 0.0)))
+            .with_device(SimDevice::new("eu", DeviceRole::CivicTerminal, "EU-DE", Point::new(1.0, 0.0)));
+        let report = sim.run(30, 1.0);
+
+        // State caps at 15 m even though the city allows 25 m.
+        assert_eq!(report.first_admission("kiosk"), Some(15.0));
+        // Personal devices are allowed by the credential but not by the city.
+        assert!(!report.ever_admitted("phone"));
+        assert!(report.denials("phone").contains(&EncounterDenial::RoleNotPermitted(DeviceRole::PersonalDevice)));
+        assert_eq!(report.denials("eu")[0], EncounterDenial::UnknownJurisdiction("EU-DE".to_string()));
+    }
+
+    #[test]
+    fn test_line_of_sight_and_pairing_policies_are_enforced() {
+        let wall = Wall::new(Point::new(5.0, -1.0), Point::new(5.0, 1.0));
+        let mut sim = EncounterSimulator::new(
+            shard(DiscoveryPolicy::RadiusPlusLineOfSight, phoenix_rules()),
+            Point::new(0.0, 0.0),
+        )
+        .with_wall(wall)
+        .with_device(SimDevice::new("hidden", DeviceRole::TransitHub, "US-AZ-PHX", Point::new(10.0, 0.0)))
+        .with_device(SimDevice::new("visible", DeviceRole::TransitHub, "US-AZ-PHX", Point::new(0.0, 10.0)));
+        let report = sim.run(1, 1.0);
+        assert_eq!(report.denials("hidden"), vec![EncounterDenial::NoLineOfSight]);
+        assert!(report.ever_admitted("visible"));
+
+        let mut rules = phoenix_rules();
+        rules.insert(JurisdictionRule::new("US-AZ-PHX").min_discovery(DiscoveryPolicy::ManualPairing));
+        let mut sim = EncounterSimulator::new(shard(DiscoveryPolicy::RadiusOnly, rules), Point::new(0.0, 0.0))
+            .with_device(SimDevice::new("paired", DeviceRole::TransitHub, "US-AZ-PHX", Point::new(3.0, 0.0)).paired())
+            .with_device(SimDevice::new("stranger", DeviceRole::TransitHub, "US-AZ-PHX", Point::new(3.0, 0.0)));
+        let report = sim.run(1, 1.0);
+        assert!(report.ever_admitted("paired"));
+        assert_eq!(report.denials("stranger"), vec![EncounterDenial::NotPaired]);
+    }
+
+    #[test]
+    fn test_us_baseline_matches_former_check() {
+        let s = shard(DiscoveryPolicy::RadiusOnly, JurisdictionRuleTable::us_baseline());
+        let us = JurisdictionCode("US-CA".to_string());
+        assert!(s.allow_device(DeviceRole::PersonalDevice, &us, 10.0));
+        assert!(!s.allow_device(DeviceRole::PersonalDevice, &us, 21.0));
+        assert!(!s.allow_device(DeviceRole::AdTech, &us, 1.0));
+        assert!(!s.allow_device(DeviceRole::PersonalDevice, &JurisdictionCode("CA-ON".to_string()), 1.0));
+    }
+
+    #[test]
+    fn test_non_finite_or_negative_distance_is_denied() {
+        let s = shard(DiscoveryPolicy::RadiusOnly, phoenix_rules());
+        let phx = JurisdictionCode("US-AZ-PHX".to_string());
+        for d in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY, -1.0] {
+            assert!(!s.allow_device(DeviceRole::CivicTerminal, &phx, d), "{}", d);
+            let denial = s
+                .evaluate(&DeviceEncounter {
+                    role: DeviceRole::CivicTerminal,
+                    jurisdiction: phx.clone(),
+                    distance_m: d,
+                    line_of_sight: true,
+                    manually_paired: true,
+                })
+                .unwrap_err();
+            assert!(matches!(denial, EncounterDenial::InvalidDistance(_)), "{:?}", denial);
+        }
+        assert!(s.allow_device(DeviceRole::CivicTerminal, &phx, 0.0));
+    }
+
+    #[test]
+    fn test_allow_device_refuses_line_of_sight_and_pairing_shards() {
+        let phx = JurisdictionCode("US-AZ-PHX".to_string());
+        for policy in [DiscoveryPolicy::RadiusPlusLineOfSight, DiscoveryPolicy::ManualPairing] {
+            assert!(!shard(policy, phoenix_rules()).allow_device(DeviceRole::CivicTerminal, &phx, 1.0));
+        }
+    }
+}
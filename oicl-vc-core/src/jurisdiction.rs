@@ -0,0 +1,211 @@
+//! Hierarchical jurisdiction rules (country → state → city, e.g.
+//! `US-AZ-PHX`) for neurospace discovery, merged strictest-wins.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{DeviceRole, JurisdictionCode};
+use crate::vc::DiscoveryPolicy;
+
+impl JurisdictionCode {
+    /// `US-AZ-PHX` → `["US", "AZ", "PHX"]`.
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.0.split('-').filter(|s| !s.is_empty())
+    }
+
+    pub fn depth(&self) -> usize {
+        self.segments().count()
+    }
+
+    /// `US-AZ-PHX` → `US-AZ`; `None` for a country.
+    pub fn parent(&self) -> Option<JurisdictionCode> {
+        self.0
+            .rsplit_once('-')
+            .map(|(parent, _)| JurisdictionCode(parent.to_string()))
+    }
+
+    /// This code and every enclosing region, most specific first.
+    pub fn lineage(&self) -> Vec<JurisdictionCode> {
+        let mut out = vec![self.clone()];
+        while let Some(parent) = out.last().and_then(JurisdictionCode::parent) {
+            out.push(parent);
+        }
+        out
+    }
+
+    /// Whether `self` lies inside (or is) `region`.
+    pub fn is_within(&self, region: &JurisdictionCode) -> bool {
+        self.0 == region.0
+            || self
+                .0
+                .strip_prefix(&region.0)
+                .is_some_and(|rest| rest.starts_with('-'))
+    }
+}
+
+impl DiscoveryPolicy {
+    /// Higher is stricter: ManualPairing > RadiusPlusLineOfSight > RadiusOnly.
+    pub fn strictness(self) -> u8 {
+        match self {
+            DiscoveryPolicy::RadiusOnly => 0,
+            DiscoveryPolicy::RadiusPlusLineOfSight => 1,
+            DiscoveryPolicy::ManualPairing => 2,
+        }
+    }
+
+    pub fn stricter(self, other: DiscoveryPolicy) -> DiscoveryPolicy {
+        if other.strictness() > self.strictness() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// Constraints one jurisdiction places on neurospace discovery. Unset fields
+/// defer to enclosing regions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JurisdictionRule {
+    pub code: JurisdictionCode,
+    /// Roles that may be admitted at all; `None` places no restriction.
+    pub allowed_roles: Option<Vec<DeviceRole>>,
+    pub blocked_roles: Vec<DeviceRole>,
+    pub max_radius_meters: Option<f32>,
+    pub min_discovery_policy: Option<DiscoveryPolicy>,
+}
+
+impl JurisdictionRule {
+    pub fn new(code: &str) -> Self {
+        Self {
+            code: JurisdictionCode(code.to_string()),
+            allowed_roles: None,
+            blocked_roles: Vec::new(),
+            max_radius_meters: None,
+            min_discovery_policy: None,
+        }
+    }
+
+    pub fn allow(mut self, roles: &[DeviceRole]) -> Self {
+        self.allowed_roles = Some(roles.to_vec());
+        self
+    }
+
+    pub fn block(mut self, roles: &[DeviceRole]) -> Self {
+        self.blocked_roles.extend_from_slice(roles);
+        self
+    }
+
+    pub fn max_radius(mut self, meters: f32) -> Self {
+        self.max_radius_meters = Some(meters);
+        self
+    }
+
+    pub fn min_discovery(mut self, policy: DiscoveryPolicy) -> Self {
+        self.min_discovery_policy = Some(policy);
+        self
+    }
+}
+
+/// Rules merged over every region that applies; always at least as strict
+/// as each contributing rule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EffectiveRules {
+    pub allowed_roles: Vec<DeviceRole>,
+    pub max_radius_meters: f32,
+    pub min_discovery_policy: DiscoveryPolicy,
+    /// Codes of the rules that contributed, most specific first.
+    pub sources: Vec<JurisdictionCode>,
+}
+
+impl EffectiveRules {
+    fn unrestricted() -> Self {
+        Self {
+            allowed_roles: DeviceRole::ALL.to_vec(),
+            max_radius_meters: f32::INFINITY,
+            min_discovery_policy: DiscoveryPolicy::RadiusOnly,
+            sources: Vec::new(),
+        }
+    }
+
+    /// Strictest-wins merge: intersect roles, take the smaller radius and
+    /// the stricter discovery policy.
+    fn merge(&mut self, rule: &JurisdictionRule) {
+        if let Some(allowed) = &rule.allowed_roles {
+            self.allowed_roles.retain(|r| allowed.contains(r));
+        }
+        self.allowed_roles.retain(|r| !rule.blocked_roles.contains(r));
+        if let Some(max) = rule.max_radius_meters {
+            self.max_radius_meters = self.max_radius_meters.min(max);
+        }
+        if let Some(policy) = rule.min_discovery_policy {
+            self.min_discovery_policy = self.min_discovery_policy.stricter(policy);
+        }
+        self.sources.push(rule.code.clone());
+    }
+
+    pub fn permits_role(&self, role: DeviceRole) -> bool {
+        self.allowed_roles.contains(&role)
+    }
+}
+
+/// Jurisdiction rules keyed by code. A location is governed by the rules of
+/// its code and every ancestor; a location with no rule anywhere in its
+/// lineage is unknown and nothing is admitted there.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct JurisdictionRuleTable {
+    rules: HashMap<String, JurisdictionRule>,
+}
+
+impl JurisdictionRuleTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Equivalent of the former hardcoded check: any `US*` jurisdiction,
+    /// with the credential alone deciding roles and radius.
+    pub fn us_baseline() -> Self {
+        let mut table = Self::new();
+        table.insert(JurisdictionRule::new("US"));
+        table
+    }
+
+    pub fn insert(&mut self, rule: JurisdictionRule) {
+        self.rules.insert(rule.code.0.clone(), rule);
+    }
+
+    pub fn get(&self, code: &JurisdictionCode) -> Option<&JurisdictionRule> {
+        self.rules.get(&code.0)
+    }
+
+    /// Merged rules for one location, or `None` if no region covers it.
+    pub fn effective(&self, code: &JurisdictionCode) -> Option<EffectiveRules> {
+        self.effective_for(std::slice::from_ref(code))
+    }
+
+    /// Merged rules where several (possibly overlapping) regions apply at
+    /// once, e.g. a city plus a special zone. Every location must be covered.
+    pub fn effective_for(&self, codes: &[JurisdictionCode]) -> Option<EffectiveRules> {
+        if codes.is_empty() {
+            return None;
+        }
+        let mut eff = EffectiveRules::unrestricted();
+        for code in codes {
+            let mut covered = false;
+            for region in code.lineage() {
+                if eff.sources.contains(&region) {
+                    covered = true;
+                    continue;
+                }
+                if let Some(rule) = self.rules.get(&region.0) {
+                    eff.merge(rule);
+                    covered = true;
+                }
+            }
+            if !covered {
+                return None;
+            }
+        }
+        Some(eff)
+    }
+}
@@ -2,6 +2,12 @@ pub mod did;
 pub mod types;
 pub mod vc;
 pub mod aln_bindings;
+pub mod jurisdiction;
+pub mod encounter_sim;
+pub mod offline_sync;
+pub mod credential;
+pub mod proof;
+pub mod status_list;
 
 pub use did::OiclDid;
 pub use types::{
@@ -16,9 +22,41 @@ pub use vc::{
     NeurorightsEnvelopeCredential,
     OfflineCompanionRightsCredential,
     NeurospaceRadiusCredential,
+    CredentialSubject,
+    DiscoveryPolicy,
 };
+pub use credential::{
+    verify_credential,
+    verify_credential_json,
+    CredentialIssuer,
+    Subject,
+    VcError,
+    VerifiableCredential,
+    VerificationOptions,
+};
+pub use proof::{
+    DataIntegrityProof,
+    DidKeyResolver,
+    KeyResolverChain,
+    StaticKeyResolver,
+    VerificationKeyResolver,
+};
+pub use status_list::{StatusList, StatusList2021Entry, StatusListStore};
 pub use aln_bindings::{
     OfflineCompanionShard,
     NeurospaceRadiusShard,
     NeurorightsShard,
+    DeviceEncounter,
+    EncounterDenial,
+};
+pub use jurisdiction::{EffectiveRules, JurisdictionRule, JurisdictionRuleTable};
+pub use encounter_sim::{EncounterSimulator, SimDevice, SimReport};
+pub use offline_sync::{
+    ConflictResolution,
+    OfflineAction,
+    OfflineCompanionEngine,
+    OfflineError,
+    ReconcileReport,
+    SyncTransport,
+    SyncTrigger,
 };
@@ -0,0 +1,364 @@
+//! Offline companion reconciliation: actions performed while offline are
+//! queued per `OfflineServiceClass` and reconciled according to the
+//! credential's `OfflineReconciliationPolicy` once connectivity returns.
+
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::aln_bindings::OfflineCompanionShard;
+use crate::types::{OfflineReconciliationPolicy, OfflineServiceClass};
+
+/// Reconciliation order: health and routing before civic and leisure.
+pub const CLASS_PRIORITY: [OfflineServiceClass; 4] = [
+    OfflineServiceClass::HealthAccess,
+    OfflineServiceClass::EssentialRoute,
+    OfflineServiceClass::CivicBasic,
+    OfflineServiceClass::EcoLeisure,
+];
+
+/// One change made while offline to a record identified by `key`.
+/// `base_version` is the upstream version the device last saw (`None` for a
+/// record the device created).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OfflineAction {
+    pub seq: u64,
+    pub class: OfflineServiceClass,
+    pub key: String,
+    pub payload: Value,
+    pub base_version: Option<u64>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum OfflineError {
+    #[error("credential grants no right to offline companion mode")]
+    NoOfflineRight,
+    #[error("offline class {0:?} is not permitted by the credential")]
+    ClassNotPermitted(OfflineServiceClass),
+    #[error("no pending conflict for action {0}")]
+    UnknownConflict(u64),
+}
+
+#[derive(Clone, Debug, PartialEq, Error)]
+#[error("sync transport: {0}")]
+pub struct TransportError(pub String);
+
+/// Upstream the engine reconciles against.
+pub trait SyncTransport {
+    fn is_online(&self) -> bool;
+    /// Current upstream version of `key`, `None` if it does not exist.
+    fn remote_version(&mut self, key: &str) -> Result<Option<u64>, TransportError>;
+    /// Apply `action` upstream, returning the record's new version.
+    fn push(&mut self, action: &OfflineAction) -> Result<u64, TransportError>;
+}
+
+/// Why the engine is being asked to reconcile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncTrigger {
+    /// Connectivity has just returned.
+    ConnectivityRestored,
+    /// A scheduled sync window (e.g. charging on Wi-Fi) has opened.
+    Scheduled,
+}
+
+/// The upstream record changed since the device last saw it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncConflict {
+    pub action: OfflineAction,
+    pub remote_version: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Re-queue the local change on top of the remote version.
+    KeepLocal,
+    /// Drop the local change.
+    KeepRemote,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReconcileReport {
+    /// `(seq, new upstream version)` in push order.
+    pub pushed: Vec<(u64, u64)>,
+    pub conflicts: Vec<u64>,
+    pub retained_locally: usize,
+    /// Actions still queued for a later pass.
+    pub deferred: usize,
+    pub transport_error: Option<TransportError>,
+}
+
+/// Queues offline actions and reconciles them under one credential.
+#[derive(Clone, Debug)]
+pub struct OfflineCompanionEngine {
+    shard: OfflineCompanionShard,
+    queues: HashMap<OfflineServiceClass, VecDeque<OfflineAction>>,
+    /// `LocalOnly` actions; never handed to a transport.
+    local: Vec<OfflineAction>,
+    conflicts: Vec<SyncConflict>,
+    next_seq: u64,
+}
+
+impl OfflineCompanionEngine {
+    pub fn new(shard: OfflineCompanionShard) -> Self {
+        Self {
+            shard,
+            queues: HashMap::new(),
+            local: Vec::new(),
+            conflicts: Vec::new(),
+            next_seq: 1,
+        }
+    }
+
+    pub fn policy(&self) -> OfflineReconciliationPolicy {
+        self.shard.reconciliation_policy
+    }
+
+    /// Record an action taken offline; returns its sequence number.
+    pub fn record(
+        &mut self,
+        class: OfflineServiceClass,
+        key: &str,
+        payload: Value,
+        base_version: Option<u64>,
+        now: DateTime<Utc>,
+    ) -> Result<u64, OfflineError> {
+        if !self.shard.right_to_offline_companion {
+            return Err(OfflineError::NoOfflineRight);
+        }
+        if !self.shard.allows(class) {
+            return Err(OfflineError::ClassNotPermitted(class));
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        let action = OfflineAction { seq, class, key: key.to_string(), payload, base_version, recorded_at: now };
+        if self.policy() == OfflineReconciliationPolicy::LocalOnly {
+            self.local.push(action);
+        } else {
+            self.queues.entry(class).or_default().push_back(action);
+        }
+        Ok(seq)
+    }
+
+    pub fn pending(&self, class: OfflineServiceClass) -> usize {
+        self.queues.get(&class).map_or(0, VecDeque::len)
+    }
+
+    pub fn pending_total(&self) -> usize {
+        self.queues.values().map(VecDeque::len).sum()
+    }
+
+    pub fn local_records(&self) -> &[OfflineAction] {
+        &self.local
+    }
+
+    pub fn conflicts(&self) -> &[SyncConflict] {
+        &self.conflicts
+    }
+
+    /// Reconcile queued actions. `LocalOnly` never touches the transport;
+    /// `DeferredSync` waits for a [`SyncTrigger::Scheduled`] window;
+    /// `ImmediateSyncWhenAvailable` syncs on either trigger. Classes go in
+    /// [`CLASS_PRIORITY`] order, FIFO within a class. A transport failure
+    /// stops the pass and leaves the remaining actions queued.
+    pub fn reconcile(&mut self, transport: &mut dyn SyncTransport, trigger: SyncTrigger) -> ReconcileReport {
+        let mut report = ReconcileReport { retained_locally: self.local.len(), ..Default::default() };
+        let due = match self.policy() {
+            OfflineReconciliationPolicy::LocalOnly => false,
+            OfflineReconciliationPolicy::DeferredSync => trigger == SyncTrigger::Scheduled,
+            OfflineReconciliationPolicy::ImmediateSyncWhenAvailable => true,
+        };
+        if !due || !transport.is_online() {
+            report.deferred = self.pending_total();
+            return report;
+        }
+
+        // Versions produced by this device's own pushes, so later offline
+        // edits of the same record don't conflict with earlier ones.
+        let mut rebased: HashMap<String, (Option<u64>, u64)> = HashMap::new();
+        'classes: for class in CLASS_PRIORITY {
+            let Some(queue) = self.queues.get_mut(&class) else { continue };
+            while let Some(mut action) = queue.pop_front() {
+                if let Some((from, to)) = rebased.get(&action.key) {
+                    if action.base_version == *from {
+                        action.base_version = Some(*to);
+                    }
+                }
+                let remote = match transport.remote_version(&action.key) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        queue.push_front(action);
+                        report.transport_error = Some(e);
+                        break 'classes;
+                    }
+                };
+                if remote != action.base_version {
+                    report.conflicts.push(action.seq);
+                    self.conflicts.push(SyncConflict { action, remote_version: remote });
+                    continue;
+                }
+                match transport.push(&action) {
+                    Ok(version) => {
+                        report.pushed.push((action.seq, version));
+                        rebased.insert(action.key.clone(), (action.base_version, version));
+                    }
+                    Err(e) => {
+                        queue.push_front(action);
+                        report.transport_error = Some(e);
+                        break 'classes;
+                    }
+                }
+            }
+        }
+        report.deferred = self.pending_total();
+        report
+    }
+
+    /// Settle a conflict. `KeepLocal` re-queues the action at the front of
+    /// its class on top of the remote version.
+    pub fn resolve_conflict(&mut self, seq: u64, resolution: ConflictResolution) -> Result<(), OfflineError> {
+        let idx = self
+            .conflicts
+            .iter()
+            .position(|c| c.action.seq == seq)
+            .ok_or(OfflineError::UnknownConflict(seq))?;
+        let SyncConflict { mut action, remote_version } = self.conflicts.remove(idx);
+        if resolution == ConflictResolution::KeepLocal {
+            action.base_version = remote_version;
+            self.queues.entry(action.class).or_default().push_front(action);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[derive(Default)]
+    struct RecordingTransport {
+        online: bool,
+        versions: HashMap<String, u64>,
+        pushed: Vec<OfflineAction>,
+        queried: Vec<String>,
+        fail_after: Option<usize>,
+    }
+
+    impl SyncTransport for RecordingTransport {
+        fn is_online(&self) -> bool {
+            self.online
+        }
+
+        fn remote_version(&mut self, key: &str) -> Result<Option<u64>, TransportError> {
+            self.queried.push(key.to_string());
+            Ok(self.versions.get(key).copied())
+        }
+
+        fn push(&mut self, action: &OfflineAction) -> Result<u64, TransportError> {
+            if self.fail_after == Some(self.pushed.len()) {
+                return Err(TransportError("link dropped".to_string()));
+            }
+            self.pushed.push(action.clone());
+            let v = self.versions.entry(action.key.clone()).or_insert(0);
+            *v += 1;
+            Ok(*v)
+        }
+    }
+
+    fn engine(policy: OfflineReconciliationPolicy) -> OfflineCompanionEngine {
+        OfflineCompanionEngine::new(OfflineCompanionShard {
+            right_to_offline_companion: true,
+            allow_essential_route: true,
+            allow_health_access: true,
+            allow_civic_basic: true,
+            allow_eco_leisure: false,
+            reconciliation_policy: policy,
+        })
+    }
+
+    fn at(s: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000 + s, 0).unwrap()
+    }
+
+    #[test]
+    fn test_local_only_never_leaves_the_device() {
+        let mut e = engine(OfflineReconciliationPolicy::LocalOnly);
+        e.record(OfflineServiceClass::HealthAccess, "med-log", json!({"dose": 1}), None, at(0)).unwrap();
+        e.record(OfflineServiceClass::EssentialRoute, "route", json!([1, 2]), Some(3), at(1)).unwrap();
+
+        let mut t = RecordingTransport { online: true, ..Default::default() };
+        for trigger in [SyncTrigger::ConnectivityRestored, SyncTrigger::Scheduled] {
+            let report = e.reconcile(&mut t, trigger);
+            assert!(report.pushed.is_empty());
+            assert_eq!(report.retained_locally, 2);
+        }
+        assert!(t.pushed.is_empty());
+        // Not even the record keys are disclosed upstream.
+        assert!(t.queried.is_empty());
+        assert_eq!(e.pending_total(), 0);
+        assert_eq!(e.local_records().len(), 2);
+    }
+
+    #[test]
+    fn test_deferred_waits_for_scheduled_window_and_orders_by_priority() {
+        let mut e = engine(OfflineReconciliationPolicy::DeferredSync);
+        let civic = e.record(OfflineServiceClass::CivicBasic, "form", json!("a"), None, at(0)).unwrap();
+        let health = e.record(OfflineServiceClass::HealthAccess, "vitals", json!("b"), None, at(1)).unwrap();
+        assert_eq!(
+            e.record(OfflineServiceClass::EcoLeisure, "park", json!("c"), None, at(2)),
+            Err(OfflineError::ClassNotPermitted(OfflineServiceClass::EcoLeisure))
+        );
+
+        let mut t = RecordingTransport { online: true, ..Default::default() };
+        let report = e.reconcile(&mut t, SyncTrigger::ConnectivityRestored);
+        assert!(report.pushed.is_empty());
+        assert_eq!(report.deferred, 2);
+
+        let report = e.reconcile(&mut t, SyncTrigger::Scheduled);
+        assert_eq!(report.pushed, vec![(health, 1), (civic, 1)]);
+        assert_eq!(e.pending_total(), 0);
+    }
+
+    #[test]
+    fn test_conflicts_are_held_until_resolved_and_own_edits_chain() {
+        let mut e = engine(OfflineReconciliationPolicy::ImmediateSyncWhenAvailable);
+        let first = e.record(OfflineServiceClass::CivicBasic, "profile", json!(1), Some(4), at(0)).unwrap();
+        let second = e.record(OfflineServiceClass::CivicBasic, "profile", json!(2), Some(4), at(1)).unwrap();
+        let stale = e.record(OfflineServiceClass::EssentialRoute, "route", json!(3), Some(1), at(2)).unwrap();
+
+        let mut t = RecordingTransport { online: true, ..Default::default() };
+        t.versions.insert("profile".to_string(), 4);
+        t.versions.insert("route".to_string(), 2);
+        let report = e.reconcile(&mut t, SyncTrigger::ConnectivityRestored);
+        assert_eq!(report.pushed, vec![(first, 5), (second, 6)]);
+        assert_eq!(report.conflicts, vec![stale]);
+        assert_eq!(e.conflicts()[0].remote_version, Some(2));
+
+        e.resolve_conflict(stale, ConflictResolution::KeepLocal).unwrap();
+        let report = e.reconcile(&mut t, SyncTrigger::ConnectivityRestored);
+        assert_eq!(report.pushed, vec![(stale, 3)]);
+        assert_eq!(e.resolve_conflict(stale, ConflictResolution::KeepRemote), Err(OfflineError::UnknownConflict(stale)));
+    }
+
+    #[test]
+    fn test_transport_failure_keeps_remaining_actions_queued() {
+        let mut e = engine(OfflineReconciliationPolicy::ImmediateSyncWhenAvailable);
+        for i in 0..3 {
+            e.record(OfflineServiceClass::HealthAccess, &format!("k{}", i), json!(i), None, at(i)).unwrap();
+        }
+        let mut t = RecordingTransport { online: true, fail_after: Some(1), ..Default::default() };
+        let report = e.reconcile(&mut t, SyncTrigger::ConnectivityRestored);
+        assert_eq!(report.pushed.len(), 1);
+        assert!(report.transport_error.is_some());
+        assert_eq!(report.deferred, 2);
+
+        t.fail_after = None;
+        let report = e.reconcile(&mut t, SyncTrigger::ConnectivityRestored);
+        assert_eq!(report.pushed.iter().map(|p| p.0).collect::<Vec<_>>(), vec![2, 3]);
+    }
+}
@@ -0,0 +1,300 @@
+//! Ed25519 Data Integrity proofs using the `eddsa-jcs-2022` cryptosuite.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::credential::VcError;
+
+pub const DATA_INTEGRITY_PROOF: &str = "DataIntegrityProof";
+pub const EDDSA_JCS_2022: &str = "eddsa-jcs-2022";
+pub const ASSERTION_METHOD: &str = "assertionMethod";
+
+/// Multicodec varint prefix of an ed25519 public key.
+const ED25519_PUB_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataIntegrityProof {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub cryptosuite: String,
+    pub created: String,
+    pub verification_method: String,
+    pub proof_purpose: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_value: Option<String>,
+}
+
+/// JSON Canonicalization Scheme (RFC 8785): object members sorted by
+/// UTF-16 code units, no insignificant whitespace.
+pub fn jcs(value: &Value) -> String {
+    let mut out = String::new();
+    write_jcs(value, &mut out);
+    out
+}
+
+fn write_jcs(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by(|a, b| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, k) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(k.clone()).to_string());
+                out.push(':');
+                write_jcs(&map[k], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, v) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_jcs(v, out);
+            }
+            out.push(']');
+        }
+        Value::Number(n) => match n.as_f64() {
+            // ES6 number serialization: integral floats have no fraction.
+            // Above 2^53 ES6 prints the shortest round-trip digits padded
+            // with zeros, which is what the float's Display does below 1e21.
+            Some(f) if n.is_f64() && f.fract() == 0.0 && f.abs() < 1e21 => {
+                if f.abs() < 9_007_199_254_740_992.0 {
+                    out.push_str(&format!("{}", f as i64))
+                } else {
+                    out.push_str(&format!("{}", f))
+                }
+            }
+            _ => out.push_str(&n.to_string()),
+        },
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// `SHA-256(JCS(proof options)) || SHA-256(JCS(document))`, the bytes the
+/// cryptosuite signs.
+fn hash_data(document: &Map<String, Value>, proof_options: &Map<String, Value>) -> Vec<u8> {
+    let mut out = Sha256::digest(jcs(&Value::Object(proof_options.clone())).as_bytes()).to_vec();
+    out.extend_from_slice(&Sha256::digest(jcs(&Value::Object(document.clone())).as_bytes()));
+    out
+}
+
+fn proof_options(document: &Map<String, Value>, proof: &DataIntegrityProof) -> Result<Map<String, Value>, VcError> {
+    let mut options = match serde_json::to_value(proof)? {
+        Value::Object(m) => m,
+        _ => unreachable!("proof serializes to an object"),
+    };
+    options.remove("proofValue");
+    if let Some(ctx) = document.get("@context") {
+        options.insert("@context".to_string(), ctx.clone());
+    }
+    Ok(options)
+}
+
+/// Sign `document` (which must not already carry a proof).
+pub fn create_proof(
+    document: &Map<String, Value>,
+    signing_key: &SigningKey,
+    verification_method: &str,
+    created: DateTime<Utc>,
+) -> Result<DataIntegrityProof, VcError> {
+    let mut proof = DataIntegrityProof {
+        kind: DATA_INTEGRITY_PROOF.to_string(),
+        cryptosuite: EDDSA_JCS_2022.to_string(),
+        created: created.to_rfc3339_opts(SecondsFormat::Secs, true),
+        verification_method: verification_method.to_string(),
+        proof_purpose: ASSERTION_METHOD.to_string(),
+        proof_value: None,
+    };
+    let data = hash_data(document, &proof_options(document, &proof)?);
+    let signature = signing_key.sign(&data);
+    proof.proof_value = Some(format!("z{}", bs58::encode(signature.to_bytes()).into_string()));
+    Ok(proof)
+}
+
+/// Check `proof` over `document` (the secured document minus `proof`).
+pub fn verify_proof(
+    document: &Map<String, Value>,
+    proof: &DataIntegrityProof,
+    keys: &dyn VerificationKeyResolver,
+) -> Result<(), VcError> {
+    if proof.kind != DATA_INTEGRITY_PROOF || proof.cryptosuite != EDDSA_JCS_2022 {
+        return Err(VcError::UnsupportedProof(format!("{} / {}", proof.kind, proof.cryptosuite)));
+    }
+    if proof.proof_purpose != ASSERTION_METHOD {
+        return Err(VcError::UnsupportedProof(format!("proofPurpose {}", proof.proof_purpose)));
+    }
+    let value = proof.proof_value.as_deref().ok_or(VcError::MissingProof)?;
+    let sig_bytes = value
+        .strip_prefix('z')
+        .and_then(|b58| bs58::decode(b58).into_vec().ok())
+        .ok_or_else(|| VcError::UnsupportedProof("proofValue is not base58btc multibase".to_string()))?;
+    let signature = Signature::from_slice(&sig_bytes).map_err(|_| VcError::InvalidSignature)?;
+    let key = keys
+        .ed25519_key(&proof.verification_method)
+        .ok_or_else(|| VcError::UnknownVerificationMethod(proof.verification_method.clone()))?;
+    let data = hash_data(document, &proof_options(document, proof)?);
+    key.verify(&data, &signature).map_err(|_| VcError::InvalidSignature)
+}
+
+/// Maps a proof's `verificationMethod` to an ed25519 key.
+pub trait VerificationKeyResolver {
+    fn ed25519_key(&self, verification_method: &str) -> Option<VerifyingKey>;
+}
+
+/// Resolves `did:key:z6Mk...#z6Mk...` methods from the identifier itself.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DidKeyResolver;
+
+impl VerificationKeyResolver for DidKeyResolver {
+    fn ed25519_key(&self, verification_method: &str) -> Option<VerifyingKey> {
+        let did = verification_method.split('#').next()?;
+        let bytes = bs58::decode(did.strip_prefix("did:key:z")?).into_vec().ok()?;
+        let raw: [u8; 32] = bytes.strip_prefix(&ED25519_PUB_MULTICODEC[..])?.try_into().ok()?;
+        VerifyingKey::from_bytes(&raw).ok()
+    }
+}
+
+/// Fixed verification method to key mapping, e.g. issuer keys pinned in
+/// configuration. Only pinned methods resolve; chain a [`DidKeyResolver`]
+/// through [`KeyResolverChain`] to also accept self-describing `did:key`s.
+#[derive(Clone, Debug, Default)]
+pub struct StaticKeyResolver {
+    keys: HashMap<String, VerifyingKey>,
+}
+
+impl StaticKeyResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, verification_method: &str, key: VerifyingKey) {
+        self.keys.insert(verification_method.to_string(), key);
+    }
+}
+
+impl VerificationKeyResolver for StaticKeyResolver {
+    fn ed25519_key(&self, verification_method: &str) -> Option<VerifyingKey> {
+        self.keys.get(verification_method).copied()
+    }
+}
+
+/// Tries each resolver in order and returns the first key found.
+#[derive(Default)]
+pub struct KeyResolverChain<'a> {
+    resolvers: Vec<&'a dyn VerificationKeyResolver>,
+}
+
+impl<'a> KeyResolverChain<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn then(mut self, resolver: &'a dyn VerificationKeyResolver) -> Self {
+        self.resolvers.push(resolver);
+        self
+    }
+}
+
+impl VerificationKeyResolver for KeyResolverChain<'_> {
+    fn ed25519_key(&self, verification_method: &str) -> Option<VerifyingKey> {
+        self.resolvers.iter().find_map(|r| r.ed25519_key(verification_method))
+    }
+}
+
+/// `did:key` identifier for an ed25519 key, and its verification method id.
+pub fn did_key_for(key: &VerifyingKey) -> (String, String) {
+    let mut bytes = ED25519_PUB_MULTICODEC.to_vec();
+    bytes.extend_from_slice(key.as_bytes());
+    let multibase = format!("z{}", bs58::encode(bytes).into_string());
+    let did = format!("did:key:{}", multibase);
+    let method = format!("{}#{}", did, multibase);
+    (did, method)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn doc(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(m) => m,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn test_jcs_sorts_members_and_drops_whitespace() {
+        let v = json!({ "b": [1, true, null], "a": { "d": "x", "c": 2 } });
+        assert_eq!(jcs(&v), r#"{"a":{"c":2,"d":"x"},"b":[1,true,null]}"#);
+    }
+
+    #[test]
+    fn test_jcs_integral_floats() {
+        assert_eq!(jcs(&json!(1.0)), "1");
+        assert_eq!(jcs(&json!(-0.0)), "0");
+        assert_eq!(jcs(&json!(0.5)), "0.5");
+        assert_eq!(jcs(&json!(9_007_199_254_740_993u64)), "9007199254740993");
+        // JSON.stringify(2 ** 63) and friends.
+        assert_eq!(jcs(&json!(9_223_372_036_854_775_808.0)), "9223372036854776000");
+        assert_eq!(jcs(&json!(-9_223_372_036_854_775_808.0)), "-9223372036854776000");
+        assert_eq!(jcs(&json!(4_611_686_018_427_387_904.0)), "4611686018427388000");
+        assert_eq!(jcs(&json!(1e20)), "100000000000000000000");
+    }
+
+    #[test]
+    fn test_proof_roundtrip_and_tamper() {
+        let signer = key(1);
+        let (_, method) = did_key_for(&signer.verifying_key());
+        let document = doc(json!({ "@context": ["https://www.w3.org/ns/credentials/v2"], "claim": 0.25 }));
+        let created = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let proof = create_proof(&document, &signer, &method, created).unwrap();
+        assert_eq!(proof.created, "2026-01-01T00:00:00Z");
+        verify_proof(&document, &proof, &DidKeyResolver).unwrap();
+
+        let mut tampered = document.clone();
+        tampered.insert("claim".into(), json!(0.5));
+        assert!(matches!(verify_proof(&tampered, &proof, &DidKeyResolver), Err(VcError::InvalidSignature)));
+
+        let mut wrong_purpose = proof.clone();
+        wrong_purpose.proof_purpose = "authentication".into();
+        assert!(matches!(
+            verify_proof(&document, &wrong_purpose, &DidKeyResolver),
+            Err(VcError::UnsupportedProof(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_resolvers() {
+        let (k1, k2) = (key(1), key(2));
+        let (_, method) = did_key_for(&k1.verifying_key());
+        assert_eq!(DidKeyResolver.ed25519_key(&method), Some(k1.verifying_key()));
+        assert_eq!(DidKeyResolver.ed25519_key("did:web:example.com#key-1"), None);
+
+        let mut pinned = StaticKeyResolver::new();
+        pinned.insert("did:web:example.com#key-1", k2.verifying_key());
+        assert_eq!(pinned.ed25519_key("did:web:example.com#key-1"), Some(k2.verifying_key()));
+        // Pinning keys does not silently trust every did:key.
+        assert_eq!(pinned.ed25519_key(&method), None);
+
+        let chain = KeyResolverChain::new().then(&pinned).then(&DidKeyResolver);
+        assert_eq!(chain.ed25519_key("did:web:example.com#key-1"), Some(k2.verifying_key()));
+        assert_eq!(chain.ed25519_key(&method), Some(k1.verifying_key()));
+        assert_eq!(chain.ed25519_key("did:web:example.com#key-2"), None);
+    }
+}
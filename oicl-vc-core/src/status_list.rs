@@ -0,0 +1,231 @@
+//! StatusList2021 revocation bitstrings, checked locally.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::credential::VcError;
+
+pub const STATUS_LIST_2021_CONTEXT: &str = "https://w3id.org/vc/status-list/2021/v1";
+
+/// Minimum list size from the spec (16 KiB), so a list does not leak how
+/// many credentials an issuer has issued.
+pub const MIN_STATUS_LIST_BITS: usize = 131_072;
+
+/// Largest decompressed list `StatusList::decode` accepts (16 MiB, over
+/// 134 million entries). An all-zero list gzips roughly a thousandfold, so
+/// without a cap a small `encodedList` could exhaust memory.
+pub const MAX_STATUS_LIST_BYTES: usize = 16 * 1024 * 1024;
+
+/// `credentialStatus` entry pointing at one bit of a status list.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusList2021Entry {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub status_purpose: String,
+    /// Decimal string, as the spec requires.
+    pub status_list_index: String,
+    pub status_list_credential: String,
+}
+
+impl StatusList2021Entry {
+    pub fn revocation(status_list_credential: &str, index: usize) -> Self {
+        Self {
+            id: format!("{}#{}", status_list_credential, index),
+            kind: "StatusList2021Entry".to_string(),
+            status_purpose: "revocation".to_string(),
+            status_list_index: index.to_string(),
+            status_list_credential: status_list_credential.to_string(),
+        }
+    }
+
+    pub fn index(&self) -> Result<usize, VcError> {
+        self.status_list_index
+            .parse()
+            .map_err(|_| VcError::InvalidStatus(format!("bad statusListIndex {}", self.status_list_index)))
+    }
+}
+
+/// Bitstring with the spec's bit order: index 0 is the most significant
+/// bit of the first byte.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatusList {
+    bits: Vec<u8>,
+}
+
+impl StatusList {
+    pub fn new(len_bits: usize) -> Self {
+        let len = len_bits.max(MIN_STATUS_LIST_BITS);
+        Self { bits: vec![0; len.div_ceil(8)] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<bool> {
+        let byte = self.bits.get(index / 8)?;
+        Some(byte & (0x80 >> (index % 8)) != 0)
+    }
+
+    pub fn set(&mut self, index: usize, revoked: bool) -> Result<(), VcError> {
+        let len = self.len();
+        let byte = self
+            .bits
+            .get_mut(index / 8)
+            .ok_or_else(|| VcError::InvalidStatus(format!("index {} outside list of {} bits", index, len)))?;
+        let mask = 0x80 >> (index % 8);
+        if revoked {
+            *byte |= mask;
+        } else {
+            *byte &= !mask;
+        }
+        Ok(())
+    }
+
+    /// `encodedList` form: base64url (no padding) of the GZIP'd bitstring.
+    pub fn encode(&self) -> String {
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(&self.bits).expect("in-memory gzip");
+        URL_SAFE_NO_PAD.encode(gz.finish().expect("in-memory gzip"))
+    }
+
+    pub fn decode(encoded_list: &str) -> Result<Self, VcError> {
+        let gz = URL_SAFE_NO_PAD
+            .decode(encoded_list.trim_end_matches('='))
+            .map_err(|e| VcError::InvalidStatus(format!("encodedList base64: {}", e)))?;
+        let mut bits = Vec::new();
+        GzDecoder::new(gz.as_slice())
+            .take(MAX_STATUS_LIST_BYTES as u64 + 1)
+            .read_to_end(&mut bits)
+            .map_err(|e| VcError::InvalidStatus(format!("encodedList gzip: {}", e)))?;
+        if bits.len() > MAX_STATUS_LIST_BYTES {
+            return Err(VcError::InvalidStatus(format!(
+                "encodedList inflates past {} bytes",
+                MAX_STATUS_LIST_BYTES
+            )));
+        }
+        Ok(Self { bits })
+    }
+}
+
+/// Status lists known locally, keyed by status list credential URL. No
+/// network fetches: an entry pointing at an unknown list fails closed.
+#[derive(Clone, Debug, Default)]
+pub struct StatusListStore {
+    lists: HashMap<String, StatusList>,
+}
+
+impl StatusListStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, status_list_credential: &str, list: StatusList) {
+        self.lists.insert(status_list_credential.to_string(), list);
+    }
+
+    /// Load a list from its `encodedList`.
+    pub fn insert_encoded(&mut self, status_list_credential: &str, encoded_list: &str) -> Result<(), VcError> {
+        self.insert(status_list_credential, StatusList::decode(encoded_list)?);
+        Ok(())
+    }
+
+    pub fn get_mut(&mut self, status_list_credential: &str) -> Option<&mut StatusList> {
+        self.lists.get_mut(status_list_credential)
+    }
+
+    /// Whether the entry's bit is set.
+    pub fn is_revoked(&self, entry: &StatusList2021Entry) -> Result<bool, VcError> {
+        if entry.kind != "StatusList2021Entry" || entry.status_purpose != "revocation" {
+            return Err(VcError::InvalidStatus(format!(
+                "unsupported status {} / {}",
+                entry.kind, entry.status_purpose
+            )));
+        }
+        let list = self
+            .lists
+            .get(&entry.status_list_credential)
+            .ok_or_else(|| VcError::UnknownStatusList(entry.status_list_credential.clone()))?;
+        let index = entry.index()?;
+        list.get(index)
+            .ok_or_else(|| VcError::InvalidStatus(format!("index {} outside list of {} bits", index, list.len())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST_URL: &str = "https://issuer.example/status/1";
+
+    #[test]
+    fn test_bit_order_and_minimum_size() {
+        let mut list = StatusList::new(8);
+        assert_eq!(list.len(), MIN_STATUS_LIST_BITS);
+        list.set(0, true).unwrap();
+        list.set(9, true).unwrap();
+        assert_eq!(list.bits[0], 0x80);
+        assert_eq!(list.bits[1], 0x40);
+        assert_eq!(list.get(9), Some(true));
+        list.set(9, false).unwrap();
+        assert_eq!(list.get(9), Some(false));
+        assert_eq!(list.get(MIN_STATUS_LIST_BITS), None);
+        assert!(list.set(MIN_STATUS_LIST_BITS, true).is_err());
+    }
+
+    #[test]
+    fn test_encoded_list_roundtrip() {
+        let mut list = StatusList::new(0);
+        list.set(42, true).unwrap();
+        let encoded = list.encode();
+        assert_eq!(StatusList::decode(&encoded).unwrap(), list);
+        assert_eq!(StatusList::decode(&format!("{}==", encoded)).unwrap(), list);
+        assert!(StatusList::decode("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_decode_caps_the_inflated_size() {
+        let at_cap = StatusList { bits: vec![0; MAX_STATUS_LIST_BYTES] };
+        assert_eq!(StatusList::decode(&at_cap.encode()).unwrap().len(), MAX_STATUS_LIST_BYTES * 8);
+
+        let bomb = StatusList { bits: vec![0; MAX_STATUS_LIST_BYTES + 1] };
+        assert!(matches!(StatusList::decode(&bomb.encode()), Err(VcError::InvalidStatus(_))));
+    }
+
+    #[test]
+    fn test_store_revocation_checks() {
+        let mut list = StatusList::new(0);
+        list.set(7, true).unwrap();
+        let mut store = StatusListStore::new();
+        store.insert_encoded(LIST_URL, &list.encode()).unwrap();
+
+        assert!(store.is_revoked(&StatusList2021Entry::revocation(LIST_URL, 7)).unwrap());
+        assert!(!store.is_revoked(&StatusList2021Entry::revocation(LIST_URL, 8)).unwrap());
+
+        let unknown = StatusList2021Entry::revocation("https://other.example/status", 7);
+        assert!(matches!(store.is_revoked(&unknown), Err(VcError::UnknownStatusList(_))));
+
+        let mut suspension = StatusList2021Entry::revocation(LIST_URL, 7);
+        suspension.status_purpose = "suspension".into();
+        assert!(matches!(store.is_revoked(&suspension), Err(VcError::InvalidStatus(_))));
+
+        let mut bad_index = StatusList2021Entry::revocation(LIST_URL, 7);
+        bad_index.status_list_index = "-1".into();
+        assert!(matches!(store.is_revoked(&bad_index), Err(VcError::InvalidStatus(_))));
+        let outside = StatusList2021Entry::revocation(LIST_URL, MIN_STATUS_LIST_BITS);
+        assert!(matches!(store.is_revoked(&outside), Err(VcError::InvalidStatus(_))));
+    }
+}
@@ -1,7 +1,10 @@
-#[derive(Clone, Debug, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct JurisdictionCode(pub String); // e.g., "US-AZ-PHX"
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AccessibilityRole {
     None,
     LowVision,
@@ -11,7 +14,7 @@ pub enum AccessibilityRole {
     MultiModal,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InteractionMode {
     Voice,
     Text,
@@ -20,14 +23,14 @@ pub enum InteractionMode {
     Hybrid,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EcoPreference {
     Neutral,
     EcoPriority,
     TimePriority,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DeviceRole {
     CivicTerminal,
     TransitHub,
@@ -37,7 +40,40 @@ pub enum DeviceRole {
     NonCivicProfiling,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+impl DeviceRole {
+    /// Every role, in declaration order.
+    pub const ALL: [DeviceRole; 6] = [
+        DeviceRole::CivicTerminal,
+        DeviceRole::TransitHub,
+        DeviceRole::EmergencyService,
+        DeviceRole::PersonalDevice,
+        DeviceRole::AdTech,
+        DeviceRole::NonCivicProfiling,
+    ];
+
+    /// Exhaustive, so a new variant does not compile until it gets a
+    /// position here; the check below then requires `ALL` to list it there.
+    const fn position(self) -> usize {
+        match self {
+            DeviceRole::CivicTerminal => 0,
+            DeviceRole::TransitHub => 1,
+            DeviceRole::EmergencyService => 2,
+            DeviceRole::PersonalDevice => 3,
+            DeviceRole::AdTech => 4,
+            DeviceRole::NonCivicProfiling => 5,
+        }
+    }
+}
+
+const _: () = {
+    let mut i = 0;
+    while i < DeviceRole::ALL.len() {
+        assert!(DeviceRole::ALL[i].position() == i, "DeviceRole::ALL is out of sync with DeviceRole");
+        i += 1;
+    }
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OfflineServiceClass {
     EssentialRoute,
     HealthAccess,
@@ -45,14 +81,14 @@ pub enum OfflineServiceClass {
     EcoLeisure,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OfflineReconciliationPolicy {
     LocalOnly,
     DeferredSync,
     ImmediateSyncWhenAvailable,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProhibitedDataUse {
     NoAffectiveAds,
     NoBehavioralScoring,
@@ -1,3 +1,6 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
 use crate::did::OiclDid;
 use crate::types::{
     AccessibilityRole,
@@ -10,7 +13,8 @@ use crate::types::{
     ProhibitedDataUse,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AugmentedCitizenProfileCredential {
     pub host_did: OiclDid,
     pub jurisdiction: JurisdictionCode,
@@ -20,7 +24,8 @@ pub struct AugmentedCitizenProfileCredential {
     pub eco_preference: EcoPreference,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct NeurorightsEnvelopeCredential {
     pub envelope_id: String,
     pub max_cognitive_load: f32,    // 0.0–1.0
@@ -31,14 +36,16 @@ pub struct NeurorightsEnvelopeCredential {
     pub jurisdiction: JurisdictionCode,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct OfflineCompanionRightsCredential {
     pub right_to_offline_companion: bool,
     pub offline_mode_permissions: Vec<OfflineServiceClass>,
     pub reconciliation_policy: OfflineReconciliationPolicy,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct NeurospaceRadiusCredential {
     pub radius_meters: f32,
     pub permitted_device_roles: Vec<DeviceRole>,
@@ -46,9 +53,32 @@ pub struct NeurospaceRadiusCredential {
     pub discovery_policy: DiscoveryPolicy,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DiscoveryPolicy {
     RadiusOnly,
     RadiusPlusLineOfSight,
     ManualPairing,
 }
+
+/// Claims that can be carried as the `credentialSubject` of a
+/// `VerifiableCredential`.
+pub trait CredentialSubject: Serialize + DeserializeOwned {
+    /// Credential `type` listed after `VerifiableCredential`.
+    const CREDENTIAL_TYPE: &'static str;
+}
+
+impl CredentialSubject for AugmentedCitizenProfileCredential {
+    const CREDENTIAL_TYPE: &'static str = "AugmentedCitizenProfileCredential";
+}
+
+impl CredentialSubject for NeurorightsEnvelopeCredential {
+    const CREDENTIAL_TYPE: &'static str = "NeurorightsEnvelopeCredential";
+}
+
+impl CredentialSubject for OfflineCompanionRightsCredential {
+    const CREDENTIAL_TYPE: &'static str = "OfflineCompanionRightsCredential";
+}
+
+impl CredentialSubject for NeurospaceRadiusCredential {
+    const CREDENTIAL_TYPE: &'static str = "NeurospaceRadiusCredential";
+}